@@ -0,0 +1,728 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+// Expands the anchors and aliases of the repeated values, e.g.
+//
+// ```text
+// {
+//     web: {limits: Anchor::Define("small", {cpu: 1, memory: 512})}
+//     worker: {limits: Anchor::Alias("small")}
+// }
+// ```
+//
+// `Anchor::Define(name, value)` is replaced by the value, and
+// `Anchor::Alias(name)` is replaced by a copy of the value.
+// An anchor must be defined before its aliases, so that the document
+// can be expanded in a single pass, and the only possible cycle is
+// an alias inside the value of its own anchor.
+//
+// The expansion is done on tokens, so both the AST parser and the
+// deserializer are supported, and the copies keep the locations
+// of the definition.
+//
+// Without `ParseOptions::with_anchors`, the anchors and aliases are kept
+// in the `AsonNode` tree as ordinary Variants, so a document can be edited
+// and printed back with its anchors, and `expand_anchors` expands
+// them on the tree later.
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::{
+    ast::{AsonNode, VariantValue},
+    limits::Limits,
+    location::Location,
+    path::AsonPath,
+    printer::print_to_writer,
+    token::{Token, TokenWithRange},
+    AsonError, ErrorKind,
+};
+
+pub(crate) const ANCHOR_TYPE_NAME: &str = "Anchor";
+pub(crate) const ANCHOR_DEFINE_MEMBER_NAME: &str = "Define";
+pub(crate) const ANCHOR_ALIAS_MEMBER_NAME: &str = "Alias";
+
+pub(crate) struct AnchorTokenIter<'a> {
+    upstream: &'a mut dyn Iterator<Item = Result<TokenWithRange, AsonError>>,
+
+    // the tokens are passed through if it is false.
+    enabled: bool,
+
+    // the size (in bytes) of tokens produced by aliases is limited by
+    // `Limits::max_document_size`, to prevent the exponential expansion
+    // of nested aliases.
+    limits: Limits,
+    expanded_size: usize,
+
+    // the tokens of the anchors and their sizes in bytes.
+    anchors: HashMap<String, (Vec<TokenWithRange>, usize)>,
+
+    // the names of anchors whose value is being read.
+    defining: Vec<String>,
+
+    // the tokens of the expanded anchor or alias, and the token
+    // pushed back after looking ahead.
+    pending: VecDeque<TokenWithRange>,
+}
+
+impl<'a> AnchorTokenIter<'a> {
+    pub fn new(
+        upstream: &'a mut dyn Iterator<Item = Result<TokenWithRange, AsonError>>,
+        enabled: bool,
+        limits: Limits,
+    ) -> Self {
+        Self {
+            upstream,
+            enabled,
+            limits,
+            expanded_size: 0,
+            anchors: HashMap::new(),
+            defining: vec![],
+            pending: VecDeque::new(),
+        }
+    }
+
+    // returns the next token with the anchors and aliases expanded.
+    fn pull(&mut self) -> Result<Option<TokenWithRange>, AsonError> {
+        if let Some(token_with_range) = self.pending.pop_front() {
+            return Ok(Some(token_with_range));
+        }
+
+        let token_with_range = match self.upstream.next() {
+            Some(result) => result?,
+            None => return Ok(None),
+        };
+
+        let tokens = match &token_with_range.token {
+            Token::Variant(type_name, member_name) if type_name == ANCHOR_TYPE_NAME => {
+                match member_name.as_str() {
+                    ANCHOR_DEFINE_MEMBER_NAME => self.define()?,
+                    ANCHOR_ALIAS_MEMBER_NAME => self.alias()?,
+                    _ => {
+                        return Err(AsonError::new_syntax(
+                            format!(
+                                "Unknown anchor operation \"{}\", expect \"{}\" or \"{}\".",
+                                member_name, ANCHOR_DEFINE_MEMBER_NAME, ANCHOR_ALIAS_MEMBER_NAME
+                            ),
+                            token_with_range.range,
+                        ))
+                    }
+                }
+            }
+            _ => return Ok(Some(token_with_range)),
+        };
+
+        // the pending queue is always empty here, since the upstream
+        // is read only after the pending tokens are consumed.
+        self.pending.extend(tokens);
+        Ok(self.pending.pop_front())
+    }
+
+    // `Anchor::Define("name", value)`
+    fn define(&mut self) -> Result<Vec<TokenWithRange>, AsonError> {
+        self.expect_token(&Token::LeftParen, "(")?;
+        let (name, name_range) = self.expect_name()?;
+
+        match self.pull()? {
+            Some(TokenWithRange {
+                token: Token::Comma | Token::NewLine,
+                ..
+            }) => {}
+            Some(TokenWithRange { range, .. }) => {
+                return Err(AsonError::new_syntax(
+                    "Expect a comma or new-line after the anchor name.".to_owned(),
+                    range,
+                ))
+            }
+            None => return Err(new_unexpected_end_error()),
+        }
+
+        if self.anchors.contains_key(&name) || self.defining.contains(&name) {
+            return Err(AsonError::new(
+                ErrorKind::InvalidReference,
+                format!("Anchor \"{}\" is already defined.", name),
+            )
+            .with_location(name_range));
+        }
+
+        self.defining.push(name.clone());
+        let tokens = self.read_value()?;
+        self.defining.pop();
+
+        // the optional trailing comma
+        match self.pull_skip_new_lines()? {
+            Some(TokenWithRange {
+                token: Token::Comma,
+                ..
+            }) => self.expect_token(&Token::RightParen, ")")?,
+            Some(TokenWithRange {
+                token: Token::RightParen,
+                ..
+            }) => {}
+            Some(TokenWithRange { range, .. }) => {
+                return Err(AsonError::new_syntax(
+                    "Expect a \")\" after the value of anchor.".to_owned(),
+                    range,
+                ))
+            }
+            None => return Err(new_unexpected_end_error()),
+        }
+
+        let size = tokens.iter().map(token_size).sum();
+        self.anchors.insert(name, (tokens.clone(), size));
+        Ok(tokens)
+    }
+
+    // `Anchor::Alias("name")`
+    fn alias(&mut self) -> Result<Vec<TokenWithRange>, AsonError> {
+        self.expect_token(&Token::LeftParen, "(")?;
+        let (name, name_range) = self.expect_name()?;
+        self.expect_token(&Token::RightParen, ")")?;
+
+        if self.defining.contains(&name) {
+            return Err(AsonError::new(
+                ErrorKind::InvalidReference,
+                format!("Alias \"{}\" references its own anchor.", name),
+            )
+            .with_location(name_range));
+        }
+
+        let (tokens, size) = match self.anchors.get(&name) {
+            Some((tokens, size)) => (tokens.clone(), *size),
+            None => {
+                return Err(AsonError::new(
+                    ErrorKind::InvalidReference,
+                    format!("Anchor \"{}\" is not defined.", name),
+                )
+                .with_location(name_range))
+            }
+        };
+
+        self.expanded_size += size;
+        if self.expanded_size > self.limits.max_document_size {
+            return Err(self.limits.new_document_size_error(name_range));
+        }
+
+        Ok(tokens)
+    }
+
+    // reads the tokens of a single value, e.g. `123`, `[...]`, `{...}`,
+    // `Option::None` and `Option::Some(...)`.
+    fn read_value(&mut self) -> Result<Vec<TokenWithRange>, AsonError> {
+        let mut tokens = vec![];
+        let mut depth = 0;
+
+        loop {
+            let token_with_range = match self.pull()? {
+                Some(token_with_range) => token_with_range,
+                None => return Err(new_unexpected_end_error()),
+            };
+
+            match token_with_range.token {
+                Token::LeftBrace | Token::LeftBracket | Token::LeftParen => depth += 1,
+                Token::RightBrace
+                | Token::RightBracket
+                | Token::RightParen
+                | Token::Comma
+                | Token::NewLine
+                    if depth == 0 =>
+                {
+                    return Err(AsonError::new_syntax(
+                        "Expect a value for the anchor.".to_owned(),
+                        token_with_range.range,
+                    ))
+                }
+                Token::RightBrace | Token::RightBracket | Token::RightParen => depth -= 1,
+                _ => {}
+            }
+
+            let is_variant = matches!(token_with_range.token, Token::Variant(..));
+            tokens.push(token_with_range);
+
+            if depth > 0 {
+                continue;
+            }
+
+            // the value of variant, e.g. the `(...)` of `Option::Some(...)`.
+            if is_variant {
+                match self.pull()? {
+                    Some(
+                        next @ TokenWithRange {
+                            token: Token::LeftParen | Token::LeftBrace,
+                            ..
+                        },
+                    ) => {
+                        tokens.push(next);
+                        depth += 1;
+                        continue;
+                    }
+                    Some(next) => self.pending.push_front(next),
+                    None => {}
+                }
+            }
+
+            return Ok(tokens);
+        }
+    }
+
+    fn pull_skip_new_lines(&mut self) -> Result<Option<TokenWithRange>, AsonError> {
+        loop {
+            match self.pull()? {
+                Some(TokenWithRange {
+                    token: Token::NewLine,
+                    ..
+                }) => {}
+                other => return Ok(other),
+            }
+        }
+    }
+
+    fn expect_token(&mut self, expected: &Token, description: &str) -> Result<(), AsonError> {
+        match self.pull_skip_new_lines()? {
+            Some(TokenWithRange { token, .. }) if &token == expected => Ok(()),
+            Some(TokenWithRange { range, .. }) => Err(AsonError::new_syntax(
+                format!("Expect a \"{}\" for the anchor.", description),
+                range,
+            )),
+            None => Err(new_unexpected_end_error()),
+        }
+    }
+
+    fn expect_name(&mut self) -> Result<(String, Location), AsonError> {
+        match self.pull_skip_new_lines()? {
+            Some(TokenWithRange {
+                token: Token::String(name),
+                range,
+            }) if !name.is_empty() => Ok((name, range)),
+            Some(TokenWithRange { range, .. }) => Err(AsonError::new_syntax(
+                "Expect a String for the anchor name.".to_owned(),
+                range,
+            )),
+            None => Err(new_unexpected_end_error()),
+        }
+    }
+}
+
+impl Iterator for AnchorTokenIter<'_> {
+    type Item = Result<TokenWithRange, AsonError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.enabled {
+            return self.upstream.next();
+        }
+
+        self.pull().transpose()
+    }
+}
+
+// the size of the token text in bytes, the whitespaces and comments
+// between the tokens are not counted. The Strings and Chars are counted
+// by their (unescaped) UTF-8 content and the quotes, the other tokens
+// are ASCII text unless they contain names.
+fn token_size(token_with_range: &TokenWithRange) -> usize {
+    match &token_with_range.token {
+        Token::Identifier(name) => name.len(),
+        Token::Variant(type_name, member_name) => type_name.len() + member_name.len() + 2,
+        Token::String(s) => s.len() + 2,
+        Token::Char(c) => c.len_utf8() + 2,
+        _ => token_with_range.range.length,
+    }
+}
+
+fn new_unexpected_end_error() -> AsonError {
+    AsonError::new_unexpected_end_of_document("Incomplete anchor.".to_owned())
+}
+
+/// Expands the anchors and aliases of a tree which is parsed without
+/// `ParseOptions::with_anchors`, with the same rules as the parser.
+///
+/// The tree has no locations, so the errors carry the path of the anchor
+/// or alias instead. The total size of the copies made by the aliases
+/// (in bytes of the printed values) is limited by `Limits::max_document_size`.
+///
+/// ```
+/// use ason::{expand_anchors, parse_from_str, print_to_string, Limits};
+///
+/// let text = r#"[Anchor::Define("a", {cpu: 1}), Anchor::Alias("a")]"#;
+/// let node = parse_from_str(text).unwrap();
+/// assert!(print_to_string(&node).contains("Anchor::Alias"));
+///
+/// let expanded = expand_anchors(node, Limits::default()).unwrap();
+/// assert_eq!(expanded, parse_from_str("[{cpu: 1}, {cpu: 1}]").unwrap());
+/// ```
+pub fn expand_anchors(mut node: AsonNode, limits: Limits) -> Result<AsonNode, AsonError> {
+    let mut expander = AnchorNodeExpander {
+        limits,
+        expanded_size: 0,
+        anchors: HashMap::new(),
+        defining: vec![],
+    };
+    expander.expand(&mut node, &mut AsonPath::root())?;
+    Ok(node)
+}
+
+struct AnchorNodeExpander {
+    limits: Limits,
+    expanded_size: usize,
+
+    // the expanded values and their printed sizes.
+    anchors: HashMap<String, (AsonNode, usize)>,
+
+    // the names of anchors whose value is being expanded.
+    defining: Vec<String>,
+}
+
+impl AnchorNodeExpander {
+    fn expand(&mut self, node: &mut AsonNode, path: &mut AsonPath) -> Result<(), AsonError> {
+        match node {
+            AsonNode::Variant(variant) if variant.type_name == ANCHOR_TYPE_NAME => {
+                *node = match (variant.member_name.as_str(), &mut variant.value) {
+                    (ANCHOR_DEFINE_MEMBER_NAME, VariantValue::Tuple(items)) if items.len() == 2 => {
+                        let value = items.pop().unwrap();
+                        let name = anchor_name(&items[0], path)?.to_owned();
+                        self.define(name, value, path)?
+                    }
+                    (ANCHOR_ALIAS_MEMBER_NAME, VariantValue::Value(value)) => {
+                        let name = anchor_name(value, path)?.to_owned();
+                        self.alias(&name, path)?
+                    }
+                    _ => return Err(new_invalid_anchor_error(path)),
+                };
+            }
+            AsonNode::Variant(variant) => match &mut variant.value {
+                VariantValue::Empty => {}
+                VariantValue::Value(value) => {
+                    path.push_index(0);
+                    self.expand(value, path)?;
+                    path.segments.pop();
+                }
+                VariantValue::Tuple(items) => self.expand_items(items, path)?,
+                VariantValue::Object(kvps) => {
+                    for kvp in kvps {
+                        path.push_key(&kvp.key);
+                        self.expand(&mut kvp.value, path)?;
+                        path.segments.pop();
+                    }
+                }
+            },
+            AsonNode::List(items) | AsonNode::Tuple(items) => self.expand_items(items, path)?,
+            AsonNode::Object(kvps) => {
+                for kvp in kvps {
+                    path.push_key(&kvp.key);
+                    self.expand(&mut kvp.value, path)?;
+                    path.segments.pop();
+                }
+            }
+            AsonNode::Map(nvps) => {
+                for nvp in nvps {
+                    // the name is expanded first, as the parser reads it first.
+                    self.expand(&mut nvp.name, path)?;
+                    path.push_name(&nvp.name);
+                    self.expand(&mut nvp.value, path)?;
+                    path.segments.pop();
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn expand_items(
+        &mut self,
+        items: &mut [AsonNode],
+        path: &mut AsonPath,
+    ) -> Result<(), AsonError> {
+        for (index, item) in items.iter_mut().enumerate() {
+            path.push_index(index);
+            self.expand(item, path)?;
+            path.segments.pop();
+        }
+        Ok(())
+    }
+
+    fn define(
+        &mut self,
+        name: String,
+        mut value: AsonNode,
+        path: &mut AsonPath,
+    ) -> Result<AsonNode, AsonError> {
+        if self.anchors.contains_key(&name) || self.defining.contains(&name) {
+            return Err(AsonError::new(
+                ErrorKind::InvalidReference,
+                format!("Anchor \"{}\" is already defined.", name),
+            )
+            .with_path(path.clone()));
+        }
+
+        self.defining.push(name.clone());
+        self.expand(&mut value, path)?;
+        self.defining.pop();
+
+        let mut buf: Vec<u8> = vec![];
+        print_to_writer(&mut buf, &value)?;
+        let size = buf.len();
+        self.anchors.insert(name, (value.clone(), size));
+        Ok(value)
+    }
+
+    fn alias(&mut self, name: &str, path: &AsonPath) -> Result<AsonNode, AsonError> {
+        if self.defining.iter().any(|defining| defining == name) {
+            return Err(AsonError::new(
+                ErrorKind::InvalidReference,
+                format!("Alias \"{}\" references its own anchor.", name),
+            )
+            .with_path(path.clone()));
+        }
+
+        let Some((value, size)) = self.anchors.get(name) else {
+            return Err(AsonError::new(
+                ErrorKind::InvalidReference,
+                format!("Anchor \"{}\" is not defined.", name),
+            )
+            .with_path(path.clone()));
+        };
+
+        self.expanded_size += size;
+        if self.expanded_size > self.limits.max_document_size {
+            return Err(AsonError::new(
+                ErrorKind::DocumentSizeLimitExceeded,
+                format!(
+                    "The size of expanded document exceeds the limit {} bytes.",
+                    self.limits.max_document_size
+                ),
+            )
+            .with_path(path.clone()));
+        }
+
+        Ok(value.clone())
+    }
+}
+
+fn anchor_name<'a>(node: &'a AsonNode, path: &AsonPath) -> Result<&'a str, AsonError> {
+    match node {
+        AsonNode::String(name) if !name.is_empty() => Ok(name),
+        _ => Err(new_invalid_anchor_error(path)),
+    }
+}
+
+fn new_invalid_anchor_error(path: &AsonPath) -> AsonError {
+    AsonError::new(
+        ErrorKind::Syntax,
+        format!(
+            "Invalid anchor, expect `{}::{}(\"name\", value)` or `{}::{}(\"name\")`.",
+            ANCHOR_TYPE_NAME, ANCHOR_DEFINE_MEMBER_NAME, ANCHOR_TYPE_NAME, ANCHOR_ALIAS_MEMBER_NAME
+        ),
+    )
+    .with_path(path.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        location::Location, options::ParseOptions, parser::parse_from_str, path::AsonPath,
+        printer::print_to_string, ErrorKind, Limits,
+    };
+
+    use super::expand_anchors;
+
+    #[test]
+    fn test_expand() {
+        let node = ParseOptions::new()
+            .with_anchors()
+            .parse_str(
+                r#"{
+                web: {
+                    limits: Anchor::Define("small", {cpu: 1, memory: 512})
+                    tags: Anchor::Define(
+                        "tags"
+                        [Option::Some(Anchor::Define("default", "a")), Option::None]
+                    )
+                }
+                worker: {
+                    limits: Anchor::Alias("small")
+                    tags: Anchor::Alias("tags")
+                }
+                defaults: (Anchor::Alias("default"), Anchor::Define("color", Color::Rgb(1, 2, 3)))
+                colors: [Anchor::Alias("color"), Anchor::Alias( "color" )]
+            }"#,
+            )
+            .unwrap();
+
+        assert_eq!(
+            node,
+            parse_from_str(
+                r#"{
+                    web: {
+                        limits: {cpu: 1, memory: 512}
+                        tags: [Option::Some("a"), Option::None]
+                    }
+                    worker: {
+                        limits: {cpu: 1, memory: 512}
+                        tags: [Option::Some("a"), Option::None]
+                    }
+                    defaults: ("a", Color::Rgb(1, 2, 3))
+                    colors: [Color::Rgb(1, 2, 3), Color::Rgb(1, 2, 3)]
+                }"#
+            )
+            .unwrap()
+        );
+
+        // the anchors are only expanded by `ParseOptions::with_anchors`
+        assert!(parse_from_str(r#"Anchor::Alias("small")"#).is_ok());
+    }
+
+    #[test]
+    fn test_expand_errors() {
+        let err = ParseOptions::new()
+            .with_anchors()
+            .parse_str(r#"[Anchor::Alias("small")]"#)
+            .unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidReference);
+        assert_eq!(err.message, r#"Anchor "small" is not defined."#);
+        assert_eq!(err.location, Some(Location::new_range(15, 0, 15, 7)));
+
+        // defined after the alias
+        let err = ParseOptions::new()
+            .with_anchors()
+            .parse_str(r#"[Anchor::Alias("a"), Anchor::Define("a", 1)]"#)
+            .unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidReference);
+
+        let err = ParseOptions::new()
+            .with_anchors()
+            .parse_str(r#"Anchor::Define("node", {children: [Anchor::Alias("node")]})"#)
+            .unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidReference);
+        assert_eq!(err.message, r#"Alias "node" references its own anchor."#);
+
+        let err = ParseOptions::new()
+            .with_anchors()
+            .parse_str(r#"[Anchor::Define("a", 1), Anchor::Define("a", 2)]"#)
+            .unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidReference);
+        assert_eq!(err.message, r#"Anchor "a" is already defined."#);
+
+        let err = ParseOptions::new()
+            .with_anchors()
+            .parse_str(r#"Anchor::Define("a")"#)
+            .unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Syntax);
+
+        let err = ParseOptions::new()
+            .with_anchors()
+            .parse_str(r#"Anchor::Define(a, 1)"#)
+            .unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Syntax);
+
+        let err = ParseOptions::new()
+            .with_anchors()
+            .parse_str(r#"Anchor::Link("a")"#)
+            .unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Syntax);
+
+        let err = ParseOptions::new()
+            .with_anchors()
+            .parse_str(r#"[Anchor::Define("a", 1"#)
+            .unwrap_err();
+        assert_eq!(err.kind, ErrorKind::UnexpectedEndOfDocument);
+    }
+
+    #[test]
+    fn test_expansion_limit() {
+        // each level doubles the size of document
+        let mut text = r#"[Anchor::Define("a0", [1, 2])"#.to_owned();
+        for level in 1..8 {
+            text.push_str(&format!(
+                r#", Anchor::Define("a{}", [Anchor::Alias("a{}"), Anchor::Alias("a{}")])"#,
+                level,
+                level - 1,
+                level - 1
+            ));
+        }
+        text.push(']');
+
+        assert!(ParseOptions::new().with_anchors().parse_str(&text).is_ok());
+
+        let limits = Limits {
+            max_document_size: 1000,
+            ..Limits::default()
+        };
+        let err = ParseOptions::new()
+            .with_limits(limits)
+            .with_anchors()
+            .parse_str(&text)
+            .unwrap_err();
+        assert_eq!(err.kind, ErrorKind::DocumentSizeLimitExceeded);
+    }
+
+    #[test]
+    fn test_preserve_and_expand_tree() {
+        let text = r#"{
+            web: {limits: Anchor::Define("small", {cpu: 1, memory: 512})}
+            worker: {limits: Anchor::Alias("small")}
+            defaults: [Anchor::Define("a", Option::Some(Anchor::Define("b", 1))), Anchor::Alias("b")]
+            names: [1: Anchor::Alias("a")]
+        }"#;
+
+        // the anchors are kept in the tree and printed back unchanged
+        let node = parse_from_str(text).unwrap();
+        assert_eq!(parse_from_str(&print_to_string(&node)).unwrap(), node);
+
+        // the tree is expanded the same as the parser does
+        assert_eq!(
+            expand_anchors(node, Limits::default()).unwrap(),
+            ParseOptions::new().with_anchors().parse_str(text).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_expand_tree_errors() {
+        let expand = |text: &str| {
+            expand_anchors(parse_from_str(text).unwrap(), Limits::default()).unwrap_err()
+        };
+
+        let err = expand(r#"{a: [Anchor::Alias("small")]}"#);
+        assert_eq!(err.kind, ErrorKind::InvalidReference);
+        assert_eq!(err.message, r#"Anchor "small" is not defined."#);
+        assert_eq!(err.path(), Some(&AsonPath::parse(".a[0]").unwrap()));
+
+        // defined after the alias
+        let err = expand(r#"[Anchor::Alias("a"), Anchor::Define("a", 1)]"#);
+        assert_eq!(err.kind, ErrorKind::InvalidReference);
+
+        let err = expand(r#"Anchor::Define("node", {children: [Anchor::Alias("node")]})"#);
+        assert_eq!(err.kind, ErrorKind::InvalidReference);
+        assert_eq!(err.message, r#"Alias "node" references its own anchor."#);
+        assert_eq!(err.path(), Some(&AsonPath::parse(".children[0]").unwrap()));
+
+        let err = expand(r#"[Anchor::Define("a", 1), Anchor::Define("a", 2)]"#);
+        assert_eq!(err.kind, ErrorKind::InvalidReference);
+        assert_eq!(err.message, r#"Anchor "a" is already defined."#);
+
+        let err = expand(r#"Anchor::Define("a", 1, 2)"#);
+        assert_eq!(err.kind, ErrorKind::Syntax);
+
+        let err = expand(r#"Anchor::Alias(1)"#);
+        assert_eq!(err.kind, ErrorKind::Syntax);
+
+        let err = expand(r#"Anchor::Link("a")"#);
+        assert_eq!(err.kind, ErrorKind::Syntax);
+
+        let limits = Limits {
+            max_document_size: 20,
+            ..Limits::default()
+        };
+        let node = parse_from_str(
+            r#"[Anchor::Define("a", "0123456789"), Anchor::Alias("a"), Anchor::Alias("a")]"#,
+        )
+        .unwrap();
+        let err = expand_anchors(node, limits).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::DocumentSizeLimitExceeded);
+        assert_eq!(err.path(), Some(&AsonPath::parse("[2]").unwrap()));
+    }
+}
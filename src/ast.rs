@@ -24,19 +24,19 @@ pub enum Number {
     F64(f64),
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct KeyValuePair {
     pub key: String,
     pub value: Box<AsonNode>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct NameValuePair {
     pub name: Box<AsonNode>,
     pub value: Box<AsonNode>,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Variant {
     // variant type name, e.g. the "Option" of "Option::None"
     pub type_name: String,
@@ -47,7 +47,7 @@ pub struct Variant {
     pub value: VariantValue,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum VariantValue {
     Empty,                     // unit variant
     Value(Box<AsonNode>),      // new type variant
@@ -55,7 +55,8 @@ pub enum VariantValue {
     Object(Vec<KeyValuePair>), // struct variant
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
 pub enum AsonNode {
     Number(Number),
     Boolean(bool),
@@ -68,6 +69,28 @@ pub enum AsonNode {
     Tuple(Vec<AsonNode>),
     Object(Vec<KeyValuePair>),
     Map(Vec<NameValuePair>),
+
+    // the placeholder of the invalid value in a partial tree, it is
+    // produced only by the parser with error recovery.
+    Error,
+}
+
+impl Number {
+    /// The name of the number type, e.g. "i32".
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Number::I8(_) => "i8",
+            Number::U8(_) => "u8",
+            Number::I16(_) => "i16",
+            Number::U16(_) => "u16",
+            Number::I32(_) => "i32",
+            Number::U32(_) => "u32",
+            Number::I64(_) => "i64",
+            Number::U64(_) => "u64",
+            Number::F32(_) => "f32",
+            Number::F64(_) => "f64",
+        }
+    }
 }
 
 impl KeyValuePair {
@@ -0,0 +1,568 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+// The `ason` command-line tool.
+//
+// Exit codes:
+//
+// - 0: success.
+// - 1: the check does not pass, i.e. a document is invalid (`validate`),
+//      is not formatted (`fmt --check`), the documents are different (`diff`),
+//      the value does not exist (`get`), or there are conflicts (`merge3`).
+// - 2: usage error, I/O error or conversion error.
+
+use std::{
+    fs,
+    io::{self, IsTerminal, Read, Write},
+    path::Path,
+    process::ExitCode,
+    str::FromStr,
+};
+
+use argh::FromArgs;
+use ason::{
+    ast::AsonNode,
+    diff,
+    json::{self, JsonToAsonOptions},
+    merge::{MergeStrategy, Merger, Override},
+    path::AsonPath,
+    print_to_string, print_to_writer,
+    threeway::{self, ConflictSide},
+    tokenizer::{TokenKind, Tokenizer},
+    AsonError, ParseOptions, RenderOptions,
+};
+
+const EXIT_CODE_FAILURE: u8 = 1;
+const EXIT_CODE_ERROR: u8 = 2;
+
+// the file name for reading from stdin.
+const STDIN_FILE_NAME: &str = "-";
+
+#[derive(FromArgs)]
+/// Tools for ASON documents.
+struct Args {
+    #[argh(subcommand)]
+    command: Command,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+enum Command {
+    Fmt(FmtCommand),
+    Validate(ValidateCommand),
+    Convert(ConvertCommand),
+    Get(GetCommand),
+    Diff(DiffCommand),
+    Merge(MergeCommand),
+    Merge3(Merge3Command),
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "fmt")]
+/// Reformat documents in place, or reformat stdin to stdout if no file is given.
+/// The formatter does not preserve comments, so documents containing comments
+/// are skipped (and pass `--check`) unless `--strip-comments` is given.
+struct FmtCommand {
+    #[argh(switch)]
+    /// do not write anything, exit with status 1 if any document is not formatted.
+    check: bool,
+
+    #[argh(switch)]
+    /// format the documents containing comments as well, the comments are removed.
+    strip_comments: bool,
+
+    #[argh(positional)]
+    files: Vec<String>,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "validate")]
+/// Check the syntax of documents, read stdin if no file is given.
+struct ValidateCommand {
+    #[argh(positional)]
+    files: Vec<String>,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "convert")]
+/// Convert a document between ASON and JSON and write it to stdout.
+struct ConvertCommand {
+    #[argh(option)]
+    /// the source format, "ason" or "json", inferred from the file extension by default.
+    from: Option<Format>,
+
+    #[argh(option)]
+    /// the target format, "ason" or "json", defaults to the other one of the source format.
+    to: Option<Format>,
+
+    #[argh(switch)]
+    /// use the lossless tagged JSON encoding.
+    tagged: bool,
+
+    #[argh(positional, default = "STDIN_FILE_NAME.to_owned()")]
+    file: String,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "get")]
+/// Print the value located by the path, e.g. `.servers[0].port`.
+struct GetCommand {
+    #[argh(positional)]
+    path: String,
+
+    #[argh(positional, default = "STDIN_FILE_NAME.to_owned()")]
+    file: String,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "diff")]
+/// Compare the values of two documents and print the changes, e.g. `~ .port: 80 -> 8080`,
+/// formatting and comments are ignored.
+struct DiffCommand {
+    #[argh(positional)]
+    left: String,
+
+    #[argh(positional)]
+    right: String,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "merge")]
+/// Merge the documents in order, then apply the overrides, and print the result.
+/// Objects and Maps are merged recursively, Lists and other values are replaced.
+struct MergeCommand {
+    #[argh(option)]
+    /// set a value after merging, e.g. `--set server.port=8080`, strings must be quoted.
+    set: Vec<String>,
+
+    #[argh(switch)]
+    /// append Lists instead of replacing them.
+    append_lists: bool,
+
+    #[argh(switch)]
+    /// print the file (or override) that supplied each value to stderr.
+    show_sources: bool,
+
+    #[argh(positional)]
+    files: Vec<String>,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "merge3")]
+/// Three-way merge the changes from BASE to OURS and from BASE to THEIRS,
+/// and print the result with conflict markers. It can be used as a git merge driver,
+/// e.g. `ason merge3 --in-place %O %A %B`. Comments are not preserved, so documents
+/// containing comments are refused unless `--strip-comments` is given.
+struct Merge3Command {
+    #[argh(switch)]
+    /// write the result to OURS instead of stdout.
+    in_place: bool,
+
+    #[argh(switch)]
+    /// merge the documents containing comments as well, the comments are removed.
+    strip_comments: bool,
+
+    #[argh(option)]
+    /// resolve the conflicts by taking the values of "ours" or "theirs"
+    /// instead of writing conflict markers.
+    resolve: Option<Side>,
+
+    #[argh(option, default = "String::from(\"ours\")")]
+    /// the label of the "ours" conflict markers.
+    ours_label: String,
+
+    #[argh(option, default = "String::from(\"theirs\")")]
+    /// the label of the "theirs" conflict markers.
+    theirs_label: String,
+
+    #[argh(positional)]
+    base: String,
+
+    #[argh(positional)]
+    ours: String,
+
+    #[argh(positional)]
+    theirs: String,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+struct Side(ConflictSide);
+
+impl FromStr for Side {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ours" => Ok(Side(ConflictSide::Ours)),
+            "theirs" => Ok(Side(ConflictSide::Theirs)),
+            _ => Err(format!("Unsupported side \"{}\".", s)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Format {
+    Ason,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ason" => Ok(Format::Ason),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("Unsupported format \"{}\".", s)),
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let strings: Vec<String> = std::env::args().collect();
+    let command_name = strings
+        .first()
+        .and_then(|s| Path::new(s).file_name())
+        .and_then(|s| s.to_str())
+        .unwrap_or("ason");
+    let arguments: Vec<&str> = strings.iter().skip(1).map(|s| s.as_str()).collect();
+
+    let args = match Args::from_args(&[command_name], &arguments) {
+        Ok(args) => args,
+        Err(early_exit) => {
+            return match early_exit.status {
+                Ok(_) => {
+                    // `--help`
+                    println!("{}", early_exit.output);
+                    ExitCode::SUCCESS
+                }
+                Err(_) => {
+                    eprintln!("{}", early_exit.output);
+                    ExitCode::from(EXIT_CODE_ERROR)
+                }
+            };
+        }
+    };
+
+    let result = match args.command {
+        Command::Fmt(command) => run_fmt(&command),
+        Command::Validate(command) => run_validate(&command),
+        Command::Convert(command) => run_convert(&command),
+        Command::Get(command) => run_get(&command),
+        Command::Diff(command) => run_diff(&command),
+        Command::Merge(command) => run_merge(&command),
+        Command::Merge3(command) => run_merge3(&command),
+    };
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(EXIT_CODE_FAILURE),
+        Err(message) => {
+            eprintln!("{}", message);
+            ExitCode::from(EXIT_CODE_ERROR)
+        }
+    }
+}
+
+// the result of commands, `Ok(false)` means the check does not pass.
+type CommandResult = Result<bool, String>;
+
+fn read_file(file: &str) -> Result<String, String> {
+    if file == STDIN_FILE_NAME {
+        let mut s = String::new();
+        io::stdin()
+            .read_to_string(&mut s)
+            .map_err(|e| format!("Can not read stdin: {}", e))?;
+        Ok(s)
+    } else {
+        fs::read_to_string(file).map_err(|e| format!("Can not read file \"{}\": {}", file, e))
+    }
+}
+
+fn display_name(file: &str) -> &str {
+    if file == STDIN_FILE_NAME {
+        "<stdin>"
+    } else {
+        file
+    }
+}
+
+fn print_syntax_error(file: &str, source: &str, error: &AsonError) {
+    let options = RenderOptions {
+        colored: io::stderr().is_terminal(),
+        file_name: Some(display_name(file).to_owned()),
+        ..Default::default()
+    };
+    eprintln!("{}\n", error.to_diagnostic(source).render(source, &options));
+}
+
+// parse the document, all syntax errors are printed and `None` is returned if it is invalid.
+fn parse_document(file: &str, source: &str) -> Option<AsonNode> {
+    let (node, errors) = ParseOptions::new().parse_str_with_recovery(source);
+    if errors.is_empty() {
+        Some(node)
+    } else {
+        for error in &errors {
+            print_syntax_error(file, source, error);
+        }
+        None
+    }
+}
+
+fn has_comments(source: &str) -> bool {
+    Tokenizer::new(source).any(|token| matches!(token.kind, TokenKind::Comment(_)))
+}
+
+fn files_or_stdin(files: &[String]) -> Vec<String> {
+    if files.is_empty() {
+        vec![STDIN_FILE_NAME.to_owned()]
+    } else {
+        files.to_vec()
+    }
+}
+
+fn run_fmt(command: &FmtCommand) -> CommandResult {
+    let mut passed = true;
+
+    for file in files_or_stdin(&command.files) {
+        let source = read_file(&file)?;
+        let Some(node) = parse_document(&file, &source) else {
+            passed = false;
+            continue;
+        };
+
+        if !command.strip_comments && has_comments(&source) {
+            eprintln!(
+                "{}: skipped, the comments would be lost by formatting, use `--strip-comments` to format it anyway.",
+                display_name(&file)
+            );
+
+            // stdin is passed through unchanged.
+            if file == STDIN_FILE_NAME && !command.check {
+                io::stdout()
+                    .write_all(source.as_bytes())
+                    .map_err(|e| e.to_string())?;
+            }
+            continue;
+        }
+
+        let mut formatted = print_to_string(&node);
+        formatted.push('\n');
+
+        if command.check {
+            if formatted != source {
+                eprintln!("{}: not formatted.", display_name(&file));
+                passed = false;
+            }
+        } else if file == STDIN_FILE_NAME {
+            io::stdout()
+                .write_all(formatted.as_bytes())
+                .map_err(|e| e.to_string())?;
+        } else if formatted != source {
+            fs::write(&file, formatted)
+                .map_err(|e| format!("Can not write file \"{}\": {}", file, e))?;
+        }
+    }
+
+    Ok(passed)
+}
+
+fn run_validate(command: &ValidateCommand) -> CommandResult {
+    let mut passed = true;
+
+    for file in files_or_stdin(&command.files) {
+        let source = read_file(&file)?;
+        if parse_document(&file, &source).is_none() {
+            passed = false;
+        }
+    }
+
+    Ok(passed)
+}
+
+fn run_convert(command: &ConvertCommand) -> CommandResult {
+    let from = command.from.unwrap_or_else(|| {
+        if command.file.ends_with(".json") {
+            Format::Json
+        } else {
+            Format::Ason
+        }
+    });
+
+    let to = command.to.unwrap_or(match from {
+        Format::Ason => Format::Json,
+        Format::Json => Format::Ason,
+    });
+
+    let source = read_file(&command.file)?;
+
+    let node = match from {
+        Format::Ason => match parse_document(&command.file, &source) {
+            Some(node) => node,
+            None => return Ok(false),
+        },
+        Format::Json => {
+            let value: serde_json::Value = serde_json::from_str(&source)
+                .map_err(|e| format!("{}: {}", display_name(&command.file), e))?;
+            if command.tagged {
+                json::tagged::decode(&value)
+            } else {
+                json::json_to_ason(&value, &JsonToAsonOptions::default())
+            }
+            .map_err(|e| e.to_string())?
+        }
+    };
+
+    let mut stdout = io::stdout();
+    match to {
+        Format::Ason => {
+            print_to_writer(&mut stdout, &node).map_err(|e| e.to_string())?;
+        }
+        Format::Json => {
+            let value = if command.tagged {
+                json::tagged::encode(&node)
+            } else {
+                json::ason_to_json(&node).map_err(|e| e.to_string())?
+            };
+            serde_json::to_writer_pretty(&mut stdout, &value).map_err(|e| e.to_string())?;
+        }
+    }
+    writeln!(stdout).map_err(|e| e.to_string())?;
+
+    Ok(true)
+}
+
+fn run_get(command: &GetCommand) -> CommandResult {
+    let path = AsonPath::parse(&command.path).map_err(|e| e.to_string())?;
+    let source = read_file(&command.file)?;
+    let Some(node) = parse_document(&command.file, &source) else {
+        return Ok(false);
+    };
+
+    match path.find(&node) {
+        Some(value) => {
+            println!("{}", print_to_string(value));
+            Ok(true)
+        }
+        None => {
+            eprintln!("{}: value not found.", path);
+            Ok(false)
+        }
+    }
+}
+
+fn run_diff(command: &DiffCommand) -> CommandResult {
+    if command.left == STDIN_FILE_NAME && command.right == STDIN_FILE_NAME {
+        return Err("Only one of the documents can be read from stdin.".to_owned());
+    }
+
+    let left_source = read_file(&command.left)?;
+    let right_source = read_file(&command.right)?;
+
+    let (Some(left), Some(right)) = (
+        parse_document(&command.left, &left_source),
+        parse_document(&command.right, &right_source),
+    ) else {
+        return Err("Can not compare invalid documents.".to_owned());
+    };
+
+    // `==` can not be used since `NaN` is not equal to itself.
+    let changes = diff::diff(&left, &right);
+    if changes.is_empty() {
+        return Ok(true);
+    }
+
+    println!("--- {}", display_name(&command.left));
+    println!("+++ {}", display_name(&command.right));
+    print!("{}", changes);
+
+    Ok(false)
+}
+
+fn run_merge(command: &MergeCommand) -> CommandResult {
+    let mut merger = Merger::new();
+    if command.append_lists {
+        merger.set_list_strategy(MergeStrategy::Append);
+    }
+
+    for file in files_or_stdin(&command.files) {
+        let source = read_file(&file)?;
+        let Some(node) = parse_document(&file, &source) else {
+            return Err("Can not merge invalid documents.".to_owned());
+        };
+        merger.add_layer(display_name(&file), node);
+    }
+
+    for expression in &command.set {
+        let item = Override::parse(expression).map_err(|e| e.to_string())?;
+        merger
+            .add_override(&format!("--set {}", expression), &item)
+            .map_err(|e| e.to_string())?;
+    }
+
+    let (node, provenance) = merger.finish();
+    if command.show_sources {
+        eprint!("{}", provenance);
+    }
+
+    let mut stdout = io::stdout();
+    print_to_writer(&mut stdout, &node).map_err(|e| e.to_string())?;
+    writeln!(stdout).map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+fn run_merge3(command: &Merge3Command) -> CommandResult {
+    let files = [&command.base, &command.ours, &command.theirs];
+    if files
+        .iter()
+        .filter(|file| **file == STDIN_FILE_NAME)
+        .count()
+        > 1
+    {
+        return Err("Only one of the documents can be read from stdin.".to_owned());
+    }
+
+    let mut nodes = vec![];
+    for file in files {
+        let source = read_file(file)?;
+        if !command.strip_comments && has_comments(&source) {
+            return Err(format!(
+                "{}: the comments would be lost by merging, use `--strip-comments` to merge it anyway.",
+                display_name(file)
+            ));
+        }
+
+        let Some(node) = parse_document(file, &source) else {
+            return Err("Can not merge invalid documents.".to_owned());
+        };
+        nodes.push(node);
+    }
+
+    let result = threeway::merge(&nodes[0], &nodes[1], &nodes[2]);
+    for conflict in &result.conflicts {
+        eprint!("{}", conflict);
+    }
+
+    let mut text = match command.resolve {
+        Some(Side(side)) => print_to_string(&result.resolve(side)),
+        None => result.to_string_with_markers(&command.ours_label, &command.theirs_label),
+    };
+    text.push('\n');
+
+    if command.in_place {
+        if command.ours == STDIN_FILE_NAME {
+            return Err("Can not write the result to stdin.".to_owned());
+        }
+        fs::write(&command.ours, text)
+            .map_err(|e| format!("Can not write file \"{}\": {}", command.ours, e))?;
+    } else {
+        io::stdout()
+            .write_all(text.as_bytes())
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(result.is_clean() || command.resolve.is_some())
+}
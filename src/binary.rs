@@ -0,0 +1,441 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+//! Compact binary encoding of the ASON data model.
+//!
+//! The encoding is self-describing, each value starts with a one byte tag:
+//!
+//! | Tag    | Value                    | Payload                                      |
+//! |--------|--------------------------|----------------------------------------------|
+//! | `0x01` | `false`                  |                                              |
+//! | `0x02` | `true`                   |                                              |
+//! | `0x10` | i8                       | 1 byte                                       |
+//! | `0x11` | u8                       | 1 byte                                       |
+//! | `0x12` | i16                      | 2 bytes, little-endian                       |
+//! | `0x13` | u16                      | 2 bytes, little-endian                       |
+//! | `0x14` | i32                      | 4 bytes, little-endian                       |
+//! | `0x15` | u32                      | 4 bytes, little-endian                       |
+//! | `0x16` | i64                      | 8 bytes, little-endian                       |
+//! | `0x17` | u64                      | 8 bytes, little-endian                       |
+//! | `0x18` | f32                      | 4 bytes, IEEE 754 bits, little-endian        |
+//! | `0x19` | f64                      | 8 bytes, IEEE 754 bits, little-endian        |
+//! | `0x20` | char                     | 4 bytes, Unicode scalar value                |
+//! | `0x21` | String                   | length (varint) + UTF-8 bytes                |
+//! | `0x22` | DateTime                 | i64 seconds + u32 nanoseconds + i32 offset seconds |
+//! | `0x23` | Hex byte data            | length (varint) + bytes                      |
+//! | `0x30` | List                     | values + `0x00`                              |
+//! | `0x31` | Tuple                    | values + `0x00`                              |
+//! | `0x32` | Object                   | (String key, value) pairs + `0x00`           |
+//! | `0x33` | Map                      | (name, value) pairs + `0x00`                 |
+//! | `0x40` | Variant `T::M`           | type name + member name                      |
+//! | `0x41` | Variant `T::M(v)`        | type name + member name + value              |
+//! | `0x42` | Variant `T::M(a, b)`     | type name + member name + values + `0x00`    |
+//! | `0x43` | Variant `T::M{k: v}`     | type name + member name + (String key, value) pairs + `0x00` |
+//! | `0x7f` | Error placeholder        |                                              |
+//!
+//! The type name and member name of variants are written as length (varint)
+//! followed by UTF-8 bytes, without tag. The varint is unsigned LEB128.
+//!
+//! Collections are terminated by the `0x00` byte instead of being prefixed
+//! with the number of elements, so values can be written out as they are
+//! being produced.
+//!
+//! The error placeholder only exists in the partial trees produced by the
+//! parser with error recovery, it can not be deserialized to Rust values.
+//!
+//! To send multiple values through a byte stream, check out the
+//! `FrameWriter` and `FrameReader`, which prefix each encoded value with
+//! its length.
+//!
+//! Like the text parser, nothing is limited by default. The decoding is
+//! recursive, so for the untrusted data, pass the limits (e.g. a nesting
+//! depth of `Limits::DEFAULT_MAX_DEPTH`) to `decode_with_options`,
+//! `from_slice_with_options` or `FrameReader::with_options` to keep the stack
+//! from overflowing. The sizes and lengths are counted in bytes. Only
+//! the limits of `ParseOptions` apply, since the binary format has
+//! no placeholders or anchors.
+
+pub mod de;
+mod frame;
+mod node;
+pub mod ser;
+
+use std::io::Write;
+
+use chrono::{DateTime, FixedOffset};
+
+use crate::{AsonError, ErrorKind, Limits};
+
+pub use de::{from_reader, from_slice, from_slice_with_options};
+pub use frame::{FrameReader, FrameWriter};
+pub use node::{decode, decode_with_options, encode};
+pub use ser::{to_vec, to_writer};
+
+pub type Result<T> = std::result::Result<T, AsonError>;
+
+const TAG_END: u8 = 0x00;
+const TAG_FALSE: u8 = 0x01;
+const TAG_TRUE: u8 = 0x02;
+const TAG_I8: u8 = 0x10;
+const TAG_U8: u8 = 0x11;
+const TAG_I16: u8 = 0x12;
+const TAG_U16: u8 = 0x13;
+const TAG_I32: u8 = 0x14;
+const TAG_U32: u8 = 0x15;
+const TAG_I64: u8 = 0x16;
+const TAG_U64: u8 = 0x17;
+const TAG_F32: u8 = 0x18;
+const TAG_F64: u8 = 0x19;
+const TAG_CHAR: u8 = 0x20;
+const TAG_STRING: u8 = 0x21;
+const TAG_DATETIME: u8 = 0x22;
+const TAG_BYTES: u8 = 0x23;
+const TAG_LIST: u8 = 0x30;
+const TAG_TUPLE: u8 = 0x31;
+const TAG_OBJECT: u8 = 0x32;
+const TAG_MAP: u8 = 0x33;
+const TAG_VARIANT: u8 = 0x40;
+const TAG_VARIANT_WITH_VALUE: u8 = 0x41;
+const TAG_TUPLE_VARIANT: u8 = 0x42;
+const TAG_STRUCT_VARIANT: u8 = 0x43;
+const TAG_ERROR: u8 = 0x7f;
+
+// the low-level writing functions shared by the serializer and the node encoder.
+struct BinaryWriter<'a, W>
+where
+    W: Write,
+{
+    writer: &'a mut W,
+}
+
+impl<'a, W> BinaryWriter<'a, W>
+where
+    W: Write,
+{
+    fn new(writer: &'a mut W) -> Self {
+        Self { writer }
+    }
+
+    fn write(&mut self, data: &[u8]) -> Result<()> {
+        self.writer.write_all(data).map_err(AsonError::from)
+    }
+
+    fn write_tag(&mut self, tag: u8) -> Result<()> {
+        self.write(&[tag])
+    }
+
+    fn write_varint(&mut self, mut value: u64) -> Result<()> {
+        let mut buf = [0u8; 10];
+        let mut length = 0;
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                buf[length] = byte;
+                length += 1;
+                break;
+            }
+            buf[length] = byte | 0x80;
+            length += 1;
+        }
+        self.write(&buf[..length])
+    }
+
+    // write the length and the content, without tag.
+    fn write_length_prefixed(&mut self, data: &[u8]) -> Result<()> {
+        self.write_varint(data.len() as u64)?;
+        self.write(data)
+    }
+
+    fn write_string(&mut self, s: &str) -> Result<()> {
+        self.write_tag(TAG_STRING)?;
+        self.write_length_prefixed(s.as_bytes())
+    }
+
+    fn write_bytes(&mut self, data: &[u8]) -> Result<()> {
+        self.write_tag(TAG_BYTES)?;
+        self.write_length_prefixed(data)
+    }
+
+    fn write_char(&mut self, c: char) -> Result<()> {
+        self.write_tag(TAG_CHAR)?;
+        self.write(&(c as u32).to_le_bytes())
+    }
+
+    fn write_datetime(&mut self, d: &DateTime<FixedOffset>) -> Result<()> {
+        self.write_tag(TAG_DATETIME)?;
+        self.write(&d.timestamp().to_le_bytes())?;
+        self.write(&d.timestamp_subsec_nanos().to_le_bytes())?;
+        self.write(&d.offset().local_minus_utc().to_le_bytes())
+    }
+
+    // write the tag and the names of variant.
+    fn write_variant_header(&mut self, tag: u8, type_name: &str, member_name: &str) -> Result<()> {
+        self.write_tag(tag)?;
+        self.write_length_prefixed(type_name.as_bytes())?;
+        self.write_length_prefixed(member_name.as_bytes())
+    }
+}
+
+// the low-level reading functions shared by the deserializer and the node decoder.
+struct BinaryReader<'de> {
+    data: &'de [u8],
+    offset: usize,
+    limits: Limits,
+
+    // the number of the collections and variants being read.
+    depth: usize,
+}
+
+impl<'de> BinaryReader<'de> {
+    fn new(data: &'de [u8], limits: Limits) -> Result<Self> {
+        if data.len() > limits.max_document_size {
+            return Err(AsonError::new(
+                ErrorKind::DocumentSizeLimitExceeded,
+                format!(
+                    "The size of data exceeds the limit {} bytes.",
+                    limits.max_document_size
+                ),
+            ));
+        }
+
+        Ok(Self {
+            data,
+            offset: 0,
+            limits,
+            depth: 0,
+        })
+    }
+
+    // enters a collection or variant, the tag should be consumed first.
+    fn enter(&mut self) -> Result<()> {
+        self.depth += 1;
+        if self.depth > self.limits.max_depth {
+            Err(AsonError::new(
+                ErrorKind::DepthLimitExceeded,
+                format!(
+                    "The nesting depth exceeds the limit {}. (at byte offset {})",
+                    self.limits.max_depth,
+                    self.offset - 1
+                ),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn leave(&mut self) {
+        self.depth -= 1;
+    }
+
+    // the `length` is the number of elements read so far, includes the current one.
+    fn check_container_length(&self, length: usize) -> Result<()> {
+        if length > self.limits.max_container_length {
+            Err(AsonError::new(
+                ErrorKind::ContainerLengthLimitExceeded,
+                format!(
+                    "The number of elements exceeds the limit {}. (at byte offset {})",
+                    self.limits.max_container_length, self.offset
+                ),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn is_end(&self) -> bool {
+        self.offset >= self.data.len()
+    }
+
+    fn new_error(&self, message: &str) -> AsonError {
+        AsonError::new(
+            ErrorKind::InvalidData,
+            format!("{} (at byte offset {})", message, self.offset),
+        )
+    }
+
+    // the data is valid, but it does not match the type being deserialized.
+    fn new_type_mismatch_error(&self, message: &str) -> AsonError {
+        AsonError::new(
+            ErrorKind::TypeMismatch,
+            format!("{} (at byte offset {})", message, self.offset),
+        )
+    }
+
+    fn peek_tag(&self) -> Result<u8> {
+        match self.data.get(self.offset) {
+            Some(tag) => Ok(*tag),
+            None => Err(AsonError::new_unexpected_end_of_document(
+                "Expect a value.".to_owned(),
+            )),
+        }
+    }
+
+    fn read(&mut self, length: usize) -> Result<&'de [u8]> {
+        if self.data.len() - self.offset < length {
+            return Err(AsonError::new_unexpected_end_of_document(format!(
+                "Expect {} more bytes.",
+                length
+            )));
+        }
+
+        let data = &self.data[self.offset..self.offset + length];
+        self.offset += length;
+        Ok(data)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.read(N)?.try_into().unwrap())
+    }
+
+    fn read_tag(&mut self) -> Result<u8> {
+        let tag = self.peek_tag()?;
+        self.offset += 1;
+        Ok(tag)
+    }
+
+    // consume the end mark if it exists.
+    fn consume_end_if_exist(&mut self) -> Result<bool> {
+        if self.peek_tag()? == TAG_END {
+            self.offset += 1;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn consume_end(&mut self) -> Result<()> {
+        if self.consume_end_if_exist()? {
+            Ok(())
+        } else {
+            Err(self.new_error("Expect the end of collection."))
+        }
+    }
+
+    fn read_varint(&mut self) -> Result<u64> {
+        let mut value: u64 = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.read_array::<1>()?[0];
+            if shift == 63 && byte > 1 {
+                return Err(self.new_error("Varint overflow."));
+            }
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+            shift += 7;
+            if shift > 63 {
+                return Err(self.new_error("Varint overflow."));
+            }
+        }
+    }
+
+    // read the length and the content, without tag.
+    fn read_length_prefixed(&mut self) -> Result<&'de [u8]> {
+        let start = self.offset;
+        let length = self.read_varint()?;
+        let length = usize::try_from(length).map_err(|_| self.new_error("Length is too large."))?;
+        if length > self.limits.max_string_length {
+            return Err(AsonError::new(
+                ErrorKind::StringLengthLimitExceeded,
+                format!(
+                    "The length of String or byte data exceeds the limit {}. (at byte offset {})",
+                    self.limits.max_string_length, start
+                ),
+            ));
+        }
+        self.read(length)
+    }
+
+    fn read_length_prefixed_str(&mut self) -> Result<&'de str> {
+        let start = self.offset;
+        let data = self.read_length_prefixed()?;
+        std::str::from_utf8(data).map_err(|_| {
+            AsonError::new(
+                ErrorKind::InvalidData,
+                format!("Invalid UTF-8 string. (at byte offset {})", start),
+            )
+        })
+    }
+
+    // read the payload of char, the tag should be consumed first.
+    fn read_char_payload(&mut self) -> Result<char> {
+        let value = u32::from_le_bytes(self.read_array()?);
+        char::from_u32(value).ok_or_else(|| self.new_error("Invalid char."))
+    }
+
+    // read the payload of datetime, the tag should be consumed first.
+    fn read_datetime_payload(&mut self) -> Result<DateTime<FixedOffset>> {
+        let seconds = i64::from_le_bytes(self.read_array()?);
+        let nanoseconds = u32::from_le_bytes(self.read_array()?);
+        let offset_seconds = i32::from_le_bytes(self.read_array()?);
+
+        let offset = FixedOffset::east_opt(offset_seconds)
+            .ok_or_else(|| self.new_error("Invalid timezone offset."))?;
+
+        DateTime::from_timestamp(seconds, nanoseconds)
+            .map(|d| d.with_timezone(&offset))
+            .ok_or_else(|| self.new_error("Invalid datetime."))
+    }
+
+    // read the names of variant, the tag should be consumed first.
+    fn read_variant_names(&mut self) -> Result<(&'de str, &'de str)> {
+        let type_name = self.read_length_prefixed_str()?;
+        let member_name = self.read_length_prefixed_str()?;
+        Ok((type_name, member_name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::Limits;
+
+    use super::{BinaryReader, BinaryWriter};
+
+    #[test]
+    fn test_varint() {
+        let values = [
+            0u64,
+            1,
+            127,
+            128,
+            300,
+            16383,
+            16384,
+            u32::MAX as u64,
+            u64::MAX,
+        ];
+
+        for value in values {
+            let mut buf: Vec<u8> = vec![];
+            BinaryWriter::new(&mut buf).write_varint(value).unwrap();
+
+            let mut reader = BinaryReader::new(&buf, Limits::default()).unwrap();
+            assert_eq!(reader.read_varint().unwrap(), value);
+            assert!(reader.is_end());
+        }
+
+        let mut buf: Vec<u8> = vec![];
+        BinaryWriter::new(&mut buf).write_varint(300).unwrap();
+        assert_eq!(buf, vec![0xac, 0x02]);
+
+        // overflow
+        let data = [0xffu8; 11];
+        assert!(BinaryReader::new(&data, Limits::default())
+            .unwrap()
+            .read_varint()
+            .is_err());
+
+        // incomplete
+        assert!(BinaryReader::new(&[0x80], Limits::default())
+            .unwrap()
+            .read_varint()
+            .is_err());
+    }
+}
@@ -0,0 +1,806 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+use std::io::Read;
+
+use serde::de::{
+    self,
+    value::{BorrowedStrDeserializer, StringDeserializer},
+    DeserializeSeed, EnumAccess, MapAccess, SeqAccess, VariantAccess,
+};
+
+use crate::{options::ParseOptions, serde::spanned::SPANNED_STRUCT_NAME, AsonError, ErrorKind};
+
+use super::{
+    BinaryReader, Result, TAG_BYTES, TAG_CHAR, TAG_DATETIME, TAG_F32, TAG_F64, TAG_FALSE, TAG_I16,
+    TAG_I32, TAG_I64, TAG_I8, TAG_LIST, TAG_MAP, TAG_OBJECT, TAG_STRING, TAG_STRUCT_VARIANT,
+    TAG_TRUE, TAG_TUPLE, TAG_TUPLE_VARIANT, TAG_U16, TAG_U32, TAG_U64, TAG_U8, TAG_VARIANT,
+    TAG_VARIANT_WITH_VALUE,
+};
+
+pub fn from_slice<'de, T>(data: &'de [u8]) -> Result<T>
+where
+    T: de::Deserialize<'de>,
+{
+    from_slice_with_options(data, &ParseOptions::default())
+}
+
+/// Only the limits of options apply to the binary format.
+pub fn from_slice_with_options<'de, T>(data: &'de [u8], options: &ParseOptions) -> Result<T>
+where
+    T: de::Deserialize<'de>,
+{
+    let mut deserializer = Deserializer::from_slice_with_options(data, options)?;
+    let value = T::deserialize(&mut deserializer)?;
+
+    if deserializer.reader.is_end() {
+        Ok(value)
+    } else {
+        Err(deserializer
+            .reader
+            .new_error("Document has more than one node."))
+    }
+}
+
+pub fn from_reader<T, R: Read>(mut r: R) -> Result<T>
+where
+    T: de::DeserializeOwned,
+{
+    let mut data: Vec<u8> = vec![];
+    r.read_to_end(&mut data)?;
+    from_slice(&data)
+}
+
+pub struct Deserializer<'de> {
+    reader: BinaryReader<'de>,
+}
+
+impl<'de> Deserializer<'de> {
+    pub fn from_slice(data: &'de [u8]) -> Result<Self> {
+        Self::from_slice_with_options(data, &ParseOptions::default())
+    }
+
+    pub fn from_slice_with_options(data: &'de [u8], options: &ParseOptions) -> Result<Self> {
+        Ok(Self {
+            reader: BinaryReader::new(data, options.limits)?,
+        })
+    }
+
+    // skip a complete value, includes all its children.
+    fn skip_value(&mut self) -> Result<()> {
+        let tag = self.reader.read_tag()?;
+        match tag {
+            TAG_FALSE | TAG_TRUE => {}
+            TAG_I8 | TAG_U8 => {
+                self.reader.read(1)?;
+            }
+            TAG_I16 | TAG_U16 => {
+                self.reader.read(2)?;
+            }
+            TAG_I32 | TAG_U32 | TAG_F32 | TAG_CHAR => {
+                self.reader.read(4)?;
+            }
+            TAG_I64 | TAG_U64 | TAG_F64 => {
+                self.reader.read(8)?;
+            }
+            TAG_DATETIME => {
+                self.reader.read(16)?;
+            }
+            TAG_STRING | TAG_BYTES => {
+                self.reader.read_length_prefixed()?;
+            }
+            TAG_LIST | TAG_TUPLE | TAG_OBJECT | TAG_MAP => {
+                self.skip_items()?;
+            }
+            TAG_VARIANT => {
+                self.reader.read_variant_names()?;
+            }
+            TAG_VARIANT_WITH_VALUE => {
+                self.reader.read_variant_names()?;
+                self.reader.enter()?;
+                self.skip_value()?;
+                self.reader.leave();
+            }
+            TAG_TUPLE_VARIANT | TAG_STRUCT_VARIANT => {
+                self.reader.read_variant_names()?;
+                self.skip_items()?;
+            }
+            _ => {
+                return Err(self.unexpected_tag_error(tag));
+            }
+        }
+        Ok(())
+    }
+
+    // skip the elements of collection until the end mark.
+    fn skip_items(&mut self) -> Result<()> {
+        self.reader.enter()?;
+        let mut count = 0;
+        while !self.reader.consume_end_if_exist()? {
+            count += 1;
+            self.reader.check_container_length(count)?;
+            self.skip_value()?;
+        }
+        self.reader.leave();
+        Ok(())
+    }
+
+    // deserialize the value of `Option::Some` and the variant with a single value.
+    fn deserialize_inner<T>(&mut self, seed: T) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.reader.enter()?;
+        let value = seed.deserialize(&mut *self)?;
+        self.reader.leave();
+        Ok(value)
+    }
+
+    fn visit_some<V>(&mut self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.reader.enter()?;
+        let value = visitor.visit_some(&mut *self)?;
+        self.reader.leave();
+        Ok(value)
+    }
+
+    fn unexpected_tag_error(&self, tag: u8) -> AsonError {
+        AsonError::new(
+            ErrorKind::TypeMismatch,
+            format!(
+                "Unexpected tag 0x{:02x}. (at byte offset {})",
+                tag,
+                self.reader.offset - 1
+            ),
+        )
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = AsonError;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let tag = self.reader.read_tag()?;
+        match tag {
+            TAG_FALSE => visitor.visit_bool(false),
+            TAG_TRUE => visitor.visit_bool(true),
+            TAG_I8 => visitor.visit_i8(i8::from_le_bytes(self.reader.read_array()?)),
+            TAG_U8 => visitor.visit_u8(u8::from_le_bytes(self.reader.read_array()?)),
+            TAG_I16 => visitor.visit_i16(i16::from_le_bytes(self.reader.read_array()?)),
+            TAG_U16 => visitor.visit_u16(u16::from_le_bytes(self.reader.read_array()?)),
+            TAG_I32 => visitor.visit_i32(i32::from_le_bytes(self.reader.read_array()?)),
+            TAG_U32 => visitor.visit_u32(u32::from_le_bytes(self.reader.read_array()?)),
+            TAG_I64 => visitor.visit_i64(i64::from_le_bytes(self.reader.read_array()?)),
+            TAG_U64 => visitor.visit_u64(u64::from_le_bytes(self.reader.read_array()?)),
+            TAG_F32 => visitor.visit_f32(f32::from_bits(u32::from_le_bytes(
+                self.reader.read_array()?,
+            ))),
+            TAG_F64 => visitor.visit_f64(f64::from_bits(u64::from_le_bytes(
+                self.reader.read_array()?,
+            ))),
+            TAG_CHAR => visitor.visit_char(self.reader.read_char_payload()?),
+            TAG_STRING => visitor.visit_borrowed_str(self.reader.read_length_prefixed_str()?),
+            TAG_DATETIME => visitor.visit_string(self.reader.read_datetime_payload()?.to_rfc3339()),
+            TAG_BYTES => visitor.visit_borrowed_bytes(self.reader.read_length_prefixed()?),
+            TAG_LIST | TAG_TUPLE => {
+                let mut accessor = CollectionAccessor::new(self)?;
+                let value = visitor.visit_seq(&mut accessor)?;
+                accessor.finish()?;
+                Ok(value)
+            }
+            TAG_OBJECT | TAG_MAP => {
+                let mut accessor = CollectionAccessor::new(self)?;
+                let value = visitor.visit_map(&mut accessor)?;
+                accessor.finish()?;
+                Ok(value)
+            }
+            TAG_VARIANT | TAG_VARIANT_WITH_VALUE | TAG_TUPLE_VARIANT | TAG_STRUCT_VARIANT => {
+                let (type_name, member_name) = self.reader.read_variant_names()?;
+
+                match (type_name, member_name, tag) {
+                    ("Option", "None", TAG_VARIANT) => visitor.visit_none(),
+                    ("Option", "Some", TAG_VARIANT_WITH_VALUE) => self.visit_some(visitor),
+                    _ => visitor.visit_enum(VariantAccessor::new(self, tag, member_name)),
+                }
+            }
+            _ => Err(self.unexpected_tag_error(tag)),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string
+        bytes byte_buf seq tuple map struct identifier
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let tag = self.reader.read_tag()?;
+        if tag != TAG_VARIANT && tag != TAG_VARIANT_WITH_VALUE {
+            return Err(AsonError::new(
+                ErrorKind::TypeMismatch,
+                format!(
+                    "Expect the \"Option\" type of variant. (at byte offset {})",
+                    self.reader.offset - 1
+                ),
+            ));
+        }
+
+        match self.reader.read_variant_names()? {
+            ("Option", "None") if tag == TAG_VARIANT => visitor.visit_none(),
+            ("Option", "Some") if tag == TAG_VARIANT_WITH_VALUE => self.visit_some(visitor),
+            ("Option", _) => Err(self
+                .reader
+                .new_type_mismatch_error("Invalid member of variant \"Option\".")),
+            _ => Err(self
+                .reader
+                .new_type_mismatch_error("Expect the \"Option\" type of variant.")),
+        }
+    }
+
+    fn deserialize_unit<V>(self, _visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        Err(AsonError::new(
+            ErrorKind::Unsupported,
+            "Does not support Unit.".to_owned(),
+        ))
+    }
+
+    fn deserialize_unit_struct<V>(self, _name: &'static str, _visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        Err(AsonError::new(
+            ErrorKind::Unsupported,
+            "Does not support \"Unit\" style Struct.".to_owned(),
+        ))
+    }
+
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        // `Spanned<T>` is read as `T`, the binary format has no locations.
+        if name == SPANNED_STRUCT_NAME {
+            return visitor.visit_newtype_struct(self);
+        }
+
+        Err(AsonError::new(
+            ErrorKind::Unsupported,
+            "Does not support \"New-Type\" style Struct.".to_owned(),
+        ))
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        _visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        Err(AsonError::new(
+            ErrorKind::Unsupported,
+            "Does not support \"Tuple\" style Struct.".to_owned(),
+        ))
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let tag = self.reader.read_tag()?;
+        match tag {
+            TAG_DATETIME if name == "Date" => {
+                // the datetime value of `AsonNode` can be deserialized into
+                // `ason::Date`, the same as the text format.
+                let d = self.reader.read_datetime_payload()?;
+                visitor.visit_enum(DateAccessor {
+                    value: d.to_rfc3339(),
+                })
+            }
+            TAG_VARIANT | TAG_VARIANT_WITH_VALUE | TAG_TUPLE_VARIANT | TAG_STRUCT_VARIANT => {
+                let (type_name, member_name) = self.reader.read_variant_names()?;
+                if type_name == name {
+                    visitor.visit_enum(VariantAccessor::new(self, tag, member_name))
+                } else {
+                    Err(self.reader.new_type_mismatch_error(&format!(
+                        "Expect the type \"{}\" of variant.",
+                        name
+                    )))
+                }
+            }
+            _ => Err(AsonError::new(
+                ErrorKind::TypeMismatch,
+                format!(
+                    "Expect a \"Variant\". (at byte offset {})",
+                    self.reader.offset - 1
+                ),
+            )),
+        }
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.skip_value()?;
+        visitor.visit_unit()
+    }
+}
+
+// the accessor for List, Tuple, Object and Map, as well as
+// the values of tuple variant and struct variant.
+struct CollectionAccessor<'a, 'de: 'a> {
+    de: &'a mut Deserializer<'de>,
+    is_end: bool,
+    count: usize,
+}
+
+impl<'a, 'de> CollectionAccessor<'a, 'de> {
+    fn new(de: &'a mut Deserializer<'de>) -> Result<Self> {
+        de.reader.enter()?;
+        Ok(Self {
+            de,
+            is_end: false,
+            count: 0,
+        })
+    }
+
+    fn check_end(&mut self) -> Result<bool> {
+        if !self.is_end {
+            self.is_end = self.de.reader.consume_end_if_exist()?;
+            if !self.is_end {
+                self.count += 1;
+                self.de.reader.check_container_length(self.count)?;
+            }
+        }
+        Ok(self.is_end)
+    }
+
+    // consume the end mark if the visitor stops early,
+    // e.g. the target tuple has fewer members than the data.
+    fn finish(&mut self) -> Result<()> {
+        if !self.is_end {
+            self.de.reader.consume_end()?;
+        }
+        self.de.reader.leave();
+        Ok(())
+    }
+}
+
+impl<'de> SeqAccess<'de> for CollectionAccessor<'_, 'de> {
+    type Error = AsonError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        if self.check_end()? {
+            Ok(None)
+        } else {
+            seed.deserialize(&mut *self.de).map(Some)
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for CollectionAccessor<'_, 'de> {
+    type Error = AsonError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if self.check_end()? {
+            Ok(None)
+        } else {
+            seed.deserialize(&mut *self.de).map(Some)
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        seed.deserialize(&mut *self.de)
+    }
+}
+
+struct VariantAccessor<'a, 'de: 'a> {
+    de: &'a mut Deserializer<'de>,
+    tag: u8,
+    member_name: &'de str,
+}
+
+impl<'a, 'de> VariantAccessor<'a, 'de> {
+    fn new(de: &'a mut Deserializer<'de>, tag: u8, member_name: &'de str) -> Self {
+        Self {
+            de,
+            tag,
+            member_name,
+        }
+    }
+
+    fn new_mismatch_error(&self, expected: &str) -> AsonError {
+        self.de.reader.new_type_mismatch_error(&format!(
+            "Expect {} for the member \"{}\" of variant.",
+            expected, self.member_name
+        ))
+    }
+}
+
+impl<'de> EnumAccess<'de> for VariantAccessor<'_, 'de> {
+    type Error = AsonError;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant)>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let value =
+            seed.deserialize(BorrowedStrDeserializer::<AsonError>::new(self.member_name))?;
+        Ok((value, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for VariantAccessor<'_, 'de> {
+    type Error = AsonError;
+
+    fn unit_variant(self) -> Result<()> {
+        if self.tag == TAG_VARIANT {
+            Ok(())
+        } else {
+            Err(self.new_mismatch_error("no value"))
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        if self.tag == TAG_VARIANT_WITH_VALUE {
+            self.de.deserialize_inner(seed)
+        } else {
+            Err(self.new_mismatch_error("a single value"))
+        }
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        if self.tag == TAG_TUPLE_VARIANT {
+            let mut accessor = CollectionAccessor::new(self.de)?;
+            let value = visitor.visit_seq(&mut accessor)?;
+            accessor.finish()?;
+            Ok(value)
+        } else {
+            Err(self.new_mismatch_error("a tuple"))
+        }
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        if self.tag == TAG_STRUCT_VARIANT {
+            let mut accessor = CollectionAccessor::new(self.de)?;
+            let value = visitor.visit_map(&mut accessor)?;
+            accessor.finish()?;
+            Ok(value)
+        } else {
+            Err(self.new_mismatch_error("an object"))
+        }
+    }
+}
+
+// present a datetime value as `Date::Rfc3339("...")`.
+struct DateAccessor {
+    value: String,
+}
+
+impl<'de> EnumAccess<'de> for DateAccessor {
+    type Error = AsonError;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant)>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let value = seed.deserialize(BorrowedStrDeserializer::<AsonError>::new("Rfc3339"))?;
+        Ok((value, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for DateAccessor {
+    type Error = AsonError;
+
+    fn unit_variant(self) -> Result<()> {
+        Err(AsonError::new(
+            ErrorKind::TypeMismatch,
+            "Expect a datetime value.".to_owned(),
+        ))
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(StringDeserializer::<AsonError>::new(self.value))
+    }
+
+    fn tuple_variant<V>(self, _len: usize, _visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        Err(AsonError::new(
+            ErrorKind::TypeMismatch,
+            "Expect a datetime value.".to_owned(),
+        ))
+    }
+
+    fn struct_variant<V>(self, _fields: &'static [&'static str], _visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        Err(AsonError::new(
+            ErrorKind::TypeMismatch,
+            "Expect a datetime value.".to_owned(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use pretty_assertions::assert_eq;
+    use serde::{de::IgnoredAny, Deserialize, Serialize};
+    use serde_bytes::ByteBuf;
+
+    use crate::{
+        binary::{
+            de::{from_slice, from_slice_with_options},
+            ser::to_vec,
+        },
+        options::ParseOptions,
+        Date, ErrorKind, Limits,
+    };
+
+    #[test]
+    fn test_primitive_types() {
+        assert!(from_slice::<bool>(&to_vec(&true).unwrap()).unwrap());
+        assert_eq!(from_slice::<i8>(&to_vec(&-11_i8).unwrap()).unwrap(), -11);
+        assert_eq!(from_slice::<u16>(&to_vec(&13_u16).unwrap()).unwrap(), 13);
+        assert_eq!(from_slice::<i32>(&to_vec(&-17_i32).unwrap()).unwrap(), -17);
+        assert_eq!(
+            from_slice::<u64>(&to_vec(&u64::MAX).unwrap()).unwrap(),
+            u64::MAX
+        );
+        assert_eq!(
+            from_slice::<f32>(&to_vec(&3.5_f32).unwrap()).unwrap(),
+            3.5_f32
+        );
+        assert!(from_slice::<f64>(&to_vec(&f64::NAN).unwrap())
+            .unwrap()
+            .is_nan());
+        assert_eq!(from_slice::<char>(&to_vec(&'文').unwrap()).unwrap(), '文');
+        assert_eq!(
+            from_slice::<String>(&to_vec(&"abc文字🍒").unwrap()).unwrap(),
+            "abc文字🍒"
+        );
+
+        // borrowed string
+        let data = to_vec(&"foo").unwrap();
+        assert_eq!(from_slice::<&str>(&data).unwrap(), "foo");
+
+        assert_eq!(
+            from_slice::<ByteBuf>(&to_vec(&ByteBuf::from(vec![11u8, 13])).unwrap()).unwrap(),
+            ByteBuf::from(vec![11u8, 13])
+        );
+
+        // number types are converted by the visitor if it is in range
+        assert_eq!(from_slice::<u32>(&to_vec(&11_u8).unwrap()).unwrap(), 11);
+        assert!(from_slice::<u8>(&to_vec(&-1_i32).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_struct_and_enum() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Package {
+            name: String,
+            version: Option<String>,
+            dependencies: HashMap<String, Option<u32>>,
+            kind: Kind,
+            shapes: Vec<Shape>,
+            pair: (i32, char),
+            released: Option<Date>,
+        }
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        enum Kind {
+            Application,
+            Library,
+        }
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        enum Shape {
+            Circle(f64),
+            Line(i32, i32),
+            Rect { width: u8, height: u8 },
+        }
+
+        let package = Package {
+            name: "foo".to_owned(),
+            version: None,
+            dependencies: HashMap::from([("bar".to_owned(), Some(1)), ("baz".to_owned(), None)]),
+            kind: Kind::Library,
+            shapes: vec![
+                Shape::Circle(1.5),
+                Shape::Line(-1, 1),
+                Shape::Rect {
+                    width: 11,
+                    height: 13,
+                },
+            ],
+            pair: (123, 'a'),
+            released: Some(Date::from_rfc3339("2024-06-26T16:38:50+08:00").unwrap()),
+        };
+
+        let data = to_vec(&package).unwrap();
+        assert_eq!(from_slice::<Package>(&data).unwrap(), package);
+
+        // unknown fields are skipped
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct PackageName {
+            name: String,
+        }
+
+        assert_eq!(
+            from_slice::<PackageName>(&data).unwrap(),
+            PackageName {
+                name: "foo".to_owned()
+            }
+        );
+
+        // variant type mismatch
+        assert!(from_slice::<Kind>(&to_vec(&Shape::Circle(1.0)).unwrap()).is_err());
+
+        // variant member style mismatch
+        mod other {
+            use serde::Serialize;
+
+            #[derive(Serialize)]
+            pub enum Shape {
+                Circle(f64, f64),
+            }
+        }
+
+        let data = to_vec(&other::Shape::Circle(1.0, 2.0)).unwrap();
+        assert!(from_slice::<Shape>(&data).is_err());
+    }
+
+    #[test]
+    fn test_error() {
+        // incomplete
+        let data = to_vec(&vec![1, 2, 3]).unwrap();
+        assert!(from_slice::<Vec<i32>>(&data[..data.len() - 1]).is_err());
+
+        // more than one node
+        let mut data = to_vec(&1).unwrap();
+        data.extend(to_vec(&2).unwrap());
+        assert!(from_slice::<i32>(&data).is_err());
+
+        // the tuple has more elements than the target
+        let data = to_vec(&(1, 2, 3)).unwrap();
+        assert!(from_slice::<(i32, i32)>(&data).is_err());
+
+        // unknown tag
+        assert!(from_slice::<i32>(&[0xff]).is_err());
+
+        // invalid UTF-8
+        assert!(from_slice::<String>(&[0x21, 1, 0xff]).is_err());
+    }
+
+    #[test]
+    fn test_limits() {
+        #[derive(Deserialize, Debug)]
+        #[serde(untagged)]
+        #[allow(dead_code)]
+        enum Value {
+            Number(i32),
+            List(Vec<Value>),
+        }
+
+        let untrusted = ParseOptions::new().with_limits(Limits {
+            max_depth: Limits::DEFAULT_MAX_DEPTH,
+            ..Limits::default()
+        });
+
+        let data = vec![0x30; 1_000_000];
+        assert_eq!(
+            from_slice_with_options::<Value>(&data, &untrusted)
+                .unwrap_err()
+                .kind,
+            ErrorKind::DepthLimitExceeded
+        );
+        assert_eq!(
+            from_slice_with_options::<IgnoredAny>(&data, &untrusted)
+                .unwrap_err()
+                .kind,
+            ErrorKind::DepthLimitExceeded
+        );
+
+        // skip the ignored field
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct User {
+            id: i32,
+        }
+
+        let mut data = vec![0x32, 0x21, 3, b'f', b'o', b'o'];
+        data.resize(1_000_000, 0x30);
+        assert_eq!(
+            from_slice_with_options::<User>(&data, &untrusted)
+                .unwrap_err()
+                .kind,
+            ErrorKind::DepthLimitExceeded
+        );
+
+        let data = to_vec(&vec![Some(vec![1, 2, 3])]).unwrap();
+        let mut limits = Limits::unlimited();
+        limits.max_depth = 3;
+        assert_eq!(
+            from_slice_with_options::<Vec<Option<Vec<i32>>>>(
+                &data,
+                &ParseOptions::new().with_limits(limits)
+            )
+            .unwrap(),
+            vec![Some(vec![1, 2, 3])]
+        );
+
+        limits.max_depth = 2;
+        assert_eq!(
+            from_slice_with_options::<Vec<Option<Vec<i32>>>>(
+                &data,
+                &ParseOptions::new().with_limits(limits)
+            )
+            .unwrap_err()
+            .kind,
+            ErrorKind::DepthLimitExceeded
+        );
+
+        let mut limits = Limits::unlimited();
+        limits.max_container_length = 2;
+        assert_eq!(
+            from_slice_with_options::<Vec<Option<Vec<i32>>>>(
+                &data,
+                &ParseOptions::new().with_limits(limits)
+            )
+            .unwrap_err()
+            .kind,
+            ErrorKind::ContainerLengthLimitExceeded
+        );
+    }
+}
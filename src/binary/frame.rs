@@ -0,0 +1,277 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+//! Length-prefixed framing for streaming binary ASON values.
+//!
+//! Each frame consists of the length of the payload (u32, little-endian)
+//! followed by the payload, i.e. one encoded value.
+//!
+//! The limits of `FrameReader::with_options` apply to each value, and
+//! a frame longer than `max_document_size` is rejected before reading it.
+
+use std::io::{self, Read, Write};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{ast::AsonNode, options::ParseOptions, AsonError, ErrorKind};
+
+use super::{de::from_slice_with_options, node, ser::to_vec, Result};
+
+pub struct FrameWriter<W>
+where
+    W: Write,
+{
+    writer: W,
+}
+
+impl<W> FrameWriter<W>
+where
+    W: Write,
+{
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    pub fn write_frame(&mut self, payload: &[u8]) -> Result<()> {
+        let length = u32::try_from(payload.len()).map_err(|_| {
+            AsonError::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "The frame length {} exceeds the limit {}.",
+                    payload.len(),
+                    u32::MAX
+                ),
+            )
+        })?;
+
+        self.writer
+            .write_all(&length.to_le_bytes())
+            .and_then(|_| self.writer.write_all(payload))
+            .map_err(AsonError::from)
+    }
+
+    pub fn write_value<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        let payload = to_vec(value)?;
+        self.write_frame(&payload)
+    }
+
+    pub fn write_node(&mut self, node: &AsonNode) -> Result<()> {
+        let payload = node::encode(node);
+        self.write_frame(&payload)
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush().map_err(AsonError::from)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+pub struct FrameReader<'a, R>
+where
+    R: Read,
+{
+    reader: R,
+    options: ParseOptions<'a>,
+}
+
+impl<'a, R> FrameReader<'a, R>
+where
+    R: Read,
+{
+    pub fn new(reader: R) -> Self {
+        Self::with_options(reader, ParseOptions::default())
+    }
+
+    /// Only the limits of options apply to the binary format.
+    pub fn with_options(reader: R, options: ParseOptions<'a>) -> Self {
+        Self { reader, options }
+    }
+
+    /// Read the payload of the next frame.
+    ///
+    /// Returns `None` if the stream ends at the boundary of frames,
+    /// an error is returned if the stream ends in the middle of a frame.
+    pub fn read_frame(&mut self) -> Result<Option<Vec<u8>>> {
+        let mut length_bytes = [0u8; 4];
+        let mut read_count = 0;
+        while read_count < length_bytes.len() {
+            match self.reader.read(&mut length_bytes[read_count..]) {
+                Ok(0) if read_count == 0 => return Ok(None),
+                Ok(0) => {
+                    return Err(AsonError::new_unexpected_end_of_document(
+                        "Incomplete frame length.".to_owned(),
+                    ));
+                }
+                Ok(n) => read_count += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        let length = u32::from_le_bytes(length_bytes) as u64;
+        let max_length = self.options.limits.max_document_size;
+        if length > max_length as u64 {
+            return Err(AsonError::new(
+                ErrorKind::DocumentSizeLimitExceeded,
+                format!(
+                    "The frame length {} exceeds the limit {} bytes.",
+                    length, max_length
+                ),
+            ));
+        }
+
+        // the buffer grows along with the data actually read, rather than
+        // being allocated by the (untrusted) length up front.
+        let mut payload: Vec<u8> = vec![];
+        (&mut self.reader).take(length).read_to_end(&mut payload)?;
+
+        if (payload.len() as u64) < length {
+            return Err(AsonError::new_unexpected_end_of_document(format!(
+                "Incomplete frame, expect {} bytes, actual {} bytes.",
+                length,
+                payload.len()
+            )));
+        }
+
+        Ok(Some(payload))
+    }
+
+    pub fn read_value<T>(&mut self) -> Result<Option<T>>
+    where
+        T: DeserializeOwned,
+    {
+        match self.read_frame()? {
+            Some(payload) => from_slice_with_options(&payload, &self.options).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub fn read_node(&mut self) -> Result<Option<AsonNode>> {
+        match self.read_frame()? {
+            Some(payload) => node::decode_with_options(&payload, &self.options).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde::{Deserialize, Serialize};
+
+    use crate::{ast::AsonNode, options::ParseOptions, parser::parse_from_str, ErrorKind, Limits};
+
+    use super::{FrameReader, FrameWriter};
+
+    #[test]
+    fn test_write_and_read_frames() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Message {
+            id: u32,
+            body: String,
+        }
+
+        let mut writer = FrameWriter::new(vec![]);
+        writer
+            .write_value(&Message {
+                id: 1,
+                body: "foo".to_owned(),
+            })
+            .unwrap();
+        writer
+            .write_value(&Message {
+                id: 2,
+                body: "bar".to_owned(),
+            })
+            .unwrap();
+        writer
+            .write_node(&parse_from_str("(1, 'a')").unwrap())
+            .unwrap();
+        writer.write_frame(&[]).unwrap();
+
+        let data = writer.into_inner();
+        let mut reader = FrameReader::new(data.as_slice());
+
+        assert_eq!(
+            reader.read_value::<Message>().unwrap(),
+            Some(Message {
+                id: 1,
+                body: "foo".to_owned()
+            })
+        );
+        assert_eq!(
+            reader.read_value::<Message>().unwrap(),
+            Some(Message {
+                id: 2,
+                body: "bar".to_owned()
+            })
+        );
+        assert_eq!(
+            reader.read_node().unwrap(),
+            Some(parse_from_str("(1, 'a')").unwrap())
+        );
+        assert_eq!(reader.read_frame().unwrap(), Some(vec![]));
+        assert_eq!(reader.read_node().unwrap(), None::<AsonNode>);
+    }
+
+    #[test]
+    fn test_incomplete_frame() {
+        let mut writer = FrameWriter::new(vec![]);
+        writer.write_value(&123_u32).unwrap();
+        let data = writer.into_inner();
+
+        // incomplete length
+        let mut reader = FrameReader::new(&data[..2]);
+        assert!(reader.read_frame().is_err());
+
+        // incomplete payload
+        let mut reader = FrameReader::new(&data[..data.len() - 1]);
+        assert!(reader.read_frame().is_err());
+
+        // the length is large but the data is short
+        let mut reader = FrameReader::new(&[0xff, 0xff, 0xff, 0xff, 0x01][..]);
+        assert!(reader.read_frame().is_err());
+    }
+
+    #[test]
+    fn test_read_with_limits() {
+        let mut writer = FrameWriter::new(vec![]);
+        writer
+            .write_node(&parse_from_str("[[1]]").unwrap())
+            .unwrap();
+        writer.write_value(&"foo bar baz").unwrap();
+        let data = writer.into_inner();
+
+        let mut reader = FrameReader::with_options(
+            data.as_slice(),
+            ParseOptions::new().with_limits(Limits {
+                max_depth: 1,
+                max_document_size: 10,
+                ..Limits::default()
+            }),
+        );
+        assert_eq!(
+            reader.read_node().unwrap_err().kind,
+            ErrorKind::DepthLimitExceeded
+        );
+
+        // the frame is rejected before reading the payload
+        assert_eq!(
+            reader.read_value::<String>().unwrap_err().kind,
+            ErrorKind::DocumentSizeLimitExceeded
+        );
+    }
+}
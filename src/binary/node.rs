@@ -0,0 +1,455 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+use std::io::Write;
+
+use crate::{
+    ast::{AsonNode, KeyValuePair, NameValuePair, Number, Variant, VariantValue},
+    options::ParseOptions,
+    AsonError, ErrorKind,
+};
+
+use super::{
+    BinaryReader, BinaryWriter, Result, TAG_BYTES, TAG_CHAR, TAG_DATETIME, TAG_END, TAG_ERROR,
+    TAG_F32, TAG_F64, TAG_FALSE, TAG_I16, TAG_I32, TAG_I64, TAG_I8, TAG_LIST, TAG_MAP, TAG_OBJECT,
+    TAG_STRING, TAG_STRUCT_VARIANT, TAG_TRUE, TAG_TUPLE, TAG_TUPLE_VARIANT, TAG_U16, TAG_U32,
+    TAG_U64, TAG_U8, TAG_VARIANT, TAG_VARIANT_WITH_VALUE,
+};
+
+pub fn encode(node: &AsonNode) -> Vec<u8> {
+    let mut buf: Vec<u8> = vec![];
+    // writing to `Vec` never fails.
+    write_node(&mut BinaryWriter::new(&mut buf), node).unwrap();
+    buf
+}
+
+pub fn decode(data: &[u8]) -> Result<AsonNode> {
+    decode_with_options(data, &ParseOptions::default())
+}
+
+/// Only the limits of options apply to the binary format.
+pub fn decode_with_options(data: &[u8], options: &ParseOptions) -> Result<AsonNode> {
+    let mut reader = BinaryReader::new(data, options.limits)?;
+    let node = read_node(&mut reader)?;
+
+    if reader.is_end() {
+        Ok(node)
+    } else {
+        Err(reader.new_error("Document has more than one node."))
+    }
+}
+
+fn write_node<W: Write>(writer: &mut BinaryWriter<W>, node: &AsonNode) -> Result<()> {
+    match node {
+        AsonNode::Number(number) => write_number(writer, number),
+        AsonNode::Boolean(b) => writer.write_tag(if *b { TAG_TRUE } else { TAG_FALSE }),
+        AsonNode::Char(c) => writer.write_char(*c),
+        AsonNode::String(s) => writer.write_string(s),
+        AsonNode::DateTime(d) => writer.write_datetime(d),
+        AsonNode::Variant(variant) => write_variant(writer, variant),
+        AsonNode::HexByteData(data) => writer.write_bytes(data),
+        AsonNode::List(items) => {
+            writer.write_tag(TAG_LIST)?;
+            write_items(writer, items)
+        }
+        AsonNode::Tuple(items) => {
+            writer.write_tag(TAG_TUPLE)?;
+            write_items(writer, items)
+        }
+        AsonNode::Object(kvps) => {
+            writer.write_tag(TAG_OBJECT)?;
+            write_key_value_pairs(writer, kvps)
+        }
+        AsonNode::Map(nvps) => {
+            writer.write_tag(TAG_MAP)?;
+            for nvp in nvps {
+                write_node(writer, &nvp.name)?;
+                write_node(writer, &nvp.value)?;
+            }
+            writer.write_tag(TAG_END)
+        }
+        AsonNode::Error => writer.write_tag(TAG_ERROR),
+    }
+}
+
+fn write_number<W: Write>(writer: &mut BinaryWriter<W>, number: &Number) -> Result<()> {
+    match number {
+        Number::I8(v) => {
+            writer.write_tag(TAG_I8)?;
+            writer.write(&v.to_le_bytes())
+        }
+        Number::U8(v) => {
+            writer.write_tag(TAG_U8)?;
+            writer.write(&v.to_le_bytes())
+        }
+        Number::I16(v) => {
+            writer.write_tag(TAG_I16)?;
+            writer.write(&v.to_le_bytes())
+        }
+        Number::U16(v) => {
+            writer.write_tag(TAG_U16)?;
+            writer.write(&v.to_le_bytes())
+        }
+        Number::I32(v) => {
+            writer.write_tag(TAG_I32)?;
+            writer.write(&v.to_le_bytes())
+        }
+        Number::U32(v) => {
+            writer.write_tag(TAG_U32)?;
+            writer.write(&v.to_le_bytes())
+        }
+        Number::I64(v) => {
+            writer.write_tag(TAG_I64)?;
+            writer.write(&v.to_le_bytes())
+        }
+        Number::U64(v) => {
+            writer.write_tag(TAG_U64)?;
+            writer.write(&v.to_le_bytes())
+        }
+        Number::F32(v) => {
+            writer.write_tag(TAG_F32)?;
+            writer.write(&v.to_bits().to_le_bytes())
+        }
+        Number::F64(v) => {
+            writer.write_tag(TAG_F64)?;
+            writer.write(&v.to_bits().to_le_bytes())
+        }
+    }
+}
+
+fn write_items<W: Write>(writer: &mut BinaryWriter<W>, items: &[AsonNode]) -> Result<()> {
+    for item in items {
+        write_node(writer, item)?;
+    }
+    writer.write_tag(TAG_END)
+}
+
+fn write_key_value_pairs<W: Write>(
+    writer: &mut BinaryWriter<W>,
+    kvps: &[KeyValuePair],
+) -> Result<()> {
+    for kvp in kvps {
+        writer.write_string(&kvp.key)?;
+        write_node(writer, &kvp.value)?;
+    }
+    writer.write_tag(TAG_END)
+}
+
+fn write_variant<W: Write>(writer: &mut BinaryWriter<W>, variant: &Variant) -> Result<()> {
+    let type_name = &variant.type_name;
+    let member_name = &variant.member_name;
+
+    match &variant.value {
+        VariantValue::Empty => writer.write_variant_header(TAG_VARIANT, type_name, member_name),
+        VariantValue::Value(value) => {
+            writer.write_variant_header(TAG_VARIANT_WITH_VALUE, type_name, member_name)?;
+            write_node(writer, value)
+        }
+        VariantValue::Tuple(items) => {
+            writer.write_variant_header(TAG_TUPLE_VARIANT, type_name, member_name)?;
+            write_items(writer, items)
+        }
+        VariantValue::Object(kvps) => {
+            writer.write_variant_header(TAG_STRUCT_VARIANT, type_name, member_name)?;
+            write_key_value_pairs(writer, kvps)
+        }
+    }
+}
+
+fn read_node(reader: &mut BinaryReader) -> Result<AsonNode> {
+    let tag = reader.read_tag()?;
+
+    let node = match tag {
+        TAG_FALSE => AsonNode::Boolean(false),
+        TAG_TRUE => AsonNode::Boolean(true),
+        TAG_ERROR => AsonNode::Error,
+        TAG_I8 => AsonNode::Number(Number::I8(i8::from_le_bytes(reader.read_array()?))),
+        TAG_U8 => AsonNode::Number(Number::U8(u8::from_le_bytes(reader.read_array()?))),
+        TAG_I16 => AsonNode::Number(Number::I16(i16::from_le_bytes(reader.read_array()?))),
+        TAG_U16 => AsonNode::Number(Number::U16(u16::from_le_bytes(reader.read_array()?))),
+        TAG_I32 => AsonNode::Number(Number::I32(i32::from_le_bytes(reader.read_array()?))),
+        TAG_U32 => AsonNode::Number(Number::U32(u32::from_le_bytes(reader.read_array()?))),
+        TAG_I64 => AsonNode::Number(Number::I64(i64::from_le_bytes(reader.read_array()?))),
+        TAG_U64 => AsonNode::Number(Number::U64(u64::from_le_bytes(reader.read_array()?))),
+        TAG_F32 => AsonNode::Number(Number::F32(f32::from_bits(u32::from_le_bytes(
+            reader.read_array()?,
+        )))),
+        TAG_F64 => AsonNode::Number(Number::F64(f64::from_bits(u64::from_le_bytes(
+            reader.read_array()?,
+        )))),
+        TAG_CHAR => AsonNode::Char(reader.read_char_payload()?),
+        TAG_STRING => AsonNode::String(reader.read_length_prefixed_str()?.to_owned()),
+        TAG_DATETIME => AsonNode::DateTime(reader.read_datetime_payload()?),
+        TAG_BYTES => AsonNode::HexByteData(reader.read_length_prefixed()?.to_vec()),
+        TAG_LIST => AsonNode::List(read_items(reader)?),
+        TAG_TUPLE => AsonNode::Tuple(read_items(reader)?),
+        TAG_OBJECT => AsonNode::Object(read_key_value_pairs(reader)?),
+        TAG_MAP => {
+            reader.enter()?;
+            let mut nvps = vec![];
+            while !reader.consume_end_if_exist()? {
+                reader.check_container_length(nvps.len() + 1)?;
+                let name = read_node(reader)?;
+                let value = read_node(reader)?;
+                nvps.push(NameValuePair {
+                    name: Box::new(name),
+                    value: Box::new(value),
+                });
+            }
+            reader.leave();
+            AsonNode::Map(nvps)
+        }
+        TAG_VARIANT | TAG_VARIANT_WITH_VALUE | TAG_TUPLE_VARIANT | TAG_STRUCT_VARIANT => {
+            let (type_name, member_name) = reader.read_variant_names()?;
+            let variant = match tag {
+                TAG_VARIANT => Variant::new(type_name, member_name),
+                TAG_VARIANT_WITH_VALUE => {
+                    reader.enter()?;
+                    let value = read_node(reader)?;
+                    reader.leave();
+                    Variant::with_value(type_name, member_name, value)
+                }
+                TAG_TUPLE_VARIANT => {
+                    Variant::with_tuple(type_name, member_name, read_items(reader)?)
+                }
+                _ => Variant::with_object(type_name, member_name, read_key_value_pairs(reader)?),
+            };
+            AsonNode::Variant(variant)
+        }
+        _ => {
+            return Err(AsonError::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Unexpected tag 0x{:02x}. (at byte offset {})",
+                    tag,
+                    reader.offset - 1
+                ),
+            ));
+        }
+    };
+
+    Ok(node)
+}
+
+// read the elements of collection, the tag should be consumed first.
+fn read_items(reader: &mut BinaryReader) -> Result<Vec<AsonNode>> {
+    reader.enter()?;
+    let mut items = vec![];
+    while !reader.consume_end_if_exist()? {
+        reader.check_container_length(items.len() + 1)?;
+        items.push(read_node(reader)?);
+    }
+    reader.leave();
+    Ok(items)
+}
+
+fn read_key_value_pairs(reader: &mut BinaryReader) -> Result<Vec<KeyValuePair>> {
+    reader.enter()?;
+    let mut kvps = vec![];
+    while !reader.consume_end_if_exist()? {
+        reader.check_container_length(kvps.len() + 1)?;
+        if reader.read_tag()? != TAG_STRING {
+            return Err(AsonError::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Expect a string for the key of object. (at byte offset {})",
+                    reader.offset - 1
+                ),
+            ));
+        }
+
+        let key = reader.read_length_prefixed_str()?;
+        let value = read_node(reader)?;
+        kvps.push(KeyValuePair::new(key, value));
+    }
+    reader.leave();
+    Ok(kvps)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use pretty_assertions::assert_eq;
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        ast::{AsonNode, Number},
+        binary::{de::from_slice, ser::to_vec},
+        options::ParseOptions,
+        parser::parse_from_str,
+        Date, ErrorKind, Limits,
+    };
+
+    use super::{decode, decode_with_options, encode};
+
+    #[test]
+    fn test_round_trip() {
+        let text = r#"{
+            integers: [11_i8, 13_u8, 17_i16, 19_u16, 23, 29_u32, 31_i64, 37_u64]
+            floats: [1.5_f32, NaN_f32, -Inf, 2.718281828459045, -0.0]
+            char: '文'
+            string: "abc文字🍒"
+            date: d"2024-03-17 10:01:11+08:00"
+            bytes: h"00 11 ff"
+            tuple: (1, "foo", true)
+            map: [1: 'a', 2: 'b']
+            variants: [
+                Option::None
+                Option::Some(123)
+                Color::RGB(255_u8, 127_u8, 63_u8)
+                Shape::Rect{width: 11, height: 13}
+            ]
+            empty: [[], {}, []]
+        }"#;
+
+        let node = parse_from_str(text).unwrap();
+        let data = encode(&node);
+
+        // `NaN != NaN`, so compare the debug text instead.
+        assert_eq!(
+            format!("{:?}", decode(&data).unwrap()),
+            format!("{:?}", node)
+        );
+    }
+
+    #[test]
+    fn test_compatible_with_serde() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Record {
+            id: u32,
+            name: String,
+            tags: HashMap<String, i32>,
+            created: Date,
+            shape: Shape,
+        }
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        enum Shape {
+            Line(i32, i32),
+        }
+
+        let text = r#"{
+            id: 123_u32
+            name: "foo"
+            tags: ["bar": 11]
+            created: d"2024-06-26 16:38:50+08:00"
+            shape: Shape::Line(1, 2)
+        }"#;
+
+        let record = Record {
+            id: 123,
+            name: "foo".to_owned(),
+            tags: HashMap::from([("bar".to_owned(), 11)]),
+            created: Date::from_rfc3339("2024-06-26T16:38:50+08:00").unwrap(),
+            shape: Shape::Line(1, 2),
+        };
+
+        // node -> value
+        let data = encode(&parse_from_str(text).unwrap());
+        assert_eq!(from_slice::<Record>(&data).unwrap(), record);
+
+        // value -> node
+        let data = to_vec(&vec![1_u8, 2_u8]).unwrap();
+        assert_eq!(
+            decode(&data).unwrap(),
+            AsonNode::List(vec![
+                AsonNode::Number(Number::U8(1)),
+                AsonNode::Number(Number::U8(2))
+            ])
+        );
+    }
+
+    #[test]
+    fn test_decode_error() {
+        assert!(decode(&[]).is_err());
+        assert!(decode(&[0x30, 0x14, 1, 0, 0, 0]).is_err());
+        assert!(decode(&[0x32, 0x14, 1, 0, 0, 0, 0x02, 0x00]).is_err());
+        assert!(decode(&[0x20, 0x00, 0xd8, 0x00, 0x00]).is_err());
+        assert!(decode(&[0x02, 0x02]).is_err());
+    }
+
+    #[test]
+    fn test_decode_limits() {
+        let untrusted = ParseOptions::new().with_limits(Limits {
+            max_depth: Limits::DEFAULT_MAX_DEPTH,
+            ..Limits::default()
+        });
+
+        // the deeply nested lists
+        assert_eq!(
+            decode_with_options(&vec![0x30; 1_000_000], &untrusted)
+                .unwrap_err()
+                .kind,
+            ErrorKind::DepthLimitExceeded
+        );
+
+        // the deeply nested `Option::Some`
+        let mut data = vec![];
+        for _ in 0..65 {
+            data.extend([
+                0x41, 6, b'O', b'p', b't', b'i', b'o', b'n', 4, b'S', b'o', b'm', b'e',
+            ]);
+        }
+        data.extend([0x01]);
+        assert_eq!(
+            decode_with_options(&data, &untrusted).unwrap_err().kind,
+            ErrorKind::DepthLimitExceeded
+        );
+
+        // nothing is limited by default, the same as the text parser
+        assert!(decode(&data).is_ok());
+
+        let data = encode(&parse_from_str(r#"[[["foo"], [1, 2, 3]]]"#).unwrap());
+        let limits = |f: fn(&mut Limits)| {
+            let mut limits = Limits::unlimited();
+            f(&mut limits);
+            limits
+        };
+
+        assert!(decode_with_options(
+            &data,
+            &ParseOptions::new().with_limits(limits(|l| l.max_depth = 3))
+        )
+        .is_ok());
+        assert_eq!(
+            decode_with_options(
+                &data,
+                &ParseOptions::new().with_limits(limits(|l| l.max_depth = 2))
+            )
+            .unwrap_err()
+            .kind,
+            ErrorKind::DepthLimitExceeded
+        );
+        assert_eq!(
+            decode_with_options(
+                &data,
+                &ParseOptions::new().with_limits(limits(|l| l.max_string_length = 2))
+            )
+            .unwrap_err()
+            .kind,
+            ErrorKind::StringLengthLimitExceeded
+        );
+        assert_eq!(
+            decode_with_options(
+                &data,
+                &ParseOptions::new().with_limits(limits(|l| l.max_container_length = 2))
+            )
+            .unwrap_err()
+            .kind,
+            ErrorKind::ContainerLengthLimitExceeded
+        );
+        assert_eq!(
+            decode_with_options(
+                &data,
+                &ParseOptions::new().with_limits(limits(|l| l.max_document_size = 8))
+            )
+            .unwrap_err()
+            .kind,
+            ErrorKind::DocumentSizeLimitExceeded
+        );
+    }
+}
@@ -0,0 +1,479 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+use std::io::Write;
+
+use serde::{ser, Serialize};
+
+use crate::{AsonError, ErrorKind};
+
+use super::{
+    BinaryWriter, Result, TAG_END, TAG_F32, TAG_F64, TAG_FALSE, TAG_I16, TAG_I32, TAG_I64, TAG_I8,
+    TAG_LIST, TAG_MAP, TAG_OBJECT, TAG_STRUCT_VARIANT, TAG_TRUE, TAG_TUPLE, TAG_TUPLE_VARIANT,
+    TAG_U16, TAG_U32, TAG_U64, TAG_U8, TAG_VARIANT, TAG_VARIANT_WITH_VALUE,
+};
+
+pub fn to_vec<T>(value: &T) -> Result<Vec<u8>>
+where
+    T: Serialize,
+{
+    let mut buf: Vec<u8> = vec![];
+    to_writer(value, &mut buf)?;
+    Ok(buf)
+}
+
+pub fn to_writer<T, W: Write>(value: &T, writer: &mut W) -> Result<()>
+where
+    T: Serialize,
+{
+    let mut serializer = Serializer::new(writer);
+    value.serialize(&mut serializer)
+}
+
+pub struct Serializer<'a, W>
+where
+    W: Write,
+{
+    writer: BinaryWriter<'a, W>,
+}
+
+impl<'a, W> Serializer<'a, W>
+where
+    W: Write,
+{
+    pub fn new(writer: &'a mut W) -> Self {
+        Self {
+            writer: BinaryWriter::new(writer),
+        }
+    }
+}
+
+impl<W> ser::Serializer for &mut Serializer<'_, W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = AsonError;
+
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn serialize_bool(self, v: bool) -> Result<()> {
+        self.writer.write_tag(if v { TAG_TRUE } else { TAG_FALSE })
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<()> {
+        self.writer.write_tag(TAG_I8)?;
+        self.writer.write(&v.to_le_bytes())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<()> {
+        self.writer.write_tag(TAG_I16)?;
+        self.writer.write(&v.to_le_bytes())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<()> {
+        self.writer.write_tag(TAG_I32)?;
+        self.writer.write(&v.to_le_bytes())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<()> {
+        self.writer.write_tag(TAG_I64)?;
+        self.writer.write(&v.to_le_bytes())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<()> {
+        self.writer.write_tag(TAG_U8)?;
+        self.writer.write(&v.to_le_bytes())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<()> {
+        self.writer.write_tag(TAG_U16)?;
+        self.writer.write(&v.to_le_bytes())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<()> {
+        self.writer.write_tag(TAG_U32)?;
+        self.writer.write(&v.to_le_bytes())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<()> {
+        self.writer.write_tag(TAG_U64)?;
+        self.writer.write(&v.to_le_bytes())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<()> {
+        self.writer.write_tag(TAG_F32)?;
+        self.writer.write(&v.to_bits().to_le_bytes())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<()> {
+        self.writer.write_tag(TAG_F64)?;
+        self.writer.write(&v.to_bits().to_le_bytes())
+    }
+
+    fn serialize_char(self, v: char) -> Result<()> {
+        self.writer.write_char(v)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<()> {
+        self.writer.write_string(v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<()> {
+        self.writer.write_bytes(v)
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        self.writer
+            .write_variant_header(TAG_VARIANT, "Option", "None")
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.writer
+            .write_variant_header(TAG_VARIANT_WITH_VALUE, "Option", "Some")?;
+        value.serialize(&mut *self)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        Err(AsonError::new(
+            ErrorKind::Unsupported,
+            "Does not support Unit.".to_owned(),
+        ))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<()> {
+        Err(AsonError::new(
+            ErrorKind::Unsupported,
+            "Does not support \"Unit\" style Struct.".to_owned(),
+        ))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<()> {
+        self.writer.write_variant_header(TAG_VARIANT, name, variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, _value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(AsonError::new(
+            ErrorKind::Unsupported,
+            "Does not support \"New-Type\" style Struct.".to_owned(),
+        ))
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.writer
+            .write_variant_header(TAG_VARIANT_WITH_VALUE, name, variant)?;
+        value.serialize(&mut *self)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        self.writer.write_tag(TAG_LIST)?;
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        self.writer.write_tag(TAG_TUPLE)?;
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(AsonError::new(
+            ErrorKind::Unsupported,
+            "Does not support \"Tuple\" style Struct.".to_owned(),
+        ))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        self.writer
+            .write_variant_header(TAG_TUPLE_VARIANT, name, variant)?;
+        Ok(self)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        self.writer.write_tag(TAG_MAP)?;
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        self.writer.write_tag(TAG_OBJECT)?;
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        self.writer
+            .write_variant_header(TAG_STRUCT_VARIANT, name, variant)?;
+        Ok(self)
+    }
+}
+
+impl<W> ser::SerializeSeq for &mut Serializer<'_, W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = AsonError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        self.writer.write_tag(TAG_END)
+    }
+}
+
+impl<W> ser::SerializeTuple for &mut Serializer<'_, W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = AsonError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        self.writer.write_tag(TAG_END)
+    }
+}
+
+impl<W> ser::SerializeTupleStruct for &mut Serializer<'_, W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = AsonError;
+
+    fn serialize_field<T>(&mut self, _value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        unreachable!()
+    }
+
+    fn end(self) -> Result<()> {
+        unreachable!()
+    }
+}
+
+impl<W> ser::SerializeTupleVariant for &mut Serializer<'_, W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = AsonError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        self.writer.write_tag(TAG_END)
+    }
+}
+
+impl<W> ser::SerializeMap for &mut Serializer<'_, W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = AsonError;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        key.serialize(&mut **self)
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        self.writer.write_tag(TAG_END)
+    }
+}
+
+impl<W> ser::SerializeStruct for &mut Serializer<'_, W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = AsonError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.writer.write_string(key)?;
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        self.writer.write_tag(TAG_END)
+    }
+}
+
+impl<W> ser::SerializeStructVariant for &mut Serializer<'_, W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = AsonError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.writer.write_string(key)?;
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<()> {
+        self.writer.write_tag(TAG_END)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde::Serialize;
+
+    use crate::binary::ser::to_vec;
+
+    #[test]
+    fn test_primitive_types() {
+        assert_eq!(to_vec(&true).unwrap(), vec![0x02]);
+        assert_eq!(to_vec(&false).unwrap(), vec![0x01]);
+        assert_eq!(to_vec(&-1_i8).unwrap(), vec![0x10, 0xff]);
+        assert_eq!(to_vec(&0x1234_u16).unwrap(), vec![0x13, 0x34, 0x12]);
+        assert_eq!(to_vec(&1_i32).unwrap(), vec![0x14, 1, 0, 0, 0]);
+        assert_eq!(to_vec(&1_u64).unwrap(), vec![0x17, 1, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(to_vec(&1.0_f32).unwrap(), vec![0x18, 0, 0, 0x80, 0x3f]);
+        assert_eq!(to_vec(&'a').unwrap(), vec![0x20, 0x61, 0, 0, 0]);
+        assert_eq!(to_vec(&"abc").unwrap(), vec![0x21, 3, b'a', b'b', b'c']);
+        assert_eq!(
+            to_vec(&serde_bytes::ByteBuf::from(vec![11u8, 13])).unwrap(),
+            vec![0x23, 2, 11, 13]
+        );
+    }
+
+    #[test]
+    fn test_collections_and_variants() {
+        assert_eq!(
+            to_vec(&vec![1_u8, 2_u8]).unwrap(),
+            vec![0x30, 0x11, 1, 0x11, 2, 0x00]
+        );
+        assert_eq!(
+            to_vec(&(1_u8, 'a')).unwrap(),
+            vec![0x31, 0x11, 1, 0x20, 0x61, 0, 0, 0, 0x00]
+        );
+        assert_eq!(
+            to_vec(&Option::<u8>::None).unwrap(),
+            vec![0x40, 6, b'O', b'p', b't', b'i', b'o', b'n', 4, b'N', b'o', b'n', b'e']
+        );
+
+        #[derive(Serialize)]
+        struct Point {
+            x: u8,
+        }
+
+        assert_eq!(
+            to_vec(&Point { x: 1 }).unwrap(),
+            vec![0x32, 0x21, 1, b'x', 0x11, 1, 0x00]
+        );
+
+        #[derive(Serialize)]
+        #[allow(dead_code)]
+        enum Shape {
+            Dot,
+            Circle(u8),
+            Line(u8, u8),
+            Rect { w: u8 },
+        }
+
+        assert_eq!(
+            to_vec(&Shape::Dot).unwrap(),
+            vec![0x40, 5, b'S', b'h', b'a', b'p', b'e', 3, b'D', b'o', b't']
+        );
+        assert_eq!(
+            to_vec(&Shape::Line(1, 2)).unwrap(),
+            vec![
+                0x42, 5, b'S', b'h', b'a', b'p', b'e', 4, b'L', b'i', b'n', b'e', 0x11, 1, 0x11, 2,
+                0x00
+            ]
+        );
+        assert_eq!(
+            to_vec(&Shape::Rect { w: 1 }).unwrap(),
+            vec![
+                0x43, 5, b'S', b'h', b'a', b'p', b'e', 4, b'R', b'e', b'c', b't', 0x21, 1, b'w',
+                0x11, 1, 0x00
+            ]
+        );
+
+        // unit is not supported
+        assert!(to_vec(&()).is_err());
+    }
+}
@@ -27,12 +27,29 @@ pub struct CharsWithPositionIter<'a> {
 }
 
 impl<'a> CharsWithPositionIter<'a> {
-    pub fn new(/* unit: usize, */ upstream: &'a mut dyn Iterator<Item = char>) -> Self {
+    pub fn new(upstream: &'a mut dyn Iterator<Item = char>) -> Self {
         Self {
             upstream,
-            current_position: Location::new_position(/*unit,*/ 0, 0, 0),
+            current_position: Location::new_position(0, 0, 0),
         }
     }
+
+    // for the chars which do not start from the beginning of source text.
+    pub fn new_with_start_position(
+        upstream: &'a mut dyn Iterator<Item = char>,
+        start_position: Location,
+    ) -> Self {
+        Self {
+            upstream,
+            current_position: start_position,
+        }
+    }
+
+    // the position of the next char, i.e., the end of the text
+    // when all chars are consumed.
+    pub fn current_position(&self) -> Location {
+        self.current_position
+    }
 }
 
 impl Iterator for CharsWithPositionIter<'_> {
@@ -46,6 +63,7 @@ impl Iterator for CharsWithPositionIter<'_> {
 
                 // increase positions
                 self.current_position.index += 1;
+                self.current_position.byte_index += c.len_utf8();
 
                 if c == '\n' {
                     self.current_position.line += 1;
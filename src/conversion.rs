@@ -0,0 +1,164 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+//! Common types of the converters between ASON and other formats.
+
+use std::fmt::{self, Display};
+
+#[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+use crate::ast::AsonNode;
+
+#[cfg(any(feature = "json", feature = "yaml"))]
+use crate::ast::{Variant, VariantValue};
+
+#[cfg(any(feature = "toml", feature = "yaml"))]
+use crate::path::is_valid_identifier;
+
+/// A construct that can not be represented exactly in the target format,
+/// the conversion continues with an approximation.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ConversionWarning {
+    /// The path of the value in the source document, e.g. `.servers[0].port`,
+    /// `.` for the root value.
+    pub path: String,
+    pub message: String,
+}
+
+impl ConversionWarning {
+    pub fn new(path: &str, message: &str) -> Self {
+        Self {
+            path: if path.is_empty() {
+                ".".to_owned()
+            } else {
+                path.to_owned()
+            },
+            message: message.to_owned(),
+        }
+    }
+}
+
+impl Display for ConversionWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// The result of a conversion, along with the warnings.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Converted<T> {
+    pub value: T,
+    pub warnings: Vec<ConversionWarning>,
+}
+
+// build the path of an Object member, e.g. `.name` or `["first name"]`.
+#[cfg(any(feature = "toml", feature = "yaml"))]
+pub(crate) fn join_key_path(parent: &str, key: &str) -> String {
+    if is_valid_identifier(key) {
+        format!("{}.{}", parent, key)
+    } else {
+        format!("{}[{:?}]", parent, key)
+    }
+}
+
+// build the path of a List element, e.g. `[2]`.
+#[cfg(any(feature = "toml", feature = "yaml"))]
+pub(crate) fn join_index_path(parent: &str, index: usize) -> String {
+    format!("{}[{}]", parent, index)
+}
+
+// checks whether the elements of an array can be a List, which
+// requires all elements to be of the same data type, the arrays
+// with mixed types are converted to Tuples.
+//
+// the elements are compared with the first one, and the Lists
+// (and Maps) are compared by their first elements.
+#[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+pub(crate) fn is_homogeneous(items: &[AsonNode]) -> bool {
+    match items.split_first() {
+        Some((first, rest)) => rest.iter().all(|item| is_same_data_type(first, item)),
+        None => true,
+    }
+}
+
+// converts the elements of an array to a List if they are of the same
+// data type, otherwise to a Tuple.
+//
+// the nulls (i.e. `Option::None`) mixed with the values of the same data
+// type are converted to a List of Options, e.g. `[null, 1]` becomes
+// `[Option::None, Option::Some(1)]`, which can be deserialized to
+// `Vec<Option<i32>>`.
+#[cfg(any(feature = "json", feature = "yaml"))]
+pub(crate) fn items_to_list_or_tuple(items: Vec<AsonNode>) -> AsonNode {
+    if is_homogeneous(&items) {
+        return AsonNode::List(items);
+    }
+
+    let (nones, values): (Vec<&AsonNode>, Vec<&AsonNode>) =
+        items.iter().partition(|item| is_none(item));
+
+    let is_optional = !nones.is_empty()
+        && values
+            .split_first()
+            .is_some_and(|(first, rest)| rest.iter().all(|item| is_same_data_type(first, item)));
+
+    if is_optional {
+        AsonNode::List(
+            items
+                .into_iter()
+                .map(|item| {
+                    if is_none(&item) {
+                        item
+                    } else {
+                        AsonNode::Variant(Variant::with_value("Option", "Some", item))
+                    }
+                })
+                .collect(),
+        )
+    } else {
+        AsonNode::Tuple(items)
+    }
+}
+
+#[cfg(any(feature = "json", feature = "yaml"))]
+fn is_none(node: &AsonNode) -> bool {
+    matches!(node, AsonNode::Variant(Variant { type_name, member_name, value: VariantValue::Empty })
+        if type_name == "Option" && member_name == "None")
+}
+
+#[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+fn is_same_data_type(left: &AsonNode, right: &AsonNode) -> bool {
+    match (left, right) {
+        (AsonNode::Number(l), AsonNode::Number(r)) => l.type_name() == r.type_name(),
+        (AsonNode::Variant(l), AsonNode::Variant(r)) => l.type_name == r.type_name,
+        (AsonNode::List(l), AsonNode::List(r)) => match (l.first(), r.first()) {
+            (Some(l), Some(r)) => is_same_data_type(l, r),
+            _ => true,
+        },
+        (AsonNode::Tuple(l), AsonNode::Tuple(r)) => {
+            l.len() == r.len() && l.iter().zip(r).all(|(l, r)| is_same_data_type(l, r))
+        }
+        (AsonNode::Object(l), AsonNode::Object(r)) => {
+            l.len() == r.len()
+                && l.iter()
+                    .zip(r)
+                    .all(|(l, r)| l.key == r.key && is_same_data_type(&l.value, &r.value))
+        }
+        (AsonNode::Map(l), AsonNode::Map(r)) => match (l.first(), r.first()) {
+            (Some(l), Some(r)) => {
+                is_same_data_type(&l.name, &r.name) && is_same_data_type(&l.value, &r.value)
+            }
+            _ => true,
+        },
+        _ => std::mem::discriminant(left) == std::mem::discriminant(right),
+    }
+}
+
+// converts through the shortest decimal form, so that `1.1_f32`
+// becomes `1.1` instead of `1.100000023841858`.
+#[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+pub(crate) fn f32_to_f64(v: f32) -> f64 {
+    v.to_string().parse().unwrap_or(v as f64)
+}
@@ -0,0 +1,786 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+//! Structural diff between two ASON documents.
+//!
+//! The members of Objects and the entries of Maps are matched by
+//! key (or name), so their order does not matter. The elements of Lists
+//! are aligned by the longest common subsequence, so an inserted element
+//! is reported as an addition rather than changes of all elements after it.
+//! The elements of Tuples are matched by position.
+//!
+//! The indices in the paths of List elements are sequential, i.e.
+//! each index refers to the List as changed by the preceding changes,
+//! which is the same as the operations of `Patch`.
+
+use std::fmt::{self, Display};
+
+use crate::{
+    ast::{AsonNode, KeyValuePair, NameValuePair, Number, Variant, VariantValue},
+    patch::{Patch, PatchOperation},
+    path::AsonPath,
+    printer::print_to_string,
+};
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ChangeKind {
+    Added(AsonNode),
+    Removed(AsonNode),
+    Changed { old: AsonNode, new: AsonNode },
+
+    // the type of number is changed, e.g. from `123` to `123_i64`,
+    // the value may also be changed.
+    NumberTypeChanged { old: Number, new: Number },
+
+    // the type or the member of variant is changed,
+    // e.g. from `Option::None` to `Option::Some(123)`.
+    VariantMemberChanged { old: Variant, new: Variant },
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Change {
+    pub path: AsonPath,
+    pub kind: ChangeKind,
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Diff {
+    pub changes: Vec<Change>,
+}
+
+impl Diff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Converts the changes to a patch which changes the old document
+    /// into the new one.
+    pub fn to_patch(&self) -> Patch {
+        let operations = self
+            .changes
+            .iter()
+            .map(|change| {
+                let path = change.path.clone();
+                match &change.kind {
+                    ChangeKind::Added(value) => PatchOperation::Add {
+                        path,
+                        value: value.clone(),
+                    },
+                    ChangeKind::Removed(_) => PatchOperation::Remove { path },
+                    ChangeKind::Changed { new, .. } => PatchOperation::Replace {
+                        path,
+                        value: new.clone(),
+                    },
+                    ChangeKind::NumberTypeChanged { new, .. } => PatchOperation::Replace {
+                        path,
+                        value: AsonNode::Number(*new),
+                    },
+                    ChangeKind::VariantMemberChanged { new, .. } => PatchOperation::Replace {
+                        path,
+                        value: AsonNode::Variant(new.clone()),
+                    },
+                }
+            })
+            .collect();
+
+        Patch::new(operations)
+    }
+}
+
+/// Compares two documents and returns the changes from `old` to `new`.
+pub fn diff(old: &AsonNode, new: &AsonNode) -> Diff {
+    let mut differ = Differ {
+        path: AsonPath::root(),
+        changes: vec![],
+    };
+    differ.diff_node(old, new);
+
+    Diff {
+        changes: differ.changes,
+    }
+}
+
+struct Differ {
+    // the path of the nodes being compared
+    path: AsonPath,
+    changes: Vec<Change>,
+}
+
+// the steps of aligning two Lists
+enum ListStep {
+    Keep,
+    Remove(usize),
+    Add(usize),
+}
+
+impl Differ {
+    fn push_change(&mut self, kind: ChangeKind) {
+        self.changes.push(Change {
+            path: self.path.clone(),
+            kind,
+        });
+    }
+
+    fn push_index_change(&mut self, index: usize, kind: ChangeKind) {
+        self.path.push_index(index);
+        self.push_change(kind);
+        self.path.segments.pop();
+    }
+
+    fn diff_node(&mut self, old: &AsonNode, new: &AsonNode) {
+        match (old, new) {
+            (AsonNode::Number(old_number), AsonNode::Number(new_number)) => {
+                if old_number.type_name() != new_number.type_name() {
+                    self.push_change(ChangeKind::NumberTypeChanged {
+                        old: *old_number,
+                        new: *new_number,
+                    });
+                } else if !is_number_equal(old_number, new_number) {
+                    self.push_change(ChangeKind::Changed {
+                        old: old.clone(),
+                        new: new.clone(),
+                    });
+                }
+            }
+            (AsonNode::List(old_items), AsonNode::List(new_items)) => {
+                self.diff_list(old_items, new_items)
+            }
+            (AsonNode::Tuple(old_items), AsonNode::Tuple(new_items)) => {
+                self.diff_tuple(old_items, new_items)
+            }
+            (AsonNode::Object(old_kvps), AsonNode::Object(new_kvps)) => {
+                self.diff_object(old_kvps, new_kvps)
+            }
+            (AsonNode::Map(old_nvps), AsonNode::Map(new_nvps)) => self.diff_map(old_nvps, new_nvps),
+            (AsonNode::Variant(old_variant), AsonNode::Variant(new_variant)) => {
+                self.diff_variant(old, new, old_variant, new_variant)
+            }
+            _ => {
+                if old != new {
+                    self.push_change(ChangeKind::Changed {
+                        old: old.clone(),
+                        new: new.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    fn diff_variant(
+        &mut self,
+        old: &AsonNode,
+        new: &AsonNode,
+        old_variant: &Variant,
+        new_variant: &Variant,
+    ) {
+        if old_variant.type_name != new_variant.type_name
+            || old_variant.member_name != new_variant.member_name
+        {
+            self.push_change(ChangeKind::VariantMemberChanged {
+                old: old_variant.clone(),
+                new: new_variant.clone(),
+            });
+            return;
+        }
+
+        match (&old_variant.value, &new_variant.value) {
+            (VariantValue::Empty, VariantValue::Empty) => {}
+            (VariantValue::Value(old_value), VariantValue::Value(new_value)) => {
+                self.path.push_index(0);
+                self.diff_node(old_value, new_value);
+                self.path.segments.pop();
+            }
+            (VariantValue::Tuple(old_items), VariantValue::Tuple(new_items)) => {
+                self.diff_tuple(old_items, new_items)
+            }
+            (VariantValue::Object(old_kvps), VariantValue::Object(new_kvps)) => {
+                self.diff_object(old_kvps, new_kvps)
+            }
+            _ => {
+                // the kind of payload is changed, e.g. from `Type::A(1)` to `Type::A{v: 1}`,
+                // which is impossible for the variants of the same Rust enum.
+                self.push_change(ChangeKind::Changed {
+                    old: old.clone(),
+                    new: new.clone(),
+                });
+            }
+        }
+    }
+
+    fn diff_object(&mut self, old_kvps: &[KeyValuePair], new_kvps: &[KeyValuePair]) {
+        for old_kvp in old_kvps {
+            self.path.push_key(&old_kvp.key);
+            match new_kvps.iter().find(|kvp| kvp.key == old_kvp.key) {
+                Some(new_kvp) => self.diff_node(&old_kvp.value, &new_kvp.value),
+                None => self.push_change(ChangeKind::Removed(old_kvp.value.as_ref().clone())),
+            }
+            self.path.segments.pop();
+        }
+
+        for new_kvp in new_kvps {
+            if !old_kvps.iter().any(|kvp| kvp.key == new_kvp.key) {
+                self.path.push_key(&new_kvp.key);
+                self.push_change(ChangeKind::Added(new_kvp.value.as_ref().clone()));
+                self.path.segments.pop();
+            }
+        }
+    }
+
+    fn diff_map(&mut self, old_nvps: &[NameValuePair], new_nvps: &[NameValuePair]) {
+        for old_nvp in old_nvps {
+            self.path.push_name(&old_nvp.name);
+            match new_nvps.iter().find(|nvp| nvp.name == old_nvp.name) {
+                Some(new_nvp) => self.diff_node(&old_nvp.value, &new_nvp.value),
+                None => self.push_change(ChangeKind::Removed(old_nvp.value.as_ref().clone())),
+            }
+            self.path.segments.pop();
+        }
+
+        for new_nvp in new_nvps {
+            if !old_nvps.iter().any(|nvp| nvp.name == new_nvp.name) {
+                self.path.push_name(&new_nvp.name);
+                self.push_change(ChangeKind::Added(new_nvp.value.as_ref().clone()));
+                self.path.segments.pop();
+            }
+        }
+    }
+
+    fn diff_tuple(&mut self, old_items: &[AsonNode], new_items: &[AsonNode]) {
+        for (index, (old_item, new_item)) in old_items.iter().zip(new_items).enumerate() {
+            self.path.push_index(index);
+            self.diff_node(old_item, new_item);
+            self.path.segments.pop();
+        }
+
+        // the removed elements are always at the end, so the index
+        // does not advance.
+        for old_item in old_items.iter().skip(new_items.len()) {
+            self.push_index_change(new_items.len(), ChangeKind::Removed(old_item.clone()));
+        }
+
+        for (index, new_item) in new_items.iter().enumerate().skip(old_items.len()) {
+            self.push_index_change(index, ChangeKind::Added(new_item.clone()));
+        }
+    }
+
+    fn diff_list(&mut self, old_items: &[AsonNode], new_items: &[AsonNode]) {
+        // the consecutive removals and additions between two kept elements
+        // are paired by position and compared, so that a changed element
+        // is reported as the changes inside it rather than
+        // a removal and an addition.
+        let mut index = 0;
+        let mut removed: Vec<usize> = vec![];
+        let mut added: Vec<usize> = vec![];
+
+        for step in align_list(old_items, new_items)
+            .into_iter()
+            .chain(std::iter::once(ListStep::Keep))
+        {
+            match step {
+                ListStep::Remove(old_index) => removed.push(old_index),
+                ListStep::Add(new_index) => added.push(new_index),
+                ListStep::Keep => {
+                    for (old_index, new_index) in removed.iter().zip(&added) {
+                        self.path.push_index(index);
+                        self.diff_node(&old_items[*old_index], &new_items[*new_index]);
+                        self.path.segments.pop();
+                        index += 1;
+                    }
+
+                    for old_index in removed.iter().skip(added.len()) {
+                        self.push_index_change(
+                            index,
+                            ChangeKind::Removed(old_items[*old_index].clone()),
+                        );
+                    }
+
+                    for new_index in added.iter().skip(removed.len()) {
+                        self.push_index_change(
+                            index,
+                            ChangeKind::Added(new_items[*new_index].clone()),
+                        );
+                        index += 1;
+                    }
+
+                    removed.clear();
+                    added.clear();
+
+                    // the kept element
+                    index += 1;
+                }
+            }
+        }
+    }
+}
+
+// aligns two Lists by the longest common subsequence (LCS).
+fn align_list(old_items: &[AsonNode], new_items: &[AsonNode]) -> Vec<ListStep> {
+    let mut steps: Vec<ListStep> = vec![];
+    let (mut i, mut j) = (0, 0);
+
+    for (old_index, new_index) in match_list(old_items, new_items) {
+        steps.extend((i..old_index).map(ListStep::Remove));
+        steps.extend((j..new_index).map(ListStep::Add));
+        steps.push(ListStep::Keep);
+        (i, j) = (old_index + 1, new_index + 1);
+    }
+
+    steps.extend((i..old_items.len()).map(ListStep::Remove));
+    steps.extend((j..new_items.len()).map(ListStep::Add));
+    steps
+}
+
+/// Returns the indices of the equal elements of the longest
+/// common subsequence (LCS) of two Lists, in ascending order.
+pub(crate) fn match_list(old_items: &[AsonNode], new_items: &[AsonNode]) -> Vec<(usize, usize)> {
+    // the common prefix and suffix are excluded from the LCS table,
+    // which is usually small for the Lists of configuration.
+    let prefix_length = old_items
+        .iter()
+        .zip(new_items)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let suffix_length = old_items[prefix_length..]
+        .iter()
+        .rev()
+        .zip(new_items[prefix_length..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old_middle = &old_items[prefix_length..old_items.len() - suffix_length];
+    let new_middle = &new_items[prefix_length..new_items.len() - suffix_length];
+    let (n, m) = (old_middle.len(), new_middle.len());
+
+    // `lengths[i][j]` is the length of LCS of `old_middle[i..]` and `new_middle[j..]`
+    let mut lengths = vec![vec![0_usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[i][j] = if old_middle[i] == new_middle[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut matches: Vec<(usize, usize)> = (0..prefix_length).map(|k| (k, k)).collect();
+
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old_middle[i] == new_middle[j] {
+            matches.push((prefix_length + i, prefix_length + j));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    matches.extend((0..suffix_length).map(|k| {
+        (
+            old_items.len() - suffix_length + k,
+            new_items.len() - suffix_length + k,
+        )
+    }));
+    matches
+}
+
+// the NaN numbers are considered equal, so that they are not
+// reported as changes.
+fn is_number_equal(left: &Number, right: &Number) -> bool {
+    match (left, right) {
+        (Number::F32(a), Number::F32(b)) => a == b || (a.is_nan() && b.is_nan()),
+        (Number::F64(a), Number::F64(b)) => a == b || (a.is_nan() && b.is_nan()),
+        _ => left == right,
+    }
+}
+
+// the values in multiple lines are indented, so that
+// they are distinguishable from the changes.
+fn format_value(node: &AsonNode) -> String {
+    print_to_string(node).replace('\n', "\n    ")
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ChangeKind::Added(value) => write!(f, "+ {}: {}", self.path, format_value(value)),
+            ChangeKind::Removed(value) => write!(f, "- {}: {}", self.path, format_value(value)),
+            ChangeKind::Changed { old, new } => write!(
+                f,
+                "~ {}: {} -> {}",
+                self.path,
+                format_value(old),
+                format_value(new)
+            ),
+            ChangeKind::NumberTypeChanged { old, new } => write!(
+                f,
+                "~ {}: {} -> {} ({} -> {})",
+                self.path,
+                format_value(&AsonNode::Number(*old)),
+                format_value(&AsonNode::Number(*new)),
+                old.type_name(),
+                new.type_name()
+            ),
+            ChangeKind::VariantMemberChanged { old, new } => write!(
+                f,
+                "~ {}: {} -> {}",
+                self.path,
+                format_value(&AsonNode::Variant(old.clone())),
+                format_value(&AsonNode::Variant(new.clone()))
+            ),
+        }
+    }
+}
+
+/// Renders the changes in lines, e.g.
+///
+/// ```text
+/// + .servers[2]: "c"
+/// - .debug: true
+/// ~ .port: 80 -> 8080
+/// ~ .size: 1024 -> 1024_i64 (i32 -> i64)
+/// ```
+impl Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        ast::{AsonNode, Number},
+        parser::parse_from_str,
+        printer::print_to_string,
+    };
+
+    use super::{diff, ChangeKind};
+
+    fn diff_text(old: &str, new: &str) -> String {
+        diff(&parse_from_str(old).unwrap(), &parse_from_str(new).unwrap()).to_string()
+    }
+
+    #[test]
+    fn test_diff_object() {
+        assert_eq!(
+            diff_text(
+                r#"{name: "foo", port: 80, debug: true, size: 1024}"#,
+                r#"{size: 1024_i64, port: 8080, name: "foo", tags: ["a"]}"#
+            ),
+            r#"~ .port: 80 -> 8080
+- .debug: true
+~ .size: 1024 -> 1024_i64 (i32 -> i64)
++ .tags: [
+        "a"
+    ]
+"#
+        );
+
+        assert_eq!(
+            diff_text(r#"{a: {b: 1, c: 'x'}}"#, r#"{a: {b: 1, c: "x"}}"#),
+            r#"~ .a.c: 'x' -> "x"
+"#
+        );
+
+        // no changes
+        assert!(diff(
+            &parse_from_str(r#"{a: 1, b: [1.5, NaN]}"#).unwrap(),
+            &parse_from_str(r#"{b: [1.5, NaN], a: 1}"#).unwrap()
+        )
+        .is_empty());
+
+        assert_eq!(
+            diff(
+                &AsonNode::Number(Number::I32(1)),
+                &AsonNode::Number(Number::I64(1))
+            )
+            .changes[0]
+                .kind,
+            ChangeKind::NumberTypeChanged {
+                old: Number::I32(1),
+                new: Number::I64(1)
+            }
+        );
+    }
+
+    #[test]
+    fn test_diff_list() {
+        // insertions are aligned
+        assert_eq!(
+            diff_text(r#"[1, 2, 3, 4]"#, r#"[0, 1, 2, 5, 3, 4, 6]"#),
+            r#"+ [0]: 0
++ [3]: 5
++ [6]: 6
+"#
+        );
+
+        // removals
+        assert_eq!(
+            diff_text(r#"[1, 2, 3, 4, 5]"#, r#"[2, 3, 5]"#),
+            r#"- [0]: 1
+- [2]: 4
+"#
+        );
+
+        // the changed elements are compared
+        assert_eq!(
+            diff_text(
+                r#"[{id: 1, v: "a"}, {id: 2, v: "b"}, {id: 3, v: "c"}]"#,
+                r#"[{id: 1, v: "a"}, {id: 2, v: "B"}, {id: 3, v: "c"}, {id: 4, v: "d"}]"#
+            ),
+            r#"~ [1].v: "b" -> "B"
++ [3]: {
+        id: 4
+        v: "d"
+    }
+"#
+        );
+
+        // the surplus removals of a gap
+        assert_eq!(
+            diff_text(r#"[1, 2, 3, 4]"#, r#"[1, 9, 4]"#),
+            r#"~ [1]: 2 -> 9
+- [2]: 3
+"#
+        );
+    }
+
+    #[test]
+    fn test_diff_map_tuple_and_variant() {
+        assert_eq!(
+            diff_text(
+                r#"["a": 1, 'c': 2, 3: 4, 5_u8: 6]"#,
+                r#"[5_u8: 7, "a": 1, 'c': 20, "d": 8]"#
+            ),
+            r#"~ ['c']: 2 -> 20
+- [3]: 4
+~ [5_u8]: 6 -> 7
++ .d: 8
+"#
+        );
+
+        assert_eq!(
+            diff_text(r#"(1, "a", true)"#, r#"(2, "a")"#),
+            r#"~ [0]: 1 -> 2
+- [2]: true
+"#
+        );
+
+        assert_eq!(
+            diff_text(r#"(1,)"#, r#"(1, 2, 3)"#),
+            r#"+ [1]: 2
++ [2]: 3
+"#
+        );
+
+        assert_eq!(
+            diff_text(
+                r#"{
+                    a: Option::None
+                    b: Option::Some(1)
+                    c: Shape::Rect{width: 1, height: 2}
+                    d: Color::RGB(1, 2, 3)
+                }"#,
+                r#"{
+                    a: Option::Some(1)
+                    b: Option::Some(2)
+                    c: Shape::Rect{width: 10, height: 2}
+                    d: Color::RGB(1, 2, 4)
+                }"#
+            ),
+            r#"~ .a: Option::None -> Option::Some(1)
+~ .b[0]: 1 -> 2
+~ .c.width: 1 -> 10
+~ .d[2]: 3 -> 4
+"#
+        );
+    }
+
+    #[test]
+    fn test_diff_variant_payloads() {
+        // the payloads of the same kind are compared
+        assert_eq!(
+            diff_text(r#"Color::RGB(1, 2)"#, r#"Color::RGB(1, 2, 3)"#),
+            r#"+ [2]: 3
+"#
+        );
+
+        assert_eq!(
+            diff_text(r#"Shape::Rect{w: 1, h: 2}"#, r#"Shape::Rect{h: 2, d: 3}"#),
+            r#"- .w: 1
++ .d: 3
+"#
+        );
+
+        assert_eq!(
+            diff_text(r#"Option::Some([1, 2])"#, r#"Option::Some([1, 3, 2])"#),
+            r#"+ [0][1]: 3
+"#
+        );
+
+        assert_eq!(
+            diff_text(
+                r#"Option::Some(Option::Some(1))"#,
+                r#"Option::Some(Option::None)"#
+            ),
+            r#"~ [0]: Option::Some(1) -> Option::None
+"#
+        );
+
+        // the kind of payload is changed, the whole variant is replaced
+        assert_eq!(
+            diff_text(r#"Type::A(1)"#, r#"Type::A{v: 1}"#),
+            r#"~ .: Type::A(1) -> Type::A{
+        v: 1
+    }
+"#
+        );
+
+        assert_eq!(
+            diff_text(r#"Type::A(1, 2)"#, r#"Type::A(1)"#),
+            r#"~ .: Type::A(1, 2) -> Type::A(1)
+"#
+        );
+
+        // the member is changed
+        assert!(matches!(
+            &diff(
+                &parse_from_str("Option::Some(1)").unwrap(),
+                &parse_from_str("Result::Ok(1)").unwrap()
+            )
+            .changes[0]
+                .kind,
+            ChangeKind::VariantMemberChanged { .. }
+        ));
+
+        // applying the patch to the old document produces the new one
+        for (old, new) in [
+            ("Color::RGB(1, 2)", "Color::RGB(2)"),
+            ("Color::RGB(1, 2)", "Color::RGB(1, 2, 3)"),
+            ("Shape::Rect{w: 1, h: 2}", "Shape::Rect{h: 2, d: 3}"),
+            ("Option::Some([1, 2])", "Option::Some([1, 3, 2])"),
+            ("Type::A(1)", "Type::A{v: 1}"),
+            ("Type::A{v: 1}", "Type::A(1, 2)"),
+            ("Type::A", "Type::A(1)"),
+            ("[Option::None, Option::Some(1)]", "[Option::Some(1)]"),
+        ] {
+            let old = parse_from_str(old).unwrap();
+            let new = parse_from_str(new).unwrap();
+            let mut node = old.clone();
+            diff(&old, &new).to_patch().apply(&mut node).unwrap();
+            assert_eq!(node, new);
+        }
+    }
+
+    #[test]
+    fn test_diff_alike_map_names() {
+        // the entries whose names look alike are compared separately
+        assert_eq!(
+            diff_text(
+                r#"['a': 1, "a": 2, 1: 3, 1_u8: 4]"#,
+                r#"['a': 1, "a": 20, 1_u8: 4, 1: 30]"#
+            ),
+            r#"~ .a: 2 -> 20
+~ [1]: 3 -> 30
+"#
+        );
+
+        assert_eq!(
+            diff_text(r#"['a': 1, "a": 2]"#, r#"["a": 2, 'b': 1]"#),
+            r#"- ['a']: 1
++ ['b']: 1
+"#
+        );
+
+        assert_eq!(
+            diff_text("[1: 'x', 1_i64: 'y']", "[1_i64: 'x', 1: 'y']"),
+            r#"~ [1]: 'x' -> 'y'
+~ [1_i64]: 'y' -> 'x'
+"#
+        );
+    }
+
+    #[test]
+    fn test_to_patch() {
+        // applying the patch to the old document produces the new one
+        let old = parse_from_str(
+            r#"{
+                a: [1, 2, 3, 4, 5]
+                b: (1, "a", true)
+                c: ["x": 1, 'y': 2, 3: 4]
+                d: [{id: 1, v: "a"}, {id: 2, v: "b"}]
+                e: Color::RGB(1, 2, 3)
+            }"#,
+        )
+        .unwrap();
+        let new = parse_from_str(
+            r#"{
+                a: [0, 2, 9, 5, 6]
+                b: (2, "a")
+                c: ['y': 20, 3: 4, "z": 5]
+                d: [{id: 2, v: "B"}, {id: 3, v: "c"}]
+                e: Color::RGB(1, 2, 4)
+                f: 1_i64
+            }"#,
+        )
+        .unwrap();
+
+        let mut node = old.clone();
+        diff(&old, &new).to_patch().apply(&mut node).unwrap();
+        assert!(diff(&node, &new).is_empty());
+
+        // the Maps whose names look alike
+        for (old, new) in [
+            (r#"['a': 1, "a": 2]"#, r#"['a': 1, "a": 3]"#),
+            (r#"['a': 1, "a": 2]"#, r#"['a': 4, "a": 2]"#),
+            (r#"['a': 1, "a": 2]"#, r#"["a": 2]"#),
+            ("[1_u8: 'x', 1: 'y']", "[1_u8: 'x', 1: 'z']"),
+            ("[1_u8: 'x', 1: 'y', 1_i64: 'w']", "[1: 'y', 1_i64: 'v']"),
+            ("[1: 'y']", "[1: 'y', 1_u8: 'x']"),
+        ] {
+            let old = parse_from_str(old).unwrap();
+            let new = parse_from_str(new).unwrap();
+            let mut node = old.clone();
+            diff(&old, &new).to_patch().apply(&mut node).unwrap();
+            assert_eq!(node, new);
+        }
+
+        let d = diff(
+            &parse_from_str(r#"{a: 1, b: [1, 2], c: Option::None}"#).unwrap(),
+            &parse_from_str(r#"{a: 1_i64, b: [0, 1], c: Option::Some(1)}"#).unwrap(),
+        );
+
+        assert_eq!(
+            print_to_string(&d.to_patch().to_node()),
+            r#"[
+    Patch::Replace{
+        path: ".a"
+        value: 1_i64
+    }
+    Patch::Add{
+        path: ".b[0]"
+        value: 0
+    }
+    Patch::Remove{
+        path: ".b[2]"
+    }
+    Patch::Replace{
+        path: ".c"
+        value: Option::Some(1)
+    }
+]"#
+        );
+    }
+}
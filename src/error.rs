@@ -0,0 +1,321 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    sync::Arc,
+};
+
+use crate::{location::Location, path::AsonPath};
+
+/// The category of an error, so that callers can tell errors apart
+/// without matching the messages.
+///
+/// New kinds may be added in the minor versions, so the matches on it
+/// need a wildcard arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The text is not valid ASON, e.g. an invalid token or a missing bracket.
+    Syntax,
+
+    /// The document ends before a value is complete.
+    UnexpectedEndOfDocument,
+
+    /// The value does not match the type being deserialized,
+    /// e.g. a String is found where an `u16` is expected.
+    TypeMismatch,
+
+    /// The Rust data type is not supported by ASON, e.g. the unit `()`.
+    Unsupported,
+
+    /// The binary encoded data is malformed.
+    InvalidData,
+
+    /// The value can not be converted from or to other formats,
+    /// e.g. JSON, TOML and YAML.
+    Conversion,
+
+    /// The error raised by the implementations of `Serialize` and
+    /// `Deserialize`, e.g. a missing field or an unknown variant.
+    Custom,
+
+    /// Reading or writing failed.
+    Io,
+
+    /// The nesting depth of the document exceeds `Limits::max_depth`.
+    DepthLimitExceeded,
+
+    /// A String or hexadecimal byte data exceeds `Limits::max_string_length`.
+    StringLengthLimitExceeded,
+
+    /// A List, Map, Object, Tuple or Variant has more elements
+    /// than `Limits::max_container_length`.
+    ContainerLengthLimitExceeded,
+
+    /// The document exceeds `Limits::max_document_size`.
+    DocumentSizeLimitExceeded,
+
+    /// The methods of `AsonWriter` are called in an order that does not
+    /// produce a valid document, e.g. `end` without a container, or
+    /// the AST to be printed contains the `AsonNode::Error` placeholder.
+    InvalidStructure,
+
+    /// The patch document is malformed, e.g. an unknown operation
+    /// or a missing `path`.
+    InvalidPatch,
+
+    /// An operation of patch can not be applied, e.g. the path
+    /// does not exist or the `Test` operation fails.
+    PatchFailed,
+
+    /// The included document can not be resolved, e.g. it does not
+    /// exist, it is outside the root directory or it includes itself.
+    IncludeFailed,
+
+    /// A placeholder in String can not be expanded, e.g. the variable
+    /// is not defined or its value does not match the type of placeholder.
+    Interpolation,
+
+    /// An anchor alias can not be resolved, e.g. the anchor is not
+    /// defined (yet), it is defined twice or it references itself.
+    InvalidReference,
+}
+
+#[derive(Debug, Clone)]
+pub struct AsonError {
+    pub kind: ErrorKind,
+    pub message: String,
+
+    // note that the "index" (and the result of "index+length") may exceed
+    // the last index of string, for example, the "char incomplete" error raised by a string `'a`,
+    // which index is 2.
+    //
+    // the location of `UnexpectedEndOfDocument` is the end of document.
+    pub location: Option<Location>,
+
+    // the details are boxed, so that `Result<T, AsonError>` stays small.
+    pub(crate) detail: Option<Box<ErrorDetail>>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ErrorDetail {
+    // the path of the value being deserialized when the error is raised,
+    // e.g. `.servers[2].port`.
+    path: Option<AsonPath>,
+
+    // the descriptions of the expected and the actual value,
+    // e.g. `u16` and `String`.
+    expected: Option<String>,
+    found: Option<String>,
+
+    // the underlying error, e.g. an I/O error or a JSON error.
+    cause: Option<Arc<dyn Error + Send + Sync>>,
+}
+
+impl AsonError {
+    pub fn new(kind: ErrorKind, message: String) -> Self {
+        Self {
+            kind,
+            message,
+            location: None,
+            detail: None,
+        }
+    }
+
+    pub fn new_syntax(message: String, location: Location) -> Self {
+        Self::new(ErrorKind::Syntax, message).with_location(location)
+    }
+
+    pub fn new_unexpected_end_of_document(message: String) -> Self {
+        Self::new(ErrorKind::UnexpectedEndOfDocument, message)
+    }
+
+    pub fn new_type_mismatch(expected: &str, found: &str) -> Self {
+        Self::new(
+            ErrorKind::TypeMismatch,
+            format!("Expect a \"{}\" value, found \"{}\".", expected, found),
+        )
+        .with_expected_and_found(expected, found)
+    }
+
+    pub fn with_location(mut self, location: Location) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    pub fn with_path(mut self, path: AsonPath) -> Self {
+        self.detail_mut().path = Some(path);
+        self
+    }
+
+    pub fn with_expected_and_found(mut self, expected: &str, found: &str) -> Self {
+        let detail = self.detail_mut();
+        detail.expected = Some(expected.to_owned());
+        detail.found = Some(found.to_owned());
+        self
+    }
+
+    pub fn caused_by<E>(mut self, cause: E) -> Self
+    where
+        E: Error + Send + Sync + 'static,
+    {
+        self.detail_mut().cause = Some(Arc::new(cause));
+        self
+    }
+
+    /// The path of the value being deserialized when the error is raised,
+    /// e.g. `.servers[2].port`.
+    pub fn path(&self) -> Option<&AsonPath> {
+        self.detail.as_ref()?.path.as_ref()
+    }
+
+    /// The description of the expected value, e.g. `u16`.
+    pub fn expected(&self) -> Option<&str> {
+        self.detail.as_ref()?.expected.as_deref()
+    }
+
+    /// The description of the actual value, e.g. `String`.
+    pub fn found(&self) -> Option<&str> {
+        self.detail.as_ref()?.found.as_deref()
+    }
+
+    fn detail_mut(&mut self) -> &mut ErrorDetail {
+        self.detail.get_or_insert_with(Default::default)
+    }
+
+    pub fn is_unexpected_end_of_document(&self) -> bool {
+        self.kind == ErrorKind::UnexpectedEndOfDocument
+    }
+
+    // sets the location of the `UnexpectedEndOfDocument` error
+    // to the end of document if it is absent.
+    pub(crate) fn locate_end_of_document(mut self, position: Location) -> Self {
+        if self.is_unexpected_end_of_document() && self.location.is_none() {
+            self.location = Some(position);
+        }
+        self
+    }
+}
+
+// the underlying error is not compared.
+impl PartialEq for AsonError {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind
+            && self.message == other.message
+            && self.location == other.location
+            && self.path() == other.path()
+            && self.expected() == other.expected()
+            && self.found() == other.found()
+    }
+}
+
+impl Display for AsonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.kind, &self.location) {
+            (ErrorKind::UnexpectedEndOfDocument, _) => {
+                writeln!(f, "Unexpected to reach the end of document.")?;
+            }
+            (_, Some(location)) => {
+                writeln!(
+                    f,
+                    "Error at line: {}, column: {}",
+                    location.line + 1,
+                    location.column + 1
+                )?;
+            }
+            _ => {}
+        }
+
+        f.write_str(&self.message)?;
+
+        match self.path() {
+            Some(path) if !path.is_root() => write!(f, " (at \"{}\")", path),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Error for AsonError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self.detail.as_ref()?.cause.as_ref() {
+            Some(cause) => Some(cause.as_ref()),
+            None => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for AsonError {
+    fn from(e: std::io::Error) -> Self {
+        // the error raised by the printer is passed through `std::io::Error`.
+        if e.get_ref().is_some_and(|inner| inner.is::<AsonError>()) {
+            return *e.into_inner().unwrap().downcast::<AsonError>().unwrap();
+        }
+
+        AsonError::new(ErrorKind::Io, e.to_string()).caused_by(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{location::Location, path::AsonPath};
+
+    use super::{AsonError, ErrorKind};
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            AsonError::new(ErrorKind::Custom, "missing field `id`".to_owned()).to_string(),
+            "missing field `id`"
+        );
+
+        assert_eq!(
+            AsonError::new_syntax(
+                "Expect a comma or new-line.".to_owned(),
+                Location::new_position(15, 1, 6)
+            )
+            .to_string(),
+            "Error at line: 2, column: 7\nExpect a comma or new-line."
+        );
+
+        assert_eq!(
+            AsonError::new_unexpected_end_of_document("Incomplete List.".to_owned())
+                .with_location(Location::new_position(3, 0, 3))
+                .to_string(),
+            "Unexpected to reach the end of document.\nIncomplete List."
+        );
+
+        assert_eq!(
+            AsonError::new_type_mismatch("u16", "String")
+                .with_location(Location::new_position(30, 3, 10))
+                .with_path(AsonPath::parse(".servers[2].port").unwrap())
+                .to_string(),
+            "Error at line: 4, column: 11\nExpect a \"u16\" value, found \"String\". (at \".servers[2].port\")"
+        );
+    }
+
+    #[test]
+    fn test_size() {
+        // keeps `Result<T, AsonError>` under the threshold of `clippy::result_large_err`.
+        assert!(std::mem::size_of::<AsonError>() <= 128);
+    }
+
+    #[test]
+    fn test_source() {
+        let e: AsonError = std::io::Error::other("disk full").into();
+        assert_eq!(e.kind, ErrorKind::Io);
+        assert_eq!(e.source().unwrap().to_string(), "disk full");
+
+        assert!(AsonError::new(ErrorKind::Custom, "foo".to_owned())
+            .source()
+            .is_none());
+    }
+}
@@ -4,447 +4,796 @@
 // the Mozilla Public License version 2.0 and additional exceptions,
 // more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
 
-use crate::AsonError;
-
-//                 /-- selection start
-//                 |                 /-- selection length
-//                 v                 v
-// prefix -->   ...snippet_source_text...  <-- suffix
-//                       ^^^^
-//                       |  \-- length
-//                       \----- offset
-struct SnippetRange {
-    prefix: bool,
-    suffix: bool,
-    selection_start: usize,
-    selection_length: usize,
-    offset: usize,
-    length: usize,
+use std::collections::BTreeSet;
+
+use unicode_width::UnicodeWidthChar;
+
+use crate::{location::Location, AsonError, ErrorKind};
+
+const DEFAULT_TAB_WIDTH: usize = 4;
+
+// ANSI escape sequences
+const STYLE_ERROR: &str = "\x1b[1;31m";
+const STYLE_SECONDARY: &str = "\x1b[1;34m";
+const STYLE_EMPHASIS: &str = "\x1b[1m";
+const STYLE_RESET: &str = "\x1b[0m";
+
+/// A label of diagnostic which points to a range of the source text.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DiagnosticLabel {
+    pub location: Location,
+    pub message: String,
+
+    // the primary label marks the cause of the error with `^`, and
+    // the secondary labels mark the related text with `-`.
+    pub is_primary: bool,
 }
 
-fn calculate_snippet_range(
-    original_selection_start: usize,
-    original_selection_length: usize,
-    source_total_length: usize,
-) -> SnippetRange {
-    const LEADING_LENGTH: usize = 15;
-    const SNIPPET_LENGTH: usize = 40;
-
-    let (prefix, selection_start, offset) =
-        if source_total_length < SNIPPET_LENGTH || original_selection_start < LEADING_LENGTH {
-            (false, 0, original_selection_start)
-        } else if original_selection_start + SNIPPET_LENGTH > source_total_length {
-            (
-                true,
-                source_total_length - SNIPPET_LENGTH,
-                original_selection_start - (source_total_length - SNIPPET_LENGTH),
-            )
-        } else {
-            (
-                true,
-                original_selection_start - LEADING_LENGTH,
-                LEADING_LENGTH,
-            )
-        };
+/// An error message with labels, notes and helps, which is rendered in
+/// the style of the Rust compiler, e.g.
+///
+/// ```text
+/// error: Mixed types in List.
+///  --> data.ason:2:18
+///   |
+/// 2 |     scores: [11, "foo"]
+///   |              --  ^^^^^ list element type differs from this first element
+///   |              |
+///   |              first element
+///   |
+///   = help: change all elements to the same type
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct Diagnostic {
+    pub message: String,
+    pub labels: Vec<DiagnosticLabel>,
+    pub notes: Vec<String>,
+    pub helps: Vec<String>,
+}
 
-    let (suffix, selection_length) = if selection_start + SNIPPET_LENGTH >= source_total_length {
-        (false, source_total_length - selection_start)
-    } else {
-        (true, SNIPPET_LENGTH)
-    };
+#[derive(Debug, PartialEq, Clone)]
+pub struct RenderOptions {
+    /// Colors the output with ANSI escape sequences.
+    pub colored: bool,
 
-    let length = if offset + original_selection_length > selection_length {
-        selection_length - offset
-    } else {
-        original_selection_length
-    };
+    /// The number of columns a tab character takes.
+    pub tab_width: usize,
+
+    /// The file name shown after the arrow `-->`.
+    pub file_name: Option<String>,
+}
 
-    SnippetRange {
-        prefix,
-        suffix,
-        selection_start,
-        selection_length,
-        offset,
-        length,
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            colored: false,
+            tab_width: DEFAULT_TAB_WIDTH,
+            file_name: None,
+        }
     }
 }
 
-fn generate_snippet_and_indented_detail(
-    chars: &mut dyn Iterator<Item = char>,
-    snippet_range: &SnippetRange,
-    detail: &str,
-) -> (String, String) {
-    // build snippet
-    let mut snippet = String::new();
-    snippet.push_str("| ");
-    if snippet_range.prefix {
-        snippet.push_str("...");
+impl Diagnostic {
+    pub fn new(message: &str) -> Self {
+        Self {
+            message: message.to_owned(),
+            labels: vec![],
+            notes: vec![],
+            helps: vec![],
+        }
     }
-    let selection_chars = chars
-        .skip(snippet_range.selection_start)
-        .take(snippet_range.selection_length);
-    let selection_string = selection_chars
-        .map(|c| match c {
-            '\n' => ' ',
-            '\t' => ' ',
-            _ => c,
-        })
-        .collect::<String>();
-    snippet.push_str(&selection_string);
-    if snippet_range.suffix {
-        snippet.push_str("...");
+
+    pub fn with_primary_label(self, location: Location, message: &str) -> Self {
+        self.with_label(location, message, true)
     }
 
-    // build indented detail
-    let mut indented_detail = String::new();
-    indented_detail.push_str("| ");
-    if snippet_range.prefix {
-        indented_detail.push_str("   ");
+    pub fn with_secondary_label(self, location: Location, message: &str) -> Self {
+        self.with_label(location, message, false)
     }
-    indented_detail.push_str(&" ".repeat(snippet_range.offset));
-    indented_detail.push('^');
-    if snippet_range.length > 0 {
-        indented_detail.push_str(&"^".repeat(snippet_range.length - 1));
-    } else {
-        indented_detail.push_str("____");
+
+    fn with_label(mut self, location: Location, message: &str, is_primary: bool) -> Self {
+        self.labels.push(DiagnosticLabel {
+            location,
+            message: message.to_owned(),
+            is_primary,
+        });
+        self
     }
-    indented_detail.push(' ');
-    indented_detail.push_str(detail);
 
-    (snippet, indented_detail)
-}
+    pub fn with_note(mut self, note: &str) -> Self {
+        self.notes.push(note.to_owned());
+        self
+    }
 
-impl AsonError {
-    pub fn with_source(&self, source: &str) -> String {
-        // print human readable error message with the source
-
-        let source_total_length = source.chars().count();
-        let mut chars = source.chars();
-
-        // | leading length
-        // v
-        // |------|
-        // xxxxxxxx.xxxxxxxxxxx  <-- snippet text
-        // |------------------|
-        // ^
-        // | snippet length
-
-        match self {
-            AsonError::Message(msg) => msg.to_owned(),
-            AsonError::UnexpectedEndOfDocument(detail) => {
-                let msg = "Unexpected to reach the end of document.";
-                let snippet_range =
-                    calculate_snippet_range(source_total_length, 0, source_total_length);
-                let (snippet, indented_detail) =
-                    generate_snippet_and_indented_detail(&mut chars, &snippet_range, detail);
-                format!("{}\n{}\n{}", msg, snippet, indented_detail)
+    pub fn with_help(mut self, help: &str) -> Self {
+        self.helps.push(help.to_owned());
+        self
+    }
+
+    /// Renders the diagnostic with the snippets of the source text.
+    ///
+    /// Only the `index` and `length` of the label locations are used,
+    /// the locations beyond the end of source point to the end.
+    pub fn render(&self, source: &str, options: &RenderOptions) -> String {
+        let group = LabelGroup {
+            file_name: options.file_name.as_deref(),
+            text: SourceText::new(source, options.tab_width),
+            labels: self.labels.iter().collect(),
+        };
+        self.render_groups(&[group], options)
+    }
+
+    // renders the labels of each source file in turn, the position of
+    // the first group follows `-->`, and the others follow `:::`.
+    pub(crate) fn render_groups(&self, groups: &[LabelGroup], options: &RenderOptions) -> String {
+        let painter = Painter {
+            colored: options.colored,
+        };
+
+        let mut lines = vec![format!(
+            "{}{}",
+            painter.paint(STYLE_ERROR, "error"),
+            painter.paint(STYLE_EMPHASIS, &format!(": {}", self.message))
+        )];
+
+        let group_segments: Vec<Vec<Segment>> = groups
+            .iter()
+            .map(|group| {
+                group
+                    .labels
+                    .iter()
+                    .flat_map(|label| group.text.segments(label))
+                    .collect()
+            })
+            .collect();
+
+        let gutter_width = group_segments
+            .iter()
+            .flatten()
+            .map(|segment| (segment.line + 1).to_string().len())
+            .max()
+            .unwrap_or(0);
+        let empty_gutter = format!(
+            "{}{}",
+            " ".repeat(gutter_width + 1),
+            painter.paint(STYLE_SECONDARY, "|")
+        );
+
+        for (group_index, (group, segments)) in groups.iter().zip(&group_segments).enumerate() {
+            if let Some(label) = group
+                .labels
+                .iter()
+                .find(|label| label.is_primary)
+                .or(group.labels.first())
+            {
+                let (line, column) = group.text.line_and_column(label.location.index);
+                let position = match group.file_name {
+                    Some(file_name) => format!("{}:{}:{}", file_name, line + 1, column + 1),
+                    None => format!("{}:{}", line + 1, column + 1),
+                };
+                let arrow = if group_index == 0 {
+                    "-->"
+                } else {
+                    lines.push(empty_gutter.clone());
+                    ":::"
+                };
+                lines.push(format!(
+                    "{}{} {}",
+                    " ".repeat(gutter_width),
+                    painter.paint(STYLE_SECONDARY, arrow),
+                    position
+                ));
+                lines.push(empty_gutter.clone());
             }
-            AsonError::MessageWithLocation(detail, location) => {
-                let msg = format!(
-                    "Error at line: {}, column: {}",
-                    location.line + 1,
-                    location.column + 1
-                );
-
-                let snippet_range =
-                    calculate_snippet_range(location.index, location.length, source_total_length);
-                let (snippet, indented_detail) =
-                    generate_snippet_and_indented_detail(&mut chars, &snippet_range, detail);
-                format!("{}\n{}\n{}", msg, snippet, indented_detail)
+
+            // the line numbers (0-based) to show
+            let line_numbers: BTreeSet<usize> = segments.iter().map(|s| s.line).collect();
+
+            let mut last_line_number: Option<usize> = None;
+            for line_number in line_numbers {
+                if let Some(last) = last_line_number {
+                    if line_number == last + 2 {
+                        // show the single line in the gap
+                        lines.push(group.text.render_line(&painter, last + 1, gutter_width));
+                    } else if line_number > last + 2 {
+                        lines.push(painter.paint(STYLE_SECONDARY, "..."));
+                    }
+                }
+                last_line_number = Some(line_number);
+
+                lines.push(group.text.render_line(&painter, line_number, gutter_width));
+
+                let line_segments: Vec<&Segment> =
+                    segments.iter().filter(|s| s.line == line_number).collect();
+                for row in render_underlines(&line_segments) {
+                    // paints the continuous characters with the same style at once
+                    let mut rendered_row = String::new();
+                    let mut cells = row.as_slice();
+                    while let Some((_, style)) = cells.first() {
+                        let count = cells.iter().take_while(|(_, s)| s == style).count();
+                        let run: String = cells[..count].iter().map(|(c, _)| c).collect();
+                        rendered_row.push_str(&painter.paint(style, &run));
+                        cells = &cells[count..];
+                    }
+                    lines.push(format!("{} {}", empty_gutter, rendered_row.trim_end()));
+                }
             }
         }
+
+        if !self.notes.is_empty() || !self.helps.is_empty() {
+            if group_segments.iter().any(|segments| !segments.is_empty()) {
+                lines.push(empty_gutter);
+            }
+
+            let notes = self
+                .notes
+                .iter()
+                .map(|note| ("note", note))
+                .chain(self.helps.iter().map(|help| ("help", help)));
+            for (kind, content) in notes {
+                lines.push(format!(
+                    "{}{} {}",
+                    " ".repeat(gutter_width + 1),
+                    painter.paint(STYLE_SECONDARY, "="),
+                    painter.paint(STYLE_EMPHASIS, &format!("{}:", kind)) + " " + content
+                ));
+            }
+        }
+
+        lines.join("\n")
     }
 }
 
-#[cfg(test)]
-mod tests {
+// the labels of diagnostic in the same source file.
+pub(crate) struct LabelGroup<'a> {
+    pub file_name: Option<&'a str>,
+    pub text: SourceText,
+    pub labels: Vec<&'a DiagnosticLabel>,
+}
 
-    use pretty_assertions::assert_eq;
+struct Painter {
+    colored: bool,
+}
 
-    use crate::{location::Location, AsonError};
+impl Painter {
+    fn paint(&self, style: &str, text: &str) -> String {
+        if self.colored && !style.is_empty() && !text.trim().is_empty() {
+            format!("{}{}{}", style, text, STYLE_RESET)
+        } else {
+            text.to_owned()
+        }
+    }
+}
 
-    #[test]
-    fn test_error_with_source() {
-        let source1 = "0123456789"; // 10 chars
-        let source2 = "012345678_b12345678_c12345678_d12345678_e123456789"; // 50 chars
-        let msg = "abcde";
+// the underlined part of label on a single line.
+struct Segment<'a> {
+    line: usize,
+    column: usize, // display column
+    width: usize,  // display width
+    is_primary: bool,
 
-        assert_eq!(AsonError::Message(msg.to_owned()).with_source(source1), msg);
-        assert_eq!(AsonError::Message(msg.to_owned()).with_source(source2), msg);
-    }
+    // the message of multi-line label is shown at the last line
+    message: Option<&'a str>,
+}
 
-    #[test]
-    fn test_error_with_source_and_unexpected_end_of_document() {
-        let source1 = "0123456789"; // 10 chars
-        let source2 = "012345678_b12345678_c12345678_d12345678_e123456789"; // 50 chars
-        let msg = "abcde";
+pub(crate) struct SourceText {
+    chars: Vec<char>,
+    line_starts: Vec<usize>,
+    tab_width: usize,
+}
 
-        assert_eq!(
-            AsonError::UnexpectedEndOfDocument(msg.to_owned()).with_source(source1),
-            r#"Unexpected to reach the end of document.
-| 0123456789
-|           ^____ abcde"#
+impl SourceText {
+    pub fn new(source: &str, tab_width: usize) -> Self {
+        let chars: Vec<char> = source.chars().collect();
+        let mut line_starts = vec![0];
+        line_starts.extend(
+            chars
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| **c == '\n')
+                .map(|(index, _)| index + 1),
         );
 
-        assert_eq!(
-            AsonError::UnexpectedEndOfDocument(msg.to_owned()).with_source(source2),
-            r#"Unexpected to reach the end of document.
-| ...b12345678_c12345678_d12345678_e123456789
-|                                            ^____ abcde"#
-        );
+        Self {
+            chars,
+            line_starts,
+            tab_width,
+        }
     }
 
-    #[test]
-    fn test_error_with_source_and_location() {
-        let source1 = "0123456789"; // 10 chars
-        let source2 = "012345678_b12345678_c12345678_d12345678_e123456789"; // 50 chars
-        let msg = "abcde";
+    pub fn line_and_column(&self, index: usize) -> (usize, usize) {
+        let index = index.min(self.chars.len());
+        let line = self.line_starts.partition_point(|start| *start <= index) - 1;
+        (line, index - self.line_starts[line])
+    }
 
-        // first
+    // the char index of the end of line, excludes the `\r\n` and `\n`.
+    fn line_end(&self, line: usize) -> usize {
+        let end = match self.line_starts.get(line + 1) {
+            Some(next_start) => next_start - 1,
+            None => self.chars.len(),
+        };
 
-        assert_eq!(
-            AsonError::MessageWithLocation(
-                msg.to_owned(),
-                Location::new_position(/*0,*/ 0, 11, 13)
-            )
-            .with_source(source1),
-            r#"Error at line: 12, column: 14
-| 0123456789
-| ^____ abcde"#
-        );
+        if end > self.line_starts[line] && self.chars[end - 1] == '\r' {
+            end - 1
+        } else {
+            end
+        }
+    }
 
-        assert_eq!(
-            AsonError::MessageWithLocation(
-                msg.to_owned(),
-                Location::new_position(/*0,*/ 0, 11, 13)
-            )
-            .with_source(source2),
-            r#"Error at line: 12, column: 14
-| 012345678_b12345678_c12345678_d12345678_...
-| ^____ abcde"#
-        );
+    fn width(&self, start: usize, end: usize) -> usize {
+        self.chars[start..end]
+            .iter()
+            .map(|c| match c {
+                '\t' => self.tab_width,
+                _ => c.width().unwrap_or(0),
+            })
+            .sum()
+    }
 
-        // head
+    fn segment<'a>(
+        &self,
+        line: usize,
+        start: usize,
+        end: usize,
+        is_primary: bool,
+        message: Option<&'a str>,
+    ) -> Segment<'a> {
+        let line_start = self.line_starts[line];
+        let end = end.min(self.line_end(line)).max(start);
+        Segment {
+            line,
+            column: self.width(line_start, start),
+            width: self.width(start, end).max(1),
+            is_primary,
+            message,
+        }
+    }
 
-        assert_eq!(
-            AsonError::MessageWithLocation(
-                msg.to_owned(),
-                Location::new_position(/*0,*/ 2, 11, 13)
-            )
-            .with_source(source1),
-            r#"Error at line: 12, column: 14
-| 0123456789
-|   ^____ abcde"#
-        );
+    fn segments<'a>(&self, label: &'a DiagnosticLabel) -> Vec<Segment<'a>> {
+        let start = label.location.index.min(self.chars.len());
+        let end = (start + label.location.length).min(self.chars.len());
+        let message = Some(label.message.as_str()).filter(|m| !m.is_empty());
 
-        assert_eq!(
-            AsonError::MessageWithLocation(
-                msg.to_owned(),
-                Location::new_position(/*0,*/ 15, 11, 13)
-            )
-            .with_source(source2),
-            r#"Error at line: 12, column: 14
-| ...b12345678_c12345678_d12345678_e123456789
-|         ^____ abcde"#
-        );
+        let (start_line, _) = self.line_and_column(start);
+        let (end_line, _) = self.line_and_column(if end > start { end - 1 } else { start });
 
-        // middle
+        if start_line == end_line {
+            vec![self.segment(start_line, start, end, label.is_primary, message)]
+        } else {
+            // the multi-line label, underlines the first and the last line,
+            // the leading whitespaces of the last line are excluded.
+            let last_line_start = self.line_starts[end_line];
+            let last_line_text_start = (last_line_start..end)
+                .find(|i| !matches!(self.chars[*i], ' ' | '\t'))
+                .unwrap_or(last_line_start);
+
+            vec![
+                self.segment(
+                    start_line,
+                    start,
+                    self.line_end(start_line),
+                    label.is_primary,
+                    None,
+                ),
+                self.segment(
+                    end_line,
+                    last_line_text_start,
+                    end,
+                    label.is_primary,
+                    message,
+                ),
+            ]
+        }
+    }
 
-        assert_eq!(
-            AsonError::MessageWithLocation(
-                msg.to_owned(),
-                Location::new_position(/*0,*/ 5, 11, 13)
-            )
-            .with_source(source1),
-            r#"Error at line: 12, column: 14
-| 0123456789
-|      ^____ abcde"#
-        );
+    fn render_line(&self, painter: &Painter, line: usize, gutter_width: usize) -> String {
+        let text: String = self.chars[self.line_starts[line]..self.line_end(line)]
+            .iter()
+            .map(|c| match c {
+                '\t' => " ".repeat(self.tab_width),
+                _ => c.to_string(),
+            })
+            .collect();
+
+        format!(
+            "{} {}",
+            painter.paint(
+                STYLE_SECONDARY,
+                &format!("{:>width$} |", line + 1, width = gutter_width)
+            ),
+            text
+        )
+        .trim_end()
+        .to_owned()
+    }
+}
 
-        assert_eq!(
-            AsonError::MessageWithLocation(
-                msg.to_owned(),
-                Location::new_position(/*0,*/ 25, 11, 13)
-            )
-            .with_source(source2),
-            r#"Error at line: 12, column: 14
-| ...b12345678_c12345678_d12345678_e123456789
-|                   ^____ abcde"#
-        );
+// renders the underlines and messages of the segments of a line, e.g.
+//
+// ```text
+// --  ^^^^^ message of the last label
+// |
+// message of the first label
+// ```
+fn render_underlines(segments: &[&Segment]) -> Vec<Vec<(char, &'static str)>> {
+    if segments.is_empty() {
+        return vec![];
+    }
 
-        // tail
+    let style_of = |segment: &Segment| {
+        if segment.is_primary {
+            STYLE_ERROR
+        } else {
+            STYLE_SECONDARY
+        }
+    };
 
-        assert_eq!(
-            AsonError::MessageWithLocation(
-                msg.to_owned(),
-                Location::new_position(/*0,*/ 8, 11, 13)
-            )
-            .with_source(source1),
-            r#"Error at line: 12, column: 14
-| 0123456789
-|         ^____ abcde"#
+    fn put(row: &mut Vec<(char, &'static str)>, column: usize, text: &str, style: &'static str) {
+        for (offset, c) in text.chars().enumerate() {
+            let index = column + offset;
+            if row.len() <= index {
+                row.resize(index + 1, (' ', ""));
+            }
+            row[index] = (c, style);
+        }
+    }
+
+    let mut sorted_segments = segments.to_vec();
+    sorted_segments.sort_by_key(|s| (s.column, s.width));
+
+    // the primary underlines are drawn over the secondary ones.
+    let mut underline_row = vec![];
+    for segment in sorted_segments.iter().filter(|s| !s.is_primary) {
+        put(
+            &mut underline_row,
+            segment.column,
+            &"-".repeat(segment.width),
+            STYLE_SECONDARY,
+        );
+    }
+    for segment in sorted_segments.iter().filter(|s| s.is_primary) {
+        put(
+            &mut underline_row,
+            segment.column,
+            &"^".repeat(segment.width),
+            STYLE_ERROR,
         );
+    }
 
-        assert_eq!(
-            AsonError::MessageWithLocation(
-                msg.to_owned(),
-                Location::new_position(/*0,*/ 45, 11, 13)
-            )
-            .with_source(source2),
-            r#"Error at line: 12, column: 14
-| ...b12345678_c12345678_d12345678_e123456789
-|                                       ^____ abcde"#
+    let mut labeled_segments: Vec<&Segment> = sorted_segments
+        .iter()
+        .copied()
+        .filter(|s| s.message.is_some())
+        .collect();
+
+    // the message of the last segment is placed after the underlines.
+    if let Some(last) = labeled_segments.last() {
+        if std::ptr::eq(*last, *sorted_segments.last().unwrap()) {
+            let column = underline_row.len() + 1;
+            put(
+                &mut underline_row,
+                column,
+                last.message.unwrap(),
+                style_of(last),
+            );
+            labeled_segments.pop();
+        }
+    }
+
+    let mut rows = vec![underline_row];
+
+    // the other messages are placed below, from right to left.
+    for index in (0..labeled_segments.len()).rev() {
+        let mut connector_row = vec![];
+        let mut message_row = vec![];
+        for (other_index, segment) in labeled_segments[..=index].iter().enumerate() {
+            put(&mut connector_row, segment.column, "|", style_of(segment));
+            if other_index < index {
+                put(&mut message_row, segment.column, "|", style_of(segment));
+            }
+        }
+
+        let segment = labeled_segments[index];
+        put(
+            &mut message_row,
+            segment.column,
+            segment.message.unwrap(),
+            style_of(segment),
         );
 
-        // last
+        rows.push(connector_row);
+        rows.push(message_row);
+    }
+
+    rows
+}
+
+impl AsonError {
+    /// Renders the error with the snippet of the source text, in the
+    /// style of the Rust compiler with the default options, e.g.
+    ///
+    /// ```text
+    /// error: Unexpected token.
+    ///  --> 1:17
+    ///   |
+    /// 1 | {id: 123, name: :}
+    ///   |                 ^
+    /// ```
+    ///
+    /// It is the shortcut of `to_diagnostic(source).render(source, &options)`.
+    pub fn with_source(&self, source: &str) -> String {
+        self.to_diagnostic(source)
+            .render(source, &RenderOptions::default())
+    }
+
+    /// Converts the error into `Diagnostic` for rendering.
+    pub fn to_diagnostic(&self, source: &str) -> Diagnostic {
+        let diagnostic = match (self.kind, &self.location) {
+            (ErrorKind::UnexpectedEndOfDocument, _) => {
+                let text = SourceText::new(source, DEFAULT_TAB_WIDTH);
+                let index = text.chars.len();
+                let (line, column) = text.line_and_column(index);
+                let unit = self.location.map_or(0, |location| location.unit);
+                Diagnostic::new("Unexpected to reach the end of document.").with_primary_label(
+                    Location::new_position(index, line, column)
+                        .with_unit(unit)
+                        .with_byte_index(source.len()),
+                    &self.message,
+                )
+            }
+            (_, Some(location)) => Diagnostic::new(&self.message).with_primary_label(*location, ""),
+            (_, None) => Diagnostic::new(&self.message),
+        };
+
+        match self.path() {
+            Some(path) if !path.is_root() => {
+                diagnostic.with_note(&format!("the path of the value is \"{}\"", path))
+            }
+            _ => diagnostic,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{location::Location, AsonError, ErrorKind};
+
+    use super::{Diagnostic, RenderOptions};
 
+    #[test]
+    fn test_error_with_source() {
+        let source = "0123456789";
+        let msg = "abcde";
+
+        // message only
         assert_eq!(
-            AsonError::MessageWithLocation(
-                msg.to_owned(),
-                Location::new_position(/*0,*/ 10, 11, 13)
-            )
-            .with_source(source1),
-            r#"Error at line: 12, column: 14
-| 0123456789
-|           ^____ abcde"#
+            AsonError::new(ErrorKind::Custom, msg.to_owned()).with_source(source),
+            "error: abcde"
         );
 
+        // end of document
         assert_eq!(
-            AsonError::MessageWithLocation(
-                msg.to_owned(),
-                Location::new_position(/*0,*/ 50, 11, 13)
-            )
-            .with_source(source2),
-            r#"Error at line: 12, column: 14
-| ...b12345678_c12345678_d12345678_e123456789
-|                                            ^____ abcde"#
+            AsonError::new_unexpected_end_of_document(msg.to_owned()).with_source(source),
+            r#"error: Unexpected to reach the end of document.
+ --> 1:11
+  |
+1 | 0123456789
+  |           ^ abcde"#
         );
+
+        // positions
+        for (index, expected) in [
+            (0, "  | ^"),
+            (5, "  |      ^"),
+            (10, "  |           ^"),
+            (100, "  |           ^"),
+        ] {
+            assert_eq!(
+                AsonError::new_syntax(msg.to_owned(), Location::new_position(index, 0, index))
+                    .with_source(source),
+                format!(
+                    "error: abcde\n --> 1:{}\n  |\n1 | 0123456789\n{}",
+                    index.min(10) + 1,
+                    expected
+                )
+            );
+        }
+
+        // ranges, the part beyond the end of line is excluded
+        for (index, length, expected) in [
+            (0, 4, "  | ^^^^"),
+            (5, 4, "  |      ^^^^"),
+            (8, 4, "  |         ^^"),
+        ] {
+            assert_eq!(
+                AsonError::new_syntax(msg.to_owned(), Location::new_range(index, 0, index, length))
+                    .with_source(source),
+                format!(
+                    "error: abcde\n --> 1:{}\n  |\n1 | 0123456789\n{}",
+                    index + 1,
+                    expected
+                )
+            );
+        }
     }
 
     #[test]
-    fn test_error_with_source_and_range() {
-        let source1 = "0123456789"; // 10 chars
-        let source2 = "012345678_b12345678_c12345678_d12345678_e123456789"; // 50 chars
-        let msg = "abcde";
-
-        // first
+    fn test_error_with_multiple_lines_source() {
+        // the lines are not joined, and the long line is not truncated
+        let source =
+            "{\n    id: 123\n    description: \"012345678_b12345678_c12345678_d12345678_e\"\n    name: \n}";
+        let error = crate::parse_from_str(source).unwrap_err();
 
         assert_eq!(
-            AsonError::MessageWithLocation(
-                msg.to_owned(),
-                Location::new_range(/*0,*/ 0, 17, 19, 4)
-            )
-            .with_source(source1),
-            r#"Error at line: 18, column: 20
-| 0123456789
-| ^^^^ abcde"#
+            error.with_source(source),
+            r#"error: Unexpected token.
+ --> 5:1
+  |
+5 | }
+  | ^"#
         );
 
         assert_eq!(
-            AsonError::MessageWithLocation(
-                msg.to_owned(),
-                Location::new_range(/*0,*/ 0, 17, 19, 8)
+            AsonError::new_syntax(
+                "foo".to_owned(),
+                Location::new_range(31, 2, 17, 43).with_byte_index(31)
             )
-            .with_source(source2),
-            r#"Error at line: 18, column: 20
-| 012345678_b12345678_c12345678_d12345678_...
-| ^^^^^^^^ abcde"#
+            .with_source(source),
+            r#"error: foo
+ --> 3:18
+  |
+3 |     description: "012345678_b12345678_c12345678_d12345678_e"
+  |                  ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^"#
         );
+    }
 
-        // head
+    #[test]
+    fn test_error_with_source_and_wide_chars() {
+        let source = "{name: \"文字\"\t😀}";
 
         assert_eq!(
-            AsonError::MessageWithLocation(
-                msg.to_owned(),
-                Location::new_range(/*0,*/ 2, 17, 19, 4)
+            AsonError::new_syntax(
+                "Expect token: right brace.".to_owned(),
+                Location::new_position(12, 0, 12)
             )
-            .with_source(source1),
-            r#"Error at line: 18, column: 20
-| 0123456789
-|   ^^^^ abcde"#
+            .with_source(source),
+            r#"error: Expect token: right brace.
+ --> 1:13
+  |
+1 | {name: "文字"    😀}
+  |                  ^"#
         );
+    }
 
-        assert_eq!(
-            AsonError::MessageWithLocation(
-                msg.to_owned(),
-                Location::new_range(/*0,*/ 15, 17, 19, 8)
+    #[test]
+    fn test_render_diagnostic() {
+        let source = "{\n    id: 123\n    scores: [11, \"foo\"]\n}";
+        let diagnostic = Diagnostic::new("Mixed types in List.")
+            .with_primary_label(
+                Location::new_range(31, 2, 17, 5),
+                "list element type differs from this first element",
             )
-            .with_source(source2),
-            r#"Error at line: 18, column: 20
-| ...b12345678_c12345678_d12345678_e123456789
-|         ^^^^^^^^ abcde"#
-        );
+            .with_secondary_label(Location::new_range(27, 2, 13, 2), "first element")
+            .with_help("change all elements to the same type");
 
-        // middle
+        assert_eq!(
+            diagnostic.render(
+                source,
+                &RenderOptions {
+                    file_name: Some("data.ason".to_owned()),
+                    ..Default::default()
+                }
+            ),
+            r#"error: Mixed types in List.
+ --> data.ason:3:18
+  |
+3 |     scores: [11, "foo"]
+  |              --  ^^^^^ list element type differs from this first element
+  |              |
+  |              first element
+  |
+  = help: change all elements to the same type"#
+        );
 
+        // colored
         assert_eq!(
-            AsonError::MessageWithLocation(
-                msg.to_owned(),
-                Location::new_range(/*0,*/ 5, 17, 19, 4)
-            )
-            .with_source(source1),
-            r#"Error at line: 18, column: 20
-| 0123456789
-|      ^^^^ abcde"#
+            Diagnostic::new("foo")
+                .with_primary_label(Location::new_range(1, 0, 1, 1), "bar")
+                .render(
+                    "[1]",
+                    &RenderOptions {
+                        colored: true,
+                        ..Default::default()
+                    }
+                ),
+            "\x1b[1;31merror\x1b[0m\x1b[1m: foo\x1b[0m
+ \x1b[1;34m-->\x1b[0m 1:2
+  \x1b[1;34m|\x1b[0m
+\x1b[1;34m1 |\x1b[0m [1]
+  \x1b[1;34m|\x1b[0m  \x1b[1;31m^\x1b[0m \x1b[1;31mbar\x1b[0m"
         );
 
+        // message only
         assert_eq!(
-            AsonError::MessageWithLocation(
-                msg.to_owned(),
-                Location::new_range(/*0,*/ 25, 17, 19, 8)
-            )
-            .with_source(source2),
-            r#"Error at line: 18, column: 20
-| ...b12345678_c12345678_d12345678_e123456789
-|                   ^^^^^^^^ abcde"#
+            AsonError::new(ErrorKind::Custom, "foo".to_owned())
+                .to_diagnostic("")
+                .render("", &RenderOptions::default()),
+            "error: foo"
         );
+    }
 
-        // tail
+    #[test]
+    fn test_render_diagnostic_with_tabs_and_wide_chars() {
+        let source = "{\n\tname: \"文字\" 😀: 1\n}";
+        let diagnostic = AsonError::new_syntax(
+            "Expect token: right brace.".to_owned(),
+            Location::new_position(14, 1, 12),
+        )
+        .to_diagnostic(source);
 
         assert_eq!(
-            AsonError::MessageWithLocation(
-                msg.to_owned(),
-                Location::new_range(/*0,*/ 8, 17, 19, 4)
-            )
-            .with_source(source1),
-            r#"Error at line: 18, column: 20
-| 0123456789
-|         ^^ abcde"#
+            diagnostic.render(source, &RenderOptions::default()),
+            r#"error: Expect token: right brace.
+ --> 2:13
+  |
+2 |     name: "文字" 😀: 1
+  |                  ^"#
         );
 
         assert_eq!(
-            AsonError::MessageWithLocation(
-                msg.to_owned(),
-                Location::new_range(/*0,*/ 45, 17, 19, 8)
-            )
-            .with_source(source2),
-            r#"Error at line: 18, column: 20
-| ...b12345678_c12345678_d12345678_e123456789
-|                                       ^^^^^ abcde"#
+            diagnostic.render(
+                source,
+                &RenderOptions {
+                    tab_width: 2,
+                    ..Default::default()
+                }
+            ),
+            r#"error: Expect token: right brace.
+ --> 2:13
+  |
+2 |   name: "文字" 😀: 1
+  |                ^"#
         );
+    }
 
-        // last
+    #[test]
+    fn test_render_diagnostic_with_multiple_lines() {
+        let source = "[\n  1\n  2\n  3\n  4\n]";
+        let diagnostic = Diagnostic::new("foo")
+            .with_primary_label(Location::new_range(0, 0, 0, 19), "the list")
+            .with_secondary_label(Location::new_range(4, 1, 2, 1), "first element")
+            .with_note("bar");
 
         assert_eq!(
-            AsonError::MessageWithLocation(
-                msg.to_owned(),
-                Location::new_range(/*0,*/ 10, 17, 19, 4)
-            )
-            .with_source(source1),
-            r#"Error at line: 18, column: 20
-| 0123456789
-|           ^____ abcde"#
+            diagnostic.render(source, &RenderOptions::default()),
+            r#"error: foo
+ --> 1:1
+  |
+1 | [
+  | ^
+2 |   1
+  |   - first element
+...
+6 | ]
+  | ^ the list
+  |
+  = note: bar"#
         );
 
+        // end of document
         assert_eq!(
-            AsonError::MessageWithLocation(
-                msg.to_owned(),
-                Location::new_range(/*0,*/ 50, 17, 19, 8)
-            )
-            .with_source(source2),
-            r#"Error at line: 18, column: 20
-| ...b12345678_c12345678_d12345678_e123456789
-|                                            ^____ abcde"#
+            AsonError::new_unexpected_end_of_document("Expect token: right brace.".to_owned())
+                .to_diagnostic("{a: 1,\n")
+                .render("{a: 1,\n", &RenderOptions::default()),
+            r#"error: Unexpected to reach the end of document.
+ --> 2:1
+  |
+2 |
+  | ^ Expect token: right brace."#
         );
     }
 }
@@ -0,0 +1,726 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+//! Splits a document across files with the `Include::File("path")` variant.
+//!
+//! The variant is replaced by the value of the referenced document
+//! when parsing, e.g.
+//!
+//! ```text
+//! {
+//!     name: "app"
+//!     database: Include::File("db.ason")
+//! }
+//! ```
+//!
+//! The documents are loaded by an `IncludeResolver`, the default
+//! `FileResolver` reads files under a root directory only. Each loaded
+//! document is a source unit, and the `unit` of `Location` in errors
+//! is the index of the unit where the error is raised, the errors can be
+//! rendered with the unit by `IncludeParser::source_map`.
+//!
+//! Each document is parsed with the `ParseOptions` of `IncludeParser`,
+//! so an anchor is only visible in the document where it is defined.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    ast::{AsonNode, VariantValue},
+    location::Location,
+    options::ParseOptions,
+    path::AsonPath,
+    AsonError, ErrorKind, SourceMap,
+};
+
+// the variant `Include::File(...)` is reserved for including documents.
+const INCLUDE_TYPE_NAME: &str = "Include";
+const INCLUDE_FILE_MEMBER_NAME: &str = "File";
+
+/// A loaded document, the name identifies the document in errors
+/// and in the cycle detection, e.g. the path relative to the root directory.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SourceUnit {
+    pub name: String,
+    pub text: String,
+}
+
+pub trait IncludeResolver {
+    /// Loads the document referenced by `target`, where `including` is
+    /// the name of the unit that contains the `Include::File(target)`.
+    ///
+    /// The units with the same name are treated as the same document.
+    fn resolve(&mut self, target: &str, including: &str) -> Result<SourceUnit, AsonError>;
+}
+
+/// Resolves the includes as files relative to the directory of the
+/// including file, the files outside the root directory are rejected.
+///
+/// The names of units are the paths relative to the root directory.
+pub struct FileResolver {
+    root: PathBuf,
+}
+
+impl FileResolver {
+    pub fn new<P: AsRef<Path>>(root: P) -> Result<Self, AsonError> {
+        let root = fs::canonicalize(root.as_ref()).map_err(|e| {
+            new_include_failed_error(&format!(
+                "Can not open the root directory \"{}\", {}.",
+                root.as_ref().display(),
+                e
+            ))
+            .caused_by(e)
+        })?;
+
+        Ok(Self { root })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+impl IncludeResolver for FileResolver {
+    fn resolve(&mut self, target: &str, including: &str) -> Result<SourceUnit, AsonError> {
+        let base = match self.root.join(including).parent() {
+            Some(dir) if !including.is_empty() => dir.to_path_buf(),
+            _ => self.root.clone(),
+        };
+
+        let file_path = fs::canonicalize(base.join(target)).map_err(|e| {
+            new_include_failed_error(&format!("Can not open file \"{}\", {}.", target, e))
+                .caused_by(e)
+        })?;
+
+        // the symbolic links are resolved by `canonicalize`, so a link
+        // to a file outside the root directory is rejected as well.
+        let relative_path = file_path.strip_prefix(&self.root).map_err(|_| {
+            new_include_failed_error(&format!(
+                "File \"{}\" is outside the root directory.",
+                target
+            ))
+        })?;
+
+        let text = fs::read_to_string(&file_path).map_err(|e| {
+            new_include_failed_error(&format!("Can not read file \"{}\", {}.", target, e))
+                .caused_by(e)
+        })?;
+
+        let name = relative_path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        Ok(SourceUnit { name, text })
+    }
+}
+
+/// Parses a document and the documents it includes, recursively.
+pub struct IncludeParser<'a, R: IncludeResolver> {
+    resolver: R,
+    options: ParseOptions<'a>,
+    source_map: SourceMap,
+
+    // the expanded values of the units and their sizes in bytes,
+    // a unit is parsed once no matter how many times it is included.
+    expanded: Vec<Option<(AsonNode, usize)>>,
+
+    // the total size of the units in bytes, counted each time
+    // they are included, it is limited by `Limits::max_document_size`,
+    // to prevent the exponential expansion of nested includes.
+    expanded_size: usize,
+}
+
+impl<'a, R: IncludeResolver> IncludeParser<'a, R> {
+    pub fn new(resolver: R) -> Self {
+        Self::with_options(resolver, ParseOptions::default())
+    }
+
+    /// The options are applied to each document, and the `max_document_size`
+    /// of limits limits the total size of the expanded document as well, i.e.
+    /// the size of an included document is counted each time it is included.
+    pub fn with_options(resolver: R, options: ParseOptions<'a>) -> Self {
+        Self {
+            resolver,
+            options,
+            source_map: SourceMap::new(),
+            expanded: vec![],
+            expanded_size: 0,
+        }
+    }
+
+    /// Parses the text of the main document (the unit 0), the includes
+    /// are resolved relative to the specified name.
+    pub fn parse(&mut self, name: &str, text: &str) -> Result<AsonNode, AsonError> {
+        self.source_map.clear();
+        self.source_map.add(name, text);
+        self.parse_main_unit()
+    }
+
+    /// Loads the main document by the resolver and parses it,
+    /// e.g. `parse_file("main.ason")` with `FileResolver`.
+    pub fn parse_file(&mut self, target: &str) -> Result<AsonNode, AsonError> {
+        let unit = self.resolver.resolve(target, "")?;
+        self.source_map.clear();
+        self.source_map.add_unit(unit);
+        self.parse_main_unit()
+    }
+
+    /// Returns the units loaded by the last parsing, the index of unit
+    /// is the `unit` of `Location`.
+    pub fn units(&self) -> &[SourceUnit] {
+        self.source_map.units()
+    }
+
+    /// Returns the unit where the location is.
+    pub fn source_of(&self, location: &Location) -> Option<&SourceUnit> {
+        self.source_map.source_of(location)
+    }
+
+    /// Returns the source map of the units loaded by the last parsing,
+    /// it is used to print the errors, e.g. `render_error(&e, &options)`.
+    pub fn source_map(&self) -> &SourceMap {
+        &self.source_map
+    }
+
+    pub fn into_resolver(self) -> R {
+        self.resolver
+    }
+
+    fn parse_main_unit(&mut self) -> Result<AsonNode, AsonError> {
+        self.expanded.clear();
+        self.expanded_size = 0;
+        self.add_expanded_size(self.source_map.units()[0].text.len(), &AsonPath::root())?;
+        self.parse_unit(0, &mut vec![])
+    }
+
+    // `stack` is the indices of the units being parsed, i.e. the chain
+    // of includes, which is used to detect the circular includes.
+    //
+    // the size of the unit is counted by the caller.
+    fn parse_unit(&mut self, index: usize, stack: &mut Vec<usize>) -> Result<AsonNode, AsonError> {
+        let mut node = self
+            .options
+            .parse_str(&self.source_map.units()[index].text)
+            .map_err(|e| match e.location {
+                Some(location) => e.with_location(location.with_unit(index)),
+                None => e,
+            })?;
+
+        stack.push(index);
+        self.expand(&mut node, &mut AsonPath::root(), stack)?;
+        stack.pop();
+
+        Ok(node)
+    }
+
+    fn add_expanded_size(&mut self, size: usize, path: &AsonPath) -> Result<(), AsonError> {
+        self.expanded_size += size;
+        if self.expanded_size > self.options.limits.max_document_size {
+            return Err(AsonError::new(
+                ErrorKind::DocumentSizeLimitExceeded,
+                format!(
+                    "The size of expanded document exceeds the limit {} bytes.",
+                    self.options.limits.max_document_size
+                ),
+            )
+            .with_path(path.clone()));
+        }
+        Ok(())
+    }
+
+    fn expand(
+        &mut self,
+        node: &mut AsonNode,
+        path: &mut AsonPath,
+        stack: &mut Vec<usize>,
+    ) -> Result<(), AsonError> {
+        match node {
+            AsonNode::Variant(variant) if variant.type_name == INCLUDE_TYPE_NAME => {
+                let target = match (variant.member_name.as_str(), &variant.value) {
+                    (INCLUDE_FILE_MEMBER_NAME, VariantValue::Value(value)) => {
+                        match value.as_ref() {
+                            AsonNode::String(target) => target.to_owned(),
+                            _ => return Err(self.new_invalid_include_error(path, stack)),
+                        }
+                    }
+                    _ => return Err(self.new_invalid_include_error(path, stack)),
+                };
+
+                *node = self.include(&target, path, stack)?;
+            }
+            AsonNode::Variant(variant) => match &mut variant.value {
+                VariantValue::Empty => {}
+                VariantValue::Value(value) => {
+                    path.push_index(0);
+                    self.expand(value, path, stack)?;
+                    path.segments.pop();
+                }
+                VariantValue::Tuple(items) => self.expand_items(items, path, stack)?,
+                VariantValue::Object(kvps) => {
+                    for kvp in kvps {
+                        path.push_key(&kvp.key);
+                        self.expand(&mut kvp.value, path, stack)?;
+                        path.segments.pop();
+                    }
+                }
+            },
+            AsonNode::List(items) | AsonNode::Tuple(items) => {
+                self.expand_items(items, path, stack)?
+            }
+            AsonNode::Object(kvps) => {
+                for kvp in kvps {
+                    path.push_key(&kvp.key);
+                    self.expand(&mut kvp.value, path, stack)?;
+                    path.segments.pop();
+                }
+            }
+            AsonNode::Map(nvps) => {
+                for nvp in nvps {
+                    path.push_name(&nvp.name);
+                    self.expand(&mut nvp.value, path, stack)?;
+                    path.segments.pop();
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn expand_items(
+        &mut self,
+        items: &mut [AsonNode],
+        path: &mut AsonPath,
+        stack: &mut Vec<usize>,
+    ) -> Result<(), AsonError> {
+        for (index, item) in items.iter_mut().enumerate() {
+            path.push_index(index);
+            self.expand(item, path, stack)?;
+            path.segments.pop();
+        }
+        Ok(())
+    }
+
+    fn include(
+        &mut self,
+        target: &str,
+        path: &AsonPath,
+        stack: &mut Vec<usize>,
+    ) -> Result<AsonNode, AsonError> {
+        let including = self.including_name(stack).to_owned();
+
+        let unit = self.resolver.resolve(target, &including).map_err(|e| {
+            AsonError::new(
+                ErrorKind::IncludeFailed,
+                format!(
+                    "Can not include \"{}\" in \"{}\": {}",
+                    target, including, e.message
+                ),
+            )
+            .with_path(path.clone())
+            .caused_by(e)
+        })?;
+
+        let index = match self.source_map.find(&unit.name) {
+            Some(index) => index,
+            None => self.source_map.add_unit(unit),
+        };
+
+        if stack.contains(&index) {
+            let chain = stack
+                .iter()
+                .chain([&index])
+                .map(|i| format!("\"{}\"", self.source_map.units()[*i].name))
+                .collect::<Vec<_>>()
+                .join(" -> ");
+
+            return Err(AsonError::new(
+                ErrorKind::IncludeFailed,
+                format!("Circular include in \"{}\": {}.", including, chain),
+            )
+            .with_path(path.clone()));
+        }
+
+        if let Some(Some((node, size))) = self.expanded.get(index) {
+            let node = node.clone();
+            self.add_expanded_size(*size, path)?;
+            return Ok(node);
+        }
+
+        let start_size = self.expanded_size;
+        self.add_expanded_size(self.source_map.units()[index].text.len(), path)?;
+
+        let node = self.parse_unit(index, stack).map_err(|e| {
+            // the errors of nested includes name their including units already.
+            if e.location.is_some_and(|location| location.unit == index) {
+                let message = format!(
+                    "{} (in \"{}\" included from \"{}\"{})",
+                    e.message,
+                    self.source_map.units()[index].name,
+                    including,
+                    describe_path(path)
+                );
+                AsonError { message, ..e }
+            } else {
+                e
+            }
+        })?;
+
+        if self.expanded.len() <= index {
+            self.expanded.resize(index + 1, None);
+        }
+        self.expanded[index] = Some((node.clone(), self.expanded_size - start_size));
+
+        Ok(node)
+    }
+
+    fn including_name(&self, stack: &[usize]) -> &str {
+        let index = stack.last().copied().unwrap_or(0);
+        &self.source_map.units()[index].name
+    }
+
+    fn new_invalid_include_error(&self, path: &AsonPath, stack: &[usize]) -> AsonError {
+        AsonError::new(
+            ErrorKind::IncludeFailed,
+            format!(
+                "Invalid include in \"{}\", expect `{}::{}(\"path\")`.",
+                self.including_name(stack),
+                INCLUDE_TYPE_NAME,
+                INCLUDE_FILE_MEMBER_NAME
+            ),
+        )
+        .with_path(path.clone())
+    }
+}
+
+fn describe_path(path: &AsonPath) -> String {
+    if path.is_root() {
+        String::new()
+    } else {
+        format!(" at \"{}\"", path)
+    }
+}
+
+fn new_include_failed_error(message: &str) -> AsonError {
+    AsonError::new(ErrorKind::IncludeFailed, message.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs};
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        ast::AsonNode, location::Location, options::ParseOptions, parser::parse_from_str,
+        path::AsonPath, AsonError, ErrorKind, Limits, RenderOptions,
+    };
+
+    use super::{FileResolver, IncludeParser, IncludeResolver, SourceUnit};
+
+    // resolves the includes by name from a set of in-memory documents.
+    struct MemoryResolver {
+        documents: HashMap<String, String>,
+    }
+
+    impl MemoryResolver {
+        fn new(documents: &[(&str, &str)]) -> Self {
+            Self {
+                documents: documents
+                    .iter()
+                    .map(|(name, text)| (name.to_string(), text.to_string()))
+                    .collect(),
+            }
+        }
+    }
+
+    impl IncludeResolver for MemoryResolver {
+        fn resolve(&mut self, target: &str, _including: &str) -> Result<SourceUnit, AsonError> {
+            match self.documents.get(target) {
+                Some(text) => Ok(SourceUnit {
+                    name: target.to_owned(),
+                    text: text.to_owned(),
+                }),
+                None => Err(AsonError::new(
+                    ErrorKind::IncludeFailed,
+                    "not found.".to_owned(),
+                )),
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let resolver = MemoryResolver::new(&[
+            (
+                "db.ason",
+                r#"{host: "localhost", port: 5432, pool: Include::File("pool.ason")}"#,
+            ),
+            ("pool.ason", "{size: 8}"),
+            ("tags.ason", r#"["a", "b"]"#),
+        ]);
+
+        let mut parser = IncludeParser::new(resolver);
+        let node = parser
+            .parse(
+                "main.ason",
+                r#"{
+                    name: "app"
+                    database: Include::File("db.ason")
+                    tags: [Include::File("tags.ason"), Option::Some(Include::File("pool.ason"))]
+                }"#,
+            )
+            .unwrap();
+
+        assert_eq!(
+            node,
+            parse_from_str(
+                r#"{
+                    name: "app"
+                    database: {host: "localhost", port: 5432, pool: {size: 8}}
+                    tags: [["a", "b"], Option::Some({size: 8})]
+                }"#
+            )
+            .unwrap()
+        );
+
+        // the same document is loaded as one unit
+        assert_eq!(
+            parser
+                .units()
+                .iter()
+                .map(|unit| unit.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["main.ason", "db.ason", "pool.ason", "tags.ason"]
+        );
+    }
+
+    #[test]
+    fn test_parse_with_options() {
+        let resolver = MemoryResolver::new(&[
+            (
+                "db.ason",
+                r#"{host: "${DB_HOST}", limits: Anchor::Define("a", 8), pool: Anchor::Alias("a")}"#,
+            ),
+            ("alias.ason", r#"Anchor::Alias("a")"#),
+        ]);
+        let variables = HashMap::from([("DB_HOST".to_owned(), "example.com".to_owned())]);
+
+        let mut parser = IncludeParser::with_options(
+            resolver,
+            ParseOptions::new()
+                .with_variables(&variables)
+                .with_anchors(),
+        );
+        assert_eq!(
+            parser
+                .parse("main.ason", r#"{database: Include::File("db.ason")}"#)
+                .unwrap(),
+            parse_from_str(r#"{database: {host: "example.com", limits: 8, pool: 8}}"#).unwrap()
+        );
+
+        // the anchors of the other documents are not visible
+        let err = parser
+            .parse(
+                "main.ason",
+                r#"[Include::File("db.ason"), Include::File("alias.ason")]"#,
+            )
+            .unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidReference);
+        assert_eq!(err.location.unwrap().unit, 2);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let resolver = MemoryResolver::new(&[
+            ("a.ason", r#"{b: Include::File("b.ason")}"#),
+            ("b.ason", r#"[Include::File("a.ason")]"#),
+            ("bad.ason", "{id: 123,\n  name: }"),
+            ("nested.ason", r#"{bad: Include::File("bad.ason")}"#),
+        ]);
+        let mut parser = IncludeParser::new(resolver);
+
+        // not found
+        let err = parser
+            .parse("main.ason", r#"{db: Include::File("db.ason")}"#)
+            .unwrap_err();
+        assert_eq!(err.kind, ErrorKind::IncludeFailed);
+        assert_eq!(
+            err.message,
+            r#"Can not include "db.ason" in "main.ason": not found."#
+        );
+        assert_eq!(err.path(), Some(&AsonPath::parse(".db").unwrap()));
+
+        // circular include
+        let err = parser
+            .parse("main.ason", r#"{a: Include::File("a.ason")}"#)
+            .unwrap_err();
+        assert_eq!(err.kind, ErrorKind::IncludeFailed);
+        assert_eq!(
+            err.message,
+            r#"Circular include in "b.ason": "main.ason" -> "a.ason" -> "b.ason" -> "a.ason"."#
+        );
+        assert_eq!(err.path(), Some(&AsonPath::parse("[0]").unwrap()));
+
+        // invalid include
+        let err = parser
+            .parse("main.ason", r#"{a: Include::File(123)}"#)
+            .unwrap_err();
+        assert_eq!(err.kind, ErrorKind::IncludeFailed);
+        assert_eq!(
+            err.message,
+            r#"Invalid include in "main.ason", expect `Include::File("path")`."#
+        );
+
+        // syntax error in the included document
+        let err = parser
+            .parse("main.ason", r#"{n: Include::File("nested.ason")}"#)
+            .unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Syntax);
+        assert!(err
+            .message
+            .ends_with(r#"(in "bad.ason" included from "nested.ason" at ".bad")"#));
+
+        let location = err.location.unwrap();
+        assert_eq!(
+            location,
+            Location {
+                unit: 2,
+                index: 18,
+                byte_index: 18,
+                line: 1,
+                column: 8,
+                length: 0
+            }
+        );
+        assert_eq!(parser.source_of(&location).unwrap().name, "bad.ason");
+        assert_eq!(
+            parser.source_map().resolve(&location).unwrap().to_string(),
+            "bad.ason:2:9"
+        );
+        assert!(parser
+            .source_map()
+            .render_error(&err, &RenderOptions::default())
+            .contains("\n --> bad.ason:2:9\n"));
+
+        // syntax error in the main document
+        let err = parser.parse("main.ason", "{id: }").unwrap_err();
+        assert_eq!(err.location.unwrap().unit, 0);
+    }
+
+    #[test]
+    fn test_expanded_size_limit() {
+        // each level doubles the size of the expanded document
+        let resolver = MemoryResolver::new(&[
+            (
+                "a.ason",
+                r#"[Include::File("b.ason"), Include::File("b.ason")]"#,
+            ),
+            (
+                "b.ason",
+                r#"[Include::File("c.ason"), Include::File("c.ason")]"#,
+            ),
+            ("c.ason", "[1, 2, 3, 4, 5, 6, 7, 8]"),
+        ]);
+        let text = r#"[Include::File("a.ason"), Include::File("a.ason")]"#;
+
+        let mut parser = IncludeParser::new(resolver);
+        let node = parser.parse("main.ason", text).unwrap();
+        let c = parse_from_str("[1, 2, 3, 4, 5, 6, 7, 8]").unwrap();
+        let b = AsonNode::List(vec![c.clone(), c]);
+        let a = AsonNode::List(vec![b.clone(), b]);
+        assert_eq!(node, AsonNode::List(vec![a.clone(), a]));
+
+        // the units are parsed once, and the sizes of the expanded units
+        // are cached along with the values.
+        let sizes = parser
+            .expanded
+            .iter()
+            .map(|entry| entry.as_ref().map(|(_, size)| *size))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sizes,
+            vec![
+                None,
+                Some(50 + 2 * (50 + 2 * 24)),
+                Some(50 + 2 * 24),
+                Some(24)
+            ]
+        );
+        assert_eq!(parser.expanded_size, 50 + 2 * (50 + 2 * (50 + 2 * 24)));
+
+        // the total size is limited, though each document is small
+        let mut parser = IncludeParser::with_options(
+            parser.into_resolver(),
+            ParseOptions::new().with_limits(Limits {
+                max_document_size: 400,
+                ..Limits::default()
+            }),
+        );
+        let err = parser.parse("main.ason", text).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::DocumentSizeLimitExceeded);
+        assert_eq!(
+            err.message,
+            "The size of expanded document exceeds the limit 400 bytes."
+        );
+        assert_eq!(err.path(), Some(&AsonPath::parse("[1]").unwrap()));
+    }
+
+    #[test]
+    fn test_file_resolver() {
+        let dir = std::env::temp_dir().join(format!("ason-include-{}", std::process::id()));
+        let root = dir.join("config");
+        fs::create_dir_all(root.join("services")).unwrap();
+
+        fs::write(
+            root.join("main.ason"),
+            r#"{web: Include::File("services/web.ason")}"#,
+        )
+        .unwrap();
+        fs::write(
+            root.join("services/web.ason"),
+            r#"{port: 8080, common: Include::File("../common.ason")}"#,
+        )
+        .unwrap();
+        fs::write(root.join("common.ason"), "{debug: false}").unwrap();
+        fs::write(
+            root.join("escape.ason"),
+            r#"{secret: Include::File("../secret.ason")}"#,
+        )
+        .unwrap();
+        fs::write(dir.join("secret.ason"), "{password: \"123\"}").unwrap();
+
+        let mut parser = IncludeParser::new(FileResolver::new(&root).unwrap());
+
+        let node = parser.parse_file("main.ason").unwrap();
+        assert_eq!(
+            node,
+            parse_from_str(r#"{web: {port: 8080, common: {debug: false}}}"#).unwrap()
+        );
+        assert_eq!(
+            parser
+                .units()
+                .iter()
+                .map(|unit| unit.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["main.ason", "services/web.ason", "common.ason"]
+        );
+
+        // outside the root directory
+        let err = parser.parse_file("escape.ason").unwrap_err();
+        assert_eq!(err.kind, ErrorKind::IncludeFailed);
+        assert_eq!(
+            err.message,
+            r#"Can not include "../secret.ason" in "escape.ason": File "../secret.ason" is outside the root directory."#
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,481 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+//! Expands the placeholders in Strings with the values of variables,
+//! e.g. the environment variables.
+//!
+//! - `${NAME}` is replaced by the value of variable `NAME`, it is an error
+//!   if the variable is not defined.
+//! - `${NAME:-default}` is replaced by `default` if the variable is not
+//!   defined or is empty.
+//! - `${NAME:u16}` (and `${NAME:u16:-8080}`) converts the value to the
+//!   specified type, the String is replaced by the Number (or Boolean),
+//!   so the placeholder must be the whole String, e.g. `port: "${PORT:u16}"`.
+//!   The types are `i8` to `u64`, `f32`, `f64` and `bool`.
+//! - `$$` is a literal `$`, and a `$` which is not followed by `{` is
+//!   kept as it is.
+//!
+//! The interpolation is opt-in, see `ParseOptions::with_variables`
+//! and `interpolate`.
+
+use std::collections::HashMap;
+
+use crate::{
+    ast::{AsonNode, VariantValue},
+    parser::convert_number_token,
+    path::AsonPath,
+    token::{NumberToken, NumberType, Token, TokenWithRange},
+    AsonError, ErrorKind,
+};
+
+/// The source of the values of variables.
+///
+/// It is `Sync` so that the options which refer to it can be held
+/// by the asynchronous readers across the `.await` points.
+pub trait VariableSource: Sync {
+    fn get(&self, name: &str) -> Option<String>;
+}
+
+/// The environment variables of the current process.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Environment;
+
+impl VariableSource for Environment {
+    fn get(&self, name: &str) -> Option<String> {
+        std::env::var(name).ok()
+    }
+}
+
+impl VariableSource for HashMap<String, String> {
+    fn get(&self, name: &str) -> Option<String> {
+        HashMap::get(self, name).cloned()
+    }
+}
+
+/// Expands the placeholders in all Strings of the tree, including
+/// the names of Map entries.
+///
+/// The tree does not carry the locations, so the errors have
+/// the path of the String instead.
+pub fn interpolate(node: &mut AsonNode, variables: &dyn VariableSource) -> Result<(), AsonError> {
+    let mut path = AsonPath::root();
+    interpolate_node(node, &mut path, variables)
+}
+
+fn interpolate_node(
+    node: &mut AsonNode,
+    path: &mut AsonPath,
+    variables: &dyn VariableSource,
+) -> Result<(), AsonError> {
+    match node {
+        AsonNode::String(s) => {
+            *node = match expand(s, variables).map_err(|e| e.with_path(path.clone()))? {
+                Token::String(s) => AsonNode::String(s),
+                Token::Number(n) => convert_number_token(&n),
+                Token::Boolean(b) => AsonNode::Boolean(b),
+                _ => unreachable!(),
+            };
+        }
+        AsonNode::Variant(variant) => match &mut variant.value {
+            VariantValue::Empty => {}
+            VariantValue::Value(value) => {
+                path.push_index(0);
+                interpolate_node(value, path, variables)?;
+                path.segments.pop();
+            }
+            VariantValue::Tuple(items) => interpolate_items(items, path, variables)?,
+            VariantValue::Object(kvps) => {
+                for kvp in kvps {
+                    path.push_key(&kvp.key);
+                    interpolate_node(&mut kvp.value, path, variables)?;
+                    path.segments.pop();
+                }
+            }
+        },
+        AsonNode::List(items) | AsonNode::Tuple(items) => {
+            interpolate_items(items, path, variables)?
+        }
+        AsonNode::Object(kvps) => {
+            for kvp in kvps {
+                path.push_key(&kvp.key);
+                interpolate_node(&mut kvp.value, path, variables)?;
+                path.segments.pop();
+            }
+        }
+        AsonNode::Map(nvps) => {
+            for nvp in nvps {
+                path.push_name(&nvp.name);
+                interpolate_node(&mut nvp.name, path, variables)?;
+                interpolate_node(&mut nvp.value, path, variables)?;
+                path.segments.pop();
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+fn interpolate_items(
+    items: &mut [AsonNode],
+    path: &mut AsonPath,
+    variables: &dyn VariableSource,
+) -> Result<(), AsonError> {
+    for (index, item) in items.iter_mut().enumerate() {
+        path.push_index(index);
+        interpolate_node(item, path, variables)?;
+        path.segments.pop();
+    }
+    Ok(())
+}
+
+/// Expands the String tokens, the errors are located at the String
+/// which contains the placeholder. The tokens are passed through
+/// if no variable source is specified.
+pub(crate) struct InterpolatedTokenIter<'a> {
+    upstream: &'a mut dyn Iterator<Item = Result<TokenWithRange, AsonError>>,
+    variables: Option<&'a dyn VariableSource>,
+}
+
+impl<'a> InterpolatedTokenIter<'a> {
+    pub fn new(
+        upstream: &'a mut dyn Iterator<Item = Result<TokenWithRange, AsonError>>,
+        variables: Option<&'a dyn VariableSource>,
+    ) -> Self {
+        Self {
+            upstream,
+            variables,
+        }
+    }
+}
+
+impl Iterator for InterpolatedTokenIter<'_> {
+    type Item = Result<TokenWithRange, AsonError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match (self.upstream.next()?, self.variables) {
+            (
+                Ok(TokenWithRange {
+                    token: Token::String(s),
+                    range,
+                }),
+                Some(variables),
+            ) => Some(
+                expand(&s, variables)
+                    .map(|token| TokenWithRange::new(token, range))
+                    .map_err(|e| e.with_location(range)),
+            ),
+            (r, _) => Some(r),
+        }
+    }
+}
+
+// the content of `${...}`.
+struct Placeholder<'a> {
+    name: &'a str,
+    type_name: Option<&'a str>,
+    default: Option<&'a str>,
+}
+
+// expands the placeholders of the String, returns the `Token::String`,
+// or the `Token::Number` and `Token::Boolean` for the typed placeholder.
+pub(crate) fn expand(s: &str, variables: &dyn VariableSource) -> Result<Token, AsonError> {
+    if !s.contains('$') {
+        return Ok(Token::String(s.to_owned()));
+    }
+
+    let mut result = String::new();
+    let mut rest = s;
+
+    while let Some(pos) = rest.find('$') {
+        result.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+
+        if let Some(remain) = after.strip_prefix('$') {
+            result.push('$');
+            rest = remain;
+        } else if let Some(remain) = after.strip_prefix('{') {
+            let end = remain.find('}').ok_or_else(|| {
+                new_interpolation_error(format!("Unterminated placeholder \"${{{}\".", remain))
+            })?;
+
+            let text = &rest[pos..pos + end + 3]; // `${...}`
+            let placeholder = parse_placeholder(&remain[..end]).map_err(|detail| {
+                new_interpolation_error(format!("Invalid placeholder \"{}\", {}", text, detail))
+            })?;
+            let value = resolve(&placeholder, variables)?;
+
+            if let Some(type_name) = placeholder.type_name {
+                if text.len() != s.len() {
+                    return Err(new_interpolation_error(format!(
+                        "Typed placeholder \"{}\" must be the whole String.",
+                        text
+                    )));
+                }
+                return convert_value(placeholder.name, type_name, &value);
+            }
+
+            result.push_str(&value);
+            rest = &remain[end + 1..];
+        } else {
+            result.push('$');
+            rest = after;
+        }
+    }
+
+    result.push_str(rest);
+    Ok(Token::String(result))
+}
+
+fn parse_placeholder(content: &str) -> Result<Placeholder<'_>, String> {
+    let (head, default) = match content.find(":-") {
+        Some(pos) => (&content[..pos], Some(&content[pos + 2..])),
+        None => (content, None),
+    };
+
+    let (name, type_name) = match head.split_once(':') {
+        Some((name, type_name)) => (name, Some(type_name)),
+        None => (head, None),
+    };
+
+    let is_valid_name = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !is_valid_name {
+        return Err("expect a variable name.".to_owned());
+    }
+
+    if let Some(type_name) = type_name {
+        if type_name != "bool" {
+            NumberType::from_str(type_name)
+                .map_err(|_| format!("unknown type \"{}\".", type_name))?;
+        }
+    }
+
+    Ok(Placeholder {
+        name,
+        type_name,
+        default,
+    })
+}
+
+// the default value is used if the variable is not defined or is empty.
+fn resolve(placeholder: &Placeholder, variables: &dyn VariableSource) -> Result<String, AsonError> {
+    match (variables.get(placeholder.name), placeholder.default) {
+        (Some(value), Some(default)) if value.is_empty() => Ok(default.to_owned()),
+        (Some(value), _) => Ok(value),
+        (None, Some(default)) => Ok(default.to_owned()),
+        (None, None) => Err(new_interpolation_error(format!(
+            "Variable \"{}\" is not defined.",
+            placeholder.name
+        ))),
+    }
+}
+
+fn convert_value(name: &str, type_name: &str, value: &str) -> Result<Token, AsonError> {
+    let text = value.trim();
+    let number = |token: Option<NumberToken>| token.map(Token::Number);
+    let token = match type_name {
+        "bool" => text.parse::<bool>().ok().map(Token::Boolean),
+        "i8" => number(text.parse::<i8>().ok().map(|v| NumberToken::I8(v as u8))),
+        "u8" => number(text.parse::<u8>().ok().map(NumberToken::U8)),
+        "i16" => number(text.parse::<i16>().ok().map(|v| NumberToken::I16(v as u16))),
+        "u16" => number(text.parse::<u16>().ok().map(NumberToken::U16)),
+        "i32" => number(text.parse::<i32>().ok().map(|v| NumberToken::I32(v as u32))),
+        "u32" => number(text.parse::<u32>().ok().map(NumberToken::U32)),
+        "i64" => number(text.parse::<i64>().ok().map(|v| NumberToken::I64(v as u64))),
+        "u64" => number(text.parse::<u64>().ok().map(NumberToken::U64)),
+        "f32" => number(text.parse::<f32>().ok().map(NumberToken::F32)),
+        "f64" => number(text.parse::<f64>().ok().map(NumberToken::F64)),
+        _ => unreachable!(),
+    };
+
+    token.ok_or_else(|| {
+        new_interpolation_error(format!(
+            "Expect a \"{}\" value for variable \"{}\", found \"{}\".",
+            type_name, name, value
+        ))
+        .with_expected_and_found(type_name, value)
+    })
+}
+
+fn new_interpolation_error(message: String) -> AsonError {
+    AsonError::new(ErrorKind::Interpolation, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use pretty_assertions::assert_eq;
+    use serde::Deserialize;
+
+    use crate::{
+        ast::{AsonNode, Number},
+        location::Location,
+        options::ParseOptions,
+        parser::parse_from_str,
+        path::AsonPath,
+        ErrorKind,
+    };
+
+    use super::interpolate;
+
+    fn variables() -> HashMap<String, String> {
+        [("HOST", "example.com"), ("PORT", "8080"), ("EMPTY", "")]
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_with_variables() {
+        let node = ParseOptions::new()
+            .with_variables(&variables())
+            .parse_str(
+                r#"{
+                url: "http://${HOST}:${PORT}/"
+                user: "${USER_NAME:-guest}"
+                empty: "${EMPTY:-none}"
+                port: "${PORT:u16}"
+                timeout: "${TIMEOUT:f32:-2.5}"
+                debug: "${DEBUG:bool:-false}"
+                price: "$$5 and $1"
+            }"#,
+            )
+            .unwrap();
+
+        assert_eq!(
+            node,
+            parse_from_str(
+                r#"{
+                    url: "http://example.com:8080/"
+                    user: "guest"
+                    empty: "none"
+                    port: 8080_u16
+                    timeout: 2.5_f32
+                    debug: false
+                    price: "$5 and $1"
+                }"#
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_with_variables_errors() {
+        let err = ParseOptions::new()
+            .with_variables(&variables())
+            .parse_str("{\n  host: \"${HOSTNAME}\"}")
+            .unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Interpolation);
+        assert_eq!(err.message, r#"Variable "HOSTNAME" is not defined."#);
+        assert_eq!(err.location, Some(Location::new_range(10, 1, 8, 13)));
+
+        let err = ParseOptions::new()
+            .with_variables(&variables())
+            .parse_str(r#""${HOST:u16}""#)
+            .unwrap_err();
+        assert_eq!(
+            err.message,
+            r#"Expect a "u16" value for variable "HOST", found "example.com"."#
+        );
+        assert_eq!(err.expected(), Some("u16"));
+        assert_eq!(err.found(), Some("example.com"));
+
+        let err = ParseOptions::new()
+            .with_variables(&variables())
+            .parse_str(r#""port ${PORT:u16}""#)
+            .unwrap_err();
+        assert_eq!(
+            err.message,
+            r#"Typed placeholder "${PORT:u16}" must be the whole String."#
+        );
+
+        let err = ParseOptions::new()
+            .with_variables(&variables())
+            .parse_str(r#""${PORT:u9}""#)
+            .unwrap_err();
+        assert_eq!(
+            err.message,
+            r#"Invalid placeholder "${PORT:u9}", unknown type "u9"."#
+        );
+
+        let err = ParseOptions::new()
+            .with_variables(&variables())
+            .parse_str(r#""${1}""#)
+            .unwrap_err();
+        assert_eq!(
+            err.message,
+            r#"Invalid placeholder "${1}", expect a variable name."#
+        );
+
+        let err = ParseOptions::new()
+            .with_variables(&variables())
+            .parse_str(r#""${HOST""#)
+            .unwrap_err();
+        assert_eq!(err.message, r#"Unterminated placeholder "${HOST"."#);
+    }
+
+    #[test]
+    fn test_from_str_with_variables() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Server {
+            host: String,
+            port: u16,
+        }
+
+        let server: Server = ParseOptions::new()
+            .with_variables(&variables())
+            .from_str(r#"{host: "${HOST}", port: "${PORT:u16}"}"#)
+            .unwrap();
+        assert_eq!(
+            server,
+            Server {
+                host: "example.com".to_owned(),
+                port: 8080
+            }
+        );
+
+        // the placeholders are only expanded by `ParseOptions::with_variables`
+        let err =
+            crate::from_str::<Server>(r#"{host: "${HOST}", port: "${PORT:u16}"}"#).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::TypeMismatch);
+
+        let err = ParseOptions::new()
+            .with_variables(&variables())
+            .from_str::<Server>(r#"{host: "${HOST}", port: "${SERVER_PORT:u16}"}"#)
+            .unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Interpolation);
+        assert_eq!(err.location, Some(Location::new_range(24, 0, 24, 20)));
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let mut node =
+            parse_from_str(r#"{servers: [{port: "${PORT:u32}"}], names: ["${HOST}": 1]}"#).unwrap();
+        interpolate(&mut node, &variables()).unwrap();
+        assert_eq!(
+            node,
+            parse_from_str(r#"{servers: [{port: 8080_u32}], names: ["example.com": 1]}"#).unwrap()
+        );
+
+        let mut node = parse_from_str(r#"{servers: [{host: "${HOSTNAME}"}]}"#).unwrap();
+        let err = interpolate(&mut node, &variables()).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Interpolation);
+        assert_eq!(err.location, None);
+        assert_eq!(
+            err.path(),
+            Some(&AsonPath::parse(".servers[0].host").unwrap())
+        );
+
+        let mut node = AsonNode::String("${PORT:i64}".to_owned());
+        interpolate(&mut node, &variables()).unwrap();
+        assert_eq!(node, AsonNode::Number(Number::I64(8080)));
+    }
+}
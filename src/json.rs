@@ -0,0 +1,669 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+//! Conversion between ASON and JSON (`serde_json::Value`).
+//!
+//! JSON has fewer data types than ASON, so the conversion from ASON to JSON
+//! is lossy. The mapping is:
+//!
+//! | ASON                         | JSON                                    |
+//! |------------------------------|-----------------------------------------|
+//! | integer numbers (i8 ~ u64)   | number                                  |
+//! | f32, f64                     | number (`NaN` and `Inf` are not allowed)|
+//! | Boolean                      | boolean                                 |
+//! | Char                         | string with one character              |
+//! | String                       | string                                  |
+//! | DateTime                     | RFC 3339 string                         |
+//! | HexByteData                  | array of numbers                        |
+//! | List, Tuple                  | array                                   |
+//! | Object                       | object                                  |
+//! | Map                          | object, the names are converted to strings |
+//! | `Option::None`               | null                                    |
+//! | `Option::Some(v)`            | `v`                                     |
+//! | `Type::Member`               | `"Member"`                              |
+//! | `Type::Member(v)`            | `{"Member": v}`                         |
+//! | `Type::Member(a, b)`         | `{"Member": [a, b]}`                    |
+//! | `Type::Member{k: v}`         | `{"Member": {"k": v}}`                  |
+//!
+//! The Variants are converted in the same way as the "externally tagged" enum
+//! representation of serde, so the JSON can be deserialized by `serde_json`
+//! into the same Rust enum. Note that the variant type name is dropped.
+//!
+//! Only Strings, Chars, integer numbers and Booleans can be converted
+//! to JSON object keys, and the names of a Map which are converted to
+//! the same key, e.g. `1` and `"1"`, raise an error.
+//!
+//! The conversion from JSON to ASON is controlled by `JsonToAsonOptions`:
+//!
+//! - integer numbers are converted to `i32` (the default ASON integer type)
+//!   if the value fits, otherwise `i64` or `u64`. If `infer_number_width` is
+//!   disabled, integer numbers are always converted to `i64` (or `u64` if the
+//!   value is greater than `i64::MAX`).
+//! - floating-point numbers are always converted to `f64`.
+//! - arrays are converted to ASON Lists if all elements are of the same
+//!   data type (including the type of numbers, e.g. `i32` and `f64` are
+//!   different), otherwise they are converted to ASON Tuples. The arrays
+//!   which mix `null` with the values of the same data type are converted
+//!   to Lists of Options, e.g. `[null, 1]` becomes `[Option::None, Option::Some(1)]`.
+//! - `null` is converted to `Option::None` if `null_as_none` is enabled,
+//!   otherwise an error is raised.
+//! - objects are converted to ASON Objects if all keys are valid
+//!   identifiers, otherwise they are converted to ASON Maps with string names.
+//!
+//! For a lossless encoding, check out the `tagged` module.
+
+pub mod tagged;
+
+use serde_json::{Map, Number as JsonNumber, Value};
+
+use crate::{
+    ast::{AsonNode, KeyValuePair, NameValuePair, Number, Variant, VariantValue},
+    conversion::{f32_to_f64, items_to_list_or_tuple},
+    parser::parse_from_str,
+    path::is_valid_identifier,
+    printer::print_to_string,
+    AsonError, ErrorKind,
+};
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct JsonToAsonOptions {
+    /// Converts JSON integer numbers to the smallest one of `i32`, `i64`
+    /// and `u64` that can hold the value, otherwise `i64` or `u64`.
+    pub infer_number_width: bool,
+
+    /// Converts JSON `null` to `Option::None`.
+    pub null_as_none: bool,
+}
+
+impl Default for JsonToAsonOptions {
+    fn default() -> Self {
+        Self {
+            infer_number_width: true,
+            null_as_none: true,
+        }
+    }
+}
+
+pub fn ason_to_json(node: &AsonNode) -> Result<Value, AsonError> {
+    let value = match node {
+        AsonNode::Number(n) => Value::Number(convert_number(n)?),
+        AsonNode::Boolean(b) => Value::Bool(*b),
+        AsonNode::Char(c) => Value::String(c.to_string()),
+        AsonNode::String(s) => Value::String(s.to_owned()),
+        AsonNode::DateTime(d) => Value::String(d.to_rfc3339()),
+        AsonNode::Variant(v) => convert_variant(v)?,
+        AsonNode::HexByteData(data) => Value::Array(
+            data.iter()
+                .map(|b| Value::Number(JsonNumber::from(*b)))
+                .collect(),
+        ),
+        AsonNode::List(items) | AsonNode::Tuple(items) => Value::Array(
+            items
+                .iter()
+                .map(ason_to_json)
+                .collect::<Result<Vec<Value>, AsonError>>()?,
+        ),
+        AsonNode::Object(kvps) => Value::Object(convert_key_value_pairs(kvps)?),
+        AsonNode::Map(nvps) => {
+            let mut map = Map::new();
+            for nvp in nvps {
+                let key = convert_map_name(&nvp.name)?;
+
+                // e.g. the names `1` and `"1"`
+                if map.contains_key(&key) {
+                    return Err(AsonError::new(
+                        ErrorKind::Conversion,
+                        format!(
+                            "The name {} of Map is converted to a duplicate JSON object key \"{}\".",
+                            print_to_string(&nvp.name),
+                            key
+                        ),
+                    ));
+                }
+
+                map.insert(key, ason_to_json(&nvp.value)?);
+            }
+            Value::Object(map)
+        }
+        AsonNode::Error => {
+            return Err(AsonError::new(
+                ErrorKind::Conversion,
+                "Can not convert the error placeholder to JSON.".to_owned(),
+            ));
+        }
+    };
+
+    Ok(value)
+}
+
+fn convert_number(number: &Number) -> Result<JsonNumber, AsonError> {
+    let n = match number {
+        Number::I8(v) => JsonNumber::from(*v),
+        Number::U8(v) => JsonNumber::from(*v),
+        Number::I16(v) => JsonNumber::from(*v),
+        Number::U16(v) => JsonNumber::from(*v),
+        Number::I32(v) => JsonNumber::from(*v),
+        Number::U32(v) => JsonNumber::from(*v),
+        Number::I64(v) => JsonNumber::from(*v),
+        Number::U64(v) => JsonNumber::from(*v),
+        Number::F32(v) => convert_floating_point(f32_to_f64(*v))?,
+        Number::F64(v) => convert_floating_point(*v)?,
+    };
+
+    Ok(n)
+}
+
+fn convert_floating_point(v: f64) -> Result<JsonNumber, AsonError> {
+    JsonNumber::from_f64(v).ok_or_else(|| {
+        AsonError::new(
+            ErrorKind::Conversion,
+            format!(
+                "Can not convert the floating-point number \"{}\" to JSON.",
+                v
+            ),
+        )
+    })
+}
+
+fn convert_key_value_pairs(kvps: &[KeyValuePair]) -> Result<Map<String, Value>, AsonError> {
+    let mut map = Map::new();
+    for kvp in kvps {
+        map.insert(kvp.key.to_owned(), ason_to_json(&kvp.value)?);
+    }
+    Ok(map)
+}
+
+fn convert_map_name(name: &AsonNode) -> Result<String, AsonError> {
+    match name {
+        AsonNode::String(s) => Ok(s.to_owned()),
+        AsonNode::Char(c) => Ok(c.to_string()),
+        AsonNode::Boolean(b) => Ok(b.to_string()),
+        AsonNode::Number(n) => match n {
+            Number::I8(v) => Ok(v.to_string()),
+            Number::U8(v) => Ok(v.to_string()),
+            Number::I16(v) => Ok(v.to_string()),
+            Number::U16(v) => Ok(v.to_string()),
+            Number::I32(v) => Ok(v.to_string()),
+            Number::U32(v) => Ok(v.to_string()),
+            Number::I64(v) => Ok(v.to_string()),
+            Number::U64(v) => Ok(v.to_string()),
+            Number::F32(_) | Number::F64(_) => Err(AsonError::new(
+                ErrorKind::Conversion,
+                "Can not convert the floating-point number name of Map to JSON object key."
+                    .to_owned(),
+            )),
+        },
+        _ => Err(AsonError::new(
+            ErrorKind::Conversion,
+            "Only String, Char, integer number and Boolean names of Map can be converted to JSON object key."
+                .to_owned(),
+        )),
+    }
+}
+
+fn convert_variant(variant: &Variant) -> Result<Value, AsonError> {
+    if variant.type_name == "Option" {
+        match (variant.member_name.as_str(), &variant.value) {
+            ("None", VariantValue::Empty) => return Ok(Value::Null),
+            ("Some", VariantValue::Value(v)) => return ason_to_json(v),
+            _ => {
+                // other members of "Option" are converted as
+                // ordinary variants.
+            }
+        }
+    }
+
+    let member_name = variant.member_name.to_owned();
+
+    let value = match &variant.value {
+        VariantValue::Empty => {
+            return Ok(Value::String(member_name));
+        }
+        VariantValue::Value(v) => ason_to_json(v)?,
+        VariantValue::Tuple(items) => Value::Array(
+            items
+                .iter()
+                .map(ason_to_json)
+                .collect::<Result<Vec<Value>, AsonError>>()?,
+        ),
+        VariantValue::Object(kvps) => Value::Object(convert_key_value_pairs(kvps)?),
+    };
+
+    let mut map = Map::new();
+    map.insert(member_name, value);
+    Ok(Value::Object(map))
+}
+
+pub fn json_to_ason(value: &Value, options: &JsonToAsonOptions) -> Result<AsonNode, AsonError> {
+    let node = match value {
+        Value::Null => {
+            if options.null_as_none {
+                AsonNode::Variant(Variant::new("Option", "None"))
+            } else {
+                return Err(AsonError::new(
+                    ErrorKind::Conversion,
+                    "Can not convert JSON null to ASON.".to_owned(),
+                ));
+            }
+        }
+        Value::Bool(b) => AsonNode::Boolean(*b),
+        Value::Number(n) => AsonNode::Number(convert_json_number(n, options)?),
+        Value::String(s) => AsonNode::String(s.to_owned()),
+        Value::Array(items) => {
+            let items = items
+                .iter()
+                .map(|item| json_to_ason(item, options))
+                .collect::<Result<Vec<AsonNode>, AsonError>>()?;
+            items_to_list_or_tuple(items)
+        }
+        Value::Object(map) => {
+            if map.keys().all(|key| is_valid_identifier(key)) {
+                let mut kvps = vec![];
+                for (key, value) in map {
+                    kvps.push(KeyValuePair::new(key, json_to_ason(value, options)?));
+                }
+                AsonNode::Object(kvps)
+            } else {
+                let mut nvps = vec![];
+                for (key, value) in map {
+                    nvps.push(NameValuePair {
+                        name: Box::new(AsonNode::String(key.to_owned())),
+                        value: Box::new(json_to_ason(value, options)?),
+                    });
+                }
+                AsonNode::Map(nvps)
+            }
+        }
+    };
+
+    Ok(node)
+}
+
+fn convert_json_number(n: &JsonNumber, options: &JsonToAsonOptions) -> Result<Number, AsonError> {
+    if let Some(v) = n.as_i64() {
+        if options.infer_number_width && v >= i32::MIN as i64 && v <= i32::MAX as i64 {
+            Ok(Number::I32(v as i32))
+        } else {
+            Ok(Number::I64(v))
+        }
+    } else if let Some(v) = n.as_u64() {
+        // the value is greater than `i64::MAX`
+        Ok(Number::U64(v))
+    } else if let Some(v) = n.as_f64() {
+        Ok(Number::F64(v))
+    } else {
+        Err(AsonError::new(
+            ErrorKind::Conversion,
+            format!("Can not convert JSON number \"{}\" to ASON.", n),
+        ))
+    }
+}
+
+/// Converts ASON text to pretty-printed JSON text.
+pub fn ason_str_to_json_string(s: &str) -> Result<String, AsonError> {
+    let node = parse_from_str(s)?;
+    let value = ason_to_json(&node)?;
+    serde_json::to_string_pretty(&value)
+        .map_err(|e| AsonError::new(ErrorKind::Conversion, e.to_string()).caused_by(e))
+}
+
+/// Converts JSON text to ASON text.
+pub fn json_str_to_ason_string(s: &str, options: &JsonToAsonOptions) -> Result<String, AsonError> {
+    let value: Value = serde_json::from_str(s)
+        .map_err(|e| AsonError::new(ErrorKind::Conversion, e.to_string()).caused_by(e))?;
+    let node = json_to_ason(&value, options)?;
+    Ok(print_to_string(&node))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::DateTime;
+    use pretty_assertions::assert_eq;
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+
+    use crate::{
+        ast::{AsonNode, KeyValuePair, NameValuePair, Number, Variant},
+        parser::parse_from_str,
+        ErrorKind,
+    };
+
+    use super::{
+        ason_str_to_json_string, ason_to_json, json_str_to_ason_string, json_to_ason,
+        JsonToAsonOptions,
+    };
+
+    fn convert_ason_text(s: &str) -> serde_json::Value {
+        ason_to_json(&parse_from_str(s).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_ason_to_json_primitive_values() {
+        assert_eq!(convert_ason_text("123"), json!(123));
+        assert_eq!(convert_ason_text("-11_i8"), json!(-11));
+        assert_eq!(convert_ason_text("0xff_u8"), json!(255));
+        assert_eq!(
+            convert_ason_text("18446744073709551615_u64"),
+            json!(18446744073709551615_u64)
+        );
+        assert_eq!(convert_ason_text("3.5"), json!(3.5));
+        assert_eq!(convert_ason_text("1.5_f32"), json!(1.5));
+        assert_eq!(convert_ason_text("1.1_f32"), json!(1.1));
+        assert_eq!(convert_ason_text("true"), json!(true));
+        assert_eq!(convert_ason_text("'文'"), json!("文"));
+        assert_eq!(convert_ason_text(r#""foo""#), json!("foo"));
+        assert_eq!(
+            convert_ason_text(r#"d"2024-03-17 10:01:11+08:00""#),
+            json!("2024-03-17T10:01:11+08:00")
+        );
+        assert_eq!(convert_ason_text(r#"h"11 13 17""#), json!([17, 19, 23]));
+
+        // err: NaN and Inf
+        assert!(ason_to_json(&AsonNode::Number(Number::F64(f64::NAN))).is_err());
+        assert!(ason_to_json(&AsonNode::Number(Number::F32(f32::INFINITY))).is_err());
+    }
+
+    #[test]
+    fn test_ason_to_json_collections() {
+        assert_eq!(convert_ason_text("[11, 13, 17]"), json!([11, 13, 17]));
+        assert_eq!(convert_ason_text(r#"(11, "foo")"#), json!([11, "foo"]));
+
+        // the order of keys should be preserved
+        assert_eq!(
+            serde_json::to_string(&convert_ason_text(
+                r#"{id: 123, name: "foo", active: true}"#
+            ))
+            .unwrap(),
+            r#"{"id":123,"name":"foo","active":true}"#
+        );
+
+        assert_eq!(
+            convert_ason_text(r#"["foo": 11, "bar": 13]"#),
+            json!({"foo": 11, "bar": 13})
+        );
+        assert_eq!(
+            convert_ason_text(r#"[1: "foo", 2: "bar"]"#),
+            json!({"1": "foo", "2": "bar"})
+        );
+
+        // err: tuple as map name
+        assert!(ason_to_json(&parse_from_str(r#"[(1, 2): "foo"]"#).unwrap()).is_err());
+
+        // err: the names are converted to the same key
+        for (text, message) in [
+            (
+                r#"[1: 'a', "1": 'b']"#,
+                r#"The name "1" of Map is converted to a duplicate JSON object key "1"."#,
+            ),
+            (
+                "['c': 1, \"c\": 2]",
+                r#"The name "c" of Map is converted to a duplicate JSON object key "c"."#,
+            ),
+            (
+                "[1_u8: 1, 1_i64: 2]",
+                r#"The name 1_i64 of Map is converted to a duplicate JSON object key "1"."#,
+            ),
+        ] {
+            let err = ason_to_json(&parse_from_str(text).unwrap()).unwrap_err();
+            assert_eq!(err.kind, ErrorKind::Conversion);
+            assert_eq!(err.message, message);
+        }
+    }
+
+    #[test]
+    fn test_ason_to_json_variants() {
+        assert_eq!(convert_ason_text("Option::None"), json!(null));
+        assert_eq!(convert_ason_text("Option::Some(123)"), json!(123));
+        assert_eq!(convert_ason_text("Color::Red"), json!("Red"));
+        assert_eq!(convert_ason_text("Color::Grey(11_u8)"), json!({"Grey": 11}));
+        assert_eq!(
+            convert_ason_text("Color::RGB(255_u8, 127_u8, 63_u8)"),
+            json!({"RGB": [255, 127, 63]})
+        );
+        assert_eq!(
+            convert_ason_text("Shape::Rect{width: 11, height: 13}"),
+            json!({"Rect": {"width": 11, "height": 13}})
+        );
+
+        // the JSON can be deserialized to the Rust enum by serde_json.
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        enum Shape {
+            Circle(i32),
+            Rect { width: i32, height: i32 },
+        }
+
+        let value = convert_ason_text("[Shape::Circle(7), Shape::Rect{width: 11, height: 13}]");
+        assert_eq!(
+            serde_json::from_value::<Vec<Shape>>(value).unwrap(),
+            vec![
+                Shape::Circle(7),
+                Shape::Rect {
+                    width: 11,
+                    height: 13
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_json_to_ason() {
+        let options = JsonToAsonOptions::default();
+
+        assert_eq!(
+            json_to_ason(&json!(123), &options).unwrap(),
+            AsonNode::Number(Number::I32(123))
+        );
+        assert_eq!(
+            json_to_ason(&json!(3_000_000_000_i64), &options).unwrap(),
+            AsonNode::Number(Number::I64(3_000_000_000))
+        );
+        assert_eq!(
+            json_to_ason(&json!(-3_000_000_000_i64), &options).unwrap(),
+            AsonNode::Number(Number::I64(-3_000_000_000))
+        );
+        assert_eq!(
+            json_to_ason(&json!(18446744073709551615_u64), &options).unwrap(),
+            AsonNode::Number(Number::U64(u64::MAX))
+        );
+        assert_eq!(
+            json_to_ason(&json!(1.5), &options).unwrap(),
+            AsonNode::Number(Number::F64(1.5))
+        );
+        assert_eq!(
+            json_to_ason(&json!(null), &options).unwrap(),
+            AsonNode::Variant(Variant::new("Option", "None"))
+        );
+
+        // arrays
+        assert_eq!(
+            json_to_ason(&json!([11, 13]), &options).unwrap(),
+            AsonNode::List(vec![
+                AsonNode::Number(Number::I32(11)),
+                AsonNode::Number(Number::I32(13))
+            ])
+        );
+        assert_eq!(
+            json_to_ason(&json!([{"id": 11}, {"id": 13}]), &options).unwrap(),
+            AsonNode::List(vec![
+                AsonNode::Object(vec![KeyValuePair::new(
+                    "id",
+                    AsonNode::Number(Number::I32(11))
+                )]),
+                AsonNode::Object(vec![KeyValuePair::new(
+                    "id",
+                    AsonNode::Number(Number::I32(13))
+                )]),
+            ])
+        );
+        // nulls mixed with values
+        assert_eq!(
+            json_to_ason(&json!([null, 11, null]), &options).unwrap(),
+            AsonNode::List(vec![
+                AsonNode::Variant(Variant::new("Option", "None")),
+                AsonNode::Variant(Variant::with_value(
+                    "Option",
+                    "Some",
+                    AsonNode::Number(Number::I32(11))
+                )),
+                AsonNode::Variant(Variant::new("Option", "None")),
+            ])
+        );
+        assert_eq!(
+            json_to_ason(&json!([null, null]), &options).unwrap(),
+            AsonNode::List(vec![
+                AsonNode::Variant(Variant::new("Option", "None")),
+                AsonNode::Variant(Variant::new("Option", "None")),
+            ])
+        );
+        assert_eq!(
+            json_to_ason(&json!([null, 11, "a"]), &options).unwrap(),
+            AsonNode::Tuple(vec![
+                AsonNode::Variant(Variant::new("Option", "None")),
+                AsonNode::Number(Number::I32(11)),
+                AsonNode::String("a".to_owned()),
+            ])
+        );
+
+        let text = json_str_to_ason_string("[1, null, 3]", &options).unwrap();
+        assert_eq!(
+            crate::from_str::<Vec<Option<i32>>>(&text).unwrap(),
+            vec![Some(1), None, Some(3)]
+        );
+        assert_eq!(
+            ason_str_to_json_string(&text).unwrap(),
+            "[\n  1,\n  null,\n  3\n]"
+        );
+
+        assert_eq!(
+            json_to_ason(&json!([[], [11]]), &options).unwrap(),
+            AsonNode::List(vec![
+                AsonNode::List(vec![]),
+                AsonNode::List(vec![AsonNode::Number(Number::I32(11))])
+            ])
+        );
+
+        // arrays with mixed types
+        assert_eq!(
+            json_to_ason(&json!([true, "foo"]), &options).unwrap(),
+            AsonNode::Tuple(vec![
+                AsonNode::Boolean(true),
+                AsonNode::String("foo".to_owned())
+            ])
+        );
+        assert_eq!(
+            json_to_ason(&json!([11, 1.5]), &options).unwrap(),
+            AsonNode::Tuple(vec![
+                AsonNode::Number(Number::I32(11)),
+                AsonNode::Number(Number::F64(1.5))
+            ])
+        );
+        assert_eq!(
+            json_to_ason(&json!([{"id": 11}, {"name": "foo"}]), &options).unwrap(),
+            AsonNode::Tuple(vec![
+                AsonNode::Object(vec![KeyValuePair::new(
+                    "id",
+                    AsonNode::Number(Number::I32(11))
+                )]),
+                AsonNode::Object(vec![KeyValuePair::new(
+                    "name",
+                    AsonNode::String("foo".to_owned())
+                )]),
+            ])
+        );
+
+        // object with identifier keys
+        assert_eq!(
+            json_to_ason(&json!({"id": 123, "名字": "foo"}), &options).unwrap(),
+            AsonNode::Object(vec![
+                KeyValuePair::new("id", AsonNode::Number(Number::I32(123))),
+                KeyValuePair::new("名字", AsonNode::String("foo".to_owned())),
+            ])
+        );
+
+        // object with non-identifier keys
+        assert_eq!(
+            json_to_ason(&json!({"foo-bar": 11, "true": 13}), &options).unwrap(),
+            AsonNode::Map(vec![
+                NameValuePair {
+                    name: Box::new(AsonNode::String("foo-bar".to_owned())),
+                    value: Box::new(AsonNode::Number(Number::I32(11)))
+                },
+                NameValuePair {
+                    name: Box::new(AsonNode::String("true".to_owned())),
+                    value: Box::new(AsonNode::Number(Number::I32(13)))
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_json_to_ason_with_options() {
+        let options = JsonToAsonOptions {
+            infer_number_width: false,
+            null_as_none: false,
+        };
+
+        assert_eq!(
+            json_to_ason(&json!(123), &options).unwrap(),
+            AsonNode::Number(Number::I64(123))
+        );
+
+        // err: null
+        assert!(json_to_ason(&json!(null), &options).is_err());
+    }
+
+    #[test]
+    fn test_convert_text() {
+        assert_eq!(
+            ason_str_to_json_string(
+                r#"{
+                    id: 123
+                    name: "foo"
+                    created: d"2024-03-17 10:01:11Z"
+                    tags: ["a", "b"]
+                }"#
+            )
+            .unwrap(),
+            r#"{
+  "id": 123,
+  "name": "foo",
+  "created": "2024-03-17T10:01:11+00:00",
+  "tags": [
+    "a",
+    "b"
+  ]
+}"#
+        );
+
+        assert_eq!(
+            json_str_to_ason_string(
+                r#"{"id": 123, "score": 4.5, "note": null, "tags": ["a", "b"]}"#,
+                &JsonToAsonOptions::default()
+            )
+            .unwrap(),
+            r#"{
+    id: 123
+    score: 4.5
+    note: Option::None
+    tags: [
+        "a"
+        "b"
+    ]
+}"#
+        );
+
+        // the DateTime value is converted to string
+        let node = parse_from_str(r#"d"2024-03-17 10:01:11+08:00""#).unwrap();
+        assert_eq!(
+            json_to_ason(&ason_to_json(&node).unwrap(), &JsonToAsonOptions::default()).unwrap(),
+            AsonNode::String(
+                DateTime::parse_from_rfc3339("2024-03-17T10:01:11+08:00")
+                    .unwrap()
+                    .to_rfc3339()
+            )
+        );
+    }
+}
@@ -0,0 +1,638 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+//! Lossless "tagged JSON" encoding of ASON values.
+//!
+//! Unlike the plain conversion in the parent module, this encoding keeps
+//! all distinctions of the ASON data model, so that
+//! `decode(&encode(&node)) == node` holds for every `AsonNode`.
+//!
+//! The values that have a natural JSON counterpart are encoded as is,
+//! others are wrapped in a single-key JSON object whose key starts with
+//! a dollar sign `$`. Since the dollar sign is not allowed in ASON
+//! identifiers, the tags never clash with the keys of ASON Objects.
+//!
+//! | ASON                      | Tagged JSON                                |
+//! |---------------------------|--------------------------------------------|
+//! | `123` (i32)               | `123`                                      |
+//! | `1.5` (f64)               | `1.5`                                      |
+//! | `NaN`, `Inf`, `-Inf`      | `{"$f64": "NaN"}`, `{"$f64": "Inf"}`, ...  |
+//! | `255_u8`                  | `{"$u8": 255}`, likewise for other types   |
+//! | `true`                    | `true`                                     |
+//! | `'a'`                     | `{"$char": "a"}`                           |
+//! | `"abc"`                   | `"abc"`                                    |
+//! | `d"2024-03-17 10:01:11Z"` | `{"$datetime": "2024-03-17T10:01:11+00:00"}` |
+//! | `h"11 13"`                | `{"$bytes": "1113"}`                       |
+//! | `[a, b]`                  | `[a, b]`                                   |
+//! | `(a, b)`                  | `{"$tuple": [a, b]}`                       |
+//! | `{k: v}`                  | `{"k": v}`                                 |
+//! | `[k: v]` (Map)            | `{"$map": [[k, v]]}`                       |
+//! | `Type::Member`            | `{"$variant": ["Type", "Member"]}`         |
+//! | `Type::Member(v)`         | `{"$variant": ["Type", "Member", v]}`      |
+//! | `Type::Member(a, b)`      | `{"$tuple_variant": ["Type", "Member", [a, b]]}` |
+//! | `Type::Member{k: v}`      | `{"$struct_variant": ["Type", "Member", {"k": v}]}` |
+//! | error placeholder         | `{"$error": null}`                         |
+//!
+//! An Object that contains duplicate keys (which can only be built
+//! programmatically) is encoded as `{"$object": [["k", v], ...]}`.
+
+use std::collections::HashSet;
+
+use chrono::DateTime;
+use serde_json::{Map, Number as JsonNumber, Value};
+
+use crate::{
+    ast::{AsonNode, KeyValuePair, NameValuePair, Number, Variant, VariantValue},
+    AsonError, ErrorKind,
+};
+
+pub fn encode(node: &AsonNode) -> Value {
+    match node {
+        AsonNode::Number(n) => encode_number(n),
+        AsonNode::Boolean(b) => Value::Bool(*b),
+        AsonNode::Char(c) => new_tagged_value("$char", Value::String(c.to_string())),
+        AsonNode::String(s) => Value::String(s.to_owned()),
+        AsonNode::DateTime(d) => new_tagged_value("$datetime", Value::String(d.to_rfc3339())),
+        AsonNode::Variant(v) => encode_variant(v),
+        AsonNode::HexByteData(data) => {
+            let hex = data
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>();
+            new_tagged_value("$bytes", Value::String(hex))
+        }
+        AsonNode::List(items) => Value::Array(items.iter().map(encode).collect()),
+        AsonNode::Tuple(items) => {
+            new_tagged_value("$tuple", Value::Array(items.iter().map(encode).collect()))
+        }
+        AsonNode::Object(kvps) => encode_key_value_pairs(kvps),
+        AsonNode::Map(nvps) => {
+            let entries = nvps
+                .iter()
+                .map(|nvp| Value::Array(vec![encode(&nvp.name), encode(&nvp.value)]))
+                .collect();
+            new_tagged_value("$map", Value::Array(entries))
+        }
+        AsonNode::Error => new_tagged_value("$error", Value::Null),
+    }
+}
+
+fn new_tagged_value(tag: &str, value: Value) -> Value {
+    let mut map = Map::new();
+    map.insert(tag.to_owned(), value);
+    Value::Object(map)
+}
+
+fn encode_number(number: &Number) -> Value {
+    match number {
+        Number::I8(v) => new_tagged_value("$i8", Value::Number(JsonNumber::from(*v))),
+        Number::U8(v) => new_tagged_value("$u8", Value::Number(JsonNumber::from(*v))),
+        Number::I16(v) => new_tagged_value("$i16", Value::Number(JsonNumber::from(*v))),
+        Number::U16(v) => new_tagged_value("$u16", Value::Number(JsonNumber::from(*v))),
+        Number::I32(v) => {
+            // the default integer number type
+            Value::Number(JsonNumber::from(*v))
+        }
+        Number::U32(v) => new_tagged_value("$u32", Value::Number(JsonNumber::from(*v))),
+        Number::I64(v) => new_tagged_value("$i64", Value::Number(JsonNumber::from(*v))),
+        Number::U64(v) => new_tagged_value("$u64", Value::Number(JsonNumber::from(*v))),
+        Number::F32(v) => new_tagged_value("$f32", encode_floating_point(*v as f64)),
+        Number::F64(v) => {
+            // the default floating-point number type,
+            // only the non-finite values need to be tagged.
+            match JsonNumber::from_f64(*v) {
+                Some(n) => Value::Number(n),
+                None => new_tagged_value("$f64", encode_floating_point(*v)),
+            }
+        }
+    }
+}
+
+fn encode_floating_point(v: f64) -> Value {
+    // note that every f32 number can be represented exactly by f64.
+    match JsonNumber::from_f64(v) {
+        Some(n) => Value::Number(n),
+        None => {
+            let s = if v.is_nan() {
+                "NaN"
+            } else if v.is_sign_positive() {
+                "Inf"
+            } else {
+                "-Inf"
+            };
+            Value::String(s.to_owned())
+        }
+    }
+}
+
+fn encode_key_value_pairs(kvps: &[KeyValuePair]) -> Value {
+    let mut keys = HashSet::new();
+    let has_duplicate_keys = !kvps.iter().all(|kvp| keys.insert(kvp.key.as_str()));
+
+    if has_duplicate_keys {
+        let entries = kvps
+            .iter()
+            .map(|kvp| Value::Array(vec![Value::String(kvp.key.to_owned()), encode(&kvp.value)]))
+            .collect();
+        new_tagged_value("$object", Value::Array(entries))
+    } else {
+        let mut map = Map::new();
+        for kvp in kvps {
+            map.insert(kvp.key.to_owned(), encode(&kvp.value));
+        }
+        Value::Object(map)
+    }
+}
+
+fn encode_variant(variant: &Variant) -> Value {
+    let type_name = Value::String(variant.type_name.to_owned());
+    let member_name = Value::String(variant.member_name.to_owned());
+
+    match &variant.value {
+        VariantValue::Empty => {
+            new_tagged_value("$variant", Value::Array(vec![type_name, member_name]))
+        }
+        VariantValue::Value(v) => new_tagged_value(
+            "$variant",
+            Value::Array(vec![type_name, member_name, encode(v)]),
+        ),
+        VariantValue::Tuple(items) => new_tagged_value(
+            "$tuple_variant",
+            Value::Array(vec![
+                type_name,
+                member_name,
+                Value::Array(items.iter().map(encode).collect()),
+            ]),
+        ),
+        VariantValue::Object(kvps) => new_tagged_value(
+            "$struct_variant",
+            Value::Array(vec![type_name, member_name, encode_key_value_pairs(kvps)]),
+        ),
+    }
+}
+
+pub fn decode(value: &Value) -> Result<AsonNode, AsonError> {
+    let node = match value {
+        Value::Null => {
+            return Err(AsonError::new(
+                ErrorKind::Conversion,
+                "JSON null is not a valid tagged ASON value.".to_owned(),
+            ));
+        }
+        Value::Bool(b) => AsonNode::Boolean(*b),
+        Value::Number(n) => {
+            if let Some(v) = n.as_i64() {
+                let v = i32::try_from(v).map_err(|_| {
+                    AsonError::new(
+                        ErrorKind::Conversion,
+                        format!("The untagged integer number {} is out of range of i32.", v),
+                    )
+                })?;
+                AsonNode::Number(Number::I32(v))
+            } else if n.is_u64() {
+                return Err(AsonError::new(
+                    ErrorKind::Conversion,
+                    format!("The untagged integer number {} is out of range of i32.", n),
+                ));
+            } else {
+                AsonNode::Number(Number::F64(n.as_f64().unwrap()))
+            }
+        }
+        Value::String(s) => AsonNode::String(s.to_owned()),
+        Value::Array(items) => AsonNode::List(decode_items(items)?),
+        Value::Object(map) => {
+            let tag = if map.len() == 1 {
+                map.iter().next().filter(|(key, _)| key.starts_with('$'))
+            } else {
+                None
+            };
+
+            match tag {
+                Some((tag_name, tag_value)) => decode_tagged_value(tag_name, tag_value)?,
+                None => AsonNode::Object(decode_object(map)?),
+            }
+        }
+    };
+
+    Ok(node)
+}
+
+fn decode_items(items: &[Value]) -> Result<Vec<AsonNode>, AsonError> {
+    items.iter().map(decode).collect()
+}
+
+fn decode_object(map: &Map<String, Value>) -> Result<Vec<KeyValuePair>, AsonError> {
+    map.iter()
+        .map(|(key, value)| Ok(KeyValuePair::new(key, decode(value)?)))
+        .collect()
+}
+
+fn decode_tagged_value(tag_name: &str, value: &Value) -> Result<AsonNode, AsonError> {
+    let node = match tag_name {
+        "$i8" => AsonNode::Number(Number::I8(decode_integer(tag_name, value)?)),
+        "$u8" => AsonNode::Number(Number::U8(decode_integer(tag_name, value)?)),
+        "$i16" => AsonNode::Number(Number::I16(decode_integer(tag_name, value)?)),
+        "$u16" => AsonNode::Number(Number::U16(decode_integer(tag_name, value)?)),
+        "$u32" => AsonNode::Number(Number::U32(decode_integer(tag_name, value)?)),
+        "$i64" => AsonNode::Number(Number::I64(decode_integer(tag_name, value)?)),
+        "$u64" => AsonNode::Number(Number::U64(decode_integer(tag_name, value)?)),
+        "$f32" => AsonNode::Number(Number::F32(decode_floating_point(tag_name, value)? as f32)),
+        "$f64" => AsonNode::Number(Number::F64(decode_floating_point(tag_name, value)?)),
+        "$char" => {
+            let s = expect_string(tag_name, value)?;
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => AsonNode::Char(c),
+                _ => {
+                    return Err(AsonError::new(
+                        ErrorKind::Conversion,
+                        format!(
+                            "Expect a string with exactly one character for tag \"{}\".",
+                            tag_name
+                        ),
+                    ));
+                }
+            }
+        }
+        "$datetime" => {
+            let s = expect_string(tag_name, value)?;
+            let d = DateTime::parse_from_rfc3339(s).map_err(|_| {
+                AsonError::new(
+                    ErrorKind::Conversion,
+                    format!("Can not convert the string \"{}\" to datetime.", s),
+                )
+            })?;
+            AsonNode::DateTime(d)
+        }
+        "$bytes" => {
+            let s = expect_string(tag_name, value)?;
+            AsonNode::HexByteData(decode_hex_string(s)?)
+        }
+        "$tuple" => AsonNode::Tuple(decode_items(expect_array(tag_name, value)?)?),
+        "$map" => {
+            let entries = expect_array(tag_name, value)?;
+            let mut nvps = vec![];
+            for entry in entries {
+                match entry {
+                    Value::Array(pair) if pair.len() == 2 => {
+                        nvps.push(NameValuePair {
+                            name: Box::new(decode(&pair[0])?),
+                            value: Box::new(decode(&pair[1])?),
+                        });
+                    }
+                    _ => {
+                        return Err(AsonError::new(
+                            ErrorKind::Conversion,
+                            "Expect a \"[name, value]\" pair for the entry of tag \"$map\"."
+                                .to_owned(),
+                        ));
+                    }
+                }
+            }
+            AsonNode::Map(nvps)
+        }
+        "$object" => AsonNode::Object(decode_object_entries(value)?),
+        "$error" => AsonNode::Error,
+        "$variant" | "$tuple_variant" | "$struct_variant" => {
+            let items = expect_array(tag_name, value)?;
+
+            let (type_name, member_name, payload) = match items.as_slice() {
+                [Value::String(t), Value::String(m)] => (t, m, None),
+                [Value::String(t), Value::String(m), v] => (t, m, Some(v)),
+                _ => {
+                    return Err(AsonError::new(
+                        ErrorKind::Conversion,
+                        format!(
+                            "Expect \"[type_name, member_name, ...]\" for tag \"{}\".",
+                            tag_name
+                        ),
+                    ));
+                }
+            };
+
+            let variant = match (tag_name, payload) {
+                ("$variant", None) => Variant::new(type_name, member_name),
+                ("$variant", Some(v)) => Variant::with_value(type_name, member_name, decode(v)?),
+                ("$tuple_variant", Some(Value::Array(items))) => {
+                    Variant::with_tuple(type_name, member_name, decode_items(items)?)
+                }
+                ("$struct_variant", Some(v)) => {
+                    let kvps = match v {
+                        Value::Object(map) if !is_tagged_object(map) => decode_object(map)?,
+                        Value::Object(map) if map.contains_key("$object") => {
+                            decode_object_entries(&map["$object"])?
+                        }
+                        _ => {
+                            return Err(AsonError::new(
+                                ErrorKind::Conversion,
+                                "Expect an object for the value of tag \"$struct_variant\"."
+                                    .to_owned(),
+                            ));
+                        }
+                    };
+                    Variant::with_object(type_name, member_name, kvps)
+                }
+                _ => {
+                    return Err(AsonError::new(
+                        ErrorKind::Conversion,
+                        format!("Invalid value for tag \"{}\".", tag_name),
+                    ));
+                }
+            };
+
+            AsonNode::Variant(variant)
+        }
+        _ => {
+            return Err(AsonError::new(
+                ErrorKind::Conversion,
+                format!("Unknown tag \"{}\".", tag_name),
+            ));
+        }
+    };
+
+    Ok(node)
+}
+
+fn is_tagged_object(map: &Map<String, Value>) -> bool {
+    map.len() == 1 && map.keys().all(|key| key.starts_with('$'))
+}
+
+fn decode_object_entries(value: &Value) -> Result<Vec<KeyValuePair>, AsonError> {
+    let entries = expect_array("$object", value)?;
+    let mut kvps = vec![];
+    for entry in entries {
+        match entry {
+            Value::Array(pair) if pair.len() == 2 && pair[0].is_string() => {
+                kvps.push(KeyValuePair::new(
+                    pair[0].as_str().unwrap(),
+                    decode(&pair[1])?,
+                ));
+            }
+            _ => {
+                return Err(AsonError::new(
+                    ErrorKind::Conversion,
+                    "Expect a \"[key, value]\" pair for the entry of tag \"$object\".".to_owned(),
+                ));
+            }
+        }
+    }
+    Ok(kvps)
+}
+
+fn expect_string<'a>(tag_name: &str, value: &'a Value) -> Result<&'a str, AsonError> {
+    value.as_str().ok_or_else(|| {
+        AsonError::new(
+            ErrorKind::Conversion,
+            format!("Expect a string for the value of tag \"{}\".", tag_name),
+        )
+    })
+}
+
+fn expect_array<'a>(tag_name: &str, value: &'a Value) -> Result<&'a Vec<Value>, AsonError> {
+    value.as_array().ok_or_else(|| {
+        AsonError::new(
+            ErrorKind::Conversion,
+            format!("Expect an array for the value of tag \"{}\".", tag_name),
+        )
+    })
+}
+
+fn decode_integer<T>(tag_name: &str, value: &Value) -> Result<T, AsonError>
+where
+    T: TryFrom<i64> + TryFrom<u64>,
+{
+    let result = match value {
+        Value::Number(n) if n.is_i64() => T::try_from(n.as_i64().unwrap()).ok(),
+        Value::Number(n) if n.is_u64() => T::try_from(n.as_u64().unwrap()).ok(),
+        _ => None,
+    };
+
+    result.ok_or_else(|| {
+        AsonError::new(
+            ErrorKind::Conversion,
+            format!("Invalid integer number for tag \"{}\".", tag_name),
+        )
+    })
+}
+
+fn decode_floating_point(tag_name: &str, value: &Value) -> Result<f64, AsonError> {
+    match value {
+        Value::Number(n) => Ok(n.as_f64().unwrap()),
+        Value::String(s) if s == "NaN" => Ok(f64::NAN),
+        Value::String(s) if s == "Inf" => Ok(f64::INFINITY),
+        Value::String(s) if s == "-Inf" => Ok(f64::NEG_INFINITY),
+        _ => Err(AsonError::new(
+            ErrorKind::Conversion,
+            format!("Invalid floating-point number for tag \"{}\".", tag_name),
+        )),
+    }
+}
+
+fn decode_hex_string(s: &str) -> Result<Vec<u8>, AsonError> {
+    let invalid_error = || {
+        AsonError::new(
+            ErrorKind::Conversion,
+            format!("Invalid hexadecimal byte data \"{}\".", s),
+        )
+    };
+
+    if !s.len().is_multiple_of(2) || !s.is_ascii() {
+        return Err(invalid_error());
+    }
+
+    (0..s.len())
+        .step_by(2)
+        .map(|idx| u8::from_str_radix(&s[idx..idx + 2], 16).map_err(|_| invalid_error()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::DateTime;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use crate::{
+        ast::{AsonNode, KeyValuePair, NameValuePair, Number, Variant},
+        parser::parse_from_str,
+    };
+
+    use super::{decode, encode};
+
+    // `NaN != NaN`, so compare the debug text instead.
+    fn assert_round_trip(node: &AsonNode) {
+        let value = encode(node);
+
+        // through the JSON text
+        let text = serde_json::to_string(&value).unwrap();
+        let value_from_text: serde_json::Value = serde_json::from_str(&text).unwrap();
+
+        let decoded = decode(&value_from_text).unwrap();
+        assert_eq!(format!("{:?}", &decoded), format!("{:?}", node));
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode(&AsonNode::Number(Number::I32(123))), json!(123));
+        assert_eq!(
+            encode(&AsonNode::Number(Number::U8(255))),
+            json!({"$u8": 255})
+        );
+        assert_eq!(
+            encode(&AsonNode::Number(Number::I64(-1))),
+            json!({"$i64": -1})
+        );
+        assert_eq!(encode(&AsonNode::Number(Number::F64(1.5))), json!(1.5));
+        assert_eq!(
+            encode(&AsonNode::Number(Number::F64(f64::NAN))),
+            json!({"$f64": "NaN"})
+        );
+        assert_eq!(
+            encode(&AsonNode::Number(Number::F32(f32::NEG_INFINITY))),
+            json!({"$f32": "-Inf"})
+        );
+        assert_eq!(encode(&AsonNode::Char('a')), json!({"$char": "a"}));
+        assert_eq!(
+            encode(&AsonNode::HexByteData(vec![0x11, 0xab])),
+            json!({"$bytes": "11ab"})
+        );
+        assert_eq!(
+            encode(&parse_from_str(r#"(1, "foo")"#).unwrap()),
+            json!({"$tuple": [1, "foo"]})
+        );
+        assert_eq!(
+            encode(&parse_from_str(r#"[1: 'a']"#).unwrap()),
+            json!({"$map": [[1, {"$char": "a"}]]})
+        );
+        assert_eq!(
+            encode(&parse_from_str(r#"Option::Some(11_u8)"#).unwrap()),
+            json!({"$variant": ["Option", "Some", {"$u8": 11}]})
+        );
+        assert_eq!(
+            encode(&parse_from_str(r#"Color::RGB(1, 2, 3)"#).unwrap()),
+            json!({"$tuple_variant": ["Color", "RGB", [1, 2, 3]]})
+        );
+        assert_eq!(
+            encode(&parse_from_str(r#"Shape::Rect{width: 11}"#).unwrap()),
+            json!({"$struct_variant": ["Shape", "Rect", {"width": 11}]})
+        );
+    }
+
+    #[test]
+    fn test_round_trip_primitive_values() {
+        let numbers = [
+            Number::I8(i8::MIN),
+            Number::U8(u8::MAX),
+            Number::I16(i16::MIN),
+            Number::U16(u16::MAX),
+            Number::I32(i32::MIN),
+            Number::U32(u32::MAX),
+            Number::I64(i64::MIN),
+            Number::U64(u64::MAX),
+            Number::F32(1.25),
+            Number::F32(f32::NAN),
+            Number::F32(f32::INFINITY),
+            Number::F32(-0.0),
+            Number::F64(2.0),
+            Number::F64(-0.0),
+            Number::F64(f64::NAN),
+            Number::F64(f64::NEG_INFINITY),
+            Number::F64(std::f64::consts::E),
+        ];
+
+        for n in numbers {
+            assert_round_trip(&AsonNode::Number(n));
+        }
+
+        assert_round_trip(&AsonNode::Boolean(false));
+        assert_round_trip(&AsonNode::Char('😋'));
+        assert_round_trip(&AsonNode::String("$tuple".to_owned()));
+        assert_round_trip(&AsonNode::DateTime(
+            DateTime::parse_from_rfc3339("2024-03-17T10:01:11.123+08:00").unwrap(),
+        ));
+        assert_round_trip(&AsonNode::HexByteData(vec![]));
+        assert_round_trip(&AsonNode::HexByteData(vec![0, 0x7f, 0xff]));
+    }
+
+    #[test]
+    fn test_round_trip_collections_and_variants() {
+        assert_round_trip(&AsonNode::List(vec![]));
+        assert_round_trip(&AsonNode::Object(vec![]));
+        assert_round_trip(&AsonNode::Map(vec![]));
+        assert_round_trip(&AsonNode::Tuple(vec![]));
+
+        assert_round_trip(
+            &parse_from_str(
+                r#"{
+                    id: 123_u64
+                    scores: [1.5, NaN, -Inf]
+                    pair: ('a', "b")
+                    ids: [1: "foo", 2: "bar"]
+                    names: [(1, 2): Option::None]
+                    color: Color::RGB(255_u8, 127_u8, 63_u8)
+                    single: Color::Grey((1, 2))
+                    shape: Shape::Rect{width: 11, height: 13}
+                    empty: Option::None
+                    nested: [[[h"01 02"]]]
+                }"#,
+            )
+            .unwrap(),
+        );
+
+        // variants that can only be built programmatically
+        assert_round_trip(&AsonNode::Variant(Variant::with_tuple(
+            "Foo",
+            "Bar",
+            vec![AsonNode::Number(Number::I32(1))],
+        )));
+        assert_round_trip(&AsonNode::Variant(Variant::with_tuple(
+            "Foo",
+            "Bar",
+            vec![],
+        )));
+        assert_round_trip(&AsonNode::Variant(Variant::with_object(
+            "Foo",
+            "Bar",
+            vec![
+                KeyValuePair::new("a", AsonNode::Boolean(true)),
+                KeyValuePair::new("a", AsonNode::Boolean(false)),
+            ],
+        )));
+
+        // object with duplicate keys
+        assert_round_trip(&AsonNode::Object(vec![
+            KeyValuePair::new("a", AsonNode::Number(Number::I32(1))),
+            KeyValuePair::new("a", AsonNode::Number(Number::I32(2))),
+        ]));
+
+        // map with duplicate names
+        assert_round_trip(&AsonNode::Map(vec![
+            NameValuePair {
+                name: Box::new(AsonNode::Char('a')),
+                value: Box::new(AsonNode::Number(Number::I32(1))),
+            },
+            NameValuePair {
+                name: Box::new(AsonNode::Char('a')),
+                value: Box::new(AsonNode::Number(Number::I32(2))),
+            },
+        ]));
+    }
+
+    #[test]
+    fn test_decode_error() {
+        assert!(decode(&json!(null)).is_err());
+        assert!(decode(&json!(3_000_000_000_i64)).is_err());
+        assert!(decode(&json!({"$u8": 256})).is_err());
+        assert!(decode(&json!({"$i8": -129})).is_err());
+        assert!(decode(&json!({"$char": "ab"})).is_err());
+        assert!(decode(&json!({"$bytes": "abc"})).is_err());
+        assert!(decode(&json!({"$datetime": "2024"})).is_err());
+        assert!(decode(&json!({"$map": [[1]]})).is_err());
+        assert!(decode(&json!({"$variant": ["Option"]})).is_err());
+        assert!(decode(&json!({"$tuple_variant": ["Foo", "Bar", 1]})).is_err());
+        assert!(decode(&json!({"$unknown": 1})).is_err());
+    }
+}
@@ -10,6 +10,7 @@ use chrono::DateTime;
 
 use crate::{
     charwithposition::CharWithPosition,
+    limits::Limits,
     location::Location,
     peekableiter::PeekableIter,
     token::{Comment, NumberToken, NumberType, Token, TokenWithRange},
@@ -20,18 +21,39 @@ pub struct Lexer<'a> {
     upstream: &'a mut PeekableIter<'a, CharWithPosition>,
     last_position: Location,
     saved_positions: Vec<Location>,
+
+    // the length of String and the size of document are checked by the lexer.
+    limits: Limits,
+
+    // the lexer stops after the document size error is yielded.
+    document_size_exceeded: bool,
 }
 
 impl<'a> Lexer<'a> {
-    pub fn new(upstream: &'a mut PeekableIter<'a, CharWithPosition>) -> Self {
+    pub fn new_with_limits(
+        upstream: &'a mut PeekableIter<'a, CharWithPosition>,
+        limits: Limits,
+    ) -> Self {
         Self {
             upstream,
             last_position: Location::new_position(/*0,*/ 0, 0, 0),
             saved_positions: vec![],
+            limits,
+            document_size_exceeded: false,
         }
     }
 
+    // the chars beyond the document size limit are treated as
+    // the end of document.
+    fn peek(&self, offset: usize) -> Option<&CharWithPosition> {
+        self.upstream.peek(offset).filter(|c| {
+            c.position.byte_index + c.character.len_utf8() <= self.limits.max_document_size
+        })
+    }
+
     fn next_char(&mut self) -> Option<char> {
+        self.peek(0)?;
+
         match self.upstream.next() {
             Some(CharWithPosition {
                 character,
@@ -45,7 +67,7 @@ impl<'a> Lexer<'a> {
     }
 
     fn peek_char(&self, offset: usize) -> Option<&char> {
-        match self.upstream.peek(offset) {
+        match self.peek(offset) {
             Some(CharWithPosition { character, .. }) => Some(character),
             None => None,
         }
@@ -53,12 +75,12 @@ impl<'a> Lexer<'a> {
 
     fn peek_char_and_equals(&self, offset: usize, expected_char: char) -> bool {
         matches!(
-            self.upstream.peek(offset),
+            self.peek(offset),
             Some(CharWithPosition { character, .. }) if character == &expected_char)
     }
 
     fn peek_position(&self, offset: usize) -> Option<&Location> {
-        match self.upstream.peek(offset) {
+        match self.peek(offset) {
             Some(CharWithPosition { position, .. }) => Some(position),
             None => None,
         }
@@ -71,12 +93,42 @@ impl<'a> Lexer<'a> {
     fn pop_saved_position(&mut self) -> Location {
         self.saved_positions.pop().unwrap()
     }
+
+    // checks the length of the String or byte data being lexed,
+    // the start position of the token has been saved.
+    fn check_string_length(&self, length: usize) -> Result<(), AsonError> {
+        self.limits
+            .check_string_length(length, *self.saved_positions.last().unwrap())
+    }
 }
 
 impl Iterator for Lexer<'_> {
     type Item = Result<TokenWithRange, AsonError>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if self.document_size_exceeded {
+            return None;
+        }
+
+        let result = self.lex_next();
+
+        // the token is replaced by the error if the lexer reaches
+        // the document size limit.
+        match self.upstream.peek(0) {
+            Some(CharWithPosition {
+                character,
+                position,
+            }) if position.byte_index + character.len_utf8() > self.limits.max_document_size => {
+                self.document_size_exceeded = true;
+                Some(Err(self.limits.new_document_size_error(*position)))
+            }
+            _ => result,
+        }
+    }
+}
+
+impl Lexer<'_> {
+    fn lex_next(&mut self) -> Option<Result<TokenWithRange, AsonError>> {
         // skip all whitespaces
         loop {
             match self.peek_char(0) {
@@ -267,7 +319,7 @@ impl Lexer<'_> {
                 // identifier/name/keyword
                 self.lex_identifier()
             }
-            current_char => Err(AsonError::MessageWithLocation(
+            current_char => Err(AsonError::new_syntax(
                 format!("Unexpected char '{}'.", current_char),
                 *self.peek_position(0).unwrap(),
             )),
@@ -341,7 +393,7 @@ impl Lexer<'_> {
                     break;
                 }
                 _ => {
-                    return Err(AsonError::MessageWithLocation(
+                    return Err(AsonError::new_syntax(
                         format!("Invalid char '{}' for identifier.", current_char),
                         *self.peek_position(0).unwrap(),
                     ));
@@ -463,7 +515,7 @@ impl Lexer<'_> {
                     break;
                 }
                 _ => {
-                    return Err(AsonError::MessageWithLocation(
+                    return Err(AsonError::new_syntax(
                         format!("Invalid char '{}' for decimal number.", current_char),
                         *self.peek_position(0).unwrap(),
                     ));
@@ -473,14 +525,14 @@ impl Lexer<'_> {
 
         // check syntax
         if num_string.ends_with('.') {
-            return Err(AsonError::MessageWithLocation(
+            return Err(AsonError::new_syntax(
                 "Decimal number can not ends with \".\".".to_owned(),
                 self.last_position,
             ));
         }
 
         if num_string.ends_with('e') {
-            return Err(AsonError::MessageWithLocation(
+            return Err(AsonError::new_syntax(
                 "Decimal number can not ends with \"e\".".to_owned(),
                 self.last_position,
             ));
@@ -496,7 +548,7 @@ impl Lexer<'_> {
             match nt {
                 NumberType::I8 => {
                     let v = num_string.parse::<u8>().map_err(|_| {
-                        AsonError::MessageWithLocation(
+                        AsonError::new_syntax(
                             format!("Can not convert \"{}\" to i8 integer number.", num_string),
                             num_range,
                         )
@@ -506,7 +558,7 @@ impl Lexer<'_> {
                 }
                 NumberType::U8 => {
                     let v = num_string.parse::<u8>().map_err(|_| {
-                        AsonError::MessageWithLocation(
+                        AsonError::new_syntax(
                             format!("Can not convert \"{}\" to u8 integer number.", num_string),
                             num_range,
                         )
@@ -516,7 +568,7 @@ impl Lexer<'_> {
                 }
                 NumberType::I16 => {
                     let v = num_string.parse::<u16>().map_err(|_| {
-                        AsonError::MessageWithLocation(
+                        AsonError::new_syntax(
                             format!("Can not convert \"{}\" to i16 integer number.", num_string),
                             num_range,
                         )
@@ -526,7 +578,7 @@ impl Lexer<'_> {
                 }
                 NumberType::U16 => {
                     let v = num_string.parse::<u16>().map_err(|_| {
-                        AsonError::MessageWithLocation(
+                        AsonError::new_syntax(
                             format!("Can not convert \"{}\" to u16 integer number.", num_string),
                             num_range,
                         )
@@ -536,7 +588,7 @@ impl Lexer<'_> {
                 }
                 NumberType::I32 => {
                     let v = num_string.parse::<u32>().map_err(|_| {
-                        AsonError::MessageWithLocation(
+                        AsonError::new_syntax(
                             format!("Can not convert \"{}\" to i32 integer number.", num_string),
                             num_range,
                         )
@@ -546,7 +598,7 @@ impl Lexer<'_> {
                 }
                 NumberType::U32 => {
                     let v = num_string.parse::<u32>().map_err(|_| {
-                        AsonError::MessageWithLocation(
+                        AsonError::new_syntax(
                             format!("Can not convert \"{}\" to u32 integer number.", num_string),
                             num_range,
                         )
@@ -556,7 +608,7 @@ impl Lexer<'_> {
                 }
                 NumberType::I64 => {
                     let v = num_string.parse::<u64>().map_err(|_| {
-                        AsonError::MessageWithLocation(
+                        AsonError::new_syntax(
                             format!("Can not convert \"{}\" to i64 integer number.", num_string),
                             num_range,
                         )
@@ -566,7 +618,7 @@ impl Lexer<'_> {
                 }
                 NumberType::U64 => {
                     let v = num_string.parse::<u64>().map_err(|_| {
-                        AsonError::MessageWithLocation(
+                        AsonError::new_syntax(
                             format!("Can not convert \"{}\" to u64 integer number.", num_string),
                             num_range,
                         )
@@ -576,7 +628,7 @@ impl Lexer<'_> {
                 }
                 NumberType::F32 => {
                     let v = num_string.parse::<f32>().map_err(|_| {
-                        AsonError::MessageWithLocation(
+                        AsonError::new_syntax(
                             format!(
                                 "Can not convert \"{}\" to f32 floating-point number.",
                                 num_string
@@ -587,7 +639,7 @@ impl Lexer<'_> {
 
                     // overflow when parsing from string
                     if v.is_infinite() {
-                        return Err(AsonError::MessageWithLocation(
+                        return Err(AsonError::new_syntax(
                             format!("F32 floating point number \"{}\" is overflow.", num_string),
                             num_range,
                         ));
@@ -597,7 +649,7 @@ impl Lexer<'_> {
                 }
                 NumberType::F64 => {
                     let v = num_string.parse::<f64>().map_err(|_| {
-                        AsonError::MessageWithLocation(
+                        AsonError::new_syntax(
                             format!(
                                 "Can not convert \"{}\" to f64 floating-point number.",
                                 num_string
@@ -608,7 +660,7 @@ impl Lexer<'_> {
 
                     // overflow when parsing from string
                     if v.is_infinite() {
-                        return Err(AsonError::MessageWithLocation(
+                        return Err(AsonError::new_syntax(
                             format!("F64 floating point number \"{}\" is overflow.", num_string),
                             num_range,
                         ));
@@ -621,7 +673,7 @@ impl Lexer<'_> {
             // the default floating-point number type is f64
 
             let v = num_string.parse::<f64>().map_err(|_| {
-                AsonError::MessageWithLocation(
+                AsonError::new_syntax(
                     format!(
                         "Can not convert \"{}\" to f64 floating-point number.",
                         num_string
@@ -632,7 +684,7 @@ impl Lexer<'_> {
 
             // overflow when parsing from string
             if v.is_infinite() {
-                return Err(AsonError::MessageWithLocation(
+                return Err(AsonError::new_syntax(
                     format!("F64 floating point number \"{}\" is overflow.", num_string),
                     num_range,
                 ));
@@ -643,7 +695,7 @@ impl Lexer<'_> {
             // the default integer number type is i32
 
             let v = num_string.parse::<u32>().map_err(|_| {
-                AsonError::MessageWithLocation(
+                AsonError::new_syntax(
                     format!("Can not convert \"{}\" to i32 integer number.", num_string,),
                     num_range,
                 )
@@ -693,7 +745,7 @@ impl Lexer<'_> {
         );
 
         let nt = NumberType::from_str(&type_name)
-            .map_err(|msg| AsonError::MessageWithLocation(msg, type_range))?;
+            .map_err(|msg| AsonError::new_syntax(msg, type_range))?;
 
         Ok(nt)
     }
@@ -785,7 +837,7 @@ impl Lexer<'_> {
                     break;
                 }
                 _ => {
-                    return Err(AsonError::MessageWithLocation(
+                    return Err(AsonError::new_syntax(
                         format!("Invalid char '{}' for hexadecimal number.", current_char),
                         *self.peek_position(0).unwrap(),
                     ));
@@ -799,14 +851,14 @@ impl Lexer<'_> {
         );
 
         if num_string.is_empty() {
-            return Err(AsonError::MessageWithLocation(
+            return Err(AsonError::new_syntax(
                 "Empty hexadecimal number".to_owned(),
                 num_range,
             ));
         }
 
         if found_point && !found_p {
-            return Err(AsonError::MessageWithLocation(
+            return Err(AsonError::new_syntax(
                 format!(
                     "Hexadecimal floating point number \"{}\" is missing the exponent.",
                     num_string
@@ -828,7 +880,7 @@ impl Lexer<'_> {
                         to_f64 = true;
                     }
                     _ => {
-                        return Err(AsonError::MessageWithLocation(format!(
+                        return Err(AsonError::new_syntax(format!(
                                 "Invalid type \"{}\" for hexadecimal floating-point numbers, only type \"f32\" and \"f64\" are allowed.",
                                 nt
                             ),
@@ -843,7 +895,7 @@ impl Lexer<'_> {
             if to_f64 {
                 let v = hexfloat2::parse::<f64>(&num_string).map_err(|_| {
                     // there is no detail message provided by `hexfloat2::parse`.
-                    AsonError::MessageWithLocation(
+                    AsonError::new_syntax(
                         format!(
                             "Can not convert \"{}\" to f64 floating-point number.",
                             num_string
@@ -856,7 +908,7 @@ impl Lexer<'_> {
             } else {
                 let v = hexfloat2::parse::<f32>(&num_string).map_err(|_| {
                     // there is no detail message provided by `hexfloat2::parse`.
-                    AsonError::MessageWithLocation(
+                    AsonError::new_syntax(
                         format!(
                             "Can not convert \"{}\" to f32 floating-point number.",
                             num_string
@@ -871,7 +923,7 @@ impl Lexer<'_> {
             match nt {
                 NumberType::I8 => {
                     let v = u8::from_str_radix(&num_string, 16).map_err(|_| {
-                        AsonError::MessageWithLocation(
+                        AsonError::new_syntax(
                             format!("Can not convert \"{}\" to i8 integer number.", num_string),
                             num_range,
                         )
@@ -881,7 +933,7 @@ impl Lexer<'_> {
                 }
                 NumberType::U8 => {
                     let v = u8::from_str_radix(&num_string, 16).map_err(|_| {
-                        AsonError::MessageWithLocation(
+                        AsonError::new_syntax(
                             format!("Can not convert \"{}\" to u8 integer number.", num_string),
                             num_range,
                         )
@@ -891,7 +943,7 @@ impl Lexer<'_> {
                 }
                 NumberType::I16 => {
                     let v = u16::from_str_radix(&num_string, 16).map_err(|_| {
-                        AsonError::MessageWithLocation(
+                        AsonError::new_syntax(
                             format!("Can not convert \"{}\" to i16 integer number.", num_string),
                             num_range,
                         )
@@ -901,7 +953,7 @@ impl Lexer<'_> {
                 }
                 NumberType::U16 => {
                     let v = u16::from_str_radix(&num_string, 16).map_err(|_| {
-                        AsonError::MessageWithLocation(
+                        AsonError::new_syntax(
                             format!("Can not convert \"{}\" to u16 integer number.", num_string),
                             num_range,
                         )
@@ -911,7 +963,7 @@ impl Lexer<'_> {
                 }
                 NumberType::I32 => {
                     let v = u32::from_str_radix(&num_string, 16).map_err(|_| {
-                        AsonError::MessageWithLocation(
+                        AsonError::new_syntax(
                             format!("Can not convert \"{}\" to i32 integer number.", num_string),
                             num_range,
                         )
@@ -921,7 +973,7 @@ impl Lexer<'_> {
                 }
                 NumberType::U32 => {
                     let v = u32::from_str_radix(&num_string, 16).map_err(|_| {
-                        AsonError::MessageWithLocation(
+                        AsonError::new_syntax(
                             format!("Can not convert \"{}\" to u32 integer number.", num_string),
                             num_range,
                         )
@@ -931,7 +983,7 @@ impl Lexer<'_> {
                 }
                 NumberType::I64 => {
                     let v = u64::from_str_radix(&num_string, 16).map_err(|_| {
-                        AsonError::MessageWithLocation(
+                        AsonError::new_syntax(
                             format!("Can not convert \"{}\" to i64 integer number.", num_string),
                             num_range,
                         )
@@ -941,7 +993,7 @@ impl Lexer<'_> {
                 }
                 NumberType::U64 => {
                     let v = u64::from_str_radix(&num_string, 16).map_err(|_| {
-                        AsonError::MessageWithLocation(
+                        AsonError::new_syntax(
                             format!("Can not convert \"{}\" to u64 integer number.", num_string),
                             num_range,
                         )
@@ -959,7 +1011,7 @@ impl Lexer<'_> {
             // default
             // convert to i32
             let v = u32::from_str_radix(&num_string, 16).map_err(|_| {
-                AsonError::MessageWithLocation(
+                AsonError::new_syntax(
                     format!("Can not convert \"{}\" to i32 integer number.", num_string),
                     num_range,
                 )
@@ -1010,7 +1062,7 @@ impl Lexer<'_> {
                     break;
                 }
                 _ => {
-                    return Err(AsonError::MessageWithLocation(
+                    return Err(AsonError::new_syntax(
                         format!("Invalid char '{}' for binary number.", current_char),
                         *self.peek_position(0).unwrap(),
                     ));
@@ -1024,7 +1076,7 @@ impl Lexer<'_> {
         );
 
         if num_string.is_empty() {
-            return Err(AsonError::MessageWithLocation(
+            return Err(AsonError::new_syntax(
                 "Empty binary number.".to_owned(),
                 num_range,
             ));
@@ -1034,7 +1086,7 @@ impl Lexer<'_> {
             match nt {
                 NumberType::I8 => {
                     let v = u8::from_str_radix(&num_string, 2).map_err(|_| {
-                        AsonError::MessageWithLocation(
+                        AsonError::new_syntax(
                             format!("Can not convert \"{}\" to i8 integer number.", num_string,),
                             num_range,
                         )
@@ -1044,7 +1096,7 @@ impl Lexer<'_> {
                 }
                 NumberType::U8 => {
                     let v = u8::from_str_radix(&num_string, 2).map_err(|_| {
-                        AsonError::MessageWithLocation(
+                        AsonError::new_syntax(
                             format!("Can not convert \"{}\" to u8 integer number.", num_string,),
                             num_range,
                         )
@@ -1054,7 +1106,7 @@ impl Lexer<'_> {
                 }
                 NumberType::I16 => {
                     let v = u16::from_str_radix(&num_string, 2).map_err(|_| {
-                        AsonError::MessageWithLocation(
+                        AsonError::new_syntax(
                             format!("Can not convert \"{}\" to i16 integer number.", num_string,),
                             num_range,
                         )
@@ -1064,7 +1116,7 @@ impl Lexer<'_> {
                 }
                 NumberType::U16 => {
                     let v = u16::from_str_radix(&num_string, 2).map_err(|_| {
-                        AsonError::MessageWithLocation(
+                        AsonError::new_syntax(
                             format!("Can not convert \"{}\" to u16 integer number.", num_string,),
                             num_range,
                         )
@@ -1074,7 +1126,7 @@ impl Lexer<'_> {
                 }
                 NumberType::I32 => {
                     let v = u32::from_str_radix(&num_string, 2).map_err(|_| {
-                        AsonError::MessageWithLocation(
+                        AsonError::new_syntax(
                             format!("Can not convert \"{}\" to i32 integer number.", num_string),
                             num_range,
                         )
@@ -1084,7 +1136,7 @@ impl Lexer<'_> {
                 }
                 NumberType::U32 => {
                     let v = u32::from_str_radix(&num_string, 2).map_err(|_| {
-                        AsonError::MessageWithLocation(
+                        AsonError::new_syntax(
                             format!("Can not convert \"{}\" to u32 integer number.", num_string),
                             num_range,
                         )
@@ -1094,7 +1146,7 @@ impl Lexer<'_> {
                 }
                 NumberType::I64 => {
                     let v = u64::from_str_radix(&num_string, 2).map_err(|_| {
-                        AsonError::MessageWithLocation(
+                        AsonError::new_syntax(
                             format!("Can not convert \"{}\" to i64 integer number.", num_string),
                             num_range,
                         )
@@ -1104,7 +1156,7 @@ impl Lexer<'_> {
                 }
                 NumberType::U64 => {
                     let v = u64::from_str_radix(&num_string, 2).map_err(|_| {
-                        AsonError::MessageWithLocation(
+                        AsonError::new_syntax(
                             format!("Can not convert \"{}\" to u64 integer number.", num_string),
                             num_range,
                         )
@@ -1121,7 +1173,7 @@ impl Lexer<'_> {
             // convert to i32
 
             let v = u32::from_str_radix(&num_string, 2).map_err(|_| {
-                AsonError::MessageWithLocation(
+                AsonError::new_syntax(
                     format!("Can not convert \"{}\" to i32 integer number.", num_string),
                     num_range,
                 )
@@ -1178,18 +1230,19 @@ impl Lexer<'_> {
                                             // unicode code point, e.g. '\u{2d}', '\u{6587}'
                                             self.unescape_unicode()?
                                         } else {
-                                            return Err(AsonError::MessageWithLocation(
+                                            return Err(AsonError::new_syntax(
                                                 "Missing the brace for unicode escape sequence."
                                                     .to_owned(),
-                                                self.last_position.move_position_forward(),
+                                                self.last_position
+                                                    .move_position_forward(previous_char),
                                             ));
                                         }
                                     }
                                     _ => {
-                                        return Err(AsonError::MessageWithLocation(
+                                        return Err(AsonError::new_syntax(
                                             format!("Unsupported escape char '{}'.", previous_char),
                                             Location::from_position_and_length(
-                                                &self.last_position.move_position_backward(),
+                                                &self.last_position.move_position_backward('\\'),
                                                 2,
                                             ),
                                         ));
@@ -1198,7 +1251,7 @@ impl Lexer<'_> {
                             }
                             None => {
                                 // `\` + EOF
-                                return Err(AsonError::UnexpectedEndOfDocument(
+                                return Err(AsonError::new_unexpected_end_of_document(
                                     "Incomplete escape character sequence.".to_owned(),
                                 ));
                             }
@@ -1206,7 +1259,7 @@ impl Lexer<'_> {
                     }
                     '\'' => {
                         // `''`
-                        return Err(AsonError::MessageWithLocation(
+                        return Err(AsonError::new_syntax(
                             "Empty char.".to_owned(),
                             Location::from_position_pair_with_end_included(
                                 &self.pop_saved_position(),
@@ -1222,7 +1275,7 @@ impl Lexer<'_> {
             }
             None => {
                 // `'EOF`
-                return Err(AsonError::UnexpectedEndOfDocument(
+                return Err(AsonError::new_unexpected_end_of_document(
                     "Incomplete character.".to_owned(),
                 ));
             }
@@ -1235,14 +1288,14 @@ impl Lexer<'_> {
             }
             Some(_) => {
                 // `'a?`
-                return Err(AsonError::MessageWithLocation(
+                return Err(AsonError::new_syntax(
                     "Expected a quote for char".to_owned(),
                     self.last_position,
                 ));
             }
             None => {
                 // `'aEOF`
-                return Err(AsonError::UnexpectedEndOfDocument(
+                return Err(AsonError::new_unexpected_end_of_document(
                     "Incomplete character.".to_owned(),
                 ));
             }
@@ -1272,7 +1325,7 @@ impl Lexer<'_> {
                     '}' => break,
                     '0'..='9' | 'a'..='f' | 'A'..='F' => codepoint_string.push(previous_char),
                     _ => {
-                        return Err(AsonError::MessageWithLocation(
+                        return Err(AsonError::new_syntax(
                             format!(
                                 "Invalid character '{}' for unicode escape sequence.",
                                 previous_char
@@ -1283,7 +1336,7 @@ impl Lexer<'_> {
                 },
                 None => {
                     // EOF
-                    return Err(AsonError::UnexpectedEndOfDocument(
+                    return Err(AsonError::new_unexpected_end_of_document(
                         "Incomplete unicode escape sequence.".to_owned(),
                     ));
                 }
@@ -1300,14 +1353,14 @@ impl Lexer<'_> {
         );
 
         if codepoint_string.len() > 6 {
-            return Err(AsonError::MessageWithLocation(
+            return Err(AsonError::new_syntax(
                 "Unicode point code exceeds six digits.".to_owned(),
                 codepoint_range,
             ));
         }
 
         if codepoint_string.is_empty() {
-            return Err(AsonError::MessageWithLocation(
+            return Err(AsonError::new_syntax(
                 "Empty unicode code point.".to_owned(),
                 codepoint_range,
             ));
@@ -1323,7 +1376,7 @@ impl Lexer<'_> {
             // https://doc.rust-lang.org/std/primitive.char.html
             Ok(ch)
         } else {
-            Err(AsonError::MessageWithLocation(
+            Err(AsonError::new_syntax(
                 "Invalid unicode code point.".to_owned(),
                 codepoint_range,
             ))
@@ -1342,6 +1395,8 @@ impl Lexer<'_> {
         let mut final_string = String::new();
 
         loop {
+            self.check_string_length(final_string.len())?;
+
             match self.next_char() {
                 Some(prev_previous_char) => {
                     match prev_previous_char {
@@ -1383,9 +1438,9 @@ impl Lexer<'_> {
                                                 let ch = self.unescape_unicode()?;
                                                 final_string.push(ch);
                                             } else {
-                                                return Err(AsonError::MessageWithLocation(
+                                                return Err(AsonError::new_syntax(
                                                     "Missing the brace for unicode escape sequence.".to_owned(),
-                                                    self.last_position.move_position_forward()
+                                                    self.last_position.move_position_forward(previous_char)
                                                 ));
                                             }
                                         }
@@ -1400,13 +1455,15 @@ impl Lexer<'_> {
                                             self.consume_all_leading_whitespaces()?;
                                         }
                                         _ => {
-                                            return Err(AsonError::MessageWithLocation(
+                                            return Err(AsonError::new_syntax(
                                                 format!(
                                                     "Unsupported escape char '{}'.",
                                                     previous_char
                                                 ),
                                                 Location::from_position_and_length(
-                                                    &self.last_position.move_position_backward(),
+                                                    &self
+                                                        .last_position
+                                                        .move_position_backward('\\'),
                                                     2,
                                                 ),
                                             ));
@@ -1415,7 +1472,7 @@ impl Lexer<'_> {
                                 }
                                 None => {
                                     // `\` + EOF
-                                    return Err(AsonError::UnexpectedEndOfDocument(
+                                    return Err(AsonError::new_unexpected_end_of_document(
                                         "Incomplete character escape sequence.".to_owned(),
                                     ));
                                 }
@@ -1433,7 +1490,7 @@ impl Lexer<'_> {
                 }
                 None => {
                     // `"...EOF`
-                    return Err(AsonError::UnexpectedEndOfDocument(
+                    return Err(AsonError::new_unexpected_end_of_document(
                         "Incomplete string.".to_owned(),
                     ));
                 }
@@ -1470,7 +1527,7 @@ impl Lexer<'_> {
                 }
                 None => {
                     // EOF
-                    return Err(AsonError::UnexpectedEndOfDocument(
+                    return Err(AsonError::new_unexpected_end_of_document(
                         "Incomplete string.".to_owned(),
                     ));
                 }
@@ -1494,6 +1551,8 @@ impl Lexer<'_> {
         let mut final_string = String::new();
 
         loop {
+            self.check_string_length(final_string.len())?;
+
             match self.next_char() {
                 Some(previous_char) => {
                     match previous_char {
@@ -1509,7 +1568,7 @@ impl Lexer<'_> {
                 }
                 None => {
                     // `r"...EOF`
-                    return Err(AsonError::UnexpectedEndOfDocument(
+                    return Err(AsonError::new_unexpected_end_of_document(
                         "Incomplete string.".to_owned(),
                     ));
                 }
@@ -1545,6 +1604,8 @@ impl Lexer<'_> {
         let mut final_string = String::new();
 
         loop {
+            self.check_string_length(final_string.len())?;
+
             match self.next_char() {
                 Some(previous_char) => {
                     match previous_char {
@@ -1561,7 +1622,7 @@ impl Lexer<'_> {
                 }
                 None => {
                     // `r#"...EOF`
-                    return Err(AsonError::UnexpectedEndOfDocument(
+                    return Err(AsonError::new_unexpected_end_of_document(
                         "Incomplete string.".to_owned(),
                     ));
                 }
@@ -1605,16 +1666,23 @@ impl Lexer<'_> {
             self.next_char(); // consume '\r'
             self.next_char(); // consume '\n'
         } else {
-            return Err(AsonError::MessageWithLocation(
+            return Err(AsonError::new_syntax(
                 "The content of auto-trimmed string should start on a new line.".to_owned(),
-                self.last_position.move_position_forward(),
+                self.last_position.move_position_forward('"'),
             ));
         }
 
         let mut lines = vec![]; // String::new();
         let mut current_line = vec![]; //String::new();
 
+        // the length of the non-whitespace chars, which never exceeds
+        // the length of the final string, so the limit can be checked
+        // before trimming.
+        let mut content_length: usize = 0;
+
         loop {
+            self.check_string_length(content_length)?;
+
             match self.next_char() {
                 Some(previous_char) => {
                     match previous_char {
@@ -1644,13 +1712,16 @@ impl Lexer<'_> {
                         }
                         _ => {
                             // ordinary char
+                            if !matches!(previous_char, ' ' | '\t') {
+                                content_length += previous_char.len_utf8();
+                            }
                             current_line.push(previous_char);
                         }
                     }
                 }
                 None => {
                     // `"""\n...EOF`
-                    return Err(AsonError::UnexpectedEndOfDocument(
+                    return Err(AsonError::new_unexpected_end_of_document(
                         "Incomplete string.".to_owned(),
                     ));
                 }
@@ -1718,6 +1789,9 @@ impl Lexer<'_> {
             .collect::<Vec<String>>()
             .join("");
 
+        self.limits
+            .check_string_length(content.len(), range.get_position_by_range_start())?;
+
         Ok(TokenWithRange::new(Token::String(content), range))
     }
 
@@ -1747,7 +1821,7 @@ impl Lexer<'_> {
                             date_string.push(previous_char);
                         }
                         _ => {
-                            return Err(AsonError::MessageWithLocation(
+                            return Err(AsonError::new_syntax(
                                 format!("Invalid char '{}' for datetime.", previous_char),
                                 self.last_position,
                             ));
@@ -1756,7 +1830,7 @@ impl Lexer<'_> {
                 }
                 None => {
                     // d"...EOF
-                    return Err(AsonError::UnexpectedEndOfDocument(
+                    return Err(AsonError::new_unexpected_end_of_document(
                         "Incomplete date time.".to_owned(),
                     ));
                 }
@@ -1781,7 +1855,7 @@ impl Lexer<'_> {
             // YYYY-MM-DDTHH:mm:ssZ
             // YYYY-MM-DDTHH:mm:ss+08:00
         } else {
-            return Err(AsonError::MessageWithLocation(
+            return Err(AsonError::new_syntax(
                 format!(
                     "Invalid date time string: {}, the required format is: \"YYYY-MM-DD HH:mm:ss\"",
                     date_string
@@ -1791,7 +1865,7 @@ impl Lexer<'_> {
         }
 
         let rfc3339 = DateTime::parse_from_rfc3339(&date_string).map_err(|_| {
-            AsonError::MessageWithLocation(
+            AsonError::new_syntax(
                 format!(
                     "Can not convert the string \"{}\" to datetime.",
                     date_string
@@ -1837,10 +1911,10 @@ impl Lexer<'_> {
                                 if amount > 0 {
                                     break;
                                 } else {
-                                    return Err(AsonError::MessageWithLocation(
+                                    return Err(AsonError::new_syntax(
                                         "Expect a whitespace between the hexadecimal byte data digits."
                                             .to_owned(),
-                                        iter.last_position.move_position_forward()
+                                        *iter.peek_position(0).unwrap()
                                     ));
                                 }
                             }
@@ -1848,7 +1922,7 @@ impl Lexer<'_> {
                     }
                     None => {
                         // h"...EOF
-                        return Err(AsonError::UnexpectedEndOfDocument(
+                        return Err(AsonError::new_unexpected_end_of_document(
                             "Incomplete hexadecimal byte data.".to_owned(),
                         ));
                     }
@@ -1880,7 +1954,7 @@ impl Lexer<'_> {
                             *c = previous_char;
                         }
                         _ => {
-                            return Err(AsonError::MessageWithLocation(
+                            return Err(AsonError::new_syntax(
                                 format!(
                                     "Invalid digit '{}' for hexadecimal byte data.",
                                     previous_char
@@ -1890,7 +1964,7 @@ impl Lexer<'_> {
                         }
                     },
                     None => {
-                        return Err(AsonError::UnexpectedEndOfDocument(
+                        return Err(AsonError::new_unexpected_end_of_document(
                             "Incomplete hexadecimal byte data.".to_owned(),
                         ))
                     }
@@ -1900,6 +1974,7 @@ impl Lexer<'_> {
             let byte_string = String::from_iter(chars);
             let byte_number = u8::from_str_radix(&byte_string, 16).unwrap();
             bytes.push(byte_number);
+            self.check_string_length(bytes.len())?;
 
             if self.peek_char_and_equals(0, '"') {
                 break;
@@ -1942,7 +2017,7 @@ impl Lexer<'_> {
                 '\n' => {
                     break;
                 }
-                '\r' if self.peek_char_and_equals(0, '\n') => {
+                '\r' if self.peek_char_and_equals(1, '\n') => {
                     break;
                 }
                 _ => {
@@ -2018,7 +2093,7 @@ impl Lexer<'_> {
                         "Incomplete block comment.".to_owned()
                     };
 
-                    return Err(AsonError::UnexpectedEndOfDocument(msg));
+                    return Err(AsonError::new_unexpected_end_of_document(msg));
                 }
             }
         }
@@ -2044,9 +2119,11 @@ mod tests {
         charwithposition::CharsWithPositionIter,
         // charstream::CharStreamFromCharIter,
         lexer::{Comment, NumberToken, TokenWithRange},
+        limits::Limits,
         location::Location,
         peekableiter::PeekableIter,
         AsonError,
+        ErrorKind,
     };
 
     use super::{Lexer, Token, LEXER_PEEK_CHAR_MAX_COUNT};
@@ -2066,11 +2143,15 @@ mod tests {
     }
 
     fn lex_from_str(s: &str) -> Result<Vec<TokenWithRange>, AsonError> {
+        lex_from_str_with_limits(s, Limits::default())
+    }
+
+    fn lex_from_str_with_limits(s: &str, limits: Limits) -> Result<Vec<TokenWithRange>, AsonError> {
         let mut chars = s.chars();
         let mut char_position_iter = CharsWithPositionIter::new(&mut chars);
         let mut peekable_char_position_iter =
             PeekableIter::new(&mut char_position_iter, LEXER_PEEK_CHAR_MAX_COUNT);
-        let lexer = Lexer::new(&mut peekable_char_position_iter);
+        let lexer = Lexer::new_with_limits(&mut peekable_char_position_iter, limits);
 
         // do not use `iter.collect::<Vec<_>>()` because the `Lexer` throws
         // exceptions though the function `next() -> Option<Result<...>>`,
@@ -2087,7 +2168,14 @@ mod tests {
     }
 
     fn lex_from_str_without_location(s: &str) -> Result<Vec<Token>, AsonError> {
-        let tokens = lex_from_str(s)?
+        lex_from_str_without_location_with_limits(s, Limits::default())
+    }
+
+    fn lex_from_str_without_location_with_limits(
+        s: &str,
+        limits: Limits,
+    ) -> Result<Vec<Token>, AsonError> {
+        let tokens = lex_from_str_with_limits(s, limits)?
             .into_iter()
             .map(|e| e.token)
             .collect::<Vec<Token>>();
@@ -2242,16 +2330,18 @@ mod tests {
         // err: invalid char
         assert!(matches!(
             lex_from_str_without_location("abc&xyz"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 3,
+                    byte_index: 3,
                     line: 0,
                     column: 3,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
     }
 
@@ -2407,31 +2497,35 @@ mod tests {
         // err: invalid char for decimal number
         assert!(matches!(
             lex_from_str_without_location("12x34"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 2,
+                    byte_index: 2,
                     line: 0,
                     column: 2,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: integer number overflow
         assert!(matches!(
             lex_from_str_without_location("4_294_967_296"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 0,
+                    byte_index: 0,
                     line: 0,
                     column: 0,
                     length: 13
-                }
-            ))
+                }),
+                ..
+            })
         ));
     }
 
@@ -2471,76 +2565,86 @@ mod tests {
         // err: incomplete floating point number since ends with '.'
         assert!(matches!(
             lex_from_str_without_location("123."),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 3,
+                    byte_index: 3,
                     line: 0,
                     column: 3,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: incomplete floating point number since ends with 'e'
         assert!(matches!(
             lex_from_str_without_location("123e"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 3,
+                    byte_index: 3,
                     line: 0,
                     column: 3,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: multiple '.' (point)
         assert!(matches!(
             lex_from_str_without_location("1.23.456"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 4,
+                    byte_index: 4,
                     line: 0,
                     column: 4,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: multiple 'e' (exponent)
         assert!(matches!(
             lex_from_str_without_location("1e23e456"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 4,
+                    byte_index: 4,
                     line: 0,
                     column: 4,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: unsupports start with dot
         assert!(matches!(
             lex_from_str_without_location(".123"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 0,
+                    byte_index: 0,
                     line: 0,
                     column: 0,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
     }
 
@@ -2599,16 +2703,18 @@ mod tests {
             // err: unsigned overflow
             assert!(matches!(
                 lex_from_str_without_location("256_u8"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 6
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
         }
 
@@ -2627,16 +2733,18 @@ mod tests {
             // err: unsigned overflow
             assert!(matches!(
                 lex_from_str_without_location("65536_u16"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 9
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
         }
 
@@ -2655,16 +2763,18 @@ mod tests {
             // err: unsigned overflow
             assert!(matches!(
                 lex_from_str_without_location("4_294_967_296_u32"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 17
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
         }
 
@@ -2685,16 +2795,18 @@ mod tests {
             // err: unsigned overflow
             assert!(matches!(
                 lex_from_str_without_location("18_446_744_073_709_551_616_u64"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 30
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
         }
 
@@ -2713,16 +2825,18 @@ mod tests {
             // err: overflow
             assert!(matches!(
                 lex_from_str_without_location("3.4e39_f32"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 10
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
         }
 
@@ -2743,16 +2857,18 @@ mod tests {
             // err: overflow
             assert!(matches!(
                 lex_from_str_without_location("1.8e309_f64"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 11
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
         }
     }
@@ -2795,46 +2911,52 @@ mod tests {
         // err: invalid char for hex number
         assert!(matches!(
             lex_from_str_without_location("0x1234xyz"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 6,
+                    byte_index: 6,
                     line: 0,
                     column: 6,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: hex number overflow
         assert!(matches!(
             lex_from_str_without_location("0x1_0000_0000"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 0,
+                    byte_index: 0,
                     line: 0,
                     column: 0,
                     length: 13
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: empty hex number
         assert!(matches!(
             lex_from_str_without_location("0x"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 0,
+                    byte_index: 0,
                     line: 0,
                     column: 0,
                     length: 2
-                }
-            ))
+                }),
+                ..
+            })
         ));
     }
 
@@ -2893,16 +3015,18 @@ mod tests {
             // err: unsigned overflow
             assert!(matches!(
                 lex_from_str_without_location("0x1_ff_u8"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 9
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
         }
 
@@ -2921,16 +3045,18 @@ mod tests {
             // err: unsigned overflow
             assert!(matches!(
                 lex_from_str_without_location("0x1_ffff_u16"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 12
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
         }
 
@@ -2949,16 +3075,18 @@ mod tests {
             // err: unsigned overflow
             assert!(matches!(
                 lex_from_str_without_location("0x1_ffff_ffff_u32"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 17
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
         }
 
@@ -2979,16 +3107,18 @@ mod tests {
             // err: unsigned overflow
             assert!(matches!(
                 lex_from_str_without_location("0x1_ffff_ffff_ffff_ffff_u64"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 27
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
         }
 
@@ -3048,76 +3178,86 @@ mod tests {
         // err: missing the exponent
         assert!(matches!(
             lex_from_str_without_location("0x1.23"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 0,
+                    byte_index: 0,
                     line: 0,
                     column: 0,
                     length: 6
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: multiple '.' (point)
         assert!(matches!(
             lex_from_str_without_location("0x1.2.3"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 5,
+                    byte_index: 5,
                     line: 0,
                     column: 5,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: multiple 'p' (exponent)
         assert!(matches!(
             lex_from_str_without_location("0x1.2p3p4"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 7,
+                    byte_index: 7,
                     line: 0,
                     column: 7,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: incorrect type (invalid dot '.' after 'p')
         assert!(matches!(
             lex_from_str_without_location("0x1.23p4.5"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 8,
+                    byte_index: 8,
                     line: 0,
                     column: 8,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: incorrect type (invalid char 'i' after 'p')
         assert!(matches!(
             lex_from_str_without_location("0x1.23p4_i32"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 9,
+                    byte_index: 9,
                     line: 0,
                     column: 9,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
     }
 
@@ -3159,61 +3299,69 @@ mod tests {
         // err: does not support binary floating point
         assert!(matches!(
             lex_from_str_without_location("0b11.10"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 4,
+                    byte_index: 4,
                     line: 0,
                     column: 4,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: binary number overflow
         assert!(matches!(
             lex_from_str_without_location("0b1_0000_0000_0000_0000_0000_0000_0000_0000"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 0,
+                    byte_index: 0,
                     line: 0,
                     column: 0,
                     length: 43
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: invalid char for binary number
         assert!(matches!(
             lex_from_str_without_location("0b101xyz"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 5,
+                    byte_index: 5,
                     line: 0,
                     column: 5,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: empty binary number
         assert!(matches!(
             lex_from_str_without_location("0b"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 0,
+                    byte_index: 0,
                     line: 0,
                     column: 0,
                     length: 2
-                }
-            ))
+                }),
+                ..
+            })
         ));
     }
 
@@ -3272,16 +3420,18 @@ mod tests {
             // err: unsigned overflow
             assert!(matches!(
                 lex_from_str_without_location("0b1_1111_1111_u8"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 16
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
         }
 
@@ -3300,16 +3450,18 @@ mod tests {
             // err: unsigned overflow
             assert!(matches!(
                 lex_from_str_without_location("0b1_1111_1111_1111_1111_u16"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 27
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
         }
 
@@ -3330,16 +3482,18 @@ mod tests {
             // err: unsigned overflow
             assert!(matches!(
                 lex_from_str_without_location("0b1_1111_1111_1111_1111__1111_1111_1111_1111_u32"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 48
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
         }
 
@@ -3358,32 +3512,36 @@ mod tests {
             // err: unsigned overflow
             assert!(matches!(
                 lex_from_str_without_location("0b1_1111_1111_1111_1111__1111_1111_1111_1111__1111_1111_1111_1111__1111_1111_1111_1111_u64"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
-                        index: 0,
-                        line: 0,
-                        column: 0,
-                        length: 90
-                    }
-                ))
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                            unit: 0,
+                            index: 0,
+                            byte_index: 0,
+                            line: 0,
+                            column: 0,
+                            length: 90
+                        }),
+                    ..
+                })
             ));
         }
 
         // err: does not support binary floating pointer number (invalid char 'f' for binary number)
         assert!(matches!(
             lex_from_str_without_location("0b11_f32"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 5,
+                    byte_index: 5,
                     line: 0,
                     column: 5,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
     }
 
@@ -3509,88 +3667,104 @@ mod tests {
         // err: empty char
         assert!(matches!(
             lex_from_str_without_location("''"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 0,
+                    byte_index: 0,
                     line: 0,
                     column: 0,
                     length: 2
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: empty char, missing the char
         assert!(matches!(
             lex_from_str_without_location("'"),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
 
         // err: incomplete char, missing the right quote, encounter EOF
         assert!(matches!(
             lex_from_str_without_location("'a"),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
 
         // err: invalid char, expect the right quote, encounter another char
         assert!(matches!(
             lex_from_str_without_location("'ab"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 2,
+                    byte_index: 2,
                     line: 0,
                     column: 2,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: invalid char, expect the right quote, encounter another char
         assert!(matches!(
             lex_from_str_without_location("'ab'"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 2,
+                    byte_index: 2,
                     line: 0,
                     column: 2,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: unsupported escape char \v
         assert!(matches!(
             lex_from_str_without_location(r#"'\v'"#),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 1,
+                    byte_index: 1,
                     line: 0,
                     column: 1,
                     length: 2
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: unsupported hex escape "\x.."
         assert!(matches!(
             lex_from_str_without_location(r#"'\x33'"#),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 1,
+                    byte_index: 1,
                     line: 0,
                     column: 1,
                     length: 2
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: empty unicode escape string
@@ -3598,16 +3772,18 @@ mod tests {
         //  01 2345     // index
         assert!(matches!(
             lex_from_str_without_location("'\\u{}'"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 3,
+                    byte_index: 3,
                     line: 0,
                     column: 3,
                     length: 2
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: invalid unicode code point, digits too much
@@ -3615,16 +3791,18 @@ mod tests {
         //  01 234567890    // index
         assert!(matches!(
             lex_from_str_without_location("'\\u{1000111}'"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 3,
+                    byte_index: 3,
                     line: 0,
                     column: 3,
                     length: 8
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: invalid unicode code point, code point out of range
@@ -3632,67 +3810,78 @@ mod tests {
         //  01 2345678901
         assert!(matches!(
             lex_from_str_without_location("'\\u{123456}'"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 3,
+                    byte_index: 3,
                     line: 0,
                     column: 3,
                     length: 8
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: invalid char in the unicode escape sequence
         assert!(matches!(
             lex_from_str_without_location("'\\u{12mn}''"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 6,
+                    byte_index: 6,
                     line: 0,
                     column: 6,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: missing the closed brace for unicode escape sequence
         assert!(matches!(
             lex_from_str_without_location("'\\u{1234'"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 8,
+                    byte_index: 8,
                     line: 0,
                     column: 8,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: incomplete unicode escape sequence, encounter EOF
         assert!(matches!(
             lex_from_str_without_location("'\\u{1234"),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
 
         // err: missing left brace for unicode escape sequence
         assert!(matches!(
             lex_from_str_without_location("'\\u1234}'"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 3,
+                    byte_index: 3,
                     line: 0,
                     column: 3,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
     }
 
@@ -3786,49 +3975,131 @@ mod tests {
         // err: incomplete string, missing the closed quote
         assert!(matches!(
             lex_from_str_without_location("\"abc"),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
 
         // err: incomplete string, missing the closed quote, ends with \n
         assert!(matches!(
             lex_from_str_without_location("\"abc\n"),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
 
         // err: incomplete string, missing the closed quote, ends with whitespaces/other chars
         assert!(matches!(
             lex_from_str_without_location("\"abc\n   "),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
 
         // err: unsupported escape char \v
         assert!(matches!(
             lex_from_str_without_location(r#""abc\vxyz""#),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 4,
+                    byte_index: 4,
                     line: 0,
                     column: 4,
                     length: 2
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: unsupported hex escape "\x.."
         assert!(matches!(
             lex_from_str_without_location(r#""abc\x33xyz""#),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 4,
+                    byte_index: 4,
                     line: 0,
                     column: 4,
                     length: 2
-                }
-            ))
+                }),
+                ..
+            })
+        ));
+
+        // err: unsupported escape char, the byte index counts
+        // the UTF-8 bytes of the preceding chars.
+        assert!(matches!(
+            lex_from_str_without_location(r#""文字\v""#),
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
+                    index: 3,
+                    byte_index: 7,
+                    line: 0,
+                    column: 3,
+                    length: 2
+                }),
+                ..
+            })
+        ));
+
+        // err: missing the brace for unicode escape
+        assert!(matches!(
+            lex_from_str_without_location(r#""文字\u""#),
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
+                    index: 5,
+                    byte_index: 9,
+                    line: 0,
+                    column: 5,
+                    length: 0
+                }),
+                ..
+            })
+        ));
+
+        // err: the content of auto-trimmed string does not start on a new line
+        assert!(matches!(
+            lex_from_str_without_location(r#""文" """abc"#),
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
+                    index: 7,
+                    byte_index: 9,
+                    line: 0,
+                    column: 7,
+                    length: 0
+                }),
+                ..
+            })
+        ));
+
+        // err: missing the whitespace between the hexadecimal bytes
+        assert!(matches!(
+            lex_from_str_without_location(r#""🍒" h"1122""#),
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
+                    index: 8,
+                    byte_index: 11,
+                    line: 0,
+                    column: 8,
+                    length: 0
+                }),
+                ..
+            })
         ));
 
         // err: empty unicode escape string
@@ -3836,16 +4107,18 @@ mod tests {
         // 012345678    // index
         assert!(matches!(
             lex_from_str_without_location(r#""abc\u{}xyz""#),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 6,
+                    byte_index: 6,
                     line: 0,
                     column: 6,
                     length: 2
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: invalid unicode code point, too much digits
@@ -3853,16 +4126,18 @@ mod tests {
         // 0123456789023456789    // index
         assert!(matches!(
             lex_from_str_without_location(r#""abc\u{1000111}xyz""#),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 6,
+                    byte_index: 6,
                     line: 0,
                     column: 6,
                     length: 8
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: invalid unicode code point, code point out of range
@@ -3870,67 +4145,78 @@ mod tests {
         // 012345678901234567
         assert!(matches!(
             lex_from_str_without_location(r#""abc\u{123456}xyz""#),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 6,
+                    byte_index: 6,
                     line: 0,
                     column: 6,
                     length: 8
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: invalid char in the unicode escape sequence
         assert!(matches!(
             lex_from_str_without_location(r#""abc\u{12mn}xyz""#),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 9,
+                    byte_index: 9,
                     line: 0,
                     column: 9,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: missing the right brace for unicode escape sequence
         assert!(matches!(
             lex_from_str_without_location(r#""abc\u{1234""#),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 11,
+                    byte_index: 11,
                     line: 0,
                     column: 11,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: incomplete unicode escape sequence, encounter EOF
         assert!(matches!(
             lex_from_str_without_location(r#""abc\u{1234"#),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
 
         // err: missing left brace for unicode escape sequence
         assert!(matches!(
             lex_from_str_without_location(r#""abc\u1234}xyz""#),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 6,
+                    byte_index: 6,
                     line: 0,
                     column: 6,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
     }
 
@@ -3967,13 +4253,19 @@ mod tests {
         // err: incomplete string, missing the closed quote, ends with \n
         assert!(matches!(
             lex_from_str_without_location("\"abc\n    \n\n    \n"),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
 
         // err: incomplete string, missing the closed quote, whitespaces/other chars
         assert!(matches!(
             lex_from_str_without_location("\"abc\n    \n\n    \n   "),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
     }
 
@@ -4012,13 +4304,19 @@ mod tests {
         // err: incomplete string, missing the right quote, ends with \n
         assert!(matches!(
             lex_from_str_without_location("\"abc\\\n"),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
 
         // err: incomplete string, missing the right quote, ends with whitespaces/other chars
         assert!(matches!(
             lex_from_str_without_location("\"abc\\\n    "),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
     }
 
@@ -4055,19 +4353,28 @@ mod tests {
         // err: incomplete string, missing the right quote
         assert!(matches!(
             lex_from_str_without_location("r\"abc    "),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
 
         // err: incomplete string, missing the right quote, ends with \n
         assert!(matches!(
             lex_from_str_without_location("r\"abc\n"),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
 
         // err: incomplete string, missing the right quote, ends with whitespaces/other chars
         assert!(matches!(
             lex_from_str_without_location("r\"abc\n   "),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
     }
 
@@ -4103,19 +4410,28 @@ mod tests {
         // err: incomplete string, missing the closed hash
         assert!(matches!(
             lex_from_str_without_location("r#\"abc    \""),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
 
         // err: incomplete string, missing the closed quote, ends with \n
         assert!(matches!(
             lex_from_str_without_location("r#\"abc\n"),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
 
         // err: incomplete string, missing the closed quote, ends with whitespace/other chars
         assert!(matches!(
             lex_from_str_without_location("r#\"abc\nxyz"),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
     }
 
@@ -4265,16 +4581,18 @@ mod tests {
 """
 "#
             ),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 4,
+                    byte_index: 4,
                     line: 1,
                     column: 3,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: missing the ending marker (the ending marker does not start on a new line)
@@ -4285,7 +4603,10 @@ mod tests {
 hello"""
 "#
             ),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
 
         // err: missing the ending marker
@@ -4296,7 +4617,10 @@ hello"""
 hello
 world"#
             ),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
 
         // err: missing the ending marker, ends with \n
@@ -4307,7 +4631,10 @@ world"#
 hello
 "#
             ),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
     }
 
@@ -4395,79 +4722,96 @@ hello
         // err: not enough digits
         assert!(matches!(
             lex_from_str_without_location("h\"11 1\""),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 6,
+                    byte_index: 6,
                     line: 0,
                     column: 6,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: too much digits | no whitespace between two bytes
         assert!(matches!(
             lex_from_str_without_location("h\"11 1317\""),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 7,
+                    byte_index: 7,
                     line: 0,
                     column: 7,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: invalid char for byte string
         assert!(matches!(
             lex_from_str_without_location("h\"11 1x\""),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 6,
+                    byte_index: 6,
                     line: 0,
                     column: 6,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: invalid separator
         assert!(matches!(
             lex_from_str_without_location("h\"11-13\""),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 4,
+                    byte_index: 4,
                     line: 0,
                     column: 4,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: missing the close quote
         assert!(matches!(
             lex_from_str_without_location("h\"11 13"),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
 
         // err: missing the close quote, ends with \n
         assert!(matches!(
             lex_from_str_without_location("h\"11 13\n"),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
 
         // err: missing the close quote, ends with whitespaces/other chars
         assert!(matches!(
             lex_from_str_without_location("h\"11 13\n    "),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
     }
 
@@ -4500,6 +4844,17 @@ hello
             ]
         );
 
+        // the `\r\n` does not belong to the comment
+        assert_eq!(
+            lex_from_str_without_location("7 //11\r\n13").unwrap(),
+            vec![
+                Token::Number(NumberToken::I32(7)),
+                Token::Comment(Comment::Line("11".to_owned())),
+                Token::NewLine,
+                Token::Number(NumberToken::I32(13)),
+            ]
+        );
+
         // location
 
         assert_eq!(
@@ -4644,25 +4999,37 @@ hello
         // err: incomplete, missing "*/"
         assert!(matches!(
             lex_from_str_without_location("7 /* 11"),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
 
         // err: incomplete, missing "*/", ends with \n
         assert!(matches!(
             lex_from_str_without_location("7 /* 11\n"),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
 
         // err: incomplete, unpaired, missing "*/"
         assert!(matches!(
             lex_from_str_without_location("a /* b /* c */"),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
 
         // err: incomplete, unpaired, missing "*/", ends with \n
         assert!(matches!(
             lex_from_str_without_location("a /* b /* c */\n"),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
     }
 
@@ -4728,52 +5095,61 @@ hello
         // err: syntex error, should be YYYY-MM-DD HH:mm:ss
         assert!(matches!(
             lex_from_str_without_location("d\"2024-3-16 4:30:50\""),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 0,
+                    byte_index: 0,
                     line: 0,
                     column: 0,
                     length: 20
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: missing date part
         assert!(matches!(
             lex_from_str_without_location("d\"16:30:50\""),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 0,
+                    byte_index: 0,
                     line: 0,
                     column: 0,
                     length: 11
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: invalid char
         assert!(matches!(
             lex_from_str_without_location("d\"Aug 8, 2024\""),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 2,
+                    byte_index: 2,
                     line: 0,
                     column: 2,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: incomplete date string
         assert!(matches!(
             lex_from_str_without_location("d\"2024-08-08"),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
     }
 
@@ -4917,4 +5293,126 @@ hello
             ]
         );
     }
+
+    #[test]
+    fn test_lex_with_limits() {
+        let limits = Limits {
+            max_string_length: 3,
+            max_document_size: 32,
+            ..Limits::default()
+        };
+
+        assert_eq!(
+            lex_from_str_without_location_with_limits(r#""abc" r"abc" h"11 22 33""#, limits)
+                .unwrap(),
+            vec![
+                Token::new_string("abc"),
+                Token::new_string("abc"),
+                Token::HexByteData(vec![0x11, 0x22, 0x33])
+            ]
+        );
+
+        // string
+        assert!(matches!(
+            lex_from_str_with_limits(r#"  "abcd""#, limits),
+            Err(AsonError {
+                kind: ErrorKind::StringLengthLimitExceeded,
+                location: Some(Location { index: 2, .. }),
+                ..
+            })
+        ));
+
+        // the length is counted in UTF-8 bytes
+        assert!(matches!(
+            lex_from_str_with_limits(r#""文字""#, limits),
+            Err(AsonError {
+                kind: ErrorKind::StringLengthLimitExceeded,
+                ..
+            })
+        ));
+
+        // raw string
+        assert!(matches!(
+            lex_from_str_with_limits(r##"r#"abcd"#"##, limits),
+            Err(AsonError {
+                kind: ErrorKind::StringLengthLimitExceeded,
+                ..
+            })
+        ));
+
+        // auto-trimmed string, the leading whitespaces are trimmed
+        assert_eq!(
+            lex_from_str_without_location_with_limits("\"\"\"\n        abc\n    \"\"\"", limits)
+                .unwrap(),
+            vec![Token::new_string("abc")]
+        );
+
+        assert!(matches!(
+            lex_from_str_with_limits("\"\"\"\n  ab\n  cd\n\"\"\"", limits),
+            Err(AsonError {
+                kind: ErrorKind::StringLengthLimitExceeded,
+                ..
+            })
+        ));
+
+        // byte data
+        assert!(matches!(
+            lex_from_str_with_limits(r#"h"11 22 33 44""#, limits),
+            Err(AsonError {
+                kind: ErrorKind::StringLengthLimitExceeded,
+                location: Some(Location { index: 0, .. }),
+                ..
+            })
+        ));
+
+        // document size
+        assert!(matches!(
+            lex_from_str_with_limits("[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]", limits),
+            Err(AsonError {
+                kind: ErrorKind::DocumentSizeLimitExceeded,
+                location: Some(Location { index: 32, .. }),
+                ..
+            })
+        ));
+
+        assert!(
+            lex_from_str_with_limits(r#"[1, "0123456789abcdef"]"#, Limits::unlimited()).is_ok()
+        );
+
+        assert!(matches!(
+            lex_from_str_with_limits(
+                r#"[1, "0123456789abcdef"]"#,
+                Limits {
+                    max_document_size: 20,
+                    ..Limits::unlimited()
+                }
+            ),
+            Err(AsonError {
+                kind: ErrorKind::DocumentSizeLimitExceeded,
+                ..
+            })
+        ));
+
+        // the size is counted in UTF-8 bytes, "文字" is 6 bytes,
+        // and the limit falls in the middle of the last char.
+        let limits = Limits {
+            max_document_size: 8,
+            ..Limits::unlimited()
+        };
+        assert!(lex_from_str_with_limits(r#""文字""#, limits).is_ok());
+        assert!(matches!(
+            lex_from_str_with_limits(r#""文字" 1"#, limits),
+            Err(AsonError {
+                kind: ErrorKind::DocumentSizeLimitExceeded,
+                ..
+            })
+        ));
+        assert!(matches!(
+            lex_from_str_with_limits(r#""文字🍒""#, limits),
+            Err(AsonError {
+                kind: ErrorKind::DocumentSizeLimitExceeded,
+                ..
+            })
+        ));
+    }
 }
@@ -4,67 +4,74 @@
 // the Mozilla Public License version 2.0 and additional exceptions,
 // more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
 
+mod anchor;
 pub mod ast;
+pub mod binary;
 mod charstream;
 mod charwithposition;
+#[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+pub mod conversion;
+pub mod diff;
+mod error;
 mod errorprinter;
+pub mod include;
+pub mod interpolation;
+#[cfg(feature = "json")]
+pub mod json;
 mod lexer;
+mod limits;
 mod location;
+#[cfg(feature = "lsp")]
+pub mod lsp;
+pub mod merge;
 mod normalizer;
+mod options;
 mod parser;
+pub mod patch;
+pub mod path;
 mod peekableiter;
 mod printer;
 mod serde;
+mod sourceindex;
+mod sourcemap;
+mod stream;
+pub mod threeway;
 mod token;
+pub mod tokenizer;
+#[cfg(feature = "tokio")]
+pub mod tokio;
+#[cfg(feature = "toml")]
+pub mod toml;
+mod writer;
+#[cfg(feature = "yaml")]
+pub mod yaml;
 
 pub use parser::parse_from_reader;
 pub use parser::parse_from_str;
 pub use printer::print_to_string;
 pub use printer::print_to_writer;
+pub use stream::{PushParser, PushStatus, StreamParser};
+pub use writer::AsonWriter;
 
 pub use serde::de::from_reader;
 pub use serde::de::from_str;
 pub use serde::ser::to_string;
+pub use serde::ser::to_string_with_anchors;
 pub use serde::ser::to_writer;
+pub use serde::ser::to_writer_with_anchors;
 pub use serde::serde_date::Date;
+pub use serde::shared::Shared;
+pub use serde::spanned::Spanned;
+pub use serde::stream::{StreamDeserializer, StreamWriter};
 
-use std::fmt::{self, Display};
-
-use crate::location::Location;
-
-#[derive(Debug, PartialEq, Clone)]
-pub enum AsonError {
-    Message(String),
-    UnexpectedEndOfDocument(String),
-
-    // note that the "index" (and the result of "index+length") may exceed
-    // the last index of string, for example, the "char incomplete" error raised by a string `'a`,
-    // which index is 2.
-    MessageWithLocation(String, Location),
-}
-
-impl Display for AsonError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            AsonError::Message(msg) => f.write_str(msg),
-            AsonError::UnexpectedEndOfDocument(detail) => {
-                writeln!(f, "Unexpected to reach the end of document.")?;
-                write!(f, "{}", detail)
-            }
-            AsonError::MessageWithLocation(detail, location) => {
-                writeln!(
-                    f,
-                    "Error at line: {}, column: {}",
-                    location.line + 1,
-                    location.column + 1
-                )?;
-                write!(f, "{}", detail)
-            }
-        }
-    }
-}
-
-impl std::error::Error for AsonError {}
+pub use anchor::expand_anchors;
+pub use error::{AsonError, ErrorKind};
+pub use errorprinter::{Diagnostic, DiagnosticLabel, RenderOptions};
+pub use limits::Limits;
+pub use location::Location;
+pub use options::ParseOptions;
+pub use sourceindex::SourceIndex;
+pub use sourcemap::{SourceMap, SourcePosition};
 
 // #[cfg(test)]
 // mod tests {
@@ -0,0 +1,119 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+use crate::{location::Location, AsonError, ErrorKind};
+
+/// The resource limits of parsing and deserializing, which protect
+/// the application from hostile documents, e.g. deeply nested Lists
+/// that overflow the stack, or huge Strings that exhaust the memory.
+///
+/// Nothing is limited by default, use the struct update syntax to opt in,
+/// e.g. `Limits { max_depth: Limits::DEFAULT_MAX_DEPTH, ..Limits::default() }`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// The maximum nesting depth (in levels) of List, Map, Object, Tuple
+    /// and Variant, e.g. the depth of `[[1]]` is 2.
+    pub max_depth: usize,
+
+    /// The maximum length (in bytes) of String, i.e. the UTF-8 bytes, and
+    /// hexadecimal byte data, i.e. the decoded bytes.
+    pub max_string_length: usize,
+
+    /// The maximum number of elements (in count) of a single List, Map,
+    /// Object, Tuple or Variant.
+    pub max_container_length: usize,
+
+    /// The maximum size (in bytes) of the UTF-8 text of the document,
+    /// or of the binary data.
+    pub max_document_size: usize,
+}
+
+impl Limits {
+    /// The recommended nesting depth for the untrusted documents.
+    //
+    // the parser takes about 25 KiB of stack for each level in
+    // the debug build, this value keeps it within the default
+    // stack size (2 MiB) of the spawned threads.
+    pub const DEFAULT_MAX_DEPTH: usize = 64;
+
+    pub const fn unlimited() -> Self {
+        Self {
+            max_depth: usize::MAX,
+            max_string_length: usize::MAX,
+            max_container_length: usize::MAX,
+            max_document_size: usize::MAX,
+        }
+    }
+
+    pub(crate) fn check_depth(&self, depth: usize, location: Location) -> Result<(), AsonError> {
+        if depth > self.max_depth {
+            Err(AsonError::new(
+                ErrorKind::DepthLimitExceeded,
+                format!("The nesting depth exceeds the limit {}.", self.max_depth),
+            )
+            .with_location(location))
+        } else {
+            Ok(())
+        }
+    }
+
+    // the location is the start of the String or the byte data.
+    pub(crate) fn check_string_length(
+        &self,
+        length: usize,
+        location: Location,
+    ) -> Result<(), AsonError> {
+        if length > self.max_string_length {
+            Err(AsonError::new(
+                ErrorKind::StringLengthLimitExceeded,
+                format!(
+                    "The length of String or byte data exceeds the limit {}.",
+                    self.max_string_length
+                ),
+            )
+            .with_location(location))
+        } else {
+            Ok(())
+        }
+    }
+
+    // the location is the element that exceeds the limit.
+    pub(crate) fn check_container_length(
+        &self,
+        length: usize,
+        location: Location,
+    ) -> Result<(), AsonError> {
+        if length > self.max_container_length {
+            Err(AsonError::new(
+                ErrorKind::ContainerLengthLimitExceeded,
+                format!(
+                    "The number of elements exceeds the limit {}.",
+                    self.max_container_length
+                ),
+            )
+            .with_location(location))
+        } else {
+            Ok(())
+        }
+    }
+
+    pub(crate) fn new_document_size_error(&self, location: Location) -> AsonError {
+        AsonError::new(
+            ErrorKind::DocumentSizeLimitExceeded,
+            format!(
+                "The size of document exceeds the limit {} bytes.",
+                self.max_document_size
+            ),
+        )
+        .with_location(location)
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self::unlimited()
+    }
+}
@@ -6,54 +6,68 @@
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Location {
-    // pub unit: usize,   // the index of source file
-    pub index: usize,  // character index
-    pub line: usize,   // line index
-    pub column: usize, // column index
-    pub length: usize, // text length, 0 for position
+    pub unit: usize,       // the index of source unit (file), 0 for the main document
+    pub index: usize,      // character index
+    pub byte_index: usize, // byte index of the UTF-8 text
+    pub line: usize,       // line index
+    pub column: usize,     // column index
+    pub length: usize,     // text length (in chars), 0 for position
 }
 
+// the `byte_index` of the constructors is the same as `index`, i.e. the
+// source is ASCII, use `with_byte_index` to set the actual byte index.
+// they are internal, the locations of other texts are built by
+// `SourceIndex::location_of`, which sets the byte index properly.
 impl Location {
-    pub fn new_position(/* unit: usize,*/ index: usize, line: usize, column: usize) -> Self {
+    pub(crate) fn new_position(index: usize, line: usize, column: usize) -> Self {
         Self {
-            // unit,
+            unit: 0,
             index,
+            byte_index: index,
             line,
             column,
             length: 0,
         }
     }
 
-    pub fn new_range(/* unit: usize, */ index: usize, line: usize, column: usize, length: usize) -> Self {
+    pub(crate) fn new_range(index: usize, line: usize, column: usize, length: usize) -> Self {
         Self {
-            // unit,
+            unit: 0,
             index,
+            byte_index: index,
             line,
             column,
             length,
         }
     }
 
+    /// Returns the location in the specified source unit.
+    pub fn with_unit(self, unit: usize) -> Self {
+        Self { unit, ..self }
+    }
+
+    /// Returns the location with the specified byte index.
+    pub fn with_byte_index(self, byte_index: usize) -> Self {
+        Self { byte_index, ..self }
+    }
+
     /// Build Range with Position and length
     pub fn from_position_and_length(position: &Location, length: usize) -> Self {
-        Self::new_range(
-            // position.unit,
-            position.index,
-            position.line,
-            position.column,
-            length,
-        )
+        Self::new_range(position.index, position.line, position.column, length)
+            .with_unit(position.unit)
+            .with_byte_index(position.byte_index)
     }
 
     /// Convert two Positions to Range
     pub fn from_position_pair(position_start: &Location, position_end: &Location) -> Self {
         Self::new_range(
-            // position_start.unit,
             position_start.index,
             position_start.line,
             position_start.column,
             position_end.index - position_start.index,
         )
+        .with_unit(position_start.unit)
+        .with_byte_index(position_start.byte_index)
     }
 
     /// Convert two Positions to Range
@@ -62,28 +76,32 @@ impl Location {
         position_end_included: &Location,
     ) -> Self {
         Self::new_range(
-            // position_start.unit,
             position_start.index,
             position_start.line,
             position_start.column,
             position_end_included.index - position_start.index + 1,
         )
+        .with_unit(position_start.unit)
+        .with_byte_index(position_start.byte_index)
     }
 
     /// Combine two ranges into a new range
     pub fn from_range_pair(range_start: &Location, range_end: &Location) -> Self {
         Self::new_range(
-            // range_start.unit,
             range_start.index,
             range_start.line,
             range_start.column,
             range_end.index - range_start.index + range_end.length,
         )
+        .with_unit(range_start.unit)
+        .with_byte_index(range_start.byte_index)
     }
 
     /// Convert Range to Position
     pub fn get_position_by_range_start(&self) -> Self {
-        Self::new_position(/* self.unit, */ self.index, self.line, self.column)
+        Self::new_position(self.index, self.line, self.column)
+            .with_unit(self.unit)
+            .with_byte_index(self.byte_index)
     }
 
     // Convert Range to Position
@@ -93,17 +111,21 @@ impl Location {
     //     Self::new_position(self.unit, index, self.line, column)
     // }
 
-    pub fn move_position_forward(&self) -> Self {
+    // moves over the char at this position, it must not be a new line.
+    pub fn move_position_forward(&self, current_char: char) -> Self {
         Self {
             index: self.index + 1,
+            byte_index: self.byte_index + current_char.len_utf8(),
             column: self.column + 1,
             ..*self
         }
     }
 
-    pub fn move_position_backward(&self) -> Self {
+    // moves over the char before this position, it must not be a new line.
+    pub fn move_position_backward(&self, previous_char: char) -> Self {
         Self {
             index: self.index - 1,
+            byte_index: self.byte_index - previous_char.len_utf8(),
             column: self.column - 1,
             ..*self
         }
@@ -0,0 +1,344 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+//! Language Server Protocol server for ASON documents.
+//!
+//! The server communicates through stdio, and provides:
+//!
+//! - syntax diagnostics, updated on every change.
+//! - document formatting via the printer. Documents containing comments
+//!   are not formatted since the printer does not preserve comments.
+//! - document symbols (outline) for Objects and Maps.
+//! - folding ranges for Objects, Lists, Maps, Tuples and block comments.
+//! - hover showing the type of values, e.g. `u8` for `0xff_u8`.
+//! - semantic tokens.
+//!
+//! The binary `ason-lsp` is built with the cargo feature `lsp`.
+
+mod document;
+mod outline;
+
+use std::{collections::HashMap, error::Error};
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+        Notification as NotificationTrait, PublishDiagnostics,
+    },
+    request::{
+        DocumentSymbolRequest, FoldingRangeRequest, Formatting, HoverRequest,
+        Request as RequestTrait, SemanticTokensFullRequest,
+    },
+    DocumentSymbolResponse, FoldingRangeProviderCapability, HoverProviderCapability, OneOf,
+    PositionEncodingKind, PublishDiagnosticsParams, SemanticTokens, SemanticTokensFullOptions,
+    SemanticTokensLegend, SemanticTokensOptions, SemanticTokensResult,
+    SemanticTokensServerCapabilities, ServerCapabilities, TextDocumentSyncCapability,
+    TextDocumentSyncKind, Uri,
+};
+
+use document::{Document, SEMANTIC_TOKEN_TYPES};
+
+/// Runs the server on stdio until the client requests to shut down.
+pub fn run_stdio() -> Result<(), Box<dyn Error + Sync + Send>> {
+    let (connection, io_threads) = Connection::stdio();
+    serve(&connection)?;
+    io_threads.join()?;
+    Ok(())
+}
+
+fn server_capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        position_encoding: Some(PositionEncodingKind::UTF16),
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        document_formatting_provider: Some(OneOf::Left(true)),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        semantic_tokens_provider: Some(SemanticTokensServerCapabilities::SemanticTokensOptions(
+            SemanticTokensOptions {
+                legend: SemanticTokensLegend {
+                    token_types: SEMANTIC_TOKEN_TYPES.to_vec(),
+                    token_modifiers: vec![],
+                },
+                full: Some(SemanticTokensFullOptions::Bool(true)),
+                ..Default::default()
+            },
+        )),
+        ..Default::default()
+    }
+}
+
+fn serve(connection: &Connection) -> Result<(), Box<dyn Error + Sync + Send>> {
+    let capabilities = serde_json::to_value(server_capabilities())?;
+    connection.initialize(capabilities)?;
+
+    let mut server = Server::default();
+
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    return Ok(());
+                }
+                let response = server.handle_request(request);
+                connection.sender.send(Message::Response(response))?;
+            }
+            Message::Notification(notification) => {
+                if let Some(reply) = server.handle_notification(notification) {
+                    connection.sender.send(Message::Notification(reply))?;
+                }
+            }
+            Message::Response(_) => {
+                // the server does not send requests to client.
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct Server {
+    documents: HashMap<Uri, Document>,
+}
+
+impl Server {
+    fn handle_request(&self, request: Request) -> Response {
+        let id = request.id.clone();
+
+        let result = match request.method.as_str() {
+            Formatting::METHOD => self.dispatch::<Formatting>(request, |server, params| {
+                server
+                    .document(&params.text_document.uri)?
+                    .format()
+                    .map(Some)
+            }),
+            DocumentSymbolRequest::METHOD => {
+                self.dispatch::<DocumentSymbolRequest>(request, |server, params| {
+                    let symbols = server.document(&params.text_document.uri)?.symbols();
+                    Ok(Some(DocumentSymbolResponse::Nested(symbols)))
+                })
+            }
+            FoldingRangeRequest::METHOD => {
+                self.dispatch::<FoldingRangeRequest>(request, |server, params| {
+                    let ranges = server.document(&params.text_document.uri)?.folding_ranges();
+                    Ok(Some(ranges))
+                })
+            }
+            HoverRequest::METHOD => self.dispatch::<HoverRequest>(request, |server, params| {
+                let position_params = params.text_document_position_params;
+                Ok(server
+                    .document(&position_params.text_document.uri)?
+                    .hover(&position_params.position))
+            }),
+            SemanticTokensFullRequest::METHOD => {
+                self.dispatch::<SemanticTokensFullRequest>(request, |server, params| {
+                    let data = server
+                        .document(&params.text_document.uri)?
+                        .semantic_tokens();
+                    Ok(Some(SemanticTokensResult::Tokens(SemanticTokens {
+                        result_id: None,
+                        data,
+                    })))
+                })
+            }
+            method => Err((
+                ErrorCode::MethodNotFound,
+                format!("Unsupported method \"{}\".", method),
+            )),
+        };
+
+        match result {
+            Ok(value) => Response::new_ok(id, value),
+            Err((code, message)) => Response::new_err(id, code as i32, message),
+        }
+    }
+
+    fn dispatch<R>(
+        &self,
+        request: Request,
+        handler: fn(&Self, R::Params) -> Result<R::Result, String>,
+    ) -> Result<serde_json::Value, (ErrorCode, String)>
+    where
+        R: RequestTrait,
+    {
+        let (_, params) = request
+            .extract::<R::Params>(R::METHOD)
+            .map_err(|e| (ErrorCode::InvalidParams, format!("{:?}", e)))?;
+
+        let result = handler(self, params).map_err(|e| (ErrorCode::RequestFailed, e))?;
+        serde_json::to_value(result).map_err(|e| (ErrorCode::InternalError, e.to_string()))
+    }
+
+    fn document(&self, uri: &Uri) -> Result<&Document, String> {
+        self.documents
+            .get(uri)
+            .ok_or_else(|| format!("Document \"{}\" is not opened.", uri.as_str()))
+    }
+
+    // returns the diagnostics notification if the documents are updated.
+    fn handle_notification(&mut self, notification: Notification) -> Option<Notification> {
+        let (uri, version, diagnostics) = match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params = notification
+                    .extract::<<DidOpenTextDocument as NotificationTrait>::Params>(
+                        DidOpenTextDocument::METHOD,
+                    )
+                    .ok()?;
+                let text_document = params.text_document;
+                let document = Document::new(text_document.text);
+                let diagnostics = document.diagnostics();
+                self.documents.insert(text_document.uri.clone(), document);
+                (text_document.uri, Some(text_document.version), diagnostics)
+            }
+            DidChangeTextDocument::METHOD => {
+                let params = notification
+                    .extract::<<DidChangeTextDocument as NotificationTrait>::Params>(
+                        DidChangeTextDocument::METHOD,
+                    )
+                    .ok()?;
+
+                // the server requests full text synchronization, so
+                // the last change contains the whole text.
+                let text = params.content_changes.into_iter().last()?.text;
+                let document = Document::new(text);
+                let diagnostics = document.diagnostics();
+                self.documents
+                    .insert(params.text_document.uri.clone(), document);
+                (
+                    params.text_document.uri,
+                    Some(params.text_document.version),
+                    diagnostics,
+                )
+            }
+            DidCloseTextDocument::METHOD => {
+                let params = notification
+                    .extract::<<DidCloseTextDocument as NotificationTrait>::Params>(
+                        DidCloseTextDocument::METHOD,
+                    )
+                    .ok()?;
+                self.documents.remove(&params.text_document.uri);
+
+                // clear the diagnostics
+                (params.text_document.uri, None, vec![])
+            }
+            _ => {
+                return None;
+            }
+        };
+
+        Some(Notification::new(
+            PublishDiagnostics::METHOD.to_owned(),
+            PublishDiagnosticsParams::new(uri, diagnostics, version),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_server::{Connection, Message, Notification, Request, RequestId};
+    use lsp_types::{
+        notification::{
+            DidOpenTextDocument, Exit, Initialized, Notification as NotificationTrait,
+            PublishDiagnostics,
+        },
+        request::{HoverRequest, Initialize, Request as RequestTrait, Shutdown},
+        DidOpenTextDocumentParams, Hover, HoverContents, HoverParams, InitializeParams,
+        InitializedParams, Position, PublishDiagnosticsParams, TextDocumentIdentifier,
+        TextDocumentItem, TextDocumentPositionParams, Uri,
+    };
+    use pretty_assertions::assert_eq;
+
+    use super::serve;
+
+    fn request<R: RequestTrait>(id: i32, params: R::Params) -> Message {
+        Message::Request(Request::new(
+            RequestId::from(id),
+            R::METHOD.to_owned(),
+            params,
+        ))
+    }
+
+    fn notification<N: NotificationTrait>(params: N::Params) -> Message {
+        Message::Notification(Notification::new(N::METHOD.to_owned(), params))
+    }
+
+    fn receive_response(client: &Connection) -> serde_json::Value {
+        match client.receiver.recv().unwrap() {
+            Message::Response(response) => response.result.unwrap(),
+            message => panic!("Unexpected message: {:?}", message),
+        }
+    }
+
+    #[test]
+    fn test_server() {
+        let (server, client) = Connection::memory();
+        let server_thread = std::thread::spawn(move || serve(&server).unwrap());
+
+        client
+            .sender
+            .send(request::<Initialize>(1, InitializeParams::default()))
+            .unwrap();
+        let result = receive_response(&client);
+        assert!(result["capabilities"]["hoverProvider"].as_bool().unwrap());
+
+        client
+            .sender
+            .send(notification::<Initialized>(InitializedParams {}))
+            .unwrap();
+
+        let uri: Uri = "file:///tmp/test.ason".parse().unwrap();
+        client
+            .sender
+            .send(notification::<DidOpenTextDocument>(
+                DidOpenTextDocumentParams {
+                    text_document: TextDocumentItem::new(
+                        uri.clone(),
+                        "ason".to_owned(),
+                        1,
+                        "{id: 123_u16, name: }".to_owned(),
+                    ),
+                },
+            ))
+            .unwrap();
+
+        let params = match client.receiver.recv().unwrap() {
+            Message::Notification(n) => n
+                .extract::<PublishDiagnosticsParams>(PublishDiagnostics::METHOD)
+                .unwrap(),
+            message => panic!("Unexpected message: {:?}", message),
+        };
+        assert_eq!(params.uri, uri);
+        assert_eq!(params.version, Some(1));
+        assert_eq!(params.diagnostics.len(), 1);
+
+        client
+            .sender
+            .send(request::<HoverRequest>(
+                2,
+                HoverParams {
+                    text_document_position_params: TextDocumentPositionParams::new(
+                        TextDocumentIdentifier::new(uri),
+                        Position::new(0, 6),
+                    ),
+                    work_done_progress_params: Default::default(),
+                },
+            ))
+            .unwrap();
+        let hover: Hover = serde_json::from_value(receive_response(&client)).unwrap();
+        match hover.contents {
+            HoverContents::Markup(content) => assert_eq!(content.value, "`u16`"),
+            contents => panic!("Unexpected hover contents: {:?}", contents),
+        }
+
+        client.sender.send(request::<Shutdown>(3, ())).unwrap();
+        receive_response(&client);
+        client.sender.send(notification::<Exit>(())).unwrap();
+
+        server_thread.join().unwrap();
+    }
+}
@@ -0,0 +1,566 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+use lsp_types::{
+    Diagnostic, DiagnosticSeverity, DocumentSymbol, FoldingRange, FoldingRangeKind, Hover,
+    HoverContents, MarkupContent, MarkupKind, Position, Range, SemanticToken, SemanticTokenType,
+    TextEdit,
+};
+
+use crate::{
+    location::Location,
+    options::ParseOptions,
+    parser::parse_from_str,
+    printer::print_to_string,
+    sourceindex::SourceIndex,
+    tokenizer::{Comment, Token, TokenKind, Tokenizer},
+    ErrorKind,
+};
+
+use super::outline::{build_outline, token_type_name, OutlineNode};
+
+pub const SEMANTIC_TOKEN_TYPES: [SemanticTokenType; 7] = [
+    SemanticTokenType::NUMBER,
+    SemanticTokenType::STRING,
+    SemanticTokenType::KEYWORD,
+    SemanticTokenType::PROPERTY,
+    SemanticTokenType::TYPE,
+    SemanticTokenType::ENUM_MEMBER,
+    SemanticTokenType::COMMENT,
+];
+
+// the indices of `SEMANTIC_TOKEN_TYPES`
+const SEMANTIC_TOKEN_NUMBER: u32 = 0;
+const SEMANTIC_TOKEN_STRING: u32 = 1;
+const SEMANTIC_TOKEN_KEYWORD: u32 = 2;
+const SEMANTIC_TOKEN_PROPERTY: u32 = 3;
+const SEMANTIC_TOKEN_TYPE: u32 = 4;
+const SEMANTIC_TOKEN_ENUM_MEMBER: u32 = 5;
+const SEMANTIC_TOKEN_COMMENT: u32 = 6;
+
+// An opened text document.
+//
+// The positions of LSP are (line, UTF-16 code unit offset) pairs,
+// while the `Location` of ASON is based on char index, they are
+// converted by the `SourceIndex` of the text.
+pub struct Document {
+    index: SourceIndex<'static>,
+    chars: Vec<char>,
+
+    // all tokens including whitespaces, comments and errors.
+    tokens: Vec<Token>,
+}
+
+impl Document {
+    pub fn new(text: String) -> Self {
+        let chars: Vec<char> = text.chars().collect();
+        let tokens = Tokenizer::with_error_recovery(&text).collect();
+
+        Self {
+            index: SourceIndex::from_string(text),
+            chars,
+            tokens,
+        }
+    }
+
+    fn text(&self) -> &str {
+        self.index.source()
+    }
+
+    // converts the char index to LSP position.
+    fn position_at(&self, index: usize) -> Position {
+        let (line, character) = self.index.utf16_line_column(self.index.char_to_byte(index));
+        Position::new(line as u32, character as u32)
+    }
+
+    // converts the LSP position to char index, the position in the middle
+    // of a surrogate pair points to the start of pair.
+    fn index_at(&self, position: &Position) -> usize {
+        self.index.byte_to_char(
+            self.index
+                .byte_of_utf16_line_column(position.line as usize, position.character as usize),
+        )
+    }
+
+    fn range_of(&self, start: usize, end: usize) -> Range {
+        Range::new(self.position_at(start), self.position_at(end))
+    }
+
+    fn range_of_location(&self, location: &Location) -> Range {
+        self.range_of(location.index, location.index + location.length)
+    }
+
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        let (_, errors) = ParseOptions::new().parse_str_with_recovery(self.text());
+
+        errors
+            .into_iter()
+            .map(|error| {
+                let (range, message) = match (error.kind, error.location) {
+                    (ErrorKind::UnexpectedEndOfDocument, _) => (
+                        self.range_of(self.chars.len(), self.chars.len()),
+                        format!("Unexpected to reach the end of document. {}", error.message),
+                    ),
+                    (_, Some(location)) => (self.range_of_location(&location), error.message),
+                    (_, None) => (self.range_of(0, 0), error.message),
+                };
+
+                Diagnostic {
+                    range,
+                    severity: Some(DiagnosticSeverity::ERROR),
+                    source: Some("ason".to_owned()),
+                    message,
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+
+    pub fn format(&self) -> Result<Vec<TextEdit>, String> {
+        if self
+            .tokens
+            .iter()
+            .any(|t| matches!(t.kind, TokenKind::Comment(_)))
+        {
+            return Err(
+                "Can not format the document containing comments, the comments would be lost."
+                    .to_owned(),
+            );
+        }
+
+        let node = parse_from_str(self.text()).map_err(|e| e.to_string())?;
+        let mut formatted = print_to_string(&node);
+        formatted.push('\n');
+
+        if formatted == self.text() {
+            Ok(vec![])
+        } else {
+            Ok(vec![TextEdit::new(
+                self.range_of(0, self.chars.len()),
+                formatted,
+            )])
+        }
+    }
+
+    pub fn symbols(&self) -> Vec<DocumentSymbol> {
+        match build_outline(&self.chars, &self.tokens) {
+            Some(root) => self.symbols_of(&root),
+            None => vec![],
+        }
+    }
+
+    // the children symbols of node, only the members of Objects, the entries of Maps
+    // and the elements containing them are included.
+    fn symbols_of(&self, node: &OutlineNode) -> Vec<DocumentSymbol> {
+        node.entries
+            .iter()
+            .filter_map(|entry| {
+                let children = self.symbols_of(&entry.value);
+                if entry.is_element && children.is_empty() {
+                    return None;
+                }
+
+                #[allow(deprecated)]
+                Some(DocumentSymbol {
+                    name: entry.name.clone(),
+                    detail: entry.value.detail.clone(),
+                    kind: entry.value.kind,
+                    tags: None,
+                    deprecated: None,
+                    range: self.range_of(entry.name_start, entry.value.end),
+                    selection_range: self.range_of(entry.name_start, entry.name_end),
+                    children: Some(children),
+                })
+            })
+            .collect()
+    }
+
+    pub fn folding_ranges(&self) -> Vec<FoldingRange> {
+        let mut ranges = vec![];
+
+        if let Some(root) = build_outline(&self.chars, &self.tokens) {
+            self.collect_folding_ranges(&root, &mut ranges);
+        }
+
+        for token in &self.tokens {
+            if let TokenKind::Comment(Comment::Block(_)) = token.kind {
+                let range = self.range_of_location(&token.range);
+                if range.start.line < range.end.line {
+                    ranges.push(FoldingRange {
+                        start_line: range.start.line,
+                        end_line: range.end.line,
+                        kind: Some(FoldingRangeKind::Comment),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+
+        ranges.sort_by_key(|r| (r.start_line, r.end_line));
+        ranges
+    }
+
+    fn collect_folding_ranges(&self, node: &OutlineNode, ranges: &mut Vec<FoldingRange>) {
+        let range = self.range_of(node.start, node.end);
+        if range.start.line < range.end.line {
+            ranges.push(FoldingRange {
+                start_line: range.start.line,
+                end_line: range.end.line,
+                kind: Some(FoldingRangeKind::Region),
+                ..Default::default()
+            });
+        }
+
+        for entry in &node.entries {
+            self.collect_folding_ranges(&entry.value, ranges);
+        }
+    }
+
+    pub fn hover(&self, position: &Position) -> Option<Hover> {
+        let index = self.index_at(position);
+        let token = self
+            .tokens
+            .iter()
+            .find(|t| t.range.index <= index && index < t.range.index + t.range.length)?;
+
+        let description = match &token.kind {
+            TokenKind::Variant(type_name, member_name) => {
+                format!(
+                    "`{}::{}`: variant of `{}`",
+                    type_name, member_name, type_name
+                )
+            }
+            other => format!("`{}`", token_type_name(other)?),
+        };
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: description,
+            }),
+            range: Some(self.range_of_location(&token.range)),
+        })
+    }
+
+    pub fn semantic_tokens(&self) -> Vec<SemanticToken> {
+        // (start index, end index, token type)
+        let mut items: Vec<(usize, usize, u32)> = vec![];
+
+        for (idx, token) in self.tokens.iter().enumerate() {
+            let start = token.range.index;
+            let end = token.range.index + token.range.length;
+
+            let token_type = match &token.kind {
+                TokenKind::Number(_) => SEMANTIC_TOKEN_NUMBER,
+                TokenKind::Char(_)
+                | TokenKind::String(_)
+                | TokenKind::DateTime(_)
+                | TokenKind::HexByteData(_) => SEMANTIC_TOKEN_STRING,
+                TokenKind::Boolean(_) => SEMANTIC_TOKEN_KEYWORD,
+                TokenKind::Identifier(_) => SEMANTIC_TOKEN_PROPERTY,
+                TokenKind::Comment(_) => SEMANTIC_TOKEN_COMMENT,
+                TokenKind::Minus | TokenKind::Plus
+                    if matches!(
+                        self.tokens.get(idx + 1),
+                        Some(Token {
+                            kind: TokenKind::Number(_),
+                            ..
+                        })
+                    ) =>
+                {
+                    SEMANTIC_TOKEN_NUMBER
+                }
+                TokenKind::Variant(type_name, _) => {
+                    // `Type::Member`, the type name and the member name are
+                    // highlighted separately.
+                    let type_name_end = start + type_name.chars().count();
+                    items.push((start, type_name_end, SEMANTIC_TOKEN_TYPE));
+                    items.push((type_name_end + 2, end, SEMANTIC_TOKEN_ENUM_MEMBER));
+                    continue;
+                }
+                _ => continue,
+            };
+
+            items.push((start, end, token_type));
+        }
+
+        let mut semantic_tokens = vec![];
+        let mut last_position = Position::new(0, 0);
+
+        for (start, end, token_type) in items {
+            // tokens can not span multiple lines, so the multi-line
+            // strings and comments are split into lines.
+            let mut segment_start = start;
+            while segment_start < end {
+                let segment_end = self.chars[segment_start..end]
+                    .iter()
+                    .position(|c| *c == '\n')
+                    .map_or(end, |offset| segment_start + offset);
+
+                let range = self.range_of(segment_start, segment_end);
+                if range.end.character > range.start.character {
+                    let delta_line = range.start.line - last_position.line;
+                    let delta_start = if delta_line == 0 {
+                        range.start.character - last_position.character
+                    } else {
+                        range.start.character
+                    };
+
+                    semantic_tokens.push(SemanticToken {
+                        delta_line,
+                        delta_start,
+                        length: range.end.character - range.start.character,
+                        token_type,
+                        token_modifiers_bitset: 0,
+                    });
+
+                    last_position = range.start;
+                }
+
+                segment_start = segment_end + 1;
+            }
+        }
+
+        semantic_tokens
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::{
+        DiagnosticSeverity, FoldingRangeKind, HoverContents, Position, Range, SemanticToken,
+        SymbolKind,
+    };
+    use pretty_assertions::assert_eq;
+
+    use super::Document;
+
+    fn range(start_line: u32, start_character: u32, end_line: u32, end_character: u32) -> Range {
+        Range::new(
+            Position::new(start_line, start_character),
+            Position::new(end_line, end_character),
+        )
+    }
+
+    #[test]
+    fn test_positions() {
+        let document = Document::new("[\n    \"\u{1f600}\", 'a'\n]".to_owned());
+
+        // the emoji is 2 UTF-16 code units
+        assert_eq!(document.position_at(0), Position::new(0, 0));
+        assert_eq!(document.position_at(2), Position::new(1, 0));
+        assert_eq!(document.position_at(8), Position::new(1, 7));
+        assert_eq!(document.position_at(11), Position::new(1, 10));
+        assert_eq!(document.position_at(100), Position::new(2, 1));
+
+        assert_eq!(document.index_at(&Position::new(1, 7)), 8);
+        assert_eq!(document.index_at(&Position::new(1, 10)), 11);
+        // in the middle of the surrogate pair of emoji
+        assert_eq!(document.index_at(&Position::new(1, 6)), 7);
+        assert_eq!(document.index_at(&Position::new(1, 100)), 14);
+        assert_eq!(document.index_at(&Position::new(100, 0)), 16);
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        assert!(Document::new("{id: 123}".to_owned())
+            .diagnostics()
+            .is_empty());
+
+        let diagnostics =
+            Document::new("{\n    id: 123,\n    name: 'abc'\n}".to_owned()).diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].range, range(2, 12, 2, 12));
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::ERROR));
+
+        let diagnostics = Document::new("{\n    id: 123".to_owned()).diagnostics();
+        assert_eq!(diagnostics[0].range, range(1, 11, 1, 11));
+
+        // all errors are reported
+        let diagnostics =
+            Document::new("{\n    id: 12_x8\n    name: :\n}".to_owned()).diagnostics();
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[1].range.start, Position::new(2, 10));
+    }
+
+    #[test]
+    fn test_format() {
+        let edits = Document::new("{id:123,name:\"foo\"}".to_owned())
+            .format()
+            .unwrap();
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].range, range(0, 0, 0, 19));
+        assert_eq!(edits[0].new_text, "{\n    id: 123\n    name: \"foo\"\n}\n");
+
+        // formatted already
+        assert!(Document::new("{\n    id: 123\n}\n".to_owned())
+            .format()
+            .unwrap()
+            .is_empty());
+
+        // comments
+        assert!(Document::new("{id: 123 // comment\n}".to_owned())
+            .format()
+            .is_err());
+
+        // invalid document
+        assert!(Document::new("{id: }".to_owned()).format().is_err());
+    }
+
+    #[test]
+    fn test_symbols() {
+        let document = Document::new(
+            r#"{
+    id: 123_u8
+    servers: [
+        {host: "a", port: 80}
+        {host: "b"}
+    ]
+    names: [
+        "one": 1
+        "two": Option::Some(2)
+    ]
+    tags: ["x", "y"]
+}"#
+            .to_owned(),
+        );
+
+        let symbols = document.symbols();
+        let names: Vec<(&str, SymbolKind, Option<&str>)> = symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s.kind, s.detail.as_deref()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("id", SymbolKind::NUMBER, Some("u8")),
+                ("servers", SymbolKind::ARRAY, None),
+                ("names", SymbolKind::OBJECT, None),
+                ("tags", SymbolKind::ARRAY, None),
+            ]
+        );
+
+        assert_eq!(symbols[0].range, range(1, 4, 1, 14));
+        assert_eq!(symbols[0].selection_range, range(1, 4, 1, 6));
+
+        // list elements which are not containers are omitted
+        let servers = symbols[1].children.as_ref().unwrap();
+        assert_eq!(servers.len(), 2);
+        assert_eq!(servers[0].name, "[0]");
+        assert_eq!(servers[0].range, range(3, 8, 3, 29));
+        let server_fields: Vec<&str> = servers[0]
+            .children
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect();
+        assert_eq!(server_fields, vec!["host", "port"]);
+
+        let entries = symbols[2].children.as_ref().unwrap();
+        assert_eq!(entries[0].name, "\"one\"");
+        assert_eq!(entries[1].name, "\"two\"");
+        assert_eq!(entries[1].kind, SymbolKind::ENUM_MEMBER);
+        assert_eq!(entries[1].detail.as_deref(), Some("Option"));
+
+        assert!(symbols[3].children.as_ref().unwrap().is_empty());
+
+        // incomplete document
+        let symbols = Document::new("{\n    id: 123\n    name: ".to_owned()).symbols();
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].name, "id");
+    }
+
+    #[test]
+    fn test_folding_ranges() {
+        let document = Document::new(
+            "{\n    a: [\n        1\n    ]\n    /* multiple\n    lines */\n    b: (1, 2)\n}"
+                .to_owned(),
+        );
+
+        let ranges: Vec<(u32, u32, Option<FoldingRangeKind>)> = document
+            .folding_ranges()
+            .into_iter()
+            .map(|r| (r.start_line, r.end_line, r.kind))
+            .collect();
+
+        assert_eq!(
+            ranges,
+            vec![
+                (0, 7, Some(FoldingRangeKind::Region)),
+                (1, 3, Some(FoldingRangeKind::Region)),
+                (4, 5, Some(FoldingRangeKind::Comment)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hover() {
+        let document =
+            Document::new("{a: 0xff_u8, b: -1.5, c: 10, d: Color::Red, e: \"s\"}".to_owned());
+
+        let hover_text = |character: u32| {
+            document
+                .hover(&Position::new(0, character))
+                .map(|hover| match hover.contents {
+                    HoverContents::Markup(content) => content.value,
+                    _ => unreachable!(),
+                })
+        };
+
+        assert_eq!(hover_text(6), Some("`u8`".to_owned()));
+        assert_eq!(hover_text(17), Some("`f64`".to_owned()));
+        assert_eq!(hover_text(25), Some("`i32`".to_owned()));
+        assert_eq!(
+            hover_text(33),
+            Some("`Color::Red`: variant of `Color`".to_owned())
+        );
+        assert_eq!(hover_text(48), Some("`String`".to_owned()));
+
+        // the key and punctuations
+        assert_eq!(hover_text(1), None);
+        assert_eq!(hover_text(2), None);
+
+        assert_eq!(
+            document.hover(&Position::new(0, 6)).unwrap().range,
+            Some(range(0, 4, 0, 11))
+        );
+    }
+
+    #[test]
+    fn test_semantic_tokens() {
+        let document =
+            Document::new("{\n    a: -1 // one\n    b: E::M\n    c: \"x\ny\"\n}".to_owned());
+
+        let token = |delta_line, delta_start, length, token_type| SemanticToken {
+            delta_line,
+            delta_start,
+            length,
+            token_type,
+            token_modifiers_bitset: 0,
+        };
+
+        assert_eq!(
+            document.semantic_tokens(),
+            vec![
+                token(1, 4, 1, 3), // a
+                token(0, 3, 1, 0), // -
+                token(0, 1, 1, 0), // 1
+                token(0, 2, 6, 6), // // one
+                token(1, 4, 1, 3), // b
+                token(0, 3, 1, 4), // E
+                token(0, 3, 1, 5), // M
+                token(1, 4, 1, 3), // c
+                token(0, 3, 2, 1), // "x
+                token(1, 0, 2, 1), // y"
+            ]
+        );
+
+        // continue after lexing errors
+        let document = Document::new("[\n    'ab'\n    12\n]".to_owned());
+        assert_eq!(document.semantic_tokens(), vec![token(2, 4, 2, 0)]);
+    }
+}
@@ -0,0 +1,331 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+use lsp_types::SymbolKind;
+
+use crate::tokenizer::{NumberToken, Token, TokenKind};
+
+// The structure of document for the outline and folding ranges.
+//
+// It is built from the tokens by a tolerant parser, so that the outline
+// is still available while the document is being edited (i.e. invalid).
+#[derive(Debug, PartialEq)]
+pub struct OutlineNode {
+    pub kind: SymbolKind,
+    pub detail: Option<String>,
+    pub start: usize, // char index
+    pub end: usize,   // char index, exclusive
+    pub entries: Vec<OutlineEntry>,
+}
+
+// The member of Object, the entry of Map or the element of List and Tuple.
+#[derive(Debug, PartialEq)]
+pub struct OutlineEntry {
+    // the element of List and Tuple, which name is the index, e.g. `[0]`.
+    pub is_element: bool,
+    pub name: String,
+    pub name_start: usize,
+    pub name_end: usize,
+    pub value: OutlineNode,
+}
+
+impl OutlineNode {
+    fn new_container(kind: SymbolKind, start: usize) -> Self {
+        Self {
+            kind,
+            detail: None,
+            start,
+            end: start,
+            entries: vec![],
+        }
+    }
+
+    fn new_leaf(kind: SymbolKind, detail: Option<String>, start: usize, end: usize) -> Self {
+        Self {
+            kind,
+            detail,
+            start,
+            end,
+            entries: vec![],
+        }
+    }
+}
+
+pub fn build_outline(chars: &[char], tokens: &[Token]) -> Option<OutlineNode> {
+    let significant_tokens: Vec<&Token> = tokens
+        .iter()
+        .filter(|t| {
+            !matches!(
+                t.kind,
+                TokenKind::Whitespace
+                    | TokenKind::NewLine
+                    | TokenKind::Comment(_)
+                    | TokenKind::Error(_)
+            )
+        })
+        .collect();
+
+    let mut builder = OutlineBuilder {
+        chars,
+        tokens: significant_tokens,
+        position: 0,
+    };
+
+    builder.build_value()
+}
+
+// The readable name of the type of token, e.g. `u8`, `String`.
+pub fn token_type_name(token: &TokenKind) -> Option<String> {
+    let name = match token {
+        TokenKind::Number(number) => match number {
+            NumberToken::I8(_) => "i8",
+            NumberToken::U8(_) => "u8",
+            NumberToken::I16(_) => "i16",
+            NumberToken::U16(_) => "u16",
+            NumberToken::I32(_) => "i32",
+            NumberToken::U32(_) => "u32",
+            NumberToken::I64(_) => "i64",
+            NumberToken::U64(_) => "u64",
+            NumberToken::F32(_) => "f32",
+            NumberToken::F64(_) => "f64",
+        }
+        .to_owned(),
+        TokenKind::Boolean(_) => "bool".to_owned(),
+        TokenKind::Char(_) => "char".to_owned(),
+        TokenKind::String(_) => "String".to_owned(),
+        TokenKind::DateTime(_) => "DateTime".to_owned(),
+        TokenKind::HexByteData(data) => format!("Bytes ({} bytes)", data.len()),
+        TokenKind::Variant(type_name, _) => type_name.to_owned(),
+        _ => {
+            return None;
+        }
+    };
+
+    Some(name)
+}
+
+struct OutlineBuilder<'a> {
+    chars: &'a [char],
+    tokens: Vec<&'a Token>,
+    position: usize,
+}
+
+impl OutlineBuilder<'_> {
+    fn peek_token(&self, offset: usize) -> Option<&TokenKind> {
+        self.tokens.get(self.position + offset).map(|t| &t.kind)
+    }
+
+    fn start_of_current(&self) -> usize {
+        self.tokens[self.position].range.index
+    }
+
+    fn end_of_current(&self) -> usize {
+        let range = &self.tokens[self.position].range;
+        range.index + range.length
+    }
+
+    // the end of the last consumed token.
+    fn end_of_last(&self) -> usize {
+        match self.position {
+            0 => 0,
+            _ => {
+                let range = &self.tokens[self.position - 1].range;
+                range.index + range.length
+            }
+        }
+    }
+
+    fn text(&self, start: usize, end: usize) -> String {
+        self.chars[start..end.min(self.chars.len())]
+            .iter()
+            .collect()
+    }
+
+    fn build_value(&mut self) -> Option<OutlineNode> {
+        let token = self.peek_token(0)?;
+
+        let node = match token {
+            TokenKind::LeftBrace => self.build_object(),
+            TokenKind::LeftBracket => self.build_list_or_map(),
+            TokenKind::LeftParen => self.build_tuple(),
+            TokenKind::Plus | TokenKind::Minus => {
+                let start = self.start_of_current();
+                self.position += 1;
+                match self.build_value() {
+                    Some(mut node) => {
+                        node.start = start;
+                        node
+                    }
+                    None => {
+                        // incomplete number
+                        OutlineNode::new_leaf(SymbolKind::NUMBER, None, start, self.end_of_last())
+                    }
+                }
+            }
+            TokenKind::Variant(..) => {
+                let detail = token_type_name(token);
+                let start = self.start_of_current();
+                self.position += 1;
+
+                let mut node = match self.peek_token(0) {
+                    Some(TokenKind::LeftParen) => self.build_tuple(),
+                    Some(TokenKind::LeftBrace) => self.build_object(),
+                    _ => OutlineNode::new_leaf(SymbolKind::ENUM_MEMBER, None, start, start),
+                };
+
+                node.kind = SymbolKind::ENUM_MEMBER;
+                node.detail = detail;
+                node.start = start;
+                node.end = self.end_of_last();
+                node
+            }
+            TokenKind::Number(_)
+            | TokenKind::Boolean(_)
+            | TokenKind::Char(_)
+            | TokenKind::String(_)
+            | TokenKind::DateTime(_)
+            | TokenKind::HexByteData(_) => {
+                let kind = match token {
+                    TokenKind::Number(_) => SymbolKind::NUMBER,
+                    TokenKind::Boolean(_) => SymbolKind::BOOLEAN,
+                    TokenKind::Char(_) | TokenKind::String(_) => SymbolKind::STRING,
+                    _ => SymbolKind::CONSTANT,
+                };
+                let node = OutlineNode::new_leaf(
+                    kind,
+                    token_type_name(token),
+                    self.start_of_current(),
+                    self.end_of_current(),
+                );
+                self.position += 1;
+                node
+            }
+            _ => {
+                return None;
+            }
+        };
+
+        Some(node)
+    }
+
+    // skip commas and unexpected tokens, returns false if it reaches
+    // the closing bracket or the end of document.
+    fn skip_to_next_item(&mut self, closing: &TokenKind) -> bool {
+        loop {
+            match self.peek_token(0) {
+                None => return false,
+                Some(TokenKind::Comma) | Some(TokenKind::Colon) => self.position += 1,
+                Some(token) if token == closing => {
+                    self.position += 1;
+                    return false;
+                }
+                Some(TokenKind::RightBrace | TokenKind::RightBracket | TokenKind::RightParen) => {
+                    // mismatched closing bracket, leaves it to the outer container.
+                    return false;
+                }
+                Some(_) => return true,
+            }
+        }
+    }
+
+    fn build_object(&mut self) -> OutlineNode {
+        let mut node = OutlineNode::new_container(SymbolKind::OBJECT, self.start_of_current());
+        self.position += 1; // consume '{'
+
+        while self.skip_to_next_item(&TokenKind::RightBrace) {
+            match (self.peek_token(0), self.peek_token(1)) {
+                (Some(TokenKind::Identifier(key)), Some(TokenKind::Colon)) => {
+                    let name = key.to_owned();
+                    let name_start = self.start_of_current();
+                    let name_end = self.end_of_current();
+                    self.position += 2; // consume key and ':'
+
+                    if let Some(value) = self.build_value() {
+                        node.entries.push(OutlineEntry {
+                            is_element: false,
+                            name,
+                            name_start,
+                            name_end,
+                            value,
+                        });
+                    }
+                }
+                _ => {
+                    // unexpected token
+                    if self.build_value().is_none() {
+                        self.position += 1;
+                    }
+                }
+            }
+        }
+
+        node.end = self.end_of_last();
+        node
+    }
+
+    fn build_list_or_map(&mut self) -> OutlineNode {
+        let mut node = OutlineNode::new_container(SymbolKind::ARRAY, self.start_of_current());
+        self.position += 1; // consume '['
+
+        while self.skip_to_next_item(&TokenKind::RightBracket) {
+            let Some(value) = self.build_value() else {
+                self.position += 1; // unexpected token
+                continue;
+            };
+
+            if matches!(self.peek_token(0), Some(TokenKind::Colon)) {
+                // it is Map
+                node.kind = SymbolKind::OBJECT;
+                self.position += 1; // consume ':'
+
+                let name = self.text(value.start, value.end);
+                if let Some(entry_value) = self.build_value() {
+                    node.entries.push(OutlineEntry {
+                        is_element: false,
+                        name,
+                        name_start: value.start,
+                        name_end: value.end,
+                        value: entry_value,
+                    });
+                }
+            } else {
+                node.entries.push(OutlineEntry {
+                    is_element: true,
+                    name: format!("[{}]", node.entries.len()),
+                    name_start: value.start,
+                    name_end: value.end,
+                    value,
+                });
+            }
+        }
+
+        node.end = self.end_of_last();
+        node
+    }
+
+    fn build_tuple(&mut self) -> OutlineNode {
+        let mut node = OutlineNode::new_container(SymbolKind::ARRAY, self.start_of_current());
+        self.position += 1; // consume '('
+
+        while self.skip_to_next_item(&TokenKind::RightParen) {
+            let Some(value) = self.build_value() else {
+                self.position += 1; // unexpected token
+                continue;
+            };
+
+            node.entries.push(OutlineEntry {
+                is_element: true,
+                name: format!("[{}]", node.entries.len()),
+                name_start: value.start,
+                name_end: value.end,
+                value,
+            });
+        }
+
+        node.end = self.end_of_last();
+        node
+    }
+}
@@ -0,0 +1,579 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+//! Merges layered configurations, e.g. a base configuration, then
+//! an environment overlay, then the command-line overrides.
+//!
+//! Each layer is merged into the result of the preceding layers:
+//!
+//! - The members of Objects and the entries of Maps are merged recursively
+//!   by key (or name), the new members and entries are appended.
+//! - Lists are replaced by default, or appended with `MergeStrategy::Append`.
+//! - Variants of the same type and member have their Object payloads
+//!   (and `Type::Member(v)` values) merged, otherwise they are replaced.
+//! - The other values, including Tuples, are replaced.
+//!
+//! The layer that supplied each value is tracked, see `Provenance`.
+
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use crate::{
+    ast::{AsonNode, KeyValuePair, NameValuePair, VariantValue},
+    parser::parse_from_str,
+    patch::{Patch, PatchOperation},
+    path::{AsonPath, PathSegment},
+    AsonError, ErrorKind,
+};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum MergeStrategy {
+    // merges Objects, Maps and Variants recursively,
+    // Lists and other values are replaced.
+    Merge,
+
+    // replaces the value, no matter what type it is.
+    Replace,
+
+    // appends the elements of the overlay List,
+    // other values are merged as `Merge`.
+    Append,
+}
+
+/// A `path=value` expression that sets a value, e.g. `server.port=8080`.
+///
+/// The leading dot of path can be omitted, and the value
+/// is an ASON value, so strings must be quoted, e.g. `name="foo"`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Override {
+    pub path: AsonPath,
+    pub value: AsonNode,
+}
+
+impl Override {
+    pub fn parse(s: &str) -> Result<Self, AsonError> {
+        let new_error = |detail: &str| {
+            AsonError::new(
+                ErrorKind::Syntax,
+                format!("Invalid override \"{}\": {}", s, detail),
+            )
+        };
+
+        let pos = find_assignment(s).ok_or_else(|| new_error("expect \"path=value\"."))?;
+        let (path_text, value_text) = (s[..pos].trim(), &s[pos + 1..]);
+
+        let path = if path_text.starts_with(['.', '[']) {
+            AsonPath::parse(path_text)
+        } else {
+            AsonPath::parse(&format!(".{}", path_text))
+        }
+        .map_err(|e| new_error("invalid path.").caused_by(e))?;
+
+        let value = parse_from_str(value_text)
+            .map_err(|e| new_error(&format!("invalid value, {}", e.message)).caused_by(e))?;
+
+        Ok(Self { path, value })
+    }
+}
+
+impl FromStr for Override {
+    type Err = AsonError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Override::parse(s)
+    }
+}
+
+// finds the '=' that is not in the quoted keys or the literals of path.
+fn find_assignment(s: &str) -> Option<usize> {
+    let mut quote: Option<char> = None;
+    let mut escaping = false;
+
+    for (pos, c) in s.char_indices() {
+        match quote {
+            Some(_) if escaping => escaping = false,
+            Some(_) if c == '\\' => escaping = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '\'' | '"' => quote = Some(c),
+                '=' => return Some(pos),
+                _ => {}
+            },
+        }
+    }
+
+    None
+}
+
+/// The layers that supplied the values of the merged document.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Provenance {
+    layer_names: Vec<String>,
+
+    // the path of value and the index of layer, the values inside
+    // a recorded value are supplied by the same layer unless they are
+    // recorded individually.
+    sources: Vec<(AsonPath, usize)>,
+}
+
+impl Provenance {
+    /// Returns the name of the layer that supplied the value of the path.
+    pub fn source_of(&self, path: &AsonPath) -> Option<&str> {
+        self.sources
+            .iter()
+            .filter(|(p, _)| p == path || p.is_ancestor_of(path))
+            .max_by_key(|(p, _)| p.segments.len())
+            .map(|(_, index)| self.layer_names[*index].as_str())
+    }
+
+    /// Returns the recorded paths and the names of layers,
+    /// in the order of merging.
+    pub fn iter(&self) -> impl Iterator<Item = (&AsonPath, &str)> {
+        self.sources
+            .iter()
+            .map(|(path, index)| (path, self.layer_names[*index].as_str()))
+    }
+
+    fn record(&mut self, path: &AsonPath, layer_index: usize) {
+        self.sources
+            .retain(|(p, _)| !(p == path || path.is_ancestor_of(p)));
+        self.sources.push((path.clone(), layer_index));
+    }
+}
+
+/// Lists the values and their layers, one per line, e.g.
+///
+/// ```text
+/// .: base
+/// .server.port: production
+/// .server.host: --set
+/// ```
+impl Display for Provenance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (path, name) in self.iter() {
+            writeln!(f, "{}: {}", path, name)?;
+        }
+        Ok(())
+    }
+}
+
+/// Merges the layers of configuration in order.
+///
+/// ```rust
+/// use ason::{merge::{Merger, Override}, parse_from_str, path::AsonPath};
+///
+/// let mut merger = Merger::new();
+/// merger.add_layer("base", parse_from_str(r#"{host: "localhost", port: 80}"#).unwrap());
+/// merger.add_layer("production", parse_from_str(r#"{host: "example.com"}"#).unwrap());
+/// merger
+///     .add_override("--set", &Override::parse("port=8080").unwrap())
+///     .unwrap();
+///
+/// let (node, provenance) = merger.finish();
+/// assert_eq!(node, parse_from_str(r#"{host: "example.com", port: 8080}"#).unwrap());
+/// assert_eq!(
+///     provenance.source_of(&AsonPath::parse(".host").unwrap()),
+///     Some("production")
+/// );
+/// ```
+pub struct Merger {
+    node: AsonNode,
+    provenance: Provenance,
+    list_strategy: MergeStrategy,
+    strategies: Vec<(AsonPath, MergeStrategy)>,
+}
+
+impl Merger {
+    /// Creates a merger with an empty Object as the initial document.
+    pub fn new() -> Self {
+        Self {
+            node: AsonNode::Object(vec![]),
+            provenance: Provenance::default(),
+            list_strategy: MergeStrategy::Replace,
+            strategies: vec![],
+        }
+    }
+
+    /// Sets the strategy of all Lists, `MergeStrategy::Replace` by default.
+    pub fn set_list_strategy(&mut self, strategy: MergeStrategy) {
+        self.list_strategy = strategy;
+    }
+
+    /// Sets the strategy of the value of the path, which takes precedence
+    /// over the default strategies.
+    pub fn set_strategy(&mut self, path: AsonPath, strategy: MergeStrategy) {
+        self.strategies.retain(|(p, _)| p != &path);
+        self.strategies.push((path, strategy));
+    }
+
+    /// Merges a layer into the document.
+    pub fn add_layer(&mut self, name: &str, node: AsonNode) {
+        let layer_index = self.push_layer_name(name);
+
+        // the first layer supplies the whole document.
+        if self.provenance.sources.is_empty() {
+            self.node = node;
+            self.provenance.record(&AsonPath::root(), layer_index);
+            return;
+        }
+
+        let mut context = MergeContext {
+            path: AsonPath::root(),
+            layer_index,
+            list_strategy: self.list_strategy,
+            strategies: &self.strategies,
+            provenance: &mut self.provenance,
+        };
+        context.merge_node(&mut self.node, node);
+    }
+
+    /// Sets the value of the override, the missing Object members
+    /// on the path are created.
+    pub fn add_override(&mut self, name: &str, item: &Override) -> Result<(), AsonError> {
+        let mut operations = vec![];
+        let mut created: Option<AsonPath> = None;
+        let mut path = AsonPath::root();
+
+        for segment in &item.path.segments[..item.path.segments.len().saturating_sub(1)] {
+            path.segments.push(segment.clone());
+            if created.is_none() && path.find(&self.node).is_some() {
+                continue;
+            }
+
+            if !matches!(segment, PathSegment::Key(_)) {
+                break;
+            }
+
+            operations.push(PatchOperation::Add {
+                path: path.clone(),
+                value: AsonNode::Object(vec![]),
+            });
+            created.get_or_insert_with(|| path.clone());
+        }
+
+        operations.push(if item.path.find(&self.node).is_some() {
+            PatchOperation::Replace {
+                path: item.path.clone(),
+                value: item.value.clone(),
+            }
+        } else {
+            PatchOperation::Add {
+                path: item.path.clone(),
+                value: item.value.clone(),
+            }
+        });
+
+        Patch::new(operations).apply(&mut self.node)?;
+
+        let layer_index = self.push_layer_name(name);
+        self.provenance
+            .record(created.as_ref().unwrap_or(&item.path), layer_index);
+        Ok(())
+    }
+
+    /// Returns the merged document and the provenance of values.
+    pub fn finish(self) -> (AsonNode, Provenance) {
+        (self.node, self.provenance)
+    }
+
+    fn push_layer_name(&mut self, name: &str) -> usize {
+        self.provenance.layer_names.push(name.to_owned());
+        self.provenance.layer_names.len() - 1
+    }
+}
+
+impl Default for Merger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Merges the overlay into the base with the default strategies.
+pub fn merge(base: &AsonNode, overlay: &AsonNode) -> AsonNode {
+    let mut merger = Merger::new();
+    merger.add_layer("base", base.clone());
+    merger.add_layer("overlay", overlay.clone());
+    merger.finish().0
+}
+
+struct MergeContext<'a> {
+    // the path of the value being merged
+    path: AsonPath,
+    layer_index: usize,
+    list_strategy: MergeStrategy,
+    strategies: &'a [(AsonPath, MergeStrategy)],
+    provenance: &'a mut Provenance,
+}
+
+impl MergeContext<'_> {
+    fn replace(&mut self, target: &mut AsonNode, value: AsonNode) {
+        *target = value;
+        self.provenance.record(&self.path, self.layer_index);
+    }
+
+    fn merge_node(&mut self, target: &mut AsonNode, value: AsonNode) {
+        let strategy = self
+            .strategies
+            .iter()
+            .find(|(path, _)| path == &self.path)
+            .map(|(_, strategy)| *strategy);
+
+        if strategy == Some(MergeStrategy::Replace) {
+            self.replace(target, value);
+            return;
+        }
+
+        match (target, value) {
+            (AsonNode::Object(target_kvps), AsonNode::Object(kvps)) => {
+                self.merge_members(target_kvps, kvps)
+            }
+            (AsonNode::Map(target_nvps), AsonNode::Map(nvps)) => {
+                self.merge_entries(target_nvps, nvps)
+            }
+            (AsonNode::List(target_items), AsonNode::List(items))
+                if strategy.unwrap_or(self.list_strategy) == MergeStrategy::Append =>
+            {
+                for item in items {
+                    self.path.push_index(target_items.len());
+                    self.provenance.record(&self.path, self.layer_index);
+                    self.path.segments.pop();
+                    target_items.push(item);
+                }
+            }
+            (AsonNode::Variant(target_variant), AsonNode::Variant(variant))
+                if target_variant.type_name == variant.type_name
+                    && target_variant.member_name == variant.member_name =>
+            {
+                match (&mut target_variant.value, variant.value) {
+                    (VariantValue::Object(target_kvps), VariantValue::Object(kvps)) => {
+                        self.merge_members(target_kvps, kvps)
+                    }
+                    (VariantValue::Value(target_value), VariantValue::Value(value)) => {
+                        self.path.push_index(0);
+                        self.merge_node(target_value, *value);
+                        self.path.segments.pop();
+                    }
+                    (target_value, value) => {
+                        *target_value = value;
+                        self.provenance.record(&self.path, self.layer_index);
+                    }
+                }
+            }
+            (target, value) => self.replace(target, value),
+        }
+    }
+
+    fn merge_members(&mut self, target_kvps: &mut Vec<KeyValuePair>, kvps: Vec<KeyValuePair>) {
+        for kvp in kvps {
+            self.path.push_key(&kvp.key);
+            match target_kvps.iter_mut().find(|t| t.key == kvp.key) {
+                Some(target_kvp) => self.merge_node(&mut target_kvp.value, *kvp.value),
+                None => {
+                    self.provenance.record(&self.path, self.layer_index);
+                    target_kvps.push(kvp);
+                }
+            }
+            self.path.segments.pop();
+        }
+    }
+
+    fn merge_entries(&mut self, target_nvps: &mut Vec<NameValuePair>, nvps: Vec<NameValuePair>) {
+        for nvp in nvps {
+            self.path.push_name(&nvp.name);
+            match target_nvps.iter_mut().find(|t| t.name == nvp.name) {
+                Some(target_nvp) => self.merge_node(&mut target_nvp.value, *nvp.value),
+                None => {
+                    self.provenance.record(&self.path, self.layer_index);
+                    target_nvps.push(nvp);
+                }
+            }
+            self.path.segments.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        ast::{AsonNode, Number},
+        parser::parse_from_str,
+        path::AsonPath,
+        printer::print_to_string,
+        ErrorKind,
+    };
+
+    use super::{merge, MergeStrategy, Merger, Override};
+
+    fn node(s: &str) -> AsonNode {
+        parse_from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_merge() {
+        let base = node(
+            r#"{
+                name: "app"
+                server: {host: "localhost", port: 80}
+                labels: ['a': 1, 2: "two"]
+                tags: ["x", "y"]
+                point: (1, 2)
+                log: Log::File{path: "/tmp/app.log", level: 1}
+                cache: Option::Some({size: 10, ttl: 60})
+                db: DB::Sqlite("app.db")
+            }"#,
+        );
+
+        let overlay = node(
+            r#"{
+                server: {port: 8080, tls: true}
+                labels: ['a': 10, 'b': 2]
+                tags: ["z"]
+                point: (3,)
+                log: Log::File{level: 2}
+                cache: Option::Some({size: 20})
+                db: DB::Postgres("postgres://localhost")
+            }"#,
+        );
+
+        assert_eq!(
+            print_to_string(&merge(&base, &overlay)),
+            print_to_string(&node(
+                r#"{
+                    name: "app"
+                    server: {host: "localhost", port: 8080, tls: true}
+                    labels: ['a': 10, 2: "two", 'b': 2]
+                    tags: ["z"]
+                    point: (3,)
+                    log: Log::File{path: "/tmp/app.log", level: 2}
+                    cache: Option::Some({size: 20, ttl: 60})
+                    db: DB::Postgres("postgres://localhost")
+                }"#
+            ))
+        );
+
+        // the value of other type is replaced
+        assert_eq!(
+            merge(&node(r#"{a: {b: 1}}"#), &node(r#"{a: 2}"#)),
+            node(r#"{a: 2}"#)
+        );
+    }
+
+    #[test]
+    fn test_strategies() {
+        let base = node(r#"{tags: ["x"], plugins: ["a"], server: {host: "h", port: 80}}"#);
+        let overlay = node(r#"{tags: ["y"], plugins: ["b"], server: {port: 8080}}"#);
+
+        let mut merger = Merger::new();
+        merger.set_list_strategy(MergeStrategy::Append);
+        merger.set_strategy(AsonPath::parse(".plugins").unwrap(), MergeStrategy::Replace);
+        merger.set_strategy(AsonPath::parse(".server").unwrap(), MergeStrategy::Replace);
+        merger.add_layer("base", base);
+        merger.add_layer("overlay", overlay);
+
+        let (node_merged, provenance) = merger.finish();
+        assert_eq!(
+            node_merged,
+            node(r#"{tags: ["x", "y"], plugins: ["b"], server: {port: 8080}}"#)
+        );
+
+        let source_of = |s: &str| provenance.source_of(&AsonPath::parse(s).unwrap());
+        assert_eq!(source_of(".tags[0]"), Some("base"));
+        assert_eq!(source_of(".tags[1]"), Some("overlay"));
+        assert_eq!(source_of(".server.port"), Some("overlay"));
+    }
+
+    #[test]
+    fn test_override() {
+        assert_eq!(
+            Override::parse("server.port=8080").unwrap(),
+            Override {
+                path: AsonPath::parse(".server.port").unwrap(),
+                value: AsonNode::Number(Number::I32(8080)),
+            }
+        );
+
+        assert_eq!(
+            Override::parse(r#".labels['=']="a=b""#).unwrap(),
+            Override {
+                path: AsonPath::parse(".labels['=']").unwrap(),
+                value: AsonNode::String("a=b".to_owned()),
+            }
+        );
+
+        assert_eq!(
+            Override::parse(r#"["a b"][0] = [1, 2]"#).unwrap().value,
+            node("[1, 2]")
+        );
+
+        // errors
+        for s in ["port", "port=", "port=foo", "..port=1", "[1=1"] {
+            assert!(matches!(
+                Override::parse(s),
+                Err(e) if e.kind == ErrorKind::Syntax
+            ));
+        }
+    }
+
+    #[test]
+    fn test_provenance() {
+        let mut merger = Merger::new();
+        merger.add_layer(
+            "base",
+            node(r#"{server: {host: "localhost", port: 80}, tags: ["a"]}"#),
+        );
+        merger.add_layer("production", node(r#"{server: {host: "example.com"}}"#));
+        merger
+            .add_override("--set", &Override::parse("server.port=443").unwrap())
+            .unwrap();
+        merger
+            .add_override(
+                "--set",
+                &Override::parse("log.file.path=\"/var/log\"").unwrap(),
+            )
+            .unwrap();
+        merger
+            .add_override("--set", &Override::parse("tags[1]=\"b\"").unwrap())
+            .unwrap();
+
+        // the missing List element can not be created
+        let e = merger
+            .add_override("--set", &Override::parse("tags[5]=\"c\"").unwrap())
+            .unwrap_err();
+        assert_eq!(e.kind, ErrorKind::PatchFailed);
+
+        let (merged, provenance) = merger.finish();
+        assert_eq!(
+            merged,
+            node(
+                r#"{
+                    server: {host: "example.com", port: 443}
+                    tags: ["a", "b"]
+                    log: {file: {path: "/var/log"}}
+                }"#
+            )
+        );
+
+        assert_eq!(
+            provenance.to_string(),
+            r#".: base
+.server.host: production
+.server.port: --set
+.log: --set
+.tags[1]: --set
+"#
+        );
+
+        let source_of = |s: &str| provenance.source_of(&AsonPath::parse(s).unwrap());
+        assert_eq!(source_of(".tags[0]"), Some("base"));
+        assert_eq!(source_of(".log.file.path"), Some("--set"));
+        assert_eq!(source_of(".server"), Some("base"));
+    }
+}
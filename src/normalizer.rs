@@ -174,42 +174,42 @@ fn normalize(iter: &mut NormalizedTokenIter) -> Option<Result<TokenWithRange, As
                                 match num {
                                     NumberToken::F32(f) if f.is_nan() => {
                                         // combines two token ranges.
-                                        Some(Err(AsonError::MessageWithLocation(
+                                        Some(Err(AsonError::new_syntax(
                                             "The plus sign cannot be applied to NaN.".to_owned(),
                                             Location::from_range_pair(&start_range, current_range),
                                         )))
                                     }
                                     NumberToken::F64(f) if f.is_nan() => {
                                         // combines two token ranges.
-                                        Some(Err(AsonError::MessageWithLocation(
+                                        Some(Err(AsonError::new_syntax(
                                             "The plus sign cannot be applied to NaN.".to_owned(),
                                             Location::from_range_pair(&start_range, current_range),
                                         )))
                                     }
                                     NumberToken::I8(v) if *v > i8::MAX as u8 => {
                                         // check signed number overflow
-                                        Some(Err(AsonError::MessageWithLocation(
+                                        Some(Err(AsonError::new_syntax(
                                             format!("The i8  number {} is overflowed.", v),
                                             Location::from_range_pair(&start_range, current_range),
                                         )))
                                     }
                                     NumberToken::I16(v) if *v > i16::MAX as u16 => {
                                         // check signed number overflow
-                                        Some(Err(AsonError::MessageWithLocation(
+                                        Some(Err(AsonError::new_syntax(
                                             format!("The i16 number {} is overflowed.", v),
                                             Location::from_range_pair(&start_range, current_range),
                                         )))
                                     }
                                     NumberToken::I32(v) if *v > i32::MAX as u32 => {
                                         // check signed number overflow
-                                        Some(Err(AsonError::MessageWithLocation(
+                                        Some(Err(AsonError::new_syntax(
                                             format!("The i32 number {} is overflowed.", v),
                                             Location::from_range_pair(&start_range, current_range),
                                         )))
                                     }
                                     NumberToken::I64(v) if *v > i64::MAX as u64 => {
                                         // check signed number overflow
-                                        Some(Err(AsonError::MessageWithLocation(
+                                        Some(Err(AsonError::new_syntax(
                                             format!("The i64 number {} is overflowed.", v),
                                             Location::from_range_pair(&start_range, current_range),
                                         )))
@@ -238,7 +238,7 @@ fn normalize(iter: &mut NormalizedTokenIter) -> Option<Result<TokenWithRange, As
                                 range: current_range,
                             })) => {
                                 // combines two token ranges.
-                                Some(Err(AsonError::MessageWithLocation(
+                                Some(Err(AsonError::new_syntax(
                                     "The plus sign can only be applied to numbers.".to_owned(),
                                     Location::from_range_pair(&start_range, current_range),
                                 )))
@@ -246,7 +246,7 @@ fn normalize(iter: &mut NormalizedTokenIter) -> Option<Result<TokenWithRange, As
                             Some(Err(e)) => Some(Err(e.clone())),
                             None => {
                                 // "...+EOF"
-                                Some(Err(AsonError::UnexpectedEndOfDocument(
+                                Some(Err(AsonError::new_unexpected_end_of_document(
                                     "Missing the number that follow the plus sign.".to_owned(),
                                 )))
                             }
@@ -262,7 +262,7 @@ fn normalize(iter: &mut NormalizedTokenIter) -> Option<Result<TokenWithRange, As
                                     NumberToken::F32(v) => {
                                         if v.is_nan() {
                                             // combines two token ranges.
-                                            Some(Err(AsonError::MessageWithLocation(
+                                            Some(Err(AsonError::new_syntax(
                                                 "The minus sign cannot be applied to NaN."
                                                     .to_owned(),
                                                 Location::from_range_pair(
@@ -290,7 +290,7 @@ fn normalize(iter: &mut NormalizedTokenIter) -> Option<Result<TokenWithRange, As
                                     NumberToken::F64(v) => {
                                         if v.is_nan() {
                                             // combines two token ranges.
-                                            Some(Err(AsonError::MessageWithLocation(
+                                            Some(Err(AsonError::new_syntax(
                                                 "The minus sign cannot be applied to NaN."
                                                     .to_owned(),
                                                 Location::from_range_pair(
@@ -321,7 +321,7 @@ fn normalize(iter: &mut NormalizedTokenIter) -> Option<Result<TokenWithRange, As
 
                                         let parse_result =
                                             format!("-{}", v).parse::<i8>().map_err(|_| {
-                                                AsonError::MessageWithLocation(
+                                                AsonError::new_syntax(
                                                     format!(
                                                         "Can not convert \"{}\" to negative i8",
                                                         v
@@ -351,7 +351,7 @@ fn normalize(iter: &mut NormalizedTokenIter) -> Option<Result<TokenWithRange, As
 
                                         let parse_result =
                                             format!("-{}", v).parse::<i16>().map_err(|_| {
-                                                AsonError::MessageWithLocation(
+                                                AsonError::new_syntax(
                                                     format!(
                                                         "Can not convert \"{}\" to negative i16.",
                                                         v
@@ -381,7 +381,7 @@ fn normalize(iter: &mut NormalizedTokenIter) -> Option<Result<TokenWithRange, As
 
                                         let parse_result =
                                             format!("-{}", v).parse::<i32>().map_err(|_| {
-                                                AsonError::MessageWithLocation(
+                                                AsonError::new_syntax(
                                                     format!(
                                                         "Can not convert \"{}\" to negative i32.",
                                                         v
@@ -411,7 +411,7 @@ fn normalize(iter: &mut NormalizedTokenIter) -> Option<Result<TokenWithRange, As
 
                                         let parse_result =
                                             format!("-{}", v).parse::<i64>().map_err(|_| {
-                                                AsonError::MessageWithLocation(
+                                                AsonError::new_syntax(
                                                     format!(
                                                         "Can not convert \"{}\" to negative i64.",
                                                         v
@@ -438,13 +438,11 @@ fn normalize(iter: &mut NormalizedTokenIter) -> Option<Result<TokenWithRange, As
                                     NumberToken::U8(_)
                                     | NumberToken::U16(_)
                                     | NumberToken::U32(_)
-                                    | NumberToken::U64(_) => {
-                                        Some(Err(AsonError::MessageWithLocation(
-                                            "The minus sign cannot be applied to unsigned numbers."
-                                                .to_owned(),
-                                            Location::from_range_pair(&start_range, current_range),
-                                        )))
-                                    }
+                                    | NumberToken::U64(_) => Some(Err(AsonError::new_syntax(
+                                        "The minus sign cannot be applied to unsigned numbers."
+                                            .to_owned(),
+                                        Location::from_range_pair(&start_range, current_range),
+                                    ))),
                                 }
                             }
                             Some(Ok(TokenWithRange {
@@ -452,7 +450,7 @@ fn normalize(iter: &mut NormalizedTokenIter) -> Option<Result<TokenWithRange, As
                                 range: current_range,
                             })) => {
                                 // combines two token ranges.
-                                Some(Err(AsonError::MessageWithLocation(
+                                Some(Err(AsonError::new_syntax(
                                     "The minus sign can only be applied to numbers.".to_owned(),
                                     Location::from_range_pair(&start_range, current_range),
                                 )))
@@ -460,7 +458,7 @@ fn normalize(iter: &mut NormalizedTokenIter) -> Option<Result<TokenWithRange, As
                             Some(Err(e)) => Some(Err(e.clone())),
                             None => {
                                 // "...-EOF"
-                                Some(Err(AsonError::UnexpectedEndOfDocument(
+                                Some(Err(AsonError::new_unexpected_end_of_document(
                                     "Missing the number that follow the minus sign.".to_owned(),
                                 )))
                             }
@@ -468,28 +466,28 @@ fn normalize(iter: &mut NormalizedTokenIter) -> Option<Result<TokenWithRange, As
                     }
                     Token::Number(NumberToken::I8(v)) if *v > i8::MAX as u8 => {
                         // check signed number overflow
-                        Some(Err(AsonError::MessageWithLocation(
+                        Some(Err(AsonError::new_syntax(
                             format!("The i8 number {} is overflowed.", v),
                             start_range,
                         )))
                     }
                     Token::Number(NumberToken::I16(v)) if *v > i16::MAX as u16 => {
                         // check signed number overflow
-                        Some(Err(AsonError::MessageWithLocation(
+                        Some(Err(AsonError::new_syntax(
                             format!("The i16 number {} is overflowed.", v),
                             start_range,
                         )))
                     }
                     Token::Number(NumberToken::I32(v)) if *v > i32::MAX as u32 => {
                         // check signed number overflow
-                        Some(Err(AsonError::MessageWithLocation(
+                        Some(Err(AsonError::new_syntax(
                             format!("The i32 number {} is overflowed.", v),
                             start_range,
                         )))
                     }
                     Token::Number(NumberToken::I64(v)) if *v > i64::MAX as u64 => {
                         // check signed number overflow
-                        Some(Err(AsonError::MessageWithLocation(
+                        Some(Err(AsonError::new_syntax(
                             format!("The i64 number {} is overflowed.", v),
                             start_range,
                         )))
@@ -559,10 +557,11 @@ mod tests {
     use crate::{
         charwithposition::CharsWithPositionIter,
         lexer::{Lexer, LEXER_PEEK_CHAR_MAX_COUNT},
+        limits::Limits,
         location::Location,
         peekableiter::PeekableIter,
         token::{NumberToken, Token, TokenWithRange},
-        AsonError,
+        AsonError, ErrorKind,
     };
 
     use super::{ClearTokenIter, NormalizedTokenIter, TrimmedTokenIter};
@@ -572,7 +571,7 @@ mod tests {
         let mut char_position_iter = CharsWithPositionIter::new(&mut chars);
         let mut peekable_char_position_iter =
             PeekableIter::new(&mut char_position_iter, LEXER_PEEK_CHAR_MAX_COUNT);
-        let mut lexer = Lexer::new(&mut peekable_char_position_iter);
+        let mut lexer = Lexer::new_with_limits(&mut peekable_char_position_iter, Limits::default());
         let mut clear_iter = ClearTokenIter::new(&mut lexer);
         let mut peekable_clear_iter = PeekableIter::new(&mut clear_iter, 1);
         let mut normalized_iter = NormalizedTokenIter::new(&mut peekable_clear_iter);
@@ -867,31 +866,35 @@ mod tests {
             // err: positive overflow
             assert!(matches!(
                 lex_from_str_without_location("+2_147_483_648"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 14
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
 
             // err: negative overflow
             assert!(matches!(
                 lex_from_str_without_location("-2_147_483_649"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 14
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
         }
 
@@ -910,46 +913,52 @@ mod tests {
             // err: positive overflow
             assert!(matches!(
                 lex_from_str_without_location("+128_i8"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 7
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
 
             // err: negative overflow
             assert!(matches!(
                 lex_from_str_without_location("-129_i8"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 7
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
 
             // err: unsigned number with minus sign
             assert!(matches!(
                 lex_from_str_without_location("-1_u8"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 5
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
         }
 
@@ -968,46 +977,52 @@ mod tests {
             // err: positive overflow
             assert!(matches!(
                 lex_from_str_without_location("+32768_i16"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 10
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
 
             // err: negative overflow
             assert!(matches!(
                 lex_from_str_without_location("-32769_i16"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 10
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
 
             // err: unsigned number with minus sign
             assert!(matches!(
                 lex_from_str_without_location("-1_u16"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 6
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
         }
 
@@ -1026,46 +1041,52 @@ mod tests {
             // err: positive overflow
             assert!(matches!(
                 lex_from_str_without_location("+2_147_483_648_i32"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 18
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
 
             // err: negative overflow
             assert!(matches!(
                 lex_from_str_without_location("-2_147_483_649_i32"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 18
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
 
             // err: unsigned number with minus sign
             assert!(matches!(
                 lex_from_str_without_location("-1_u32"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 6
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
         }
 
@@ -1088,46 +1109,52 @@ mod tests {
             // err: positive overflow
             assert!(matches!(
                 lex_from_str_without_location("+9_223_372_036_854_775_808_i64"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 30
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
 
             // err: negative overflow
             assert!(matches!(
                 lex_from_str_without_location("-9_223_372_036_854_775_809_i64"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 30
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
 
             // err: unsigned number with minus sign
             assert!(matches!(
                 lex_from_str_without_location("-1_u64"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 6
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
         }
 
@@ -1177,43 +1204,53 @@ mod tests {
         // +EOF
         assert!(matches!(
             lex_from_str_without_location("abc,+"),
-            Err(AsonError::UnexpectedEndOfDocument(_,))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
 
         // -EOF
         assert!(matches!(
             lex_from_str_without_location("xyz,-"),
-            Err(AsonError::UnexpectedEndOfDocument(_,))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
 
         // err: plus sign is added to non-numbers
         assert!(matches!(
             lex_from_str_without_location("+true"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 0,
+                    byte_index: 0,
                     line: 0,
                     column: 0,
                     length: 5
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: minus sign is added to non-numbers
         assert!(matches!(
             lex_from_str_without_location("-true"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 0,
+                    byte_index: 0,
                     line: 0,
                     column: 0,
                     length: 5
-                }
-            ))
+                }),
+                ..
+            })
         ));
     }
 
@@ -1221,72 +1258,82 @@ mod tests {
     fn test_normalize_signed_integer_overflow_decimal() {
         assert!(matches!(
             lex_from_str_without_location("2_147_483_648"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 0,
+                    byte_index: 0,
                     line: 0,
                     column: 0,
                     length: 13
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         assert!(matches!(
             lex_from_str_without_location("128_i8"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 0,
+                    byte_index: 0,
                     line: 0,
                     column: 0,
                     length: 6
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         assert!(matches!(
             lex_from_str_without_location("32768_i16"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 0,
+                    byte_index: 0,
                     line: 0,
                     column: 0,
                     length: 9
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         assert!(matches!(
             lex_from_str_without_location("2_147_483_648_i32"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 0,
+                    byte_index: 0,
                     line: 0,
                     column: 0,
                     length: 17
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         assert!(matches!(
             lex_from_str_without_location("9_223_372_036_854_775_808_i64"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 0,
+                    byte_index: 0,
                     line: 0,
                     column: 0,
                     length: 29
-                }
-            ))
+                }),
+                ..
+            })
         ));
     }
 
@@ -1373,31 +1420,35 @@ mod tests {
         // err: +NaN
         assert!(matches!(
             lex_from_str_without_location("+NaN"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 0,
+                    byte_index: 0,
                     line: 0,
                     column: 0,
                     length: 4
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: -NaN
         assert!(matches!(
             lex_from_str_without_location("-NaN"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 0,
+                    byte_index: 0,
                     line: 0,
                     column: 0,
                     length: 4
-                }
-            ))
+                }),
+                ..
+            })
         ));
     }
 
@@ -1476,31 +1527,35 @@ mod tests {
             // err: +NaN
             assert!(matches!(
                 lex_from_str_without_location("+NaN_f32"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 8
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
 
             // err: -NaN
             assert!(matches!(
                 lex_from_str_without_location("-NaN_f32"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 8
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
         }
 
@@ -1577,31 +1632,35 @@ mod tests {
             // err: +NaN
             assert!(matches!(
                 lex_from_str_without_location("+NaN_f64"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 8
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
 
             // err: -NaN
             assert!(matches!(
                 lex_from_str_without_location("-NaN_f64"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 8
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
         }
     }
@@ -1624,31 +1683,35 @@ mod tests {
             // err: positive overflow
             assert!(matches!(
                 lex_from_str_without_location("+0x8000_0000"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 12
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
 
             // err: negative overflow
             assert!(matches!(
                 lex_from_str_without_location("-0x8000_0001"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 12
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
         }
 
@@ -1667,46 +1730,52 @@ mod tests {
             // err: positive overflow
             assert!(matches!(
                 lex_from_str_without_location("+0x80_i8"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 8
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
 
             // err: negative overflow
             assert!(matches!(
                 lex_from_str_without_location("-0x81_i8"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 8
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
 
             // err: unsigned number with minus sign
             assert!(matches!(
                 lex_from_str_without_location("-0x1_u8"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 7
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
         }
 
@@ -1725,46 +1794,52 @@ mod tests {
             // err: positive overflow
             assert!(matches!(
                 lex_from_str_without_location("+0x8000_i16"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 11
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
 
             // err: negative overflow
             assert!(matches!(
                 lex_from_str_without_location("-0x8001_i16"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 11
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
 
             // err: unsigned number with minus sign
             assert!(matches!(
                 lex_from_str_without_location("-0x1_u16"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 8
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
         }
 
@@ -1783,46 +1858,52 @@ mod tests {
             // err: positive overflow
             assert!(matches!(
                 lex_from_str_without_location("+0x8000_0000_i32"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 16
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
 
             // err: negative overflow
             assert!(matches!(
                 lex_from_str_without_location("-0x8000_0001_i32"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 16
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
 
             // err: unsigned number with minus sign
             assert!(matches!(
                 lex_from_str_without_location("-0x1_u32"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 8
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
         }
 
@@ -1845,46 +1926,52 @@ mod tests {
             // err: positive overflow
             assert!(matches!(
                 lex_from_str_without_location("+0x8000_0000_0000_0000_i64"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 26
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
 
             // err: negative overflow
             assert!(matches!(
                 lex_from_str_without_location("-0x8000_0000_0000_0001_i64"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 26
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
 
             // err: unsigned number with minus sign
             assert!(matches!(
                 lex_from_str_without_location("-0x1_u64"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 8
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
         }
 
@@ -1936,72 +2023,82 @@ mod tests {
     fn test_normalize_signed_integer_overflow_hex() {
         assert!(matches!(
             lex_from_str_without_location("0x8000_0000"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 0,
+                    byte_index: 0,
                     line: 0,
                     column: 0,
                     length: 11
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         assert!(matches!(
             lex_from_str_without_location("0x80_i8"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 0,
+                    byte_index: 0,
                     line: 0,
                     column: 0,
                     length: 7
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         assert!(matches!(
             lex_from_str_without_location("0x8000_i16"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 0,
+                    byte_index: 0,
                     line: 0,
                     column: 0,
                     length: 10
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         assert!(matches!(
             lex_from_str_without_location("0x8000_0000_i32"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 0,
+                    byte_index: 0,
                     line: 0,
                     column: 0,
                     length: 15
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         assert!(matches!(
             lex_from_str_without_location("0x8000_0000_0000_0000_i64"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 0,
+                    byte_index: 0,
                     line: 0,
                     column: 0,
                     length: 25
-                }
-            ))
+                }),
+                ..
+            })
         ));
     }
 
@@ -2061,31 +2158,35 @@ mod tests {
             // err: positive overflow
             assert!(matches!(
                 lex_from_str_without_location("+0b1000_0000_0000_0000__0000_0000_0000_0000"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 43
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
 
             // err: negative overflow
             assert!(matches!(
                 lex_from_str_without_location("-0b1000_0000_0000_0000__0000_0000_0000_0001"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 43
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
         }
 
@@ -2104,46 +2205,52 @@ mod tests {
             // err: positive overflow
             assert!(matches!(
                 lex_from_str_without_location("+0b1000_0000_i8"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 15
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
 
             // err: negative overflow
             assert!(matches!(
                 lex_from_str_without_location("-0b1000_0001_i8"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 15
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
 
             // err: unsigned number with minus sign
             assert!(matches!(
                 lex_from_str_without_location("-0b1_u8"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 7
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
         }
 
@@ -2162,46 +2269,52 @@ mod tests {
             // err: positive overflow
             assert!(matches!(
                 lex_from_str_without_location("+0b1000_0000_0000_0000_i16"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 26
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
 
             // err: negative overflow
             assert!(matches!(
                 lex_from_str_without_location("-0b1000_0000_0000_0001_i16"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 26
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
 
             // err: unsigned number with minus sign
             assert!(matches!(
                 lex_from_str_without_location("-0b1_u16"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 8
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
         }
 
@@ -2222,46 +2335,52 @@ mod tests {
             // err: positive overflow
             assert!(matches!(
                 lex_from_str("+0b1000_0000_0000_0000__0000_0000_0000_0000_i32"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 47
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
 
             // err: negative overflow
             assert!(matches!(
                 lex_from_str_without_location("-0b1000_0000_0000_0000__0000_0000_0000_0001_i32"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 47
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
 
             // err: unsigned number with minus sign
             assert!(matches!(
                 lex_from_str_without_location("-0b1_u32"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 8
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
         }
 
@@ -2280,47 +2399,53 @@ mod tests {
             // err: positive overflow
             assert!(matches!(
                 lex_from_str("+0b1000_0000_0000_0000__0000_0000_0000_0000__0000_0000_0000_0000__0000_0000_0000_0000_i64"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
-                        index: 0,
-                        line: 0,
-                        column: 0,
-                        length: 89
-                    }
-                ))
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                            unit: 0,
+                            index: 0,
+                            byte_index: 0,
+                            line: 0,
+                            column: 0,
+                            length: 89
+                        }),
+                    ..
+                })
             ));
 
             // err: negative overflow
             assert!(matches!(
                 lex_from_str_without_location("-0b1000_0000_0000_0000__0000_0000_0000_0000__0000_0000_0000_0000__0000_0000_0000_0001_i64"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
-                        index: 0,
-                        line: 0,
-                        column: 0,
-                        length: 89
-                    }
-
-                ))
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                            unit: 0,
+                            index: 0,
+                            byte_index: 0,
+                            line: 0,
+                            column: 0,
+                            length: 89
+                        }),
+                    ..
+
+                })
             ));
 
             // err: unsigned number with minus sign
             assert!(matches!(
                 lex_from_str_without_location("-0b1_u64"),
-                Err(AsonError::MessageWithLocation(
-                    _,
-                    Location {
-                        // unit: 0,
+                Err(AsonError {
+                    kind: ErrorKind::Syntax,
+                    location: Some(Location {
+                        unit: 0,
                         index: 0,
+                        byte_index: 0,
                         line: 0,
                         column: 0,
                         length: 8
-                    }
-                ))
+                    }),
+                    ..
+                })
             ));
 
             // location
@@ -2372,72 +2497,82 @@ mod tests {
     fn test_normalize_signed_integer_overflow_binary() {
         assert!(matches!(
             lex_from_str_without_location("0b1000_0000_0000_0000__0000_0000_0000_0000"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 0,
+                    byte_index: 0,
                     line: 0,
                     column: 0,
                     length: 42
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         assert!(matches!(
             lex_from_str_without_location("0b1000_0000_i8"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 0,
+                    byte_index: 0,
                     line: 0,
                     column: 0,
                     length: 14
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         assert!(matches!(
             lex_from_str_without_location("0b1000_0000_0000_0000_i16"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 0,
+                    byte_index: 0,
                     line: 0,
                     column: 0,
                     length: 25
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         assert!(matches!(
             lex_from_str("0b1000_0000_0000_0000__0000_0000_0000_0000_i32"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 0,
+                    byte_index: 0,
                     line: 0,
                     column: 0,
                     length: 46
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         assert!(matches!(
             lex_from_str("0b1000_0000_0000_0000__0000_0000_0000_0000__0000_0000_0000_0000__0000_0000_0000_0000_i64"),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
-                    index: 0,
-                    line: 0,
-                    column: 0,
-                    length: 88
-                }
-            ))
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                        unit: 0,
+                        index: 0,
+                        byte_index: 0,
+                        line: 0,
+                        column: 0,
+                        length: 88
+                    }),
+                ..
+            })
         ));
     }
 }
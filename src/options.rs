@@ -0,0 +1,241 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+use std::io::Read;
+
+use serde::de;
+
+use crate::{
+    ast::AsonNode, charstream::CharStream, interpolation::VariableSource, limits::Limits, parser,
+    serde::de as serde_de, AsonError,
+};
+
+/// The options of parsing and deserializing, the options can be combined,
+/// e.g.
+///
+/// ```text
+/// ParseOptions::new()
+///     .with_limits(limits)
+///     .with_variables(&Environment)
+///     .with_anchors()
+///     .from_str::<Config>(text)
+/// ```
+///
+/// The functions `parse_from_str`, `from_str` etc. are the shortcuts
+/// of the default options.
+#[derive(Clone, Copy, Default)]
+pub struct ParseOptions<'a> {
+    /// The resource limits, nothing is limited by default,
+    /// see `Limits`.
+    pub limits: Limits,
+
+    /// Expands the placeholders of Strings, e.g. `"${HOST:-localhost}"`,
+    /// see the module `interpolation`.
+    pub variables: Option<&'a dyn VariableSource>,
+
+    /// Expands the anchors and aliases of the repeated values, e.g.
+    /// `Anchor::Define("name", {...})` and `Anchor::Alias("name")`.
+    /// An anchor must be defined before its aliases.
+    pub expand_anchors: bool,
+}
+
+impl<'a> ParseOptions<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the resource limits,
+    /// it is recommended for the untrusted documents.
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn with_variables(mut self, variables: &'a dyn VariableSource) -> Self {
+        self.variables = Some(variables);
+        self
+    }
+
+    pub fn with_anchors(mut self) -> Self {
+        self.expand_anchors = true;
+        self
+    }
+
+    pub fn parse_str(&self, s: &str) -> Result<AsonNode, AsonError> {
+        let mut chars = s.chars();
+        parser::parse_from_char_stream(&mut chars, self)
+    }
+
+    pub fn parse_reader<R: Read>(&self, mut r: R) -> Result<AsonNode, AsonError> {
+        let mut char_stream = CharStream::new(&mut r);
+        parser::parse_from_char_stream(&mut char_stream, self)
+    }
+
+    /// Parses the text and continues after errors, so that all errors of
+    /// the document are reported at once.
+    ///
+    /// The parser resynchronises at commas, new-lines and closing brackets,
+    /// and the invalid values are replaced by `AsonNode::Error` placeholders.
+    /// The members of Object whose key is invalid are dropped.
+    ///
+    /// Returns the (partial) tree and the errors sorted by location,
+    /// the document is valid if there are no errors.
+    pub fn parse_str_with_recovery(&self, s: &str) -> (AsonNode, Vec<AsonError>) {
+        parser::parse_from_str_with_recovery(s, self)
+    }
+
+    pub fn from_str<T>(&self, s: &str) -> Result<T, AsonError>
+    where
+        T: de::DeserializeOwned,
+    {
+        let mut chars = s.chars();
+        serde_de::from_char_stream(&mut chars, self)
+    }
+
+    pub fn from_reader<T, R: Read>(&self, mut r: R) -> Result<T, AsonError>
+    where
+        T: de::DeserializeOwned,
+    {
+        let mut char_stream = CharStream::new(&mut r);
+        serde_de::from_char_stream(&mut char_stream, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use pretty_assertions::assert_eq;
+    use serde::Deserialize;
+
+    use crate::{
+        ast::{AsonNode, KeyValuePair, Number},
+        limits::Limits,
+        ErrorKind,
+    };
+
+    use super::ParseOptions;
+
+    const TEXT: &str = r#"{
+        primary: Anchor::Define("server", {host: "${HOST}", port: "${PORT:u16}"})
+        backup: Anchor::Alias("server")
+    }"#;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Server {
+        host: String,
+        port: u16,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Servers {
+        primary: Server,
+        backup: Server,
+    }
+
+    fn variables() -> HashMap<String, String> {
+        [("HOST", "example.com"), ("PORT", "8080")]
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_combined_options() {
+        let variables = variables();
+        let limits = Limits {
+            max_depth: 2,
+            ..Limits::default()
+        };
+        let options = ParseOptions::new()
+            .with_limits(limits)
+            .with_variables(&variables)
+            .with_anchors();
+
+        let server = AsonNode::Object(vec![
+            KeyValuePair::new("host", AsonNode::String("example.com".to_owned())),
+            KeyValuePair::new("port", AsonNode::Number(Number::U16(8080))),
+        ]);
+        assert_eq!(
+            options.parse_str(TEXT).unwrap(),
+            AsonNode::Object(vec![
+                KeyValuePair::new("primary", server.clone()),
+                KeyValuePair::new("backup", server),
+            ])
+        );
+
+        let server = Server {
+            host: "example.com".to_owned(),
+            port: 8080,
+        };
+        assert_eq!(
+            options.from_reader::<Servers, _>(TEXT.as_bytes()).unwrap(),
+            Servers {
+                primary: Server {
+                    host: "example.com".to_owned(),
+                    port: 8080,
+                },
+                backup: server,
+            }
+        );
+
+        // the limits still apply to the expanded values
+        let options = options.with_limits(Limits {
+            max_depth: 1,
+            ..Limits::default()
+        });
+        assert_eq!(
+            options.parse_str(TEXT).unwrap_err().kind,
+            ErrorKind::DepthLimitExceeded
+        );
+        assert_eq!(
+            options.from_str::<Servers>(TEXT).unwrap_err().kind,
+            ErrorKind::DepthLimitExceeded
+        );
+    }
+
+    #[test]
+    fn test_parse_with_recovery_and_options() {
+        let variables = variables();
+        let options = ParseOptions::new()
+            .with_variables(&variables)
+            .with_anchors();
+
+        let (node, errors) = options.parse_str_with_recovery(TEXT);
+        assert!(errors.is_empty());
+        assert_eq!(node, options.parse_str(TEXT).unwrap());
+
+        let (_, errors) = options.parse_str_with_recovery(
+            r#"{
+                a: "${USER}"
+                b: Anchor::Alias("none")
+                c: :
+            }"#,
+        );
+        assert_eq!(
+            errors.iter().map(|e| e.kind).collect::<Vec<_>>(),
+            vec![
+                ErrorKind::Interpolation,
+                ErrorKind::InvalidReference,
+                ErrorKind::Syntax
+            ]
+        );
+
+        let options = options.with_limits(Limits {
+            max_document_size: 10,
+            ..Limits::default()
+        });
+        // the text beyond the limit is treated as the end of document
+        let (_, errors) = options.parse_str_with_recovery("[1, 2, 3, 4, 5]");
+        assert_eq!(
+            errors.iter().map(|e| e.kind).collect::<Vec<_>>(),
+            vec![
+                ErrorKind::DocumentSizeLimitExceeded,
+                ErrorKind::UnexpectedEndOfDocument
+            ]
+        );
+    }
+}
@@ -7,67 +7,199 @@
 use std::io::Read;
 
 use crate::{
+    anchor::AnchorTokenIter,
     ast::{AsonNode, KeyValuePair, NameValuePair, Number, Variant},
-    charstream::CharStream,
     charwithposition::CharsWithPositionIter,
+    interpolation::InterpolatedTokenIter,
     lexer::{Lexer, LEXER_PEEK_CHAR_MAX_COUNT},
+    limits::Limits,
     location::Location,
     normalizer::{ClearTokenIter, NormalizedTokenIter, TrimmedTokenIter},
+    options::ParseOptions,
     peekableiter::PeekableIter,
     token::{NumberToken, Token, TokenWithRange},
+    tokenizer::TokenIter,
     AsonError,
 };
 
 pub const PARSER_PEEK_TOKEN_MAX_COUNT: usize = 3;
 
 pub fn parse_from_str(s: &str) -> Result<AsonNode, AsonError> {
-    let mut chars = s.chars();
-    parse_from_char_stream(&mut chars)
+    ParseOptions::new().parse_str(s)
 }
 
-pub fn parse_from_reader<R: Read>(mut r: R) -> Result<AsonNode, AsonError> {
-    let mut char_stream = CharStream::new(&mut r);
-    parse_from_char_stream(&mut char_stream)
+pub fn parse_from_reader<R: Read>(r: R) -> Result<AsonNode, AsonError> {
+    ParseOptions::new().parse_reader(r)
 }
 
-pub fn parse_from_char_stream(
+pub(crate) fn parse_from_char_stream(
     char_stream: &mut dyn Iterator<Item = char>,
+    options: &ParseOptions,
 ) -> Result<AsonNode, AsonError> {
+    let limits = options.limits;
     let mut char_position_iter = CharsWithPositionIter::new(char_stream);
     let mut peekable_char_position_iter =
         PeekableIter::new(&mut char_position_iter, LEXER_PEEK_CHAR_MAX_COUNT);
-    let mut lexer = Lexer::new(&mut peekable_char_position_iter);
+    let mut lexer = Lexer::new_with_limits(&mut peekable_char_position_iter, limits);
     let mut clear_iter = ClearTokenIter::new(&mut lexer);
     let mut peekable_clear_iter = PeekableIter::new(&mut clear_iter, 1);
     let mut normalized_iter = NormalizedTokenIter::new(&mut peekable_clear_iter);
     let mut peekable_normalized_iter = PeekableIter::new(&mut normalized_iter, 1);
     let mut trimmed_iter = TrimmedTokenIter::new(&mut peekable_normalized_iter);
-    let mut peekable_trimmed_iter =
-        PeekableIter::new(&mut trimmed_iter, PARSER_PEEK_TOKEN_MAX_COUNT);
-
-    let mut parser = Parser::new(&mut peekable_trimmed_iter);
-    let root = parser.parse_node()?;
-
-    // check trailing token
-    match parser.next_token()? {
-        Some(_) => Err(AsonError::MessageWithLocation(
-            "Document has more than one node.".to_owned(),
-            parser.last_range.get_position_by_range_start(),
-        )),
-        None => Ok(root),
+    let mut interpolated_iter = InterpolatedTokenIter::new(&mut trimmed_iter, options.variables);
+    let mut anchor_iter =
+        AnchorTokenIter::new(&mut interpolated_iter, options.expand_anchors, limits);
+    let mut peekable_anchor_iter = PeekableIter::new(&mut anchor_iter, PARSER_PEEK_TOKEN_MAX_COUNT);
+
+    let mut parser = Parser::new(&mut peekable_anchor_iter, limits);
+    let result = parser.parse_node().and_then(|root| {
+        // check trailing token
+        match parser.next_token()? {
+            Some(_) => Err(AsonError::new_syntax(
+                "Document has more than one node.".to_owned(),
+                parser.last_range.get_position_by_range_start(),
+            )),
+            None => Ok(root),
+        }
+    });
+
+    // all chars have been consumed if the document ends unexpectedly.
+    result.map_err(|e| e.locate_end_of_document(char_position_iter.current_position()))
+}
+
+// see `ParseOptions::parse_str_with_recovery`.
+pub(crate) fn parse_from_str_with_recovery(
+    s: &str,
+    options: &ParseOptions,
+) -> (AsonNode, Vec<AsonError>) {
+    // the invalid text (till the end of line) is yielded as `Token::Error`,
+    // which is turned into the error placeholder by the parser.
+    let mut tokens = TokenIter::new_with_limits(s, true, options.limits).map(Ok);
+
+    let mut clear_iter = ClearTokenIter::new(&mut tokens);
+    let mut peekable_clear_iter = PeekableIter::new(&mut clear_iter, 1);
+    let mut normalized_iter = NormalizedTokenIter::new(&mut peekable_clear_iter);
+    let mut peekable_normalized_iter = PeekableIter::new(&mut normalized_iter, 1);
+    let mut trimmed_iter = TrimmedTokenIter::new(&mut peekable_normalized_iter);
+    let mut error_skipped_iter = ErrorSkippedTokenIter::new(&mut trimmed_iter);
+    let mut interpolated_iter =
+        InterpolatedTokenIter::new(&mut error_skipped_iter, options.variables);
+    let anchor_iter = AnchorTokenIter::new(
+        &mut interpolated_iter,
+        options.expand_anchors,
+        options.limits,
+    );
+
+    // the Strings with invalid placeholders and the invalid aliases are
+    // replaced by the error tokens, i.e. the error placeholders.
+    let mut expanded_iter = anchor_iter.map(|result| {
+        result.or_else(|e| match e.location {
+            Some(range) => Ok(TokenWithRange::new(Token::Error(e), range)),
+            None => Err(e),
+        })
+    });
+    let mut peekable_expanded_iter =
+        PeekableIter::new(&mut expanded_iter, PARSER_PEEK_TOKEN_MAX_COUNT);
+
+    let mut parser = Parser::new_with_recovery(&mut peekable_expanded_iter, options.limits);
+    let (root, parser_errors) = match parser.parse_root_with_recovery() {
+        Ok(root) => (root, parser.errors),
+        Err(e) => {
+            // unreachable in theory since all errors are recorded
+            // in recovery mode.
+            let mut parser_errors = parser.errors;
+            parser_errors.push(e);
+            (AsonNode::Error, parser_errors)
+        }
+    };
+
+    let end_position = Location::new_position(
+        s.chars().count(),
+        s.matches('\n').count(),
+        s.rsplit('\n').next().unwrap().chars().count(),
+    )
+    .with_byte_index(s.len());
+
+    let mut errors: Vec<AsonError> = error_skipped_iter
+        .errors
+        .into_iter()
+        .chain(parser_errors)
+        .map(|e| e.locate_end_of_document(end_position))
+        .collect();
+
+    errors.sort_by_key(|e| e.location.map_or(usize::MAX, |location| location.index));
+
+    (root, errors)
+}
+
+// drops the errors from the upstream and records them, it is used by
+// the parser with error recovery.
+//
+// the errors come from the normalizer, e.g. a plus sign followed by a string,
+// the invalid token is dropped and the following tokens are still available.
+struct ErrorSkippedTokenIter<'a> {
+    upstream: &'a mut dyn Iterator<Item = Result<TokenWithRange, AsonError>>,
+    errors: Vec<AsonError>,
+}
+
+impl<'a> ErrorSkippedTokenIter<'a> {
+    fn new(upstream: &'a mut dyn Iterator<Item = Result<TokenWithRange, AsonError>>) -> Self {
+        Self {
+            upstream,
+            errors: vec![],
+        }
+    }
+}
+
+impl Iterator for ErrorSkippedTokenIter<'_> {
+    type Item = Result<TokenWithRange, AsonError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.upstream.next()? {
+                Ok(token_with_range) => return Some(Ok(token_with_range)),
+                Err(e) => self.errors.push(e),
+            }
+        }
     }
 }
 
 struct Parser<'a> {
     upstream: &'a mut PeekableIter<'a, Result<TokenWithRange, AsonError>>,
     last_range: Location,
+
+    // records the errors and continues parsing instead of returning
+    // the first error.
+    recovery: bool,
+    errors: Vec<AsonError>,
+
+    // the nesting depth and the number of elements are checked by the parser.
+    limits: Limits,
+    depth: usize,
 }
 
 impl<'a> Parser<'a> {
-    fn new(upstream: &'a mut PeekableIter<'a, Result<TokenWithRange, AsonError>>) -> Self {
+    fn new(
+        upstream: &'a mut PeekableIter<'a, Result<TokenWithRange, AsonError>>,
+        limits: Limits,
+    ) -> Self {
         Self {
             upstream,
             last_range: Location::new_range(0, 0, 0, 0),
+            recovery: false,
+            errors: vec![],
+            limits,
+            depth: 0,
+        }
+    }
+
+    fn new_with_recovery(
+        upstream: &'a mut PeekableIter<'a, Result<TokenWithRange, AsonError>>,
+        limits: Limits,
+    ) -> Self {
+        Self {
+            recovery: true,
+            ..Self::new(upstream, limits)
         }
     }
 
@@ -151,18 +283,35 @@ impl<'a> Parser<'a> {
         expected_token: &Token,
         token_description: &str,
     ) -> Result<(), AsonError> {
+        if self.recovery && !self.expect_token(0, expected_token)? {
+            // the missing token is recorded, and the current token is
+            // left to the parent node, e.g. a mismatched closing bracket.
+            let error = match self.peek_range(0)? {
+                Some(range) => AsonError::new_syntax(
+                    format!("Expect token: {}.", token_description),
+                    range.get_position_by_range_start(),
+                ),
+                None => AsonError::new_unexpected_end_of_document(format!(
+                    "Expect token: {}.",
+                    token_description
+                )),
+            };
+            self.record_error(error);
+            return Ok(());
+        }
+
         match self.next_token()? {
             Some(token) => {
                 if &token == expected_token {
                     Ok(())
                 } else {
-                    Err(AsonError::MessageWithLocation(
+                    Err(AsonError::new_syntax(
                         format!("Expect token: {}.", token_description),
                         self.last_range.get_position_by_range_start(),
                     ))
                 }
             }
-            None => Err(AsonError::UnexpectedEndOfDocument(format!(
+            None => Err(AsonError::new_unexpected_end_of_document(format!(
                 "Expect token: {}.",
                 token_description
             ))),
@@ -188,9 +337,133 @@ impl<'a> Parser<'a> {
     fn consume_colon(&mut self) -> Result<(), AsonError> {
         self.consume_token(&Token::Colon, "colon sign")
     }
+
+    // consume the separator ('\n' or ',') between items, returns false if
+    // there is no more items.
+    //
+    // in recovery mode, a missing separator is recorded and the next item
+    // is parsed as usual unless it reaches the closing bracket.
+    fn consume_item_separator(&mut self) -> Result<bool, AsonError> {
+        if self.consume_new_line_or_comma_if_exist()? {
+            return Ok(true);
+        }
+
+        if !self.recovery {
+            return Ok(false);
+        }
+
+        match self.peek_token(0)? {
+            None | Some(Token::RightBrace | Token::RightBracket | Token::RightParen) => Ok(false),
+            Some(Token::Error(_)) => {
+                // the error is recorded when parsing the next item.
+                Ok(true)
+            }
+            Some(_) => {
+                let error = AsonError::new_syntax(
+                    "Expect a comma or new-line.".to_owned(),
+                    self.peek_range(0)?.unwrap().get_position_by_range_start(),
+                );
+                self.record_error(error);
+                Ok(true)
+            }
+        }
+    }
+
+    // checks the number of elements before parsing the next element
+    // of List, Map, Object, Tuple or Variant.
+    fn check_container_length(&self, length: usize) -> Result<(), AsonError> {
+        match self.peek_range(0)? {
+            Some(range) => self
+                .limits
+                .check_container_length(length + 1, range.get_position_by_range_start()),
+            None => Ok(()),
+        }
+    }
+
+    fn record_error(&mut self, error: AsonError) {
+        let is_duplicated = match self.errors.last() {
+            // an incomplete document causes a series of errors (e.g. all of
+            // the closing brackets are missing), only the first one is kept.
+            _ if error.is_unexpected_end_of_document() => self
+                .errors
+                .iter()
+                .any(|e| e.is_unexpected_end_of_document()),
+
+            // only the first error is kept for a position, e.g. the missing colon
+            // and the unexpected token in `{a\nb: 1}` are reported at `b`.
+            Some(last) if !last.is_unexpected_end_of_document() => {
+                matches!((error.location, last.location), (Some(location), Some(last_location))
+                    if location.index == last_location.index)
+            }
+            _ => false,
+        };
+
+        if !is_duplicated {
+            self.errors.push(error);
+        }
+    }
+
+    // records the error in recovery mode and skips the tokens until
+    // a comma, new-line or closing bracket that is not nested,
+    // otherwise returns the error.
+    fn recover(&mut self, error: AsonError) -> Result<(), AsonError> {
+        if !self.recovery {
+            return Err(error);
+        }
+
+        self.record_error(error);
+
+        let mut depth = 0;
+        while let Some(token) = self.peek_token(0)? {
+            match token {
+                Token::LeftBrace | Token::LeftBracket | Token::LeftParen => depth += 1,
+                Token::RightBrace | Token::RightBracket | Token::RightParen => {
+                    if depth == 0 {
+                        break;
+                    }
+                    depth -= 1;
+                }
+                Token::NewLine | Token::Comma if depth == 0 => break,
+                Token::Error(e) => {
+                    let e = e.clone();
+                    self.record_error(e);
+                }
+                _ => {}
+            }
+            self.next_token()?;
+        }
+
+        Ok(())
+    }
 }
 
 impl Parser<'_> {
+    fn parse_root_with_recovery(&mut self) -> Result<AsonNode, AsonError> {
+        let root = self.parse_node_or_recover()?;
+
+        // check trailing token
+        if self.peek_token(0)?.is_some() {
+            let error = AsonError::new_syntax(
+                "Document has more than one node.".to_owned(),
+                self.peek_range(0)?.unwrap().get_position_by_range_start(),
+            );
+            self.record_error(error);
+        }
+
+        Ok(root)
+    }
+
+    // in recovery mode, the invalid node is replaced by the error placeholder.
+    fn parse_node_or_recover(&mut self) -> Result<AsonNode, AsonError> {
+        match self.parse_node() {
+            Ok(node) => Ok(node),
+            Err(e) => {
+                self.recover(e)?;
+                Ok(AsonNode::Error)
+            }
+        }
+    }
+
     fn parse_node(&mut self) -> Result<AsonNode, AsonError> {
         match self.peek_token(0)? {
             Some(current_token) => {
@@ -224,11 +497,11 @@ impl Parser<'_> {
                         match self.peek_token(1)? {
                             Some(Token::LeftParen) => {
                                 // tuple variant or the new type variant (i.e. single value variant)
-                                self.parse_tuple_variant()?
+                                self.parse_nested(Self::parse_tuple_variant)?
                             }
                             Some(Token::LeftBrace) => {
                                 // struct variant
-                                self.parse_struct_variant()?
+                                self.parse_nested(Self::parse_struct_variant)?
                             }
                             _ => {
                                 // unit variant
@@ -245,19 +518,26 @@ impl Parser<'_> {
                     }
                     Token::LeftBrace => {
                         // object: {...}
-                        self.parse_object()?
+                        self.parse_nested(Self::parse_object)?
                     }
                     Token::LeftBracket => {
                         // list: [...]
                         //  map: [key:value...]
-                        self.parse_list()?
+                        self.parse_nested(Self::parse_list)?
                     }
                     Token::LeftParen => {
                         // tuple: (...)
-                        self.parse_tuple()?
+                        self.parse_nested(Self::parse_tuple)?
+                    }
+                    Token::Error(e) => {
+                        // the invalid text, only exists in recovery mode.
+                        let e = e.clone();
+                        self.record_error(e);
+                        self.next_token()?;
+                        AsonNode::Error
                     }
                     _ => {
-                        return Err(AsonError::MessageWithLocation(
+                        return Err(AsonError::new_syntax(
                             "Unexpected token.".to_owned(),
                             self.peek_range(0)?.unwrap().get_position_by_range_start(),
                         ))
@@ -266,12 +546,29 @@ impl Parser<'_> {
 
                 Ok(node)
             }
-            None => Err(AsonError::UnexpectedEndOfDocument(
+            None => Err(AsonError::new_unexpected_end_of_document(
                 "Incomplete document.".to_owned(),
             )),
         }
     }
 
+    // parses the List, Map, Object, Tuple or Variant with value,
+    // and checks the nesting depth.
+    fn parse_nested(
+        &mut self,
+        parse: fn(&mut Self) -> Result<AsonNode, AsonError>,
+    ) -> Result<AsonNode, AsonError> {
+        self.limits.check_depth(
+            self.depth + 1,
+            self.peek_range(0)?.unwrap().get_position_by_range_start(),
+        )?;
+
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
     // includes tuple style and new-type style variant
     fn parse_tuple_variant(&mut self) -> Result<AsonNode, AsonError> {
         // type::member(...)?  //
@@ -312,12 +609,12 @@ impl Parser<'_> {
 
             //             if !is_first_element && !matches!(exists_separator, Some(true)) {
             //                 if let Some(false) = exists_separator {
-            //                     return Err(AsonError::MessageWithLocation(
+            //                     return Err(AsonError::new_syntax(
             //                         "Expect a comma or new-line.".to_owned(),
             //                         self.peek_range(0)?.unwrap().get_position_by_range_start(),
             //                     ));
             //                 } else {
-            //                     return Err(AsonError::UnexpectedEndOfDocument(
+            //                     return Err(AsonError::new_unexpected_end_of_document(
             //                         "Incomplete \"Tuple\" style Variant.".to_owned(),
             //                     ));
             //                 }
@@ -325,10 +622,11 @@ impl Parser<'_> {
             //
             //             is_first_element = false;
 
-            let value = self.parse_node()?;
+            self.check_container_length(items.len())?;
+            let value = self.parse_node_or_recover()?;
             items.push(value);
 
-            let found_sep = self.consume_new_line_or_comma_if_exist()?;
+            let found_sep = self.consume_item_separator()?;
             if !found_sep {
                 break;
             }
@@ -339,7 +637,7 @@ impl Parser<'_> {
 
         let variant_item = match items.len() {
             0 => {
-                return Err(AsonError::MessageWithLocation(
+                return Err(AsonError::new_syntax(
                     "The value of tuple style variant can not be empty.".to_owned(),
                     self.last_range.get_position_by_range_start(),
                 ));
@@ -404,12 +702,12 @@ impl Parser<'_> {
 
             //             if !is_first_element && !matches!(exists_separator, Some(true)) {
             //                 if let Some(false) = exists_separator {
-            //                     return Err(AsonError::MessageWithLocation(
+            //                     return Err(AsonError::new_syntax(
             //                         "Expect a comma or new-line.".to_owned(),
             //                         self.peek_range(0)?.unwrap().get_position_by_range_start(),
             //                     ));
             //                 } else {
-            //                     return Err(AsonError::UnexpectedEndOfDocument(
+            //                     return Err(AsonError::new_unexpected_end_of_document(
             //                         "Incomplete Object.".to_owned(),
             //                     ));
             //                 }
@@ -417,33 +715,47 @@ impl Parser<'_> {
             //
             //             is_first_element = false;
 
-            let name = match self.next_token()? {
-                Some(Token::Identifier(n)) => n,
-                Some(_) => {
-                    return Err(AsonError::MessageWithLocation(
-                        "Expect a key name for object.".to_owned(),
-                        self.last_range.get_position_by_range_start(),
-                    ));
-                }
-                None => {
-                    return Err(AsonError::UnexpectedEndOfDocument(
-                        "Expect a key name for object.".to_owned(),
-                    ));
+            self.check_container_length(kvps.len())?;
+
+            let name = match token {
+                Token::Identifier(n) => n.to_owned(),
+                _ => {
+                    let error = if let Token::Error(e) = token {
+                        // the invalid text, only exists in recovery mode.
+                        let e = e.clone();
+                        self.next_token()?;
+                        e
+                    } else {
+                        // the invalid token is not consumed, so that a closing bracket
+                        // can be left to the parent node in recovery mode.
+                        AsonError::new_syntax(
+                            "Expect a key name for object.".to_owned(),
+                            self.peek_range(0)?.unwrap().get_position_by_range_start(),
+                        )
+                    };
+                    self.recover(error)?;
+
+                    if self.consume_item_separator()? {
+                        continue;
+                    } else {
+                        break;
+                    }
                 }
             };
+            self.next_token()?; // consume the key name
             self.consume_new_line_if_exist()?;
 
             self.consume_colon()?;
             self.consume_new_line_if_exist()?;
 
-            let value = self.parse_node()?;
+            let value = self.parse_node_or_recover()?;
             let name_value_pair = KeyValuePair {
                 key: name,
                 value: Box::new(value),
             };
             kvps.push(name_value_pair);
 
-            let found_sep = self.consume_new_line_or_comma_if_exist()?;
+            let found_sep = self.consume_item_separator()?;
             if !found_sep {
                 break;
             }
@@ -485,12 +797,12 @@ impl Parser<'_> {
     //
     //             if !is_first_element && !matches!(exists_separator, Some(true)) {
     //                 if let Some(false) = exists_separator {
-    //                     return Err(AsonError::MessageWithLocation(
+    //                     return Err(AsonError::new_syntax(
     //                         "Expect a comma or new-line.".to_owned(),
     //                         self.peek_range(0)?.unwrap().get_position_by_range_start(),
     //                     ));
     //                 } else {
-    //                     return Err(AsonError::UnexpectedEndOfDocument(
+    //                     return Err(AsonError::new_unexpected_end_of_document(
     //                         "Incomplete Object.".to_owned(),
     //                     ));
     //                 }
@@ -557,12 +869,12 @@ impl Parser<'_> {
 
             //             if !is_first_element && !matches!(exists_separator, Some(true)) {
             //                 if let Some(false) = exists_separator {
-            //                     return Err(AsonError::MessageWithLocation(
+            //                     return Err(AsonError::new_syntax(
             //                         "Expect a comma or new-line.".to_owned(),
             //                         self.peek_range(0)?.unwrap().get_position_by_range_start(),
             //                     ));
             //                 } else {
-            //                     return Err(AsonError::UnexpectedEndOfDocument(
+            //                     return Err(AsonError::new_unexpected_end_of_document(
             //                         "Incomplete List.".to_owned(),
             //                     ));
             //                 }
@@ -570,7 +882,8 @@ impl Parser<'_> {
             //
             //             is_first_element = false;
 
-            let item = self.parse_node()?;
+            self.check_container_length(items.len() + nvps.len())?;
+            let item = self.parse_node_or_recover()?;
 
             if list_type == ListType::Unknown {
                 if self
@@ -591,7 +904,7 @@ impl Parser<'_> {
                 self.consume_colon()?;
                 self.consume_new_line_if_exist()?;
 
-                let value = self.parse_node()?;
+                let value = self.parse_node_or_recover()?;
                 let nvp = NameValuePair {
                     name: Box::new(item),
                     value: Box::new(value),
@@ -599,7 +912,7 @@ impl Parser<'_> {
                 nvps.push(nvp);
             }
 
-            let found_sep = self.consume_new_line_or_comma_if_exist()?;
+            let found_sep = self.consume_item_separator()?;
             if !found_sep {
                 break;
             }
@@ -645,12 +958,12 @@ impl Parser<'_> {
 
             //             if !is_first_element && !matches!(exists_separator, Some(true)) {
             //                 if let Some(false) = exists_separator {
-            //                     return Err(AsonError::MessageWithLocation(
+            //                     return Err(AsonError::new_syntax(
             //                         "Expect a comma or new-line.".to_owned(),
             //                         self.peek_range(0)?.unwrap().get_position_by_range_start(),
             //                     ));
             //                 } else {
-            //                     return Err(AsonError::UnexpectedEndOfDocument(
+            //                     return Err(AsonError::new_unexpected_end_of_document(
             //                         "Incomplete Tuple.".to_owned(),
             //                     ));
             //                 }
@@ -658,10 +971,11 @@ impl Parser<'_> {
             //
             //             is_first_element = false;
 
-            let value = self.parse_node()?;
+            self.check_container_length(items.len())?;
+            let value = self.parse_node_or_recover()?;
             items.push(value);
 
-            let found_sep = self.consume_new_line_or_comma_if_exist()?;
+            let found_sep = self.consume_item_separator()?;
             if !found_sep {
                 break;
             }
@@ -671,7 +985,7 @@ impl Parser<'_> {
         self.consume_right_paren()?; // consume ')'
 
         if items.is_empty() {
-            Err(AsonError::MessageWithLocation(
+            Err(AsonError::new_syntax(
                 "Tuple can not be empty.".to_owned(),
                 self.last_range.get_position_by_range_start(),
             ))
@@ -681,7 +995,7 @@ impl Parser<'_> {
     }
 }
 
-fn convert_number_token(token: &NumberToken) -> AsonNode {
+pub(crate) fn convert_number_token(token: &NumberToken) -> AsonNode {
     let number = match token {
         NumberToken::I8(v) => Number::I8(*v as i8),
         NumberToken::U8(v) => Number::U8(*v),
@@ -705,9 +1019,11 @@ mod tests {
 
     use crate::{
         ast::{KeyValuePair, NameValuePair, Number, Variant},
+        limits::Limits,
         location::Location,
+        options::ParseOptions,
         parser::parse_from_str,
-        AsonError,
+        AsonError, ErrorKind,
     };
 
     use super::AsonNode;
@@ -888,7 +1204,7 @@ mod tests {
         //     Err(Error::MessageWithLocation(
         //         _,
         //         Location {
-        //             // unit: 0,
+        //             unit: 0,
         //             index: 1,
         //             line: 0,
         //             column: 1,
@@ -903,7 +1219,7 @@ mod tests {
         //     Err(Error::MessageWithLocation(
         //         _,
         //         Location {
-        //             // unit: 0,
+        //             unit: 0,
         //             index: 1,
         //             line: 0,
         //             column: 1,
@@ -915,64 +1231,87 @@ mod tests {
         // err: missing ':'
         assert!(matches!(
             parse_from_str(r#"{id}"#),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 3,
+                    byte_index: 3,
                     line: 0,
                     column: 3,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: missing value, the '}' is not the expected token
         assert!(matches!(
             parse_from_str(r#"{id:}"#),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 4,
+                    byte_index: 4,
                     line: 0,
                     column: 4,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: missing a separator (comma or new-line)
         assert!(matches!(
             parse_from_str(r#"{id: 123 name: "foo"}"#),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 9,
+                    byte_index: 9,
                     line: 0,
                     column: 9,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: missing :, EOF
         assert!(matches!(
             parse_from_str(r#"{id"#),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
 
         // err: missing value, EOF
         assert!(matches!(
             parse_from_str(r#"{id:"#),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
 
         // err: missing '}', EOF
         assert!(matches!(
             parse_from_str(r#"{id:123"#),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                location: Some(Location {
+                    unit: 0,
+                    index: 7,
+                    byte_index: 7,
+                    line: 0,
+                    column: 7,
+                    length: 0
+                }),
+                ..
+            })
         ));
     }
 
@@ -1063,34 +1402,45 @@ mod tests {
         // err: missing a separator (comma or new-line)
         assert!(matches!(
             parse_from_str(r#"[123 456]"#),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 5,
+                    byte_index: 5,
                     line: 0,
                     column: 5,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: missing ']', EOF
         assert!(matches!(
             parse_from_str(r#"[123"#),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
 
         // err: missing ']', EOF
         assert!(matches!(
             parse_from_str(r#"[123,"#),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
 
         // err: missing ']', EOF
         assert!(matches!(
             parse_from_str(r#"[123,456"#),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
     }
 
@@ -1157,49 +1507,62 @@ mod tests {
         // err: empty tuple
         assert!(matches!(
             parse_from_str(r#"()"#),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 1,
+                    byte_index: 1,
                     line: 0,
                     column: 1,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: missing a separator (comma or new-line)
         assert!(matches!(
             parse_from_str(r#"(123 456)"#),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 5,
+                    byte_index: 5,
                     line: 0,
                     column: 5,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: missing ')', EOF
         assert!(matches!(
             parse_from_str(r#"(123"#),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
 
         // err: missing ')', EOF
         assert!(matches!(
             parse_from_str(r#"(123,"#),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
 
         // err: missing ')', EOF
         assert!(matches!(
             parse_from_str(r#"(123,456"#),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
     }
 
@@ -1271,88 +1634,104 @@ mod tests {
         // err: missing value(s)
         assert!(matches!(
             parse_from_str(r#"Option::Some()"#),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 13,
+                    byte_index: 13,
                     line: 0,
                     column: 13,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: missing a separator (comma or new-line)
         assert!(matches!(
             parse_from_str(r#"Color::RGB(11 13 17)"#),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 14,
+                    byte_index: 14,
                     line: 0,
                     column: 14,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: missing ')', EOF
         assert!(matches!(
             parse_from_str(r#"Color::RGB(11,13"#),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
 
         // err: missing ':'
         assert!(matches!(
             parse_from_str(r#"Color::Rect{width}"#),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 17,
+                    byte_index: 17,
                     line: 0,
                     column: 17,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: missing value
         assert!(matches!(
             parse_from_str(r#"Color::Rect{width:}"#),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 18,
+                    byte_index: 18,
                     line: 0,
                     column: 18,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: missing a separator (comma or new-line)
         assert!(matches!(
             parse_from_str(r#"Color::Rect{width:11 height:13}"#),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 21,
+                    byte_index: 21,
                     line: 0,
                     column: 21,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: missing '}', EOF
         assert!(matches!(
             parse_from_str(r#"Color::Rect{width:11"#),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
     }
 
@@ -1443,16 +1822,252 @@ mod tests {
         // err: document does not end properly
         assert!(matches!(
             parse_from_str(r#"true false"#),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 5,
+                    byte_index: 5,
                     line: 0,
                     column: 5,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_parse_with_recovery() {
+        // valid document
+        assert_eq!(
+            ParseOptions::new().parse_str_with_recovery("[1, 2]"),
+            (
+                AsonNode::List(vec![
+                    AsonNode::Number(Number::I32(1)),
+                    AsonNode::Number(Number::I32(2)),
+                ]),
+                vec![]
+            )
+        );
+
+        // invalid values
+        let (node, errors) = ParseOptions::new().parse_str_with_recovery(
+            r#"{
+    id: 123
+    name: :
+    tags: [1, 2_x8
+        3]
+    point: (11, :)
+    enabled: true
+}"#,
+        );
+
+        assert_eq!(
+            node,
+            AsonNode::Object(vec![
+                KeyValuePair::new("id", AsonNode::Number(Number::I32(123))),
+                KeyValuePair::new("name", AsonNode::Error),
+                KeyValuePair::new(
+                    "tags",
+                    AsonNode::List(vec![
+                        AsonNode::Number(Number::I32(1)),
+                        AsonNode::Error,
+                        AsonNode::Number(Number::I32(3))
+                    ])
+                ),
+                KeyValuePair::new(
+                    "point",
+                    AsonNode::Tuple(vec![AsonNode::Number(Number::I32(11)), AsonNode::Error])
+                ),
+                KeyValuePair::new("enabled", AsonNode::Boolean(true)),
+            ])
+        );
+
+        assert_eq!(
+            errors
+                .iter()
+                .map(|e| match e {
+                    AsonError {
+                        kind: ErrorKind::Syntax,
+                        location: Some(location),
+                        ..
+                    } => (location.line, location.column),
+                    _ => panic!("Unexpected error: {:?}", e),
+                })
+                .collect::<Vec<_>>(),
+            vec![(2, 10), (3, 16), (5, 16)]
+        );
+
+        // missing separator, key and colon
+        let (node, errors) =
+            ParseOptions::new().parse_str_with_recovery(r#"{a: 1 b: 2, "c": 3, d 4}"#);
+        assert_eq!(
+            node,
+            AsonNode::Object(vec![
+                KeyValuePair::new("a", AsonNode::Number(Number::I32(1))),
+                KeyValuePair::new("b", AsonNode::Number(Number::I32(2))),
+                KeyValuePair::new("d", AsonNode::Number(Number::I32(4))),
+            ])
+        );
+        assert_eq!(errors.len(), 3);
+
+        // incomplete document
+        let (node, errors) = ParseOptions::new().parse_str_with_recovery("{a: [1, (2, ");
+        assert_eq!(
+            node,
+            AsonNode::Object(vec![KeyValuePair::new(
+                "a",
+                AsonNode::List(vec![
+                    AsonNode::Number(Number::I32(1)),
+                    AsonNode::Tuple(vec![AsonNode::Number(Number::I32(2))])
+                ])
+            )])
+        );
+        assert!(matches!(
+            errors.as_slice(),
+            [AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                location: Some(Location { index: 12, .. }),
+                ..
+            }]
+        ));
+
+        // mismatched closing bracket
+        let (node, errors) = ParseOptions::new().parse_str_with_recovery("{a: [1, 2}");
+        assert_eq!(
+            node,
+            AsonNode::Object(vec![KeyValuePair::new(
+                "a",
+                AsonNode::List(vec![
+                    AsonNode::Number(Number::I32(1)),
+                    AsonNode::Number(Number::I32(2))
+                ])
+            )])
+        );
+        assert_eq!(
+            errors,
+            vec![AsonError::new_syntax(
+                "Expect token: right bracket.".to_owned(),
+                Location::new_position(9, 0, 9)
+            )]
+        );
+
+        // empty document
+        let (node, errors) = ParseOptions::new().parse_str_with_recovery("");
+        assert_eq!(node, AsonNode::Error);
+        assert_eq!(errors.len(), 1);
+
+        // trailing nodes
+        let (node, errors) = ParseOptions::new().parse_str_with_recovery("[1] [2]");
+        assert_eq!(node, AsonNode::List(vec![AsonNode::Number(Number::I32(1))]));
+        assert_eq!(
+            errors,
+            vec![AsonError::new_syntax(
+                "Document has more than one node.".to_owned(),
+                Location::new_position(4, 0, 4)
+            )]
+        );
+    }
+
+    #[test]
+    fn test_parse_with_limits() {
+        let limits = Limits {
+            max_depth: 3,
+            max_container_length: 3,
+            ..Limits::default()
+        };
+
+        assert!(ParseOptions::new()
+            .with_limits(limits)
+            .parse_str("[[[1, 2, 3]]]")
+            .is_ok());
+
+        // depth
+        for text in [
+            "[[[[1]]]]",
+            "{a: {b: {c: {d: 1}}}}",
+            "((((1,),),),)",
+            "[[[Option::Some(1)]]]",
+            "[[[Foo::Bar{id: 1}]]]",
+        ] {
+            assert!(matches!(
+                ParseOptions::new().with_limits(limits).parse_str(text),
+                Err(AsonError {
+                    kind: ErrorKind::DepthLimitExceeded,
+                    ..
+                })
+            ));
+        }
+
+        assert!(matches!(
+            ParseOptions::new()
+                .with_limits(limits)
+                .parse_str("[[[[1]]]]"),
+            Err(AsonError {
+                location: Some(Location { index: 3, .. }),
+                ..
+            })
+        ));
+
+        // the recommended depth limit prevents the stack overflow
+        let text = "[".repeat(100_000);
+        assert!(matches!(
+            ParseOptions::new()
+                .with_limits(Limits {
+                    max_depth: Limits::DEFAULT_MAX_DEPTH,
+                    ..Limits::default()
+                })
+                .parse_str(&text),
+            Err(AsonError {
+                kind: ErrorKind::DepthLimitExceeded,
+                ..
+            })
+        ));
+
+        // nothing is limited by default
+        let text = format!("{}1{}", "[".repeat(100), "]".repeat(100));
+        assert!(parse_from_str(&text).is_ok());
+
+        // the number of elements
+        for text in [
+            "[1, 2, 3, 4]",
+            "[1: 1, 2: 2, 3: 3, 4: 4]",
+            "{a: 1, b: 2, c: 3, d: 4}",
+            "(1, 2, 3, 4)",
+            "Foo::Bar(1, 2, 3, 4)",
+        ] {
+            assert!(matches!(
+                ParseOptions::new().with_limits(limits).parse_str(text),
+                Err(AsonError {
+                    kind: ErrorKind::ContainerLengthLimitExceeded,
+                    ..
+                })
+            ));
+        }
+
+        assert!(matches!(
+            ParseOptions::new()
+                .with_limits(limits)
+                .parse_str("[1, 2, 3, 4]"),
+            Err(AsonError {
+                location: Some(Location { index: 10, .. }),
+                ..
+            })
+        ));
+
+        // string and document size are checked by the lexer
+        assert!(matches!(
+            ParseOptions::new()
+                .with_limits(Limits {
+                    max_document_size: 8,
+                    ..Limits::default()
+                })
+                .parse_str(r#"{name: "foo"}"#),
+            Err(AsonError {
+                kind: ErrorKind::DocumentSizeLimitExceeded,
+                ..
+            })
         ));
     }
 
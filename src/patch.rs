@@ -0,0 +1,789 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+//! Patches, i.e. sequences of operations that change an ASON document,
+//! in the manner of JSON Patch (RFC 6902).
+//!
+//! A patch can be written as an ASON List of `Patch` variants, e.g.
+//!
+//! ```text
+//! [
+//!     Patch::Add{path: ".servers[2]", value: {host: "c"}}
+//!     Patch::Remove{path: ".debug"}
+//!     Patch::Replace{path: ".port", value: 8080_u16}
+//!     Patch::Move{from: ".labels['a']", path: ".labels['b']"}
+//!     Patch::Copy{from: ".servers[0]", path: ".backup"}
+//!     Patch::Test{path: ".version", value: 2}
+//! ]
+//! ```
+//!
+//! The operations are performed in order, so the index of a List element
+//! refers to the List as changed by the preceding operations.
+
+use crate::{
+    ast::{AsonNode, KeyValuePair, NameValuePair, Number, Variant, VariantValue},
+    path::{is_name_matched, AsonPath, PathSegment},
+    printer::print_to_string,
+    AsonError, ErrorKind,
+};
+
+const PATCH_TYPE_NAME: &str = "Patch";
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum PatchOperation {
+    // inserts the value into a List, Tuple or variant Tuple
+    // at the index, or sets the member of an Object or the entry of a Map.
+    Add { path: AsonPath, value: AsonNode },
+    Remove { path: AsonPath },
+
+    // the value must exist.
+    Replace { path: AsonPath, value: AsonNode },
+
+    // removes the value and adds it to the path,
+    // the path can not be inside the value.
+    Move { from: AsonPath, path: AsonPath },
+    Copy { from: AsonPath, path: AsonPath },
+
+    // checks whether the value is equal to the expected value.
+    Test { path: AsonPath, value: AsonNode },
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Patch {
+    pub operations: Vec<PatchOperation>,
+}
+
+impl Patch {
+    pub fn new(operations: Vec<PatchOperation>) -> Self {
+        Self { operations }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Converts the patch to the ASON List form.
+    pub fn to_node(&self) -> AsonNode {
+        AsonNode::List(self.operations.iter().map(operation_to_node).collect())
+    }
+
+    /// Reads the patch from the ASON List form.
+    pub fn from_node(node: &AsonNode) -> Result<Self, AsonError> {
+        match node {
+            AsonNode::List(items) => Ok(Self::new(
+                items
+                    .iter()
+                    .map(operation_from_node)
+                    .collect::<Result<Vec<_>, _>>()?,
+            )),
+            _ => Err(new_invalid_patch_error(
+                "Expect a List of operations.".to_owned(),
+            )),
+        }
+    }
+
+    /// Performs the operations on the document.
+    ///
+    /// The operations are applied atomically, i.e. the document
+    /// is left unchanged if any operation fails.
+    pub fn apply(&self, node: &mut AsonNode) -> Result<(), AsonError> {
+        let mut result = node.clone();
+        for operation in &self.operations {
+            apply_operation(&mut result, operation)?;
+        }
+        *node = result;
+        Ok(())
+    }
+}
+
+fn operation_to_node(operation: &PatchOperation) -> AsonNode {
+    let (member_name, from, path, value) = match operation {
+        PatchOperation::Add { path, value } => ("Add", None, path, Some(value)),
+        PatchOperation::Remove { path } => ("Remove", None, path, None),
+        PatchOperation::Replace { path, value } => ("Replace", None, path, Some(value)),
+        PatchOperation::Move { from, path } => ("Move", Some(from), path, None),
+        PatchOperation::Copy { from, path } => ("Copy", Some(from), path, None),
+        PatchOperation::Test { path, value } => ("Test", None, path, Some(value)),
+    };
+
+    let mut kvps = vec![];
+
+    if let Some(from) = from {
+        kvps.push(KeyValuePair::new(
+            "from",
+            AsonNode::String(from.to_string()),
+        ));
+    }
+
+    kvps.push(KeyValuePair::new(
+        "path",
+        AsonNode::String(path.to_string()),
+    ));
+
+    if let Some(value) = value {
+        kvps.push(KeyValuePair::new("value", value.clone()));
+    }
+
+    AsonNode::Variant(Variant::with_object(PATCH_TYPE_NAME, member_name, kvps))
+}
+
+fn operation_from_node(node: &AsonNode) -> Result<PatchOperation, AsonError> {
+    let (member_name, kvps) = match node {
+        AsonNode::Variant(Variant {
+            type_name,
+            member_name,
+            value: VariantValue::Object(kvps),
+        }) if type_name == PATCH_TYPE_NAME => (member_name.as_str(), kvps),
+        _ => {
+            return Err(new_invalid_patch_error(format!(
+                "Expect an operation such as `Patch::Remove{{path: \".name\"}}`, found `{}`.",
+                print_to_string(node)
+            )))
+        }
+    };
+
+    let field_names: &[&str] = match member_name {
+        "Add" | "Replace" | "Test" => &["path", "value"],
+        "Remove" => &["path"],
+        "Move" | "Copy" => &["from", "path"],
+        _ => {
+            return Err(new_invalid_patch_error(format!(
+                "Unknown operation \"{}\".",
+                member_name
+            )))
+        }
+    };
+
+    if let Some(kvp) = kvps
+        .iter()
+        .find(|kvp| !field_names.contains(&kvp.key.as_str()))
+    {
+        return Err(new_invalid_patch_error(format!(
+            "Unexpected field \"{}\" of operation \"{}\".",
+            kvp.key, member_name
+        )));
+    }
+
+    let get_field = |name: &str| -> Result<&AsonNode, AsonError> {
+        kvps.iter()
+            .find(|kvp| kvp.key == name)
+            .map(|kvp| kvp.value.as_ref())
+            .ok_or_else(|| {
+                new_invalid_patch_error(format!(
+                    "Missing field \"{}\" of operation \"{}\".",
+                    name, member_name
+                ))
+            })
+    };
+
+    let get_path = |name: &str| -> Result<AsonPath, AsonError> {
+        match get_field(name)? {
+            AsonNode::String(s) => AsonPath::parse(s).map_err(|e| {
+                new_invalid_patch_error(format!("Invalid path \"{}\".", s)).caused_by(e)
+            }),
+            _ => Err(new_invalid_patch_error(format!(
+                "The field \"{}\" of operation \"{}\" should be a String.",
+                name, member_name
+            ))),
+        }
+    };
+
+    let operation = match member_name {
+        "Add" => PatchOperation::Add {
+            path: get_path("path")?,
+            value: get_field("value")?.clone(),
+        },
+        "Remove" => PatchOperation::Remove {
+            path: get_path("path")?,
+        },
+        "Replace" => PatchOperation::Replace {
+            path: get_path("path")?,
+            value: get_field("value")?.clone(),
+        },
+        "Move" => PatchOperation::Move {
+            from: get_path("from")?,
+            path: get_path("path")?,
+        },
+        "Copy" => PatchOperation::Copy {
+            from: get_path("from")?,
+            path: get_path("path")?,
+        },
+        _ => PatchOperation::Test {
+            path: get_path("path")?,
+            value: get_field("value")?.clone(),
+        },
+    };
+
+    Ok(operation)
+}
+
+fn apply_operation(node: &mut AsonNode, operation: &PatchOperation) -> Result<(), AsonError> {
+    match operation {
+        PatchOperation::Add { path, value } => add_value(node, path, value.clone()),
+        PatchOperation::Remove { path } => remove_value(node, path).map(|_| ()),
+        PatchOperation::Replace { path, value } => {
+            let target = path
+                .find_mut(node)
+                .ok_or_else(|| new_path_not_found_error(path))?;
+            *target = value.clone();
+            Ok(())
+        }
+        PatchOperation::Move { from, path } => {
+            if from.is_ancestor_of(path) {
+                return Err(new_patch_failed_error(
+                    "Can not move a value into itself.",
+                    path,
+                ));
+            }
+            let value = remove_value(node, from)?;
+            add_value(node, path, value)
+        }
+        PatchOperation::Copy { from, path } => {
+            let value = from
+                .find(node)
+                .ok_or_else(|| new_path_not_found_error(from))?
+                .clone();
+            add_value(node, path, value)
+        }
+        PatchOperation::Test { path, value } => {
+            let actual = path
+                .find(node)
+                .ok_or_else(|| new_path_not_found_error(path))?;
+            if actual == value {
+                Ok(())
+            } else {
+                let expected = print_to_string(value);
+                let found = print_to_string(actual);
+                Err(AsonError::new(
+                    ErrorKind::PatchFailed,
+                    format!("Test failed, expect `{}`, found `{}`.", expected, found),
+                )
+                .with_path(path.clone())
+                .with_expected_and_found(&expected, &found))
+            }
+        }
+    }
+}
+
+// returns the parent value and the last segment of the path.
+fn find_parent<'a, 'b>(
+    node: &'a mut AsonNode,
+    path: &'b AsonPath,
+) -> Result<(&'a mut AsonNode, &'b PathSegment), AsonError> {
+    let (segment, _) = path
+        .segments
+        .split_last()
+        .ok_or_else(|| new_patch_failed_error("The root value can not be removed.", path))?;
+
+    let parent = path
+        .parent()
+        .and_then(|parent_path| parent_path.find_mut(node))
+        .ok_or_else(|| new_path_not_found_error(path))?;
+
+    Ok((parent, segment))
+}
+
+fn add_value(node: &mut AsonNode, path: &AsonPath, value: AsonNode) -> Result<(), AsonError> {
+    if path.is_root() {
+        *node = value;
+        return Ok(());
+    }
+
+    let (parent, segment) = find_parent(node, path)?;
+
+    match (parent, segment) {
+        (AsonNode::Object(kvps), PathSegment::Key(key)) => {
+            set_member(kvps, key, value);
+            Ok(())
+        }
+        (AsonNode::Map(nvps), _) => {
+            match nvps
+                .iter_mut()
+                .find(|nvp| is_name_matched(&nvp.name, segment))
+            {
+                Some(nvp) => *nvp.value = value,
+                None => nvps.push(NameValuePair {
+                    name: Box::new(segment_to_name(segment)),
+                    value: Box::new(value),
+                }),
+            }
+            Ok(())
+        }
+        (AsonNode::List(items) | AsonNode::Tuple(items), PathSegment::Index(index)) => {
+            insert_item(items, *index, value, path)
+        }
+        (AsonNode::Variant(variant), _) => match (&mut variant.value, segment) {
+            (VariantValue::Value(v), PathSegment::Index(0)) => {
+                **v = value;
+                Ok(())
+            }
+            (VariantValue::Tuple(items), PathSegment::Index(index)) => {
+                insert_item(items, *index, value, path)
+            }
+            (VariantValue::Object(kvps), PathSegment::Key(key)) => {
+                set_member(kvps, key, value);
+                Ok(())
+            }
+            _ => Err(new_path_not_found_error(path)),
+        },
+        _ => Err(new_path_not_found_error(path)),
+    }
+}
+
+fn remove_value(node: &mut AsonNode, path: &AsonPath) -> Result<AsonNode, AsonError> {
+    let (parent, segment) = find_parent(node, path)?;
+
+    let removed = match (parent, segment) {
+        (AsonNode::Object(kvps), PathSegment::Key(key)) => remove_member(kvps, key),
+        (AsonNode::Map(nvps), _) => nvps
+            .iter()
+            .position(|nvp| is_name_matched(&nvp.name, segment))
+            .map(|pos| *nvps.remove(pos).value),
+        (AsonNode::List(items) | AsonNode::Tuple(items), PathSegment::Index(index)) => {
+            remove_item(items, *index)
+        }
+        (AsonNode::Variant(variant), _) => match (&mut variant.value, segment) {
+            (VariantValue::Tuple(items), PathSegment::Index(index)) => remove_item(items, *index),
+            (VariantValue::Object(kvps), PathSegment::Key(key)) => remove_member(kvps, key),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    removed.ok_or_else(|| new_path_not_found_error(path))
+}
+
+fn set_member(kvps: &mut Vec<KeyValuePair>, key: &str, value: AsonNode) {
+    match kvps.iter_mut().find(|kvp| kvp.key == key) {
+        Some(kvp) => *kvp.value = value,
+        None => kvps.push(KeyValuePair::new(key, value)),
+    }
+}
+
+fn remove_member(kvps: &mut Vec<KeyValuePair>, key: &str) -> Option<AsonNode> {
+    kvps.iter()
+        .position(|kvp| kvp.key == key)
+        .map(|pos| *kvps.remove(pos).value)
+}
+
+fn insert_item(
+    items: &mut Vec<AsonNode>,
+    index: usize,
+    value: AsonNode,
+    path: &AsonPath,
+) -> Result<(), AsonError> {
+    if index > items.len() {
+        return Err(new_patch_failed_error(
+            &format!(
+                "The index {} is out of bounds, the length is {}.",
+                index,
+                items.len()
+            ),
+            path,
+        ));
+    }
+
+    items.insert(index, value);
+    Ok(())
+}
+
+fn remove_item(items: &mut Vec<AsonNode>, index: usize) -> Option<AsonNode> {
+    (index < items.len()).then(|| items.remove(index))
+}
+
+// the name of the new Map entry, the integer names are `i32`
+// which is the default type of integer.
+fn segment_to_name(segment: &PathSegment) -> AsonNode {
+    match segment {
+        PathSegment::Key(key) => AsonNode::String(key.to_owned()),
+        PathSegment::Index(index) => match i32::try_from(*index) {
+            Ok(v) => AsonNode::Number(Number::I32(v)),
+            Err(_) => AsonNode::Number(Number::U64(*index as u64)),
+        },
+        PathSegment::Name(name) => name.clone(),
+    }
+}
+
+fn new_invalid_patch_error(message: String) -> AsonError {
+    AsonError::new(ErrorKind::InvalidPatch, message)
+}
+
+fn new_patch_failed_error(message: &str, path: &AsonPath) -> AsonError {
+    AsonError::new(ErrorKind::PatchFailed, message.to_owned()).with_path(path.clone())
+}
+
+fn new_path_not_found_error(path: &AsonPath) -> AsonError {
+    new_patch_failed_error(&format!("The path \"{}\" does not exist.", path), path)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        ast::{AsonNode, Number},
+        parser::parse_from_str,
+        path::AsonPath,
+        printer::print_to_string,
+        AsonError, ErrorKind,
+    };
+
+    use super::{Patch, PatchOperation};
+
+    fn apply_text(doc: &str, patch: &str) -> Result<String, AsonError> {
+        let mut node = parse_from_str(doc).unwrap();
+        let patch = Patch::from_node(&parse_from_str(patch).unwrap())?;
+        patch.apply(&mut node)?;
+        Ok(print_to_string(&node))
+    }
+
+    #[test]
+    fn test_to_node() {
+        let patch = Patch::new(vec![
+            PatchOperation::Add {
+                path: AsonPath::parse(".servers[2]").unwrap(),
+                value: AsonNode::String("c".to_owned()),
+            },
+            PatchOperation::Remove {
+                path: AsonPath::parse(".debug").unwrap(),
+            },
+            PatchOperation::Replace {
+                path: AsonPath::parse(".port").unwrap(),
+                value: AsonNode::Number(Number::U16(8080)),
+            },
+        ]);
+
+        assert_eq!(
+            print_to_string(&patch.to_node()),
+            r#"[
+    Patch::Add{
+        path: ".servers[2]"
+        value: "c"
+    }
+    Patch::Remove{
+        path: ".debug"
+    }
+    Patch::Replace{
+        path: ".port"
+        value: 8080_u16
+    }
+]"#
+        );
+
+        assert!(Patch::default().is_empty());
+    }
+
+    #[test]
+    fn test_from_node() {
+        let text = r#"[
+    Patch::Add{
+        path: ".servers[2]"
+        value: {
+            host: "c"
+        }
+    }
+    Patch::Remove{
+        path: ".labels['x']"
+    }
+    Patch::Replace{
+        path: ".port"
+        value: 8080_u16
+    }
+    Patch::Move{
+        from: ".labels[-1]"
+        path: ".labels[11_u8]"
+    }
+    Patch::Copy{
+        from: ".servers[0]"
+        path: ".backup"
+    }
+    Patch::Test{
+        path: ".version"
+        value: 2
+    }
+]"#;
+
+        let patch = Patch::from_node(&parse_from_str(text).unwrap()).unwrap();
+        assert_eq!(patch.operations.len(), 6);
+        assert_eq!(
+            patch.operations[3],
+            PatchOperation::Move {
+                from: AsonPath::parse("[\"labels\"][-1]").unwrap(),
+                path: AsonPath::parse(".labels[11_u8]").unwrap(),
+            }
+        );
+        assert_eq!(print_to_string(&patch.to_node()), text);
+
+        // errors
+        let from_text = |s: &str| Patch::from_node(&parse_from_str(s).unwrap());
+
+        for s in [
+            r#"Patch::Remove{path: ".a"}"#,
+            r#"[Patch::Remove(".a")]"#,
+            r#"[Op::Remove{path: ".a"}]"#,
+            r#"[Patch::Delete{path: ".a"}]"#,
+            r#"[Patch::Add{path: ".a"}]"#,
+            r#"[Patch::Remove{path: ".a", value: 1}]"#,
+            r#"[Patch::Remove{path: 1}]"#,
+            r#"[Patch::Remove{path: "a"}]"#,
+        ] {
+            assert!(matches!(
+                from_text(s),
+                Err(AsonError {
+                    kind: ErrorKind::InvalidPatch,
+                    ..
+                })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_apply() {
+        let doc = r#"{
+            name: "foo"
+            servers: [{host: "a"}, {host: "b"}]
+            labels: ['x': 1, -1: 2]
+            point: (1, 2)
+            shape: Shape::Rect{width: 1, height: 2}
+            color: Color::RGB(1, 2, 3)
+            opt: Option::Some(1)
+        }"#;
+
+        let patch = r#"[
+            Patch::Test{path: ".name", value: "foo"}
+            Patch::Add{path: ".servers[1]", value: {host: "c"}}
+            Patch::Add{path: ".servers[3]", value: {host: "d"}}
+            Patch::Remove{path: ".servers[0]"}
+            Patch::Replace{path: ".servers[0].host", value: "C"}
+            Patch::Move{from: ".labels[-1]", path: ".labels[11_u8]"}
+            Patch::Add{path: ".labels.y", value: 3}
+            Patch::Add{path: ".point[2]", value: 3}
+            Patch::Remove{path: ".shape.height"}
+            Patch::Add{path: ".shape.depth", value: 3}
+            Patch::Remove{path: ".color[0]"}
+            Patch::Replace{path: ".opt[0]", value: 2}
+            Patch::Copy{from: ".point", path: ".copied"}
+            Patch::Remove{path: ".name"}
+        ]"#;
+
+        assert_eq!(
+            apply_text(doc, patch).unwrap(),
+            print_to_string(
+                &parse_from_str(
+                    r#"{
+                        servers: [{host: "C"}, {host: "b"}, {host: "d"}]
+                        labels: ['x': 1, 11_u8: 2, "y": 3]
+                        point: (1, 2, 3)
+                        shape: Shape::Rect{width: 1, depth: 3}
+                        color: Color::RGB(2, 3)
+                        opt: Option::Some(2)
+                        copied: (1, 2, 3)
+                    }"#
+                )
+                .unwrap()
+            )
+        );
+
+        // the root
+        assert_eq!(
+            apply_text("1", r#"[Patch::Replace{path: ".", value: 2}]"#).unwrap(),
+            "2"
+        );
+        assert_eq!(
+            apply_text("1", r#"[Patch::Add{path: ".", value: [3]}]"#).unwrap(),
+            "[\n    3\n]"
+        );
+
+        // the integer names of Map
+        assert_eq!(
+            apply_text(r#"[1: "a"]"#, r#"[Patch::Add{path: "[2]", value: "b"}]"#).unwrap(),
+            print_to_string(&parse_from_str(r#"[1: "a", 2: "b"]"#).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_apply_alike_map_names() {
+        // each path selects one entry only, no matter the order of entries
+        let doc = r#"['a': 1, "a": 2, 1: 3, 1_u8: 4, 1_i64: 5]"#;
+        let expect = |s: &str| print_to_string(&parse_from_str(s).unwrap());
+
+        for (patch, expected) in [
+            (
+                r#"[Patch::Replace{path: ".a", value: 20}]"#,
+                r#"['a': 1, "a": 20, 1: 3, 1_u8: 4, 1_i64: 5]"#,
+            ),
+            (
+                r#"[Patch::Replace{path: "['a']", value: 10}]"#,
+                r#"['a': 10, "a": 2, 1: 3, 1_u8: 4, 1_i64: 5]"#,
+            ),
+            (
+                r#"[Patch::Remove{path: "[1]"}]"#,
+                r#"['a': 1, "a": 2, 1_u8: 4, 1_i64: 5]"#,
+            ),
+            (
+                r#"[Patch::Remove{path: "[1_u8]"}]"#,
+                r#"['a': 1, "a": 2, 1: 3, 1_i64: 5]"#,
+            ),
+            (
+                r#"[Patch::Add{path: "[1_i64]", value: 50}]"#,
+                r#"['a': 1, "a": 2, 1: 3, 1_u8: 4, 1_i64: 50]"#,
+            ),
+            (
+                r#"[Patch::Move{from: "['a']", path: "['b']"}]"#,
+                r#"["a": 2, 1: 3, 1_u8: 4, 1_i64: 5, 'b': 1]"#,
+            ),
+            (r#"[Patch::Test{path: "[1_u8]", value: 4}]"#, doc),
+        ] {
+            assert_eq!(apply_text(doc, patch).unwrap(), expect(expected));
+        }
+
+        // the names of other kinds are not matched
+        for (patch, message) in [
+            (
+                r#"[Patch::Remove{path: "['b']"}]"#,
+                r#"The path "['b']" does not exist."#,
+            ),
+            (
+                r#"[Patch::Remove{path: "[2_u8]"}]"#,
+                r#"The path "[2_u8]" does not exist."#,
+            ),
+            (
+                r#"[Patch::Test{path: "[1]", value: 4}]"#,
+                "Test failed, expect `4`, found `3`.",
+            ),
+        ] {
+            let e = apply_text(doc, patch).unwrap_err();
+            assert_eq!(e.kind, ErrorKind::PatchFailed);
+            assert_eq!(e.message, message);
+        }
+    }
+
+    #[test]
+    fn test_apply_variant_payloads() {
+        let doc = r#"{
+            e: Option::None
+            v: Option::Some(1)
+            t: Color::RGB(1, 2)
+            o: Shape::Rect{w: 1}
+        }"#;
+        let expect = |s: &str| print_to_string(&parse_from_str(s).unwrap());
+
+        // the value of `Type::Member(v)` is replaced by adding
+        assert_eq!(
+            apply_text(doc, r#"[Patch::Add{path: ".v[0]", value: 2}]"#).unwrap(),
+            expect(
+                r#"{e: Option::None, v: Option::Some(2), t: Color::RGB(1, 2), o: Shape::Rect{w: 1}}"#
+            )
+        );
+
+        // the elements of `Type::Member(a, b)` are inserted and removed
+        assert_eq!(
+            apply_text(
+                doc,
+                r#"[
+                    Patch::Add{path: ".t[0]", value: 0}
+                    Patch::Remove{path: ".t[2]"}
+                    Patch::Copy{from: ".v[0]", path: ".t[2]"}
+                ]"#
+            )
+            .unwrap(),
+            expect(
+                r#"{e: Option::None, v: Option::Some(1), t: Color::RGB(0, 1, 1), o: Shape::Rect{w: 1}}"#
+            )
+        );
+
+        // the members of `Type::Member{k: v}` are added and replaced
+        assert_eq!(
+            apply_text(
+                doc,
+                r#"[
+                    Patch::Add{path: ".o.h", value: 2}
+                    Patch::Replace{path: ".o.w", value: 3}
+                ]"#
+            )
+            .unwrap(),
+            expect(
+                r#"{e: Option::None, v: Option::Some(1), t: Color::RGB(1, 2), o: Shape::Rect{w: 3, h: 2}}"#
+            )
+        );
+
+        // the segments that do not match the kind of payload
+        for (patch, message) in [
+            (
+                r#"[Patch::Add{path: ".e[0]", value: 1}]"#,
+                r#"The path ".e[0]" does not exist."#,
+            ),
+            (
+                r#"[Patch::Remove{path: ".v[0]"}]"#,
+                r#"The path ".v[0]" does not exist."#,
+            ),
+            (
+                r#"[Patch::Add{path: ".v[1]", value: 2}]"#,
+                r#"The path ".v[1]" does not exist."#,
+            ),
+            (
+                r#"[Patch::Add{path: ".t.x", value: 0}]"#,
+                r#"The path ".t.x" does not exist."#,
+            ),
+            (
+                r#"[Patch::Add{path: ".t[3]", value: 0}]"#,
+                "The index 3 is out of bounds, the length is 2.",
+            ),
+            (
+                r#"[Patch::Add{path: ".o[0]", value: 2}]"#,
+                r#"The path ".o[0]" does not exist."#,
+            ),
+        ] {
+            let e = apply_text(doc, patch).unwrap_err();
+            assert_eq!(e.kind, ErrorKind::PatchFailed);
+            assert_eq!(e.message, message);
+        }
+    }
+
+    #[test]
+    fn test_apply_errors() {
+        let doc = r#"{a: [1, 2], b: Option::None, c: 3}"#;
+
+        for patch in [
+            r#"[Patch::Remove{path: ".x"}]"#,
+            r#"[Patch::Remove{path: ".a[2]"}]"#,
+            r#"[Patch::Remove{path: "."}]"#,
+            r#"[Patch::Add{path: ".a[3]", value: 3}]"#,
+            r#"[Patch::Add{path: ".x.y", value: 3}]"#,
+            r#"[Patch::Add{path: ".c.y", value: 3}]"#,
+            r#"[Patch::Add{path: ".b[0]", value: 3}]"#,
+            r#"[Patch::Replace{path: ".x", value: 3}]"#,
+            r#"[Patch::Move{from: ".a", path: ".a[0]"}]"#,
+            r#"[Patch::Copy{from: ".x", path: ".y"}]"#,
+            r#"[Patch::Test{path: ".x", value: 3}]"#,
+        ] {
+            let e = apply_text(doc, patch).unwrap_err();
+            assert_eq!(e.kind, ErrorKind::PatchFailed);
+            assert!(e.path().is_some());
+        }
+
+        // test failed
+        let e = apply_text(doc, r#"[Patch::Test{path: ".c", value: 4}]"#).unwrap_err();
+        assert_eq!(e.path().unwrap().to_string(), ".c");
+        assert_eq!(e.expected(), Some("4"));
+        assert_eq!(e.found(), Some("3"));
+
+        // atomic
+        let mut node = parse_from_str(doc).unwrap();
+        let patch = Patch::from_node(
+            &parse_from_str(
+                r#"[
+                    Patch::Remove{path: ".c"}
+                    Patch::Test{path: ".a[0]", value: 2}
+                ]"#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert!(patch.apply(&mut node).is_err());
+        assert_eq!(node, parse_from_str(doc).unwrap());
+    }
+}
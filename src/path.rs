@@ -0,0 +1,584 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+//! Paths that locate a value within an ASON document.
+//!
+//! A path is a sequence of segments, e.g. `.servers[0].port`:
+//!
+//! - `.name` selects the member of an Object, or the entry of a Map
+//!   whose name is the String `"name"`.
+//! - `["first name"]` is the same as `.name`, for keys that are not
+//!   valid identifiers. The string supports the escapes `\\`, `\"`,
+//!   `\'`, `\n`, `\r`, `\t`, `\0` and `\u{...}`.
+//! - `[2]` selects the element of a List or Tuple, or the entry of a Map
+//!   whose name is the `i32` number `2`.
+//! - `[<literal>]`, e.g. `['c']`, `[11_u8]` and `[-1]`, selects the entry
+//!   of a Map whose name is equal to the literal, for the names
+//!   that can not be written as the above forms.
+//!
+//! A segment matches one kind of Map names only, e.g. `.c` does not
+//! match the Char `'c'` and `[1]` does not match `1_u8`, so that a path
+//! never selects a different entry of a Map with names such as
+//! `'c'` and `"c"`.
+//!
+//! The value of a variant is selected by `[0]` for `Type::Member(v)`,
+//! by index for `Type::Member(a, b)` and by key for `Type::Member{k: v}`.
+//!
+//! The root path is written as `.`.
+
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+use crate::{
+    ast::{AsonNode, Number, VariantValue},
+    parser::parse_from_str,
+    printer::print_to_string,
+    AsonError, ErrorKind,
+};
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+
+    // the name of Map entry other than String and non-negative `i32`.
+    Name(AsonNode),
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct AsonPath {
+    pub segments: Vec<PathSegment>,
+}
+
+impl AsonPath {
+    pub fn root() -> Self {
+        Self::default()
+    }
+
+    pub fn parse(s: &str) -> Result<Self, AsonError> {
+        PathParser::new(s).parse()
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    pub fn push_key(&mut self, key: &str) {
+        self.segments.push(PathSegment::Key(key.to_owned()));
+    }
+
+    pub fn push_index(&mut self, index: usize) {
+        self.segments.push(PathSegment::Index(index));
+    }
+
+    /// Appends the segment of the Map entry with the specified name,
+    /// the most concise form is chosen, e.g. `.name` for String `"name"`.
+    pub fn push_name(&mut self, name: &AsonNode) {
+        let segment = match name {
+            AsonNode::String(s) => PathSegment::Key(s.to_owned()),
+            AsonNode::Number(Number::I32(v)) if *v >= 0 => PathSegment::Index(*v as usize),
+            _ => PathSegment::Name(name.clone()),
+        };
+        self.segments.push(segment);
+    }
+
+    /// Returns the path without the last segment, or `None`
+    /// for the root path.
+    pub fn parent(&self) -> Option<AsonPath> {
+        let (_, segments) = self.segments.split_last()?;
+        Some(AsonPath {
+            segments: segments.to_vec(),
+        })
+    }
+
+    /// Checks whether this path is a proper prefix of the other path,
+    /// i.e. the other path locates a value inside the value of this path.
+    pub fn is_ancestor_of(&self, other: &AsonPath) -> bool {
+        other.segments.len() > self.segments.len() && other.segments.starts_with(&self.segments)
+    }
+
+    /// Returns the value located by this path, or `None`
+    /// if the value does not exist.
+    pub fn find<'a>(&self, node: &'a AsonNode) -> Option<&'a AsonNode> {
+        self.segments
+            .iter()
+            .try_fold(node, |current, segment| find_child(current, segment))
+    }
+
+    /// Returns the mutable value located by this path, or `None`
+    /// if the value does not exist.
+    pub fn find_mut<'a>(&self, node: &'a mut AsonNode) -> Option<&'a mut AsonNode> {
+        self.segments
+            .iter()
+            .try_fold(node, |current, segment| find_child_mut(current, segment))
+    }
+}
+
+fn find_child<'a>(node: &'a AsonNode, segment: &PathSegment) -> Option<&'a AsonNode> {
+    match (node, segment) {
+        (AsonNode::Object(kvps), PathSegment::Key(key)) => kvps
+            .iter()
+            .find(|kvp| &kvp.key == key)
+            .map(|kvp| kvp.value.as_ref()),
+        (AsonNode::List(items) | AsonNode::Tuple(items), PathSegment::Index(index)) => {
+            items.get(*index)
+        }
+        (AsonNode::Map(nvps), _) => nvps
+            .iter()
+            .find(|nvp| is_name_matched(&nvp.name, segment))
+            .map(|nvp| nvp.value.as_ref()),
+        (AsonNode::Variant(variant), _) => match (&variant.value, segment) {
+            (VariantValue::Value(value), PathSegment::Index(0)) => Some(value.as_ref()),
+            (VariantValue::Tuple(items), PathSegment::Index(index)) => items.get(*index),
+            (VariantValue::Object(kvps), PathSegment::Key(key)) => kvps
+                .iter()
+                .find(|kvp| &kvp.key == key)
+                .map(|kvp| kvp.value.as_ref()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn find_child_mut<'a>(node: &'a mut AsonNode, segment: &PathSegment) -> Option<&'a mut AsonNode> {
+    match (node, segment) {
+        (AsonNode::Object(kvps), PathSegment::Key(key)) => kvps
+            .iter_mut()
+            .find(|kvp| &kvp.key == key)
+            .map(|kvp| kvp.value.as_mut()),
+        (AsonNode::List(items) | AsonNode::Tuple(items), PathSegment::Index(index)) => {
+            items.get_mut(*index)
+        }
+        (AsonNode::Map(nvps), _) => nvps
+            .iter_mut()
+            .find(|nvp| is_name_matched(&nvp.name, segment))
+            .map(|nvp| nvp.value.as_mut()),
+        (AsonNode::Variant(variant), _) => match (&mut variant.value, segment) {
+            (VariantValue::Value(value), PathSegment::Index(0)) => Some(value.as_mut()),
+            (VariantValue::Tuple(items), PathSegment::Index(index)) => items.get_mut(*index),
+            (VariantValue::Object(kvps), PathSegment::Key(key)) => kvps
+                .iter_mut()
+                .find(|kvp| &kvp.key == key)
+                .map(|kvp| kvp.value.as_mut()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// the segments match the names in the same way as `AsonPath::push_name`
+// builds them, i.e. one segment matches one name only.
+pub(crate) fn is_name_matched(name: &AsonNode, segment: &PathSegment) -> bool {
+    match (name, segment) {
+        (AsonNode::String(s), PathSegment::Key(key)) => s == key,
+        (AsonNode::Number(Number::I32(v)), PathSegment::Index(index)) => {
+            *v >= 0 && *v as usize == *index
+        }
+        (_, PathSegment::Name(expected)) => name == expected,
+        _ => false,
+    }
+}
+
+impl Display for AsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.segments.is_empty() {
+            return f.write_str(".");
+        }
+
+        for segment in &self.segments {
+            match segment {
+                PathSegment::Key(key) if is_valid_identifier(key) => write!(f, ".{}", key)?,
+                PathSegment::Key(key) => write!(f, "[{:?}]", key)?,
+                PathSegment::Index(index) => write!(f, "[{}]", index)?,
+                PathSegment::Name(name) => write!(f, "[{}]", print_to_string(name))?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for AsonPath {
+    type Err = AsonError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        AsonPath::parse(s)
+    }
+}
+
+struct PathParser<'a> {
+    source: &'a str,
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl<'a> PathParser<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            chars: source.chars().peekable(),
+        }
+    }
+
+    fn new_error(&self, detail: &str) -> AsonError {
+        AsonError::new(
+            ErrorKind::Syntax,
+            format!("Invalid path \"{}\": {}", self.source, detail),
+        )
+    }
+
+    fn expect_char(&mut self, expected: char) -> Result<(), AsonError> {
+        match self.chars.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(self.new_error(&format!("expect '{}', found '{}'.", expected, c))),
+            None => Err(self.new_error(&format!("expect '{}'.", expected))),
+        }
+    }
+
+    fn parse(mut self) -> Result<AsonPath, AsonError> {
+        let mut path = AsonPath::root();
+
+        // the root path
+        if self.source == "." {
+            return Ok(path);
+        }
+
+        while let Some(c) = self.chars.next() {
+            match c {
+                '.' => {
+                    let key = self.parse_identifier()?;
+                    path.push_key(&key);
+                }
+                '[' => {
+                    if self.chars.peek() == Some(&'"') {
+                        let key = self.parse_string()?;
+                        path.push_key(&key);
+                    } else {
+                        let literal = self.parse_literal()?;
+                        if !literal.is_empty() && literal.chars().all(|c| c.is_ascii_digit()) {
+                            let index = literal.parse::<usize>().map_err(|_| {
+                                self.new_error(&format!("index \"{}\" is too large.", literal))
+                            })?;
+                            path.push_index(index);
+                        } else {
+                            let name = parse_from_str(&literal).map_err(|_| {
+                                self.new_error(&format!("\"{}\" is not a valid literal.", literal))
+                            })?;
+                            path.push_name(&name);
+                        }
+                    }
+                    self.expect_char(']')?;
+                }
+                _ => return Err(self.new_error(&format!("unexpected char '{}'.", c))),
+            }
+        }
+
+        Ok(path)
+    }
+
+    fn parse_identifier(&mut self) -> Result<String, AsonError> {
+        let mut key = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if c == '.' || c == '[' {
+                break;
+            }
+            key.push(c);
+            self.chars.next();
+        }
+
+        if is_valid_identifier(&key) {
+            Ok(key)
+        } else {
+            Err(self.new_error(&format!("\"{}\" is not a valid key name.", key)))
+        }
+    }
+
+    // the text before the closing ']', the brackets and
+    // the quotes within the literal are skipped.
+    fn parse_literal(&mut self) -> Result<String, AsonError> {
+        let mut literal = String::new();
+        let mut depth = 0;
+        let mut quote: Option<char> = None;
+        let mut escaping = false;
+
+        while let Some(&c) = self.chars.peek() {
+            match quote {
+                Some(_) if escaping => escaping = false,
+                Some(_) if c == '\\' => escaping = true,
+                Some(q) if c == q => quote = None,
+                Some(_) => {}
+                None => match c {
+                    '\'' | '"' => quote = Some(c),
+                    '[' | '(' | '{' => depth += 1,
+                    ']' if depth == 0 => break,
+                    ']' | ')' | '}' => depth -= 1,
+                    _ => {}
+                },
+            }
+            literal.push(c);
+            self.chars.next();
+        }
+
+        if literal.trim().is_empty() {
+            Err(self.new_error("expect a string, an index or a literal after '['."))
+        } else {
+            Ok(literal)
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, AsonError> {
+        self.expect_char('"')?;
+
+        let mut s = String::new();
+        loop {
+            match self.chars.next() {
+                Some('"') => break,
+                Some('\\') => {
+                    let c = match self.chars.next() {
+                        Some('\\') => '\\',
+                        Some('"') => '"',
+                        Some('\'') => '\'',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('0') => '\0',
+                        Some('u') => self.parse_unicode_escape()?,
+                        Some(c) => {
+                            return Err(self.new_error(&format!("unsupported escape '\\{}'.", c)))
+                        }
+                        None => return Err(self.new_error("incomplete string.")),
+                    };
+                    s.push(c);
+                }
+                Some(c) => s.push(c),
+                None => return Err(self.new_error("incomplete string.")),
+            }
+        }
+
+        Ok(s)
+    }
+
+    // `\u{...}`, the 'u' has been consumed.
+    fn parse_unicode_escape(&mut self) -> Result<char, AsonError> {
+        self.expect_char('{')?;
+
+        let mut digits = String::new();
+        loop {
+            match self.chars.next() {
+                Some('}') => break,
+                Some(c) if c.is_ascii_hexdigit() && digits.len() < 6 => digits.push(c),
+                _ => return Err(self.new_error("invalid unicode escape.")),
+            }
+        }
+
+        u32::from_str_radix(&digits, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or_else(|| self.new_error("invalid unicode escape."))
+    }
+}
+
+/// Checks whether the string can be used as the key of ASON Object.
+pub(crate) fn is_valid_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+
+    let first_char_valid = matches!(
+        chars.next(),
+        Some('a'..='z' | 'A'..='Z' | '_' | '\u{a0}'..='\u{d7ff}' | '\u{e000}'..='\u{10ffff}')
+    );
+
+    // keywords can not be used as key name
+    let is_keyword = matches!(
+        s,
+        "true" | "false" | "NaN" | "NaN_f32" | "NaN_f64" | "Inf" | "Inf_f32" | "Inf_f64"
+    );
+
+    first_char_valid
+        && !is_keyword
+        && chars.all(|c| {
+            matches!(c,
+                '0'..='9' | 'a'..='z' | 'A'..='Z' | '_' | '\u{a0}'..='\u{d7ff}' | '\u{e000}'..='\u{10ffff}')
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        ast::{AsonNode, Number},
+        parser::parse_from_str,
+    };
+
+    use super::{AsonPath, PathSegment};
+
+    #[test]
+    fn test_parse_and_display() {
+        assert_eq!(AsonPath::parse(".").unwrap(), AsonPath::root());
+        assert_eq!(AsonPath::parse("").unwrap(), AsonPath::root());
+
+        let path = AsonPath::parse(r#".servers[0]["first name"].port"#).unwrap();
+        assert_eq!(
+            path.segments,
+            vec![
+                PathSegment::Key("servers".to_owned()),
+                PathSegment::Index(0),
+                PathSegment::Key("first name".to_owned()),
+                PathSegment::Key("port".to_owned()),
+            ]
+        );
+        assert_eq!(path.to_string(), r#".servers[0]["first name"].port"#);
+
+        // the quoted key is printed as identifier if possible
+        assert_eq!(
+            AsonPath::parse(r#"["id"][1]"#).unwrap().to_string(),
+            ".id[1]"
+        );
+
+        // escapes
+        assert_eq!(
+            AsonPath::parse(r#"["a\"b\n\u{2764}"]"#).unwrap().segments,
+            vec![PathSegment::Key("a\"b\n\u{2764}".to_owned())]
+        );
+        assert_eq!(AsonPath::root().to_string(), ".");
+
+        // literals
+        assert_eq!(
+            AsonPath::parse("['c'][']'][-1][11_u8][0x10]")
+                .unwrap()
+                .segments,
+            vec![
+                PathSegment::Name(AsonNode::Char('c')),
+                PathSegment::Name(AsonNode::Char(']')),
+                PathSegment::Name(AsonNode::Number(Number::I32(-1))),
+                PathSegment::Name(AsonNode::Number(Number::U8(11))),
+                PathSegment::Index(16),
+            ]
+        );
+        assert_eq!(
+            AsonPath::parse("['c'][-1][11_u8]").unwrap().to_string(),
+            "['c'][-1][11_u8]"
+        );
+
+        // errors
+        assert!(AsonPath::parse("name").is_err());
+        assert!(AsonPath::parse(".").is_ok());
+        assert!(AsonPath::parse("..name").is_err());
+        assert!(AsonPath::parse(".true").is_err());
+        assert!(AsonPath::parse("[1").is_err());
+        assert!(AsonPath::parse("[-]").is_err());
+        assert!(AsonPath::parse("[]").is_err());
+        assert!(AsonPath::parse("['c]").is_err());
+        assert!(AsonPath::parse(r#"["abc]"#).is_err());
+        assert!(AsonPath::parse(r#"["\x"]"#).is_err());
+    }
+
+    #[test]
+    fn test_find() {
+        let node = parse_from_str(
+            r#"{
+                name: "foo"
+                servers: [
+                    {host: "a", ports: (80, 443)}
+                    {host: "b", ports: (8080,)}
+                ]
+                labels: [
+                    "first name": "Alice"
+                    'c': 'C'
+                    1: "one"
+                ]
+                opt: Option::Some(123)
+                shape: Shape::Rect{width: 10, height: 20}
+            }"#,
+        )
+        .unwrap();
+
+        let find = |s: &str| {
+            AsonPath::parse(s)
+                .unwrap()
+                .find(&node)
+                .map(crate::printer::print_to_string)
+        };
+
+        assert_eq!(find(".name"), Some(r#""foo""#.to_owned()));
+        assert_eq!(find(".servers[1].host"), Some(r#""b""#.to_owned()));
+        assert_eq!(find(".servers[0].ports[1]"), Some("443".to_owned()));
+        assert_eq!(
+            find(r#".labels["first name"]"#),
+            Some(r#""Alice""#.to_owned())
+        );
+        assert_eq!(find(".labels['c']"), Some("'C'".to_owned()));
+        assert_eq!(find(".labels[1]"), Some(r#""one""#.to_owned()));
+        assert_eq!(find(".opt[0]"), Some("123".to_owned()));
+        assert_eq!(find(".shape.height"), Some("20".to_owned()));
+        assert!(find(".").is_some());
+
+        // the names of Map entries
+        let mut path = AsonPath::parse(".labels").unwrap();
+        path.push_name(&AsonNode::Char('c'));
+        assert_eq!(path.to_string(), ".labels['c']");
+        assert_eq!(path.find(&node), Some(&AsonNode::Char('C')));
+
+        let mut path = AsonPath::root();
+        path.push_name(&AsonNode::String("first name".to_owned()));
+        path.push_name(&AsonNode::Number(Number::I32(1)));
+        assert_eq!(path.to_string(), r#"["first name"][1]"#);
+
+        // not found
+        assert_eq!(find(".servers[2]"), None);
+        assert_eq!(find(".name.length"), None);
+        assert_eq!(find(".labels[0]"), None);
+        assert_eq!(find(".opt[1]"), None);
+    }
+
+    #[test]
+    fn test_find_ambiguous_names() {
+        // the names which look alike are selected by different segments
+        let node = parse_from_str(
+            r#"[
+                'a': "char"
+                "a": "string"
+                1_u8: "u8"
+                1: "i32"
+                1_i64: "i64"
+            ]"#,
+        )
+        .unwrap();
+
+        let find = |s: &str| {
+            AsonPath::parse(s)
+                .unwrap()
+                .find(&node)
+                .map(crate::printer::print_to_string)
+        };
+
+        assert_eq!(find(".a"), Some(r#""string""#.to_owned()));
+        assert_eq!(find(r#"["a"]"#), Some(r#""string""#.to_owned()));
+        assert_eq!(find("['a']"), Some(r#""char""#.to_owned()));
+        assert_eq!(find("[1]"), Some(r#""i32""#.to_owned()));
+        assert_eq!(find("[1_u8]"), Some(r#""u8""#.to_owned()));
+        assert_eq!(find("[1_i64]"), Some(r#""i64""#.to_owned()));
+
+        // the path of each name selects the entry itself
+        if let AsonNode::Map(nvps) = &node {
+            for nvp in nvps {
+                let mut path = AsonPath::root();
+                path.push_name(&nvp.name);
+                assert_eq!(path.find(&node), Some(nvp.value.as_ref()));
+                assert_eq!(
+                    AsonPath::parse(&path.to_string()).unwrap().find(&node),
+                    Some(nvp.value.as_ref())
+                );
+            }
+        }
+
+        // only the names of Char or other numbers
+        let node = parse_from_str("['c': 1, 2_u8: 2]").unwrap();
+        assert_eq!(AsonPath::parse(".c").unwrap().find(&node), None);
+        assert_eq!(AsonPath::parse("[2]").unwrap().find(&node), None);
+    }
+}
@@ -10,7 +10,7 @@ use chrono::{DateTime, FixedOffset};
 
 use crate::{
     ast::{AsonNode, KeyValuePair, NameValuePair, Number, Variant, VariantValue},
-    AsonError,
+    AsonError, ErrorKind,
 };
 
 pub const DEFAULT_INDENT_CHARS: &str = "    ";
@@ -297,7 +297,7 @@ fn print_map(
     write!(writer, "{}]", leading_space)
 }
 
-fn print_node(
+pub(crate) fn print_node(
     writer: &mut dyn Write,
     node: &AsonNode,
     indent_chars: &str,
@@ -315,16 +315,31 @@ fn print_node(
         AsonNode::Tuple(v) => print_tuple(writer, v, indent_chars, indent_level),
         AsonNode::Object(v) => print_object(writer, v, indent_chars, indent_level),
         AsonNode::Map(v) => print_map(writer, v, indent_chars, indent_level),
+        // the partial tree can not be printed as a valid document.
+        AsonNode::Error => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            AsonError::new(
+                ErrorKind::InvalidStructure,
+                "Can not print the error placeholder.".to_owned(),
+            ),
+        )),
     }
 }
 
 pub fn print_to_writer(writer: &mut dyn Write, node: &AsonNode) -> Result<(), AsonError> {
     match print_node(writer, node, DEFAULT_INDENT_CHARS, 0) {
         Ok(_) => Ok(()),
-        Err(e) => Err(AsonError::Message(e.to_string())),
+        Err(e) => Err(e.into()),
     }
 }
 
+/// Formats the AST into text.
+///
+/// # Panics
+///
+/// Panics if the AST contains the `AsonNode::Error` placeholder, which is
+/// produced by the parser with error recovery, use `print_to_writer`
+/// to get the error instead.
 pub fn print_to_string(node: &AsonNode) -> String {
     let mut buf: Vec<u8> = vec![];
     print_to_writer(&mut buf, node).unwrap();
@@ -335,9 +350,13 @@ pub fn print_to_string(node: &AsonNode) -> String {
 mod tests {
     use pretty_assertions::assert_eq;
 
-    use crate::parser::parse_from_str;
+    use crate::{
+        ast::{AsonNode, Number},
+        parser::parse_from_str,
+        ErrorKind,
+    };
 
-    use super::print_to_string;
+    use super::{print_to_string, print_to_writer};
 
     // fn new_string_node(s: &str) -> AsonNode {
     //     AsonNode::String(s.to_owned())
@@ -874,6 +893,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_print_error_placeholder() {
+        let node = AsonNode::List(vec![AsonNode::Number(Number::I32(11)), AsonNode::Error]);
+        let mut buf: Vec<u8> = vec![];
+        let e = print_to_writer(&mut buf, &node).unwrap_err();
+        assert_eq!(e.kind, ErrorKind::InvalidStructure);
+        assert_eq!(e.message, "Can not print the error placeholder.");
+    }
+
     #[test]
     fn test_example_file_06() {
         let s = read_example_file_to_string("06-variant.ason");
@@ -5,12 +5,17 @@
 // more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
 
 pub mod de;
+#[cfg(feature = "tokio")]
+pub(crate) mod node;
 pub mod ser;
 pub mod serde_date;
+pub mod shared;
+pub mod spanned;
+pub mod stream;
 
 use std::fmt::Display;
 
-use crate::AsonError;
+use crate::{AsonError, ErrorKind};
 
 pub type Result<T> = std::result::Result<T, AsonError>;
 
@@ -19,7 +24,7 @@ impl serde::ser::Error for AsonError {
     where
         T: Display,
     {
-        AsonError::Message(msg.to_string())
+        AsonError::new(ErrorKind::Custom, msg.to_string())
     }
 }
 
@@ -28,7 +33,7 @@ impl serde::de::Error for AsonError {
     where
         T: Display,
     {
-        AsonError::Message(msg.to_string())
+        AsonError::new(ErrorKind::Custom, msg.to_string())
     }
 }
 //
@@ -6,38 +6,53 @@
 
 use std::io::Read;
 
-use serde::de::{self, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess};
+use serde::de::{
+    self,
+    value::{SeqDeserializer, StrDeserializer},
+    EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess,
+};
 
 use crate::{
-    charstream::CharStream,
+    anchor::AnchorTokenIter,
     charwithposition::CharsWithPositionIter,
+    interpolation::InterpolatedTokenIter,
     lexer::Lexer,
+    limits::Limits,
     location::Location,
     normalizer::{ClearTokenIter, NormalizedTokenIter, TrimmedTokenIter},
+    options::ParseOptions,
+    path::{AsonPath, PathSegment},
     peekableiter::PeekableIter,
     token::{NumberToken, Token, TokenWithRange},
-    AsonError,
+    AsonError, ErrorKind,
 };
 
-use super::Result;
+use super::{
+    spanned::{
+        from_location, SPANNED_KEY_LOCATION_FIELD, SPANNED_LOCATION_FIELD, SPANNED_STRUCT_NAME,
+        SPANNED_VALUE_FIELD,
+    },
+    Result,
+};
 
 pub fn from_str<T>(s: &str) -> Result<T>
 where
     T: de::DeserializeOwned,
 {
-    let mut chars = s.chars();
-    from_char_stream(&mut chars)
+    ParseOptions::new().from_str(s)
 }
 
-pub fn from_reader<T, R: Read>(mut r: R) -> Result<T>
+pub fn from_reader<T, R: Read>(r: R) -> Result<T>
 where
     T: de::DeserializeOwned,
 {
-    let mut char_stream = CharStream::new(&mut r);
-    from_char_stream(&mut char_stream)
+    ParseOptions::new().from_reader(r)
 }
 
-pub fn from_char_stream<T>(char_stream: &mut dyn Iterator<Item = char>) -> Result<T>
+pub(crate) fn from_char_stream<T>(
+    char_stream: &mut dyn Iterator<Item = char>,
+    options: &ParseOptions,
+) -> Result<T>
 where
     T: de::DeserializeOwned,
 {
@@ -50,46 +65,171 @@ where
 
     let mut char_position_iter = CharsWithPositionIter::new(char_stream);
     let mut peekable_char_position_iter = PeekableIter::new(&mut char_position_iter, 3);
-    let mut lexer = Lexer::new(&mut peekable_char_position_iter);
+    let limits = options.limits;
+    let mut lexer = Lexer::new_with_limits(&mut peekable_char_position_iter, limits);
 
     let mut clear_iter = ClearTokenIter::new(&mut lexer);
     let mut peekable_clear_iter = PeekableIter::new(&mut clear_iter, 1);
     let mut normalized_iter = NormalizedTokenIter::new(&mut peekable_clear_iter);
     let mut peekable_normalized_iter = PeekableIter::new(&mut normalized_iter, 1);
     let mut trimmed_iter = TrimmedTokenIter::new(&mut peekable_normalized_iter);
-    let mut peekable_trimmed_iter = PeekableIter::new(&mut trimmed_iter, 2);
-
-    let mut deserializer = Deserializer::from_token_peekable_iter(&mut peekable_trimmed_iter);
-    let value = T::deserialize(&mut deserializer)?;
+    let mut interpolated_iter = InterpolatedTokenIter::new(&mut trimmed_iter, options.variables);
+    let mut anchor_iter =
+        AnchorTokenIter::new(&mut interpolated_iter, options.expand_anchors, limits);
+    let mut peekable_anchor_iter = PeekableIter::new(&mut anchor_iter, 2);
+
+    let mut deserializer =
+        Deserializer::from_token_peekable_iter(&mut peekable_anchor_iter, limits);
+    let result = match T::deserialize(&mut deserializer) {
+        Ok(value) => match deserializer.upstream.peek(0) {
+            Some(Ok(TokenWithRange { range, .. })) => Err(AsonError::new_syntax(
+                "Document has more than one node.".to_owned(),
+                range.get_position_by_range_start(),
+            )),
+            Some(Err(e)) => Err(e.clone()),
+            None => {
+                // expected
+                Ok(value)
+            }
+        },
+        Err(e) => Err(deserializer.attach_context(e)),
+    };
 
-    match deserializer.upstream.peek(0) {
-        Some(Ok(TokenWithRange { range, .. })) => Err(AsonError::MessageWithLocation(
-            "Document has more than one node.".to_owned(),
-            range.get_position_by_range_start(),
-        )),
-        Some(Err(e)) => Err(e.clone()),
-        None => {
-            // expected
-            Ok(value)
-        }
-    }
+    // all chars have been consumed if the document ends unexpectedly.
+    result.map_err(|e| e.locate_end_of_document(char_position_iter.current_position()))
 }
 
 pub struct Deserializer<'de> {
     upstream: &'de mut PeekableIter<'de, Result<TokenWithRange>>,
     last_range: Location,
+
+    // the path of the value being deserialized, the segments are not
+    // popped when an error is raised, so it is the path of the failing value.
+    path: AsonPath,
+
+    // the range of the key of the value being deserialized,
+    // it is taken by `Spanned<T>`.
+    key_location: Option<Location>,
+
+    // the nesting depth and the number of elements are checked
+    // by the deserializer.
+    limits: Limits,
+    depth: usize,
 }
 
 impl<'de> Deserializer<'de> {
     pub fn from_token_peekable_iter(
         upstream: &'de mut PeekableIter<'de, Result<TokenWithRange>>,
+        limits: Limits,
     ) -> Self {
         Self {
             upstream,
             last_range: Location::new_range(0, 0, 0, 0),
+            path: AsonPath::root(),
+            key_location: None,
+            limits,
+            depth: 0,
         }
     }
 
+    // the current token does not match the type being deserialized.
+    fn new_type_mismatch_error(&self, expected: &str, found: &Token) -> AsonError {
+        AsonError::new_type_mismatch(expected, &describe_token(found))
+            .with_location(self.last_range)
+    }
+
+    // attaches the path of the failing value, and the location of the last
+    // token to the errors without location, e.g. the custom errors raised
+    // by the `Deserialize` implementations.
+    fn attach_context(&self, mut e: AsonError) -> AsonError {
+        if e.location.is_none() && !e.is_unexpected_end_of_document() {
+            e.location = Some(self.last_range);
+        }
+
+        if e.path().is_none() {
+            e = e.with_path(self.path.clone());
+        }
+
+        e
+    }
+
+    // deserializes the value of the member, element or entry
+    // with the path segment and the location of the key.
+    fn deserialize_child<T>(
+        &mut self,
+        segment: Option<PathSegment>,
+        key_location: Option<Location>,
+        seed: T,
+    ) -> Result<T::Value>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        self.key_location = key_location;
+
+        let pushed = segment.is_some();
+        if let Some(segment) = segment {
+            self.path.segments.push(segment);
+        }
+
+        let value = seed.deserialize(&mut *self)?;
+
+        if pushed {
+            self.path.segments.pop();
+        }
+
+        Ok(value)
+    }
+
+    // deserializes the List, Map, Object, Tuple or Variant with value,
+    // and checks the nesting depth.
+    fn deserialize_nested<T>(
+        &mut self,
+        deserialize: impl FnOnce(&mut Self) -> Result<T>,
+    ) -> Result<T> {
+        self.limits.check_depth(self.depth + 1, self.last_range)?;
+
+        self.depth += 1;
+        let result = deserialize(self);
+        self.depth -= 1;
+        result
+    }
+
+    // checks the number of elements before deserializing the next element
+    // of List, Map, Object, Tuple or Variant.
+    fn check_container_length(&self, length: usize) -> Result<()> {
+        match self.peek_range(0)? {
+            Some(range) => self
+                .limits
+                .check_container_length(length + 1, range.get_position_by_range_start()),
+            None => Ok(()),
+        }
+    }
+
+    // yields the value, the location of the value and the location
+    // of the key (if exists) for `Spanned<T>`.
+    fn deserialize_spanned<V>(&mut self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let start_range = match self.peek_range(0)? {
+            Some(range) => *range,
+            None => {
+                return Err(AsonError::new_unexpected_end_of_document(
+                    "Expect a value.".to_owned(),
+                ))
+            }
+        };
+
+        let key_location = self.key_location.take();
+        visitor.visit_map(SpannedAccessor {
+            de: self,
+            start_range,
+            location: None,
+            key_location,
+            field_index: 0,
+        })
+    }
+
     fn next_token(&mut self) -> Result<Option<Token>> {
         match self.upstream.next() {
             Some(Ok(TokenWithRange { token, range })) => {
@@ -151,13 +291,13 @@ impl<'de> Deserializer<'de> {
                 if &token == expected_token {
                     Ok(())
                 } else {
-                    Err(AsonError::MessageWithLocation(
+                    Err(AsonError::new_syntax(
                         format!("Expect token: {}.", token_description),
                         self.last_range.get_position_by_range_start(),
                     ))
                 }
             }
-            None => Err(AsonError::UnexpectedEndOfDocument(format!(
+            None => Err(AsonError::new_unexpected_end_of_document(format!(
                 "Expect token: \"{}\".",
                 token_description
             ))),
@@ -192,9 +332,9 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     where
         V: de::Visitor<'de>,
     {
-        Err(AsonError::MessageWithLocation(
-            "Unexpected value.".to_owned(),
-            self.last_range.get_position_by_range_start(),
+        Err(AsonError::new(
+            ErrorKind::Unsupported,
+            "Does not support the self-describing data type.".to_owned(),
         ))
     }
 
@@ -204,11 +344,8 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         match self.next_token()? {
             Some(Token::Boolean(v)) => visitor.visit_bool(v),
-            Some(_) => Err(AsonError::MessageWithLocation(
-                "Expect a \"Boolean\" value.".to_owned(),
-                self.last_range.get_position_by_range_start(),
-            )),
-            None => Err(AsonError::UnexpectedEndOfDocument(
+            Some(token) => Err(self.new_type_mismatch_error("Boolean", &token)),
+            None => Err(AsonError::new_unexpected_end_of_document(
                 "Expect a \"Boolean\" value.".to_owned(),
             )),
         }
@@ -220,11 +357,8 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         match self.next_token()? {
             Some(Token::Number(NumberToken::I8(v))) => visitor.visit_i8(v as i8),
-            Some(_) => Err(AsonError::MessageWithLocation(
-                "Expect an \"i8\" value.".to_owned(),
-                self.last_range.get_position_by_range_start(),
-            )),
-            None => Err(AsonError::UnexpectedEndOfDocument(
+            Some(token) => Err(self.new_type_mismatch_error("i8", &token)),
+            None => Err(AsonError::new_unexpected_end_of_document(
                 "Expect an \"i8\" value.".to_owned(),
             )),
         }
@@ -236,11 +370,8 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         match self.next_token()? {
             Some(Token::Number(NumberToken::I16(v))) => visitor.visit_i16(v as i16),
-            Some(_) => Err(AsonError::MessageWithLocation(
-                "Expect an \"i16\" value.".to_owned(),
-                self.last_range.get_position_by_range_start(),
-            )),
-            None => Err(AsonError::UnexpectedEndOfDocument(
+            Some(token) => Err(self.new_type_mismatch_error("i16", &token)),
+            None => Err(AsonError::new_unexpected_end_of_document(
                 "Expect an \"i16\" value.".to_owned(),
             )),
         }
@@ -252,11 +383,8 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         match self.next_token()? {
             Some(Token::Number(NumberToken::I32(v))) => visitor.visit_i32(v as i32),
-            Some(_) => Err(AsonError::MessageWithLocation(
-                "Expect an \"i32\" value.".to_owned(),
-                self.last_range.get_position_by_range_start(),
-            )),
-            None => Err(AsonError::UnexpectedEndOfDocument(
+            Some(token) => Err(self.new_type_mismatch_error("i32", &token)),
+            None => Err(AsonError::new_unexpected_end_of_document(
                 "Expect an \"i32\" value.".to_owned(),
             )),
         }
@@ -268,11 +396,8 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         match self.next_token()? {
             Some(Token::Number(NumberToken::I64(v))) => visitor.visit_i64(v as i64),
-            Some(_) => Err(AsonError::MessageWithLocation(
-                "Expect an \"i64\" value.".to_owned(),
-                self.last_range.get_position_by_range_start(),
-            )),
-            None => Err(AsonError::UnexpectedEndOfDocument(
+            Some(token) => Err(self.new_type_mismatch_error("i64", &token)),
+            None => Err(AsonError::new_unexpected_end_of_document(
                 "Expect an \"i64\" value.".to_owned(),
             )),
         }
@@ -284,11 +409,8 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         match self.next_token()? {
             Some(Token::Number(NumberToken::U8(v))) => visitor.visit_u8(v),
-            Some(_) => Err(AsonError::MessageWithLocation(
-                "Expect an \"u8\" value.".to_owned(),
-                self.last_range.get_position_by_range_start(),
-            )),
-            None => Err(AsonError::UnexpectedEndOfDocument(
+            Some(token) => Err(self.new_type_mismatch_error("u8", &token)),
+            None => Err(AsonError::new_unexpected_end_of_document(
                 "Expect an \"u8\" value.".to_owned(),
             )),
         }
@@ -300,11 +422,8 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         match self.next_token()? {
             Some(Token::Number(NumberToken::U16(v))) => visitor.visit_u16(v),
-            Some(_) => Err(AsonError::MessageWithLocation(
-                "Expect an \"u16\" value.".to_owned(),
-                self.last_range.get_position_by_range_start(),
-            )),
-            None => Err(AsonError::UnexpectedEndOfDocument(
+            Some(token) => Err(self.new_type_mismatch_error("u16", &token)),
+            None => Err(AsonError::new_unexpected_end_of_document(
                 "Expect an \"u16\" value.".to_owned(),
             )),
         }
@@ -316,11 +435,8 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         match self.next_token()? {
             Some(Token::Number(NumberToken::U32(v))) => visitor.visit_u32(v),
-            Some(_) => Err(AsonError::MessageWithLocation(
-                "Expect an \"u32\" value.".to_owned(),
-                self.last_range.get_position_by_range_start(),
-            )),
-            None => Err(AsonError::UnexpectedEndOfDocument(
+            Some(token) => Err(self.new_type_mismatch_error("u32", &token)),
+            None => Err(AsonError::new_unexpected_end_of_document(
                 "Expect an \"u32\" value.".to_owned(),
             )),
         }
@@ -332,11 +448,8 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         match self.next_token()? {
             Some(Token::Number(NumberToken::U64(v))) => visitor.visit_u64(v),
-            Some(_) => Err(AsonError::MessageWithLocation(
-                "Expect an \"u64\" value.".to_owned(),
-                self.last_range.get_position_by_range_start(),
-            )),
-            None => Err(AsonError::UnexpectedEndOfDocument(
+            Some(token) => Err(self.new_type_mismatch_error("u64", &token)),
+            None => Err(AsonError::new_unexpected_end_of_document(
                 "Expect an \"u64\" value.".to_owned(),
             )),
         }
@@ -348,11 +461,8 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         match self.next_token()? {
             Some(Token::Number(NumberToken::F32(v))) => visitor.visit_f32(v),
-            Some(_) => Err(AsonError::MessageWithLocation(
-                "Expect a \"f32\" value.".to_owned(),
-                self.last_range.get_position_by_range_start(),
-            )),
-            None => Err(AsonError::UnexpectedEndOfDocument(
+            Some(token) => Err(self.new_type_mismatch_error("f32", &token)),
+            None => Err(AsonError::new_unexpected_end_of_document(
                 "Expect a \"f32\" value.".to_owned(),
             )),
         }
@@ -364,11 +474,8 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         match self.next_token()? {
             Some(Token::Number(NumberToken::F64(v))) => visitor.visit_f64(v),
-            Some(_) => Err(AsonError::MessageWithLocation(
-                "Expect a \"f64\" value.".to_owned(),
-                self.last_range.get_position_by_range_start(),
-            )),
-            None => Err(AsonError::UnexpectedEndOfDocument(
+            Some(token) => Err(self.new_type_mismatch_error("f64", &token)),
+            None => Err(AsonError::new_unexpected_end_of_document(
                 "Expect a \"f64\" value.".to_owned(),
             )),
         }
@@ -380,11 +487,8 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         match self.next_token()? {
             Some(Token::Char(c)) => visitor.visit_char(c),
-            Some(_) => Err(AsonError::MessageWithLocation(
-                "Expect a \"Char\" value.".to_owned(),
-                self.last_range.get_position_by_range_start(),
-            )),
-            None => Err(AsonError::UnexpectedEndOfDocument(
+            Some(token) => Err(self.new_type_mismatch_error("Char", &token)),
+            None => Err(AsonError::new_unexpected_end_of_document(
                 "Expect a \"Char\" value.".to_owned(),
             )),
         }
@@ -396,11 +500,8 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         match self.next_token()? {
             Some(Token::String(s)) => visitor.visit_str(&s),
-            Some(_) => Err(AsonError::MessageWithLocation(
-                "Expect a \"String\" value.".to_owned(),
-                self.last_range.get_position_by_range_start(),
-            )),
-            None => Err(AsonError::UnexpectedEndOfDocument(
+            Some(token) => Err(self.new_type_mismatch_error("String", &token)),
+            None => Err(AsonError::new_unexpected_end_of_document(
                 "Expect a \"String\" value.".to_owned(),
             )),
         }
@@ -412,11 +513,8 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         match self.next_token()? {
             Some(Token::String(s)) => visitor.visit_string(s),
-            Some(_) => Err(AsonError::MessageWithLocation(
-                "Expect a \"String\" value.".to_owned(),
-                self.last_range.get_position_by_range_start(),
-            )),
-            None => Err(AsonError::UnexpectedEndOfDocument(
+            Some(token) => Err(self.new_type_mismatch_error("String", &token)),
+            None => Err(AsonError::new_unexpected_end_of_document(
                 "Expect a \"String\" value.".to_owned(),
             )),
         }
@@ -428,11 +526,8 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         match self.next_token()? {
             Some(Token::HexByteData(d)) => visitor.visit_bytes(&d),
-            Some(_) => Err(AsonError::MessageWithLocation(
-                "Expect a \"Bytes\" value.".to_owned(),
-                self.last_range.get_position_by_range_start(),
-            )),
-            None => Err(AsonError::UnexpectedEndOfDocument(
+            Some(token) => Err(self.new_type_mismatch_error("Bytes", &token)),
+            None => Err(AsonError::new_unexpected_end_of_document(
                 "Expect a \"Bytes\" value.".to_owned(),
             )),
         }
@@ -444,11 +539,8 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         match self.next_token()? {
             Some(Token::HexByteData(d)) => visitor.visit_byte_buf(d),
-            Some(_) => Err(AsonError::MessageWithLocation(
-                "Expect a \"Bytes\" value.".to_owned(),
-                self.last_range.get_position_by_range_start(),
-            )),
-            None => Err(AsonError::UnexpectedEndOfDocument(
+            Some(token) => Err(self.new_type_mismatch_error("Bytes", &token)),
+            None => Err(AsonError::new_unexpected_end_of_document(
                 "Expect a \"Bytes\" value.".to_owned(),
             )),
         }
@@ -465,27 +557,25 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
                         visitor.visit_none()
                     } else if member_name == "Some" && self.expect_token(0, &Token::LeftParen)? {
                         self.next_token()?; // consume '('
-                        let v = visitor.visit_some(&mut *self);
+                        self.path.push_index(0);
+                        let v = visitor.visit_some(&mut *self)?;
+                        self.path.segments.pop();
                         self.consume_right_paren()?;
-                        v
+                        Ok(v)
                     } else {
-                        Err(AsonError::MessageWithLocation(
+                        Err(AsonError::new(
+                            ErrorKind::TypeMismatch,
                             "Invalid member of variant \"Option\".".to_owned(),
-                            *self.peek_range(0)?.unwrap(),
-                        ))
+                        )
+                        .with_location(self.last_range))
                     }
                 } else {
-                    Err(AsonError::MessageWithLocation(
-                        "Expect the \"Option\" type of variant.".to_owned(),
-                        self.last_range,
-                    ))
+                    Err(AsonError::new_type_mismatch("Option", &type_name)
+                        .with_location(self.last_range))
                 }
             }
-            Some(_) => Err(AsonError::MessageWithLocation(
-                "Expect the \"Option\" type of variant.".to_owned(),
-                self.last_range.get_position_by_range_start(),
-            )),
-            None => Err(AsonError::UnexpectedEndOfDocument(
+            Some(token) => Err(self.new_type_mismatch_error("Option", &token)),
+            None => Err(AsonError::new_unexpected_end_of_document(
                 "Expect the \"Option\" type of variant.".to_owned(),
             )),
         }
@@ -497,7 +587,10 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         // The type of `()` in Rust.
         // It represents an anonymous value containing no data.
-        Err(AsonError::Message("Does not support Unit.".to_owned()))
+        Err(AsonError::new(
+            ErrorKind::Unsupported,
+            "Does not support Unit.".to_owned(),
+        ))
     }
 
     fn deserialize_unit_struct<V>(self, _name: &'static str, _visitor: V) -> Result<V::Value>
@@ -506,17 +599,23 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         // For example `struct Unit` or `PhantomData<T>`.
         // It represents a named value containing no data.
-        Err(AsonError::Message(
+        Err(AsonError::new(
+            ErrorKind::Unsupported,
             "Does not support \"Unit\" style Struct.".to_owned(),
         ))
     }
 
-    fn deserialize_newtype_struct<V>(self, _name: &'static str, _visitor: V) -> Result<V::Value>
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
     where
         V: de::Visitor<'de>,
     {
+        if name == SPANNED_STRUCT_NAME {
+            return self.deserialize_spanned(visitor);
+        }
+
         // For example `struct Millimeters(u8)`.
-        Err(AsonError::Message(
+        Err(AsonError::new(
+            ErrorKind::Unsupported,
             "Does not support \"New-Type\" style Struct.".to_owned(),
         ))
     }
@@ -529,16 +628,14 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
 
         match self.next_token()? {
             Some(Token::LeftBracket) => {
-                let value = visitor.visit_seq(ArrayAccessor::new(self))?;
+                let value =
+                    self.deserialize_nested(|de| visitor.visit_seq(ArrayAccessor::new(de)))?;
                 self.consume_right_bracket()?; // consume ']'
 
                 Ok(value)
             }
-            Some(_) => Err(AsonError::MessageWithLocation(
-                "Expect a \"List\".".to_owned(),
-                self.last_range.get_position_by_range_start(),
-            )),
-            None => Err(AsonError::UnexpectedEndOfDocument(
+            Some(token) => Err(self.new_type_mismatch_error("List", &token)),
+            None => Err(AsonError::new_unexpected_end_of_document(
                 "Expect a \"List\".".to_owned(),
             )),
         }
@@ -550,7 +647,8 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
     {
         match self.next_token()? {
             Some(Token::LeftParen) => {
-                let value = visitor.visit_seq(TupleAccessor::new(self))?;
+                let value =
+                    self.deserialize_nested(|de| visitor.visit_seq(TupleAccessor::new(de)))?;
 
                 // consume additional newlines or comma
                 // because the deserializer knows the number of members of the
@@ -560,11 +658,8 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
 
                 Ok(value)
             }
-            Some(_) => Err(AsonError::MessageWithLocation(
-                "Expect a \"Tuple\".".to_owned(),
-                self.last_range.get_position_by_range_start(),
-            )),
-            None => Err(AsonError::UnexpectedEndOfDocument(
+            Some(token) => Err(self.new_type_mismatch_error("Tuple", &token)),
+            None => Err(AsonError::new_unexpected_end_of_document(
                 "Expect a \"Tuple\".".to_owned(),
             )),
         }
@@ -580,7 +675,8 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
         V: de::Visitor<'de>,
     {
         // A named tuple, for example `struct Rgb(u8, u8, u8)`.
-        Err(AsonError::Message(
+        Err(AsonError::new(
+            ErrorKind::Unsupported,
             "Does not support \"Tuple\" style Struct.".to_owned(),
         ))
     }
@@ -597,16 +693,14 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
 
         match self.next_token()? {
             Some(Token::LeftBracket) => {
-                let value = visitor.visit_map(MapAccessor::new(self))?;
+                let value =
+                    self.deserialize_nested(|de| visitor.visit_map(MapAccessor::new(de)))?;
                 self.consume_right_bracket()?; // consume ']'
 
                 Ok(value)
             }
-            Some(_) => Err(AsonError::MessageWithLocation(
-                "Expect a \"Map\".".to_owned(),
-                self.last_range.get_position_by_range_start(),
-            )),
-            None => Err(AsonError::UnexpectedEndOfDocument(
+            Some(token) => Err(self.new_type_mismatch_error("Map", &token)),
+            None => Err(AsonError::new_unexpected_end_of_document(
                 "Expect a \"Map\".".to_owned(),
             )),
         }
@@ -627,16 +721,14 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
 
         match self.next_token()? {
             Some(Token::LeftBrace) => {
-                let value = visitor.visit_map(ObjectAccessor::new(self))?;
+                let value =
+                    self.deserialize_nested(|de| visitor.visit_map(ObjectAccessor::new(de)))?;
                 self.consume_right_brace()?; // consume '}'
 
                 Ok(value)
             }
-            Some(_) => Err(AsonError::MessageWithLocation(
-                "Expect an \"Object\".".to_owned(),
-                self.last_range.get_position_by_range_start(),
-            )),
-            None => Err(AsonError::UnexpectedEndOfDocument(
+            Some(token) => Err(self.new_type_mismatch_error("Object", &token)),
+            None => Err(AsonError::new_unexpected_end_of_document(
                 "Expect an \"Object\".".to_owned(),
             )),
         }
@@ -657,28 +749,27 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
                 if type_name == name {
                     if self.expect_token(0, &Token::LeftParen)? {
                         // variant with single value or multiple values
-                        let v = visitor.visit_enum(VariantAccessor::new(self, &member_name))?;
+                        let v = self.deserialize_nested(|de| {
+                            visitor.visit_enum(VariantAccessor::new(de, &member_name))
+                        })?;
                         Ok(v)
                     } else if self.expect_token(0, &Token::LeftBrace)? {
                         // variant with struct value
-                        let v = visitor.visit_enum(VariantAccessor::new(self, &member_name))?;
+                        let v = self.deserialize_nested(|de| {
+                            visitor.visit_enum(VariantAccessor::new(de, &member_name))
+                        })?;
                         Ok(v)
                     } else {
                         // variant without value
                         visitor.visit_enum(member_name.into_deserializer())
                     }
                 } else {
-                    Err(AsonError::MessageWithLocation(
-                        format!("Expect the type \"{}\" of variant.", name,),
-                        self.last_range,
-                    ))
+                    Err(AsonError::new_type_mismatch(name, &type_name)
+                        .with_location(self.last_range))
                 }
             }
-            Some(_) => Err(AsonError::MessageWithLocation(
-                "Expect a \"Variant\".".to_owned(),
-                self.last_range.get_position_by_range_start(),
-            )),
-            None => Err(AsonError::UnexpectedEndOfDocument(
+            Some(token) => Err(self.new_type_mismatch_error("Variant", &token)),
+            None => Err(AsonError::new_unexpected_end_of_document(
                 "Expect a \"Variant\".".to_owned(),
             )),
         }
@@ -691,11 +782,11 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
         // An identifier in Serde is the type that identifies a field of a struct.
         match self.next_token()? {
             Some(Token::Identifier(id)) => visitor.visit_string(id),
-            Some(_) => Err(AsonError::MessageWithLocation(
+            Some(_) => Err(AsonError::new_syntax(
                 "Expect an identifier for object.".to_owned(),
                 self.last_range.get_position_by_range_start(),
             )),
-            None => Err(AsonError::UnexpectedEndOfDocument(
+            None => Err(AsonError::new_unexpected_end_of_document(
                 "Expect an identifier for object.".to_owned(),
             )),
         }
@@ -712,6 +803,7 @@ impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
 struct ArrayAccessor<'a, 'de: 'a> {
     de: &'a mut Deserializer<'de>,
     is_first_element: bool,
+    index: usize,
 }
 
 impl<'a, 'de> ArrayAccessor<'a, 'de> {
@@ -719,6 +811,7 @@ impl<'a, 'de> ArrayAccessor<'a, 'de> {
         Self {
             de,
             is_first_element: true,
+            index: 0,
         }
     }
 }
@@ -742,13 +835,13 @@ impl<'de> SeqAccess<'de> for ArrayAccessor<'_, 'de> {
         }
 
         if self.de.peek_token(0)?.is_none() {
-            return Err(AsonError::UnexpectedEndOfDocument(
+            return Err(AsonError::new_unexpected_end_of_document(
                 "Incomplete List.".to_owned(),
             ));
         }
 
         if !self.is_first_element && !exists_separator {
-            return Err(AsonError::MessageWithLocation(
+            return Err(AsonError::new_syntax(
                 "Expect a comma or new-line.".to_owned(),
                 self.de
                     .peek_range(0)?
@@ -759,13 +852,20 @@ impl<'de> SeqAccess<'de> for ArrayAccessor<'_, 'de> {
 
         self.is_first_element = false;
 
-        seed.deserialize(&mut *self.de).map(Some)
+        self.de.check_container_length(self.index)?;
+
+        let segment = PathSegment::Index(self.index);
+        self.index += 1;
+        self.de
+            .deserialize_child(Some(segment), None, seed)
+            .map(Some)
     }
 }
 
 struct TupleAccessor<'a, 'de: 'a> {
     de: &'a mut Deserializer<'de>,
     is_first_element: bool,
+    index: usize,
 }
 
 impl<'a, 'de> TupleAccessor<'a, 'de> {
@@ -773,6 +873,7 @@ impl<'a, 'de> TupleAccessor<'a, 'de> {
         Self {
             de,
             is_first_element: true,
+            index: 0,
         }
     }
 }
@@ -795,13 +896,13 @@ impl<'de> SeqAccess<'de> for TupleAccessor<'_, 'de> {
         // ending marker ')'.
 
         if self.de.peek_token(0)?.is_none() {
-            return Err(AsonError::UnexpectedEndOfDocument(
+            return Err(AsonError::new_unexpected_end_of_document(
                 "Incomplete Tuple.".to_owned(),
             ));
         }
 
         if !self.is_first_element && !exists_separator {
-            return Err(AsonError::MessageWithLocation(
+            return Err(AsonError::new_syntax(
                 "Expect a comma or new-line.".to_owned(),
                 self.de
                     .peek_range(0)?
@@ -812,13 +913,24 @@ impl<'de> SeqAccess<'de> for TupleAccessor<'_, 'de> {
 
         self.is_first_element = false;
 
-        seed.deserialize(&mut *self.de).map(Some)
+        self.de.check_container_length(self.index)?;
+
+        let segment = PathSegment::Index(self.index);
+        self.index += 1;
+        self.de
+            .deserialize_child(Some(segment), None, seed)
+            .map(Some)
     }
 }
 
 struct MapAccessor<'a, 'de: 'a> {
     de: &'a mut Deserializer<'de>,
     is_first_element: bool,
+    length: usize,
+
+    // the path segment and the location of the current key
+    key_segment: Option<PathSegment>,
+    key_location: Option<Location>,
 }
 
 impl<'a, 'de> MapAccessor<'a, 'de> {
@@ -826,6 +938,9 @@ impl<'a, 'de> MapAccessor<'a, 'de> {
         Self {
             de,
             is_first_element: true,
+            length: 0,
+            key_segment: None,
+            key_location: None,
         }
     }
 }
@@ -848,13 +963,13 @@ impl<'de> MapAccess<'de> for MapAccessor<'_, 'de> {
         }
 
         if self.de.peek_token(0)?.is_none() {
-            return Err(AsonError::UnexpectedEndOfDocument(
+            return Err(AsonError::new_unexpected_end_of_document(
                 "Incomplete Map.".to_owned(),
             ));
         }
 
         if !self.is_first_element && !exists_separator {
-            return Err(AsonError::MessageWithLocation(
+            return Err(AsonError::new_syntax(
                 "Expect a comma or new-line.".to_owned(),
                 self.de
                     .peek_range(0)?
@@ -864,6 +979,11 @@ impl<'de> MapAccess<'de> for MapAccessor<'_, 'de> {
         }
 
         self.is_first_element = false;
+        self.de.check_container_length(self.length)?;
+        self.length += 1;
+
+        self.key_segment = self.de.peek_token(0)?.and_then(key_path_segment);
+        self.key_location = self.de.peek_range(0)?.copied();
 
         // Deserialize a field key.
         seed.deserialize(&mut *self.de).map(Some)
@@ -882,13 +1002,19 @@ impl<'de> MapAccess<'de> for MapAccessor<'_, 'de> {
         self.de.consume_new_line_if_exist()?;
 
         // Deserialize a field value.
-        seed.deserialize(&mut *self.de)
+        self.de
+            .deserialize_child(self.key_segment.take(), self.key_location.take(), seed)
     }
 }
 
 struct ObjectAccessor<'a, 'de: 'a> {
     de: &'a mut Deserializer<'de>,
     is_first_element: bool,
+    length: usize,
+
+    // the path segment and the location of the current key
+    key_segment: Option<PathSegment>,
+    key_location: Option<Location>,
 }
 
 impl<'a, 'de> ObjectAccessor<'a, 'de> {
@@ -896,6 +1022,9 @@ impl<'a, 'de> ObjectAccessor<'a, 'de> {
         Self {
             de,
             is_first_element: true,
+            length: 0,
+            key_segment: None,
+            key_location: None,
         }
     }
 }
@@ -920,13 +1049,13 @@ impl<'de> MapAccess<'de> for ObjectAccessor<'_, 'de> {
         }
 
         if self.de.peek_token(0)?.is_none() {
-            return Err(AsonError::UnexpectedEndOfDocument(
+            return Err(AsonError::new_unexpected_end_of_document(
                 "Incomplete Object.".to_owned(),
             ));
         }
 
         if !self.is_first_element && !exists_separator {
-            return Err(AsonError::MessageWithLocation(
+            return Err(AsonError::new_syntax(
                 "Expect a comma or new-line.".to_owned(),
                 self.de
                     .peek_range(0)?
@@ -936,6 +1065,11 @@ impl<'de> MapAccess<'de> for ObjectAccessor<'_, 'de> {
         }
 
         self.is_first_element = false;
+        self.de.check_container_length(self.length)?;
+        self.length += 1;
+
+        self.key_segment = self.de.peek_token(0)?.and_then(key_path_segment);
+        self.key_location = self.de.peek_range(0)?.copied();
 
         // Deserialize a field key.
         seed.deserialize(&mut *self.de).map(Some)
@@ -953,10 +1087,67 @@ impl<'de> MapAccess<'de> for ObjectAccessor<'_, 'de> {
         self.de.consume_new_line_if_exist()?;
 
         // Deserialize a field value.
-        seed.deserialize(&mut *self.de)
+        self.de
+            .deserialize_child(self.key_segment.take(), self.key_location.take(), seed)
     }
 }
 
+struct SpannedAccessor<'a, 'de: 'a> {
+    de: &'a mut Deserializer<'de>,
+    start_range: Location,
+    location: Option<Location>,
+    key_location: Option<Location>,
+
+    // the index of the current field,
+    // i.e. the value, the location and the key location.
+    field_index: usize,
+}
+
+impl<'de> MapAccess<'de> for SpannedAccessor<'_, 'de> {
+    type Error = AsonError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        let field = match self.field_index {
+            0 => SPANNED_VALUE_FIELD,
+            1 => SPANNED_LOCATION_FIELD,
+            2 if self.key_location.is_some() => SPANNED_KEY_LOCATION_FIELD,
+            _ => return Ok(None),
+        };
+
+        let deserializer: StrDeserializer<AsonError> = field.into_deserializer();
+        seed.deserialize(deserializer).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        self.field_index += 1;
+
+        match self.field_index {
+            1 => {
+                let value = seed.deserialize(&mut *self.de)?;
+                self.location = Some(Location::from_range_pair(
+                    &self.start_range,
+                    &self.de.last_range,
+                ));
+                Ok(value)
+            }
+            2 => seed.deserialize(location_deserializer(&self.location.unwrap())),
+            _ => seed.deserialize(location_deserializer(&self.key_location.unwrap())),
+        }
+    }
+}
+
+fn location_deserializer(
+    location: &Location,
+) -> SeqDeserializer<std::array::IntoIter<u64, 5>, AsonError> {
+    SeqDeserializer::new(from_location(location).into_iter())
+}
+
 struct VariantAccessor<'a, 'de: 'a> {
     de: &'a mut Deserializer<'de>,
     variant_member_name: &'a str,
@@ -984,7 +1175,8 @@ impl<'de> EnumAccess<'de> for VariantAccessor<'_, 'de> {
     where
         V: de::DeserializeSeed<'de>,
     {
-        let value = seed.deserialize(self.variant_member_name.into_deserializer())?;
+        let deserializer: StrDeserializer<AsonError> = self.variant_member_name.into_deserializer();
+        let value = seed.deserialize(deserializer)?;
         Ok((value, self))
     }
 }
@@ -1009,7 +1201,9 @@ impl<'de> VariantAccess<'de> for VariantAccessor<'_, 'de> {
         self.de.next_token()?; // consume '('
         self.de.consume_new_line_if_exist()?;
 
-        let v = seed.deserialize(&mut *self.de);
+        let v = self
+            .de
+            .deserialize_child(Some(PathSegment::Index(0)), None, seed);
         self.de.consume_new_line_if_exist()?;
 
         self.de.next_token()?; // consume ')'
@@ -1031,11 +1225,78 @@ impl<'de> VariantAccess<'de> for VariantAccessor<'_, 'de> {
     }
 }
 
+// the type name of token, e.g. `u8`, `String` and `Object`.
+fn describe_token(token: &Token) -> String {
+    let name = match token {
+        Token::NewLine => "new-line",
+        Token::Comma => "comma",
+        Token::Colon => "colon",
+        Token::LeftBrace => "Object",
+        Token::LeftBracket => "List",
+        Token::LeftParen => "Tuple",
+        Token::RightBrace => "}",
+        Token::RightBracket => "]",
+        Token::RightParen => ")",
+        Token::Plus => "+",
+        Token::Minus => "-",
+        Token::Identifier(_) => "identifier",
+        Token::Boolean(_) => "Boolean",
+        Token::Variant(type_name, _) => type_name,
+        Token::Number(number) => match number {
+            NumberToken::I8(_) => "i8",
+            NumberToken::U8(_) => "u8",
+            NumberToken::I16(_) => "i16",
+            NumberToken::U16(_) => "u16",
+            NumberToken::I32(_) => "i32",
+            NumberToken::U32(_) => "u32",
+            NumberToken::I64(_) => "i64",
+            NumberToken::U64(_) => "u64",
+            NumberToken::F32(_) => "f32",
+            NumberToken::F64(_) => "f64",
+        },
+        Token::Char(_) => "Char",
+        Token::String(_) => "String",
+        Token::Date(_) => "DateTime",
+        Token::HexByteData(_) => "Bytes",
+        Token::Comment(_) => "comment",
+        Token::Error(_) => "invalid token",
+    };
+
+    name.to_owned()
+}
+
+// the path segment of the key of Object and Map, see the module `path`.
+fn key_path_segment(token: &Token) -> Option<PathSegment> {
+    match token {
+        Token::Identifier(key) | Token::String(key) => Some(PathSegment::Key(key.to_owned())),
+        Token::Char(c) => Some(PathSegment::Key(c.to_string())),
+        Token::Number(number) => {
+            let index = match *number {
+                // the signed numbers are stored as unsigned, see `NumberToken`.
+                NumberToken::I8(v) => u64::try_from(v as i8).ok()?,
+                NumberToken::U8(v) => v as u64,
+                NumberToken::I16(v) => u64::try_from(v as i16).ok()?,
+                NumberToken::U16(v) => v as u64,
+                NumberToken::I32(v) => u64::try_from(v as i32).ok()?,
+                NumberToken::U32(v) => v as u64,
+                NumberToken::I64(v) => u64::try_from(v as i64).ok()?,
+                NumberToken::U64(v) => v,
+                NumberToken::F32(_) | NumberToken::F64(_) => return None,
+            };
+            usize::try_from(index).ok().map(PathSegment::Index)
+        }
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
 
-    use crate::{location::Location, serde::de::from_str, AsonError};
+    use crate::{
+        limits::Limits, location::Location, options::ParseOptions, serde::de::from_str, AsonError,
+        ErrorKind,
+    };
 
     use pretty_assertions::assert_eq;
     use serde::Deserialize;
@@ -1273,22 +1534,27 @@ mod tests {
         // err: missing a separator (comma or new-line)
         assert!(matches!(
             from_str::<Vec<i32>>(r#"[11 13]"#),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 4,
+                    byte_index: 4,
                     line: 0,
                     column: 4,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: missing ']', EOF
         assert!(matches!(
             from_str::<Vec<i32>>(r#"[11,13"#),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
     }
 
@@ -1375,22 +1641,27 @@ mod tests {
         // err: missing a separator (comma or new-line)
         assert!(matches!(
             from_str::<(i32, i32, i32, i32)>(r#"(11 13)"#),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 4,
+                    byte_index: 4,
                     line: 0,
                     column: 4,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: missing ')', EOF
         assert!(matches!(
             from_str::<(i32, i32, i32, i32)>(r#"(11, 13"#),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
     }
 
@@ -1499,22 +1770,27 @@ mod tests {
         // err: missing a separator (comma or new-line)
         assert!(matches!(
             from_str::<Object>(r#"{id: 123 name: "foo"}"#),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 9,
+                    byte_index: 9,
                     line: 0,
                     column: 9,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: missing '}', EOF
         assert!(matches!(
             from_str::<Object>(r#"{id: 123"#),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
     }
 
@@ -1696,22 +1972,27 @@ mod tests {
         // err: missing a separator (comma or new-line)
         assert!(matches!(
             from_str::<Color>(r#"Color::RGB(255_u8 127_u8 63_u8)"#),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 18,
+                    byte_index: 18,
                     line: 0,
                     column: 18,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: missing ')', EOF
         assert!(matches!(
             from_str::<Color>(r#"Color::RGB(255_u8"#),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
     }
 
@@ -1781,22 +2062,27 @@ mod tests {
         // err: missing a separator (comma or new-line)
         assert!(matches!(
             from_str::<Shape>(r#"Shape::Rect{width: 200 height: 100}"#),
-            Err(AsonError::MessageWithLocation(
-                _,
-                Location {
-                    // unit: 0,
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    unit: 0,
                     index: 23,
+                    byte_index: 23,
                     line: 0,
                     column: 23,
                     length: 0
-                }
-            ))
+                }),
+                ..
+            })
         ));
 
         // err: missing '}', EOF
         assert!(matches!(
             from_str::<Shape>(r#"Shape::Rect{width: 200"#),
-            Err(AsonError::UnexpectedEndOfDocument(_))
+            Err(AsonError {
+                kind: ErrorKind::UnexpectedEndOfDocument,
+                ..
+            })
         ));
     }
 
@@ -1933,4 +2219,139 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_error_context() {
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct Server {
+            host: String,
+            port: u16,
+        }
+
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct Config {
+            servers: Vec<Server>,
+        }
+
+        // type mismatch
+        let e = from_str::<Config>(
+            r#"{
+    servers: [
+        {host: "a", port: 80_u16}
+        {host: "b", port: 81_u16}
+        {host: "c", port: "8080"}
+    ]
+}"#,
+        )
+        .unwrap_err();
+
+        assert_eq!(e.kind, ErrorKind::TypeMismatch);
+        assert_eq!(e.expected(), Some("u16"));
+        assert_eq!(e.found(), Some("String"));
+        assert_eq!(e.location, Some(Location::new_range(111, 4, 26, 6)));
+        assert_eq!(e.path().unwrap().to_string(), ".servers[2].port");
+        assert_eq!(
+            e.to_string(),
+            r#"Error at line: 5, column: 27
+Expect a "u16" value, found "String". (at ".servers[2].port")"#
+        );
+
+        // custom error raised by the `Deserialize` implementation
+        let e = from_str::<Config>(r#"{servers: [{host: "a"}]}"#).unwrap_err();
+        assert_eq!(e.kind, ErrorKind::Custom);
+        assert_eq!(e.message, "missing field `port`");
+        assert_eq!(e.location, Some(Location::new_range(18, 0, 18, 3)));
+        assert_eq!(e.path().unwrap().to_string(), ".servers[0]");
+
+        // end of document
+        let e = from_str::<Config>("{servers: [\n").unwrap_err();
+        assert_eq!(e.kind, ErrorKind::UnexpectedEndOfDocument);
+        assert_eq!(e.location, Some(Location::new_position(12, 1, 0)));
+        assert_eq!(e.path().unwrap().to_string(), ".servers");
+
+        // the key of map
+        let e =
+            from_str::<HashMap<String, Vec<u8>>>(r#"["a": [1_u8], "b": [2_u8, 'c']]"#).unwrap_err();
+        assert_eq!(e.path().unwrap().to_string(), ".b[1]");
+    }
+
+    #[test]
+    fn test_limits() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Node {
+            name: String,
+            children: Vec<Node>,
+        }
+
+        let limits = Limits {
+            max_depth: 4,
+            max_container_length: 2,
+            max_string_length: 3,
+            ..Limits::default()
+        };
+
+        let text = r#"{
+    name: "a"
+    children: [{name: "b", children: []}]
+}"#;
+        assert!(ParseOptions::new()
+            .with_limits(limits)
+            .from_str::<Node>(text)
+            .is_ok());
+
+        // depth
+        let text = r#"{
+    name: "a"
+    children: [{name: "b", children: [{name: "c", children: []}]}]
+}"#;
+        let e = ParseOptions::new()
+            .with_limits(limits)
+            .from_str::<Node>(text)
+            .unwrap_err();
+        assert_eq!(e.kind, ErrorKind::DepthLimitExceeded);
+        assert_eq!(e.location, Some(Location::new_range(54, 2, 38, 1)));
+        assert_eq!(e.path().unwrap().to_string(), ".children[0].children[0]");
+
+        // the number of elements
+        let e = ParseOptions::new()
+            .with_limits(limits)
+            .from_str::<Vec<i32>>("[1, 2, 3]")
+            .unwrap_err();
+        assert_eq!(e.kind, ErrorKind::ContainerLengthLimitExceeded);
+        assert_eq!(e.location, Some(Location::new_position(7, 0, 7)));
+
+        let e = ParseOptions::new()
+            .with_limits(limits)
+            .from_str::<HashMap<String, i32>>(r#"["a": 1, "b": 2, "c": 3]"#)
+            .unwrap_err();
+        assert_eq!(e.kind, ErrorKind::ContainerLengthLimitExceeded);
+
+        let e = ParseOptions::new()
+            .with_limits(limits)
+            .from_str::<Node>(r#"{name: "a", children: [], foo: 1}"#)
+            .unwrap_err();
+        assert_eq!(e.kind, ErrorKind::ContainerLengthLimitExceeded);
+
+        // string
+        let e = ParseOptions::new()
+            .with_limits(limits)
+            .from_str::<Node>(r#"{name: "abcd", children: []}"#)
+            .unwrap_err();
+        assert_eq!(e.kind, ErrorKind::StringLengthLimitExceeded);
+        assert_eq!(e.location, Some(Location::new_position(7, 0, 7)));
+
+        // the recommended depth limit prevents the stack overflow
+        let text = r#"{name: "a", children: ["#.repeat(10_000);
+        let limits = Limits {
+            max_depth: Limits::DEFAULT_MAX_DEPTH,
+            ..Limits::default()
+        };
+        let e = ParseOptions::new()
+            .with_limits(limits)
+            .from_str::<Node>(&text)
+            .unwrap_err();
+        assert_eq!(e.kind, ErrorKind::DepthLimitExceeded);
+    }
 }
@@ -0,0 +1,518 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+use serde::{ser, Serialize};
+
+use super::{shared::SHARED_STRUCT_NAME, Result};
+use crate::{
+    ast::{AsonNode, KeyValuePair, NameValuePair, Number, Variant, VariantValue},
+    AsonError, ErrorKind,
+};
+
+/// Converts a Rust value to AST, the result is printed as the same
+/// text as `to_string`, e.g. it is used for writing the value in
+/// pieces by `AsonWriter`.
+pub(crate) fn to_node<T>(value: &T) -> Result<AsonNode>
+where
+    T: ?Sized + Serialize,
+{
+    value.serialize(NodeSerializer)
+}
+
+struct NodeSerializer;
+
+// the elements of List, Tuple and the tuple variant.
+enum ItemsKind {
+    List,
+    Tuple,
+    Variant {
+        type_name: &'static str,
+        member_name: &'static str,
+    },
+}
+
+struct ItemsSerializer {
+    kind: ItemsKind,
+    items: Vec<AsonNode>,
+}
+
+struct MapSerializer {
+    entries: Vec<NameValuePair>,
+
+    // the name of the entry whose value is expected.
+    name: Option<AsonNode>,
+}
+
+// the members of Object and the struct variant.
+struct ObjectSerializer {
+    // (type name, member name) of the struct variant.
+    variant: Option<(&'static str, &'static str)>,
+    members: Vec<KeyValuePair>,
+}
+
+fn new_variant(type_name: &str, member_name: &str, value: VariantValue) -> AsonNode {
+    AsonNode::Variant(Variant {
+        type_name: type_name.to_owned(),
+        member_name: member_name.to_owned(),
+        value,
+    })
+}
+
+impl ser::Serializer for NodeSerializer {
+    type Ok = AsonNode;
+    type Error = AsonError;
+
+    type SerializeSeq = ItemsSerializer;
+    type SerializeTuple = ItemsSerializer;
+    type SerializeTupleStruct = ItemsSerializer;
+    type SerializeTupleVariant = ItemsSerializer;
+    type SerializeMap = MapSerializer;
+    type SerializeStruct = ObjectSerializer;
+    type SerializeStructVariant = ObjectSerializer;
+
+    fn serialize_bool(self, v: bool) -> Result<AsonNode> {
+        Ok(AsonNode::Boolean(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<AsonNode> {
+        Ok(AsonNode::Number(Number::I8(v)))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<AsonNode> {
+        Ok(AsonNode::Number(Number::I16(v)))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<AsonNode> {
+        Ok(AsonNode::Number(Number::I32(v)))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<AsonNode> {
+        Ok(AsonNode::Number(Number::I64(v)))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<AsonNode> {
+        Ok(AsonNode::Number(Number::U8(v)))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<AsonNode> {
+        Ok(AsonNode::Number(Number::U16(v)))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<AsonNode> {
+        Ok(AsonNode::Number(Number::U32(v)))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<AsonNode> {
+        Ok(AsonNode::Number(Number::U64(v)))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<AsonNode> {
+        Ok(AsonNode::Number(Number::F32(v)))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<AsonNode> {
+        Ok(AsonNode::Number(Number::F64(v)))
+    }
+
+    fn serialize_char(self, v: char) -> Result<AsonNode> {
+        Ok(AsonNode::Char(v))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<AsonNode> {
+        Ok(AsonNode::String(v.to_owned()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<AsonNode> {
+        Ok(AsonNode::HexByteData(v.to_vec()))
+    }
+
+    fn serialize_none(self) -> Result<AsonNode> {
+        Ok(new_variant("Option", "None", VariantValue::Empty))
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<AsonNode>
+    where
+        T: ?Sized + Serialize,
+    {
+        let value = value.serialize(self)?;
+        Ok(new_variant(
+            "Option",
+            "Some",
+            VariantValue::Value(Box::new(value)),
+        ))
+    }
+
+    fn serialize_unit(self) -> Result<AsonNode> {
+        Err(AsonError::new(
+            ErrorKind::Unsupported,
+            "Does not support Unit.".to_owned(),
+        ))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<AsonNode> {
+        Err(AsonError::new(
+            ErrorKind::Unsupported,
+            "Does not support \"Unit\" style Struct.".to_owned(),
+        ))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<AsonNode> {
+        Ok(new_variant(name, variant, VariantValue::Empty))
+    }
+
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<AsonNode>
+    where
+        T: ?Sized + Serialize,
+    {
+        // the shared values are written as they are, since the anchors
+        // are only written by `to_string_with_anchors`.
+        if name == SHARED_STRUCT_NAME {
+            return value.serialize(self);
+        }
+
+        Err(AsonError::new(
+            ErrorKind::Unsupported,
+            "Does not support \"New-Type\" style Struct.".to_owned(),
+        ))
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<AsonNode>
+    where
+        T: ?Sized + Serialize,
+    {
+        let value = value.serialize(self)?;
+        Ok(new_variant(
+            name,
+            variant,
+            VariantValue::Value(Box::new(value)),
+        ))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<ItemsSerializer> {
+        Ok(ItemsSerializer {
+            kind: ItemsKind::List,
+            items: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<ItemsSerializer> {
+        Ok(ItemsSerializer {
+            kind: ItemsKind::Tuple,
+            items: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<ItemsSerializer> {
+        Err(AsonError::new(
+            ErrorKind::Unsupported,
+            "Does not support \"Tuple\" style Struct.".to_owned(),
+        ))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<ItemsSerializer> {
+        Ok(ItemsSerializer {
+            kind: ItemsKind::Variant {
+                type_name: name,
+                member_name: variant,
+            },
+            items: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<MapSerializer> {
+        Ok(MapSerializer {
+            entries: Vec::with_capacity(len.unwrap_or(0)),
+            name: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<ObjectSerializer> {
+        Ok(ObjectSerializer {
+            variant: None,
+            members: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<ObjectSerializer> {
+        Ok(ObjectSerializer {
+            variant: Some((name, variant)),
+            members: Vec::with_capacity(len),
+        })
+    }
+}
+
+impl ItemsSerializer {
+    fn push<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.items.push(value.serialize(NodeSerializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<AsonNode> {
+        let node = match self.kind {
+            ItemsKind::List => AsonNode::List(self.items),
+            ItemsKind::Tuple => AsonNode::Tuple(self.items),
+            ItemsKind::Variant {
+                type_name,
+                member_name,
+            } => new_variant(type_name, member_name, VariantValue::Tuple(self.items)),
+        };
+        Ok(node)
+    }
+}
+
+impl ser::SerializeSeq for ItemsSerializer {
+    type Ok = AsonNode;
+    type Error = AsonError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<AsonNode> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for ItemsSerializer {
+    type Ok = AsonNode;
+    type Error = AsonError;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<AsonNode> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for ItemsSerializer {
+    type Ok = AsonNode;
+    type Error = AsonError;
+
+    fn serialize_field<T>(&mut self, _value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        unreachable!()
+    }
+
+    fn end(self) -> Result<AsonNode> {
+        unreachable!()
+    }
+}
+
+impl ser::SerializeTupleVariant for ItemsSerializer {
+    type Ok = AsonNode;
+    type Error = AsonError;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<AsonNode> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeMap for MapSerializer {
+    type Ok = AsonNode;
+    type Error = AsonError;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.name = Some(key.serialize(NodeSerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        // serde calls `serialize_key` before `serialize_value`.
+        let name = self
+            .name
+            .take()
+            .expect("serialize_value called before serialize_key");
+        self.entries.push(NameValuePair {
+            name: Box::new(name),
+            value: Box::new(value.serialize(NodeSerializer)?),
+        });
+        Ok(())
+    }
+
+    fn end(self) -> Result<AsonNode> {
+        Ok(AsonNode::Map(self.entries))
+    }
+}
+
+impl ObjectSerializer {
+    fn push<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.members
+            .push(KeyValuePair::new(key, value.serialize(NodeSerializer)?));
+        Ok(())
+    }
+
+    fn finish(self) -> Result<AsonNode> {
+        let node = match self.variant {
+            None => AsonNode::Object(self.members),
+            Some((type_name, member_name)) => {
+                new_variant(type_name, member_name, VariantValue::Object(self.members))
+            }
+        };
+        Ok(node)
+    }
+}
+
+impl ser::SerializeStruct for ObjectSerializer {
+    type Ok = AsonNode;
+    type Error = AsonError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.push(key, value)
+    }
+
+    fn end(self) -> Result<AsonNode> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for ObjectSerializer {
+    type Ok = AsonNode;
+    type Error = AsonError;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.push(key, value)
+    }
+
+    fn end(self) -> Result<AsonNode> {
+        self.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use pretty_assertions::assert_eq;
+    use serde::Serialize;
+    use serde_bytes::ByteBuf;
+
+    use crate::{printer::print_to_string, serde::ser::to_string, ErrorKind};
+
+    use super::to_node;
+
+    #[test]
+    fn test_to_node() {
+        #[derive(Serialize)]
+        enum Shape {
+            Empty,
+            Circle(f64),
+            Rect(i32, i32),
+            Line { from: (i8, u16), to: (i64, u64) },
+        }
+
+        #[derive(Serialize)]
+        struct Item {
+            id: u32,
+            name: String,
+            initial: char,
+            enabled: bool,
+            scale: f32,
+            data: ByteBuf,
+            note: Option<String>,
+            parent: Option<i32>,
+            shapes: Vec<Shape>,
+            attributes: BTreeMap<String, i32>,
+            empty: Vec<i32>,
+        }
+
+        let item = Item {
+            id: 11,
+            name: "foo\nbar".to_owned(),
+            initial: '\'',
+            enabled: true,
+            scale: 1.5,
+            data: ByteBuf::from(vec![1, 2, 3]),
+            note: None,
+            parent: Some(13),
+            shapes: vec![
+                Shape::Empty,
+                Shape::Circle(1.0),
+                Shape::Rect(2, 3),
+                Shape::Line {
+                    from: (1, 2),
+                    to: (3, 4),
+                },
+            ],
+            attributes: [("a".to_owned(), 1), ("b".to_owned(), 2)]
+                .into_iter()
+                .collect(),
+            empty: vec![],
+        };
+
+        // the AST is printed as the same text as the serializer
+        assert_eq!(
+            print_to_string(&to_node(&item).unwrap()),
+            to_string(&item).unwrap()
+        );
+
+        #[derive(Serialize)]
+        struct Millimeters(u8);
+
+        assert_eq!(
+            to_node(&Millimeters(1)).unwrap_err().kind,
+            ErrorKind::Unsupported
+        );
+        assert_eq!(to_node(&()).unwrap_err().kind, ErrorKind::Unsupported);
+    }
+}
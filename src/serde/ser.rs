@@ -4,10 +4,13 @@
 // the Mozilla Public License version 2.0 and additional exceptions,
 // more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
 
-use std::io::Write;
+use std::{collections::HashMap, io::Write};
 
-use super::Result;
-use crate::AsonError;
+use super::{shared::SHARED_STRUCT_NAME, Result};
+use crate::{
+    anchor::{ANCHOR_ALIAS_MEMBER_NAME, ANCHOR_DEFINE_MEMBER_NAME, ANCHOR_TYPE_NAME},
+    AsonError, ErrorKind,
+};
 
 use serde::{ser, Serialize};
 
@@ -32,6 +35,59 @@ where
     // Ok(serializer.buffer.join(""))
 }
 
+/// Serializes the value, and writes the values of `Shared<Rc<T>>` and
+/// `Shared<Arc<T>>` with multiple owners only once, the first occurrence
+/// is written as `Anchor::Define("shared_1", ...)` and the others as
+/// `Anchor::Alias("shared_1")`, see `ParseOptions::with_anchors`.
+///
+/// The sharing is only detected through the `Shared<P>` wrapper. Plain
+/// `Rc<T>` and `Arc<T>` fields are serialized as `T` by serde, so their
+/// values are written in full for each owner.
+pub fn to_string_with_anchors<T>(value: &T) -> Result<String>
+where
+    T: Serialize,
+{
+    let mut buf: Vec<u8> = vec![];
+    to_writer_with_anchors(value, &mut buf)?;
+    let s = String::from_utf8(buf).unwrap();
+    Ok(s)
+}
+
+pub fn to_writer_with_anchors<T, W: Write>(value: &T, writer: &mut W) -> Result<()>
+where
+    T: Serialize,
+{
+    let mut serializer = Serializer::new(DEFAULT_INDEXT_CHARS, writer);
+    serializer.anchors = Some(HashMap::new());
+    value.serialize(&mut serializer)
+}
+
+// writes the value as a part of a larger document, the lines of
+// the nested values are indented by the specified level.
+pub(crate) fn to_writer_with_indent_level<T, W: Write>(
+    value: &T,
+    writer: &mut W,
+    indent_level: usize,
+) -> Result<()>
+where
+    T: Serialize,
+{
+    let mut serializer = Serializer::new(DEFAULT_INDEXT_CHARS, writer);
+    serializer.indent_level = indent_level;
+    value.serialize(&mut serializer)
+}
+
+// writes the value in a single line, the elements of containers are
+// separated by commas, and the new-line chars in strings are escaped.
+pub(crate) fn to_writer_single_line<T, W: Write>(value: &T, writer: &mut W) -> Result<()>
+where
+    T: Serialize,
+{
+    let mut serializer = Serializer::new(DEFAULT_INDEXT_CHARS, writer);
+    serializer.single_line = true;
+    value.serialize(&mut serializer)
+}
+
 pub struct Serializer<'a, W>
 where
     W: Write,
@@ -41,6 +97,12 @@ where
     indent_chars: String,
 
     is_first_element: bool,
+    single_line: bool,
+
+    // the ids of the shared values which have been written, the key is
+    // the address and the type name of value, `None` if the anchors
+    // are disabled.
+    anchors: Option<HashMap<(usize, &'static str), usize>>,
 }
 
 impl<'a, W> Serializer<'a, W>
@@ -53,6 +115,8 @@ where
             indent_level: 0,
             indent_chars: indent_chars.to_owned(),
             is_first_element: false,
+            single_line: false,
+            anchors: None,
         }
     }
 
@@ -60,7 +124,7 @@ where
     fn append(&mut self, s: String) -> Result<()> {
         match write!(self.writer, "{}", s) {
             Ok(_) => Ok(()),
-            Err(e) => Err(AsonError::Message(e.to_string())),
+            Err(e) => Err(e.into()),
         }
     }
 
@@ -77,6 +141,69 @@ where
     fn decrease_level(&mut self) {
         self.indent_level -= 1;
     }
+
+    // append the new-line and the leading whitespaces before an element
+    // of List, Map and Object, or the comma in the single line mode.
+    fn append_element_separator(&mut self) -> Result<()> {
+        if self.single_line {
+            if !self.is_first_element {
+                self.append(", ".to_owned())?;
+            }
+        } else {
+            self.append("\n".to_owned())?;
+            self.append_indent()?;
+        }
+
+        self.is_first_element = false;
+        Ok(())
+    }
+
+    // append the closing bracket of List, Map and Object.
+    fn append_closing(&mut self, bracket: &str) -> Result<()> {
+        self.decrease_level();
+
+        if !self.single_line {
+            self.append("\n".to_owned())?;
+            self.append_indent()?;
+        }
+
+        // the container itself is an element of its parent.
+        self.is_first_element = false;
+        self.append(bracket.to_owned())
+    }
+
+    // writes the value of `Shared<P>`, the first occurrence is written as
+    // an anchor and the others as aliases.
+    fn serialize_shared<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        let Some(anchors) = &mut self.anchors else {
+            return value.serialize(self);
+        };
+
+        let key = (
+            value as *const T as *const () as usize,
+            std::any::type_name::<T>(),
+        );
+
+        if let Some(&id) = anchors.get(&key) {
+            return self.append(format!(
+                "{}::{}(\"shared_{}\")",
+                ANCHOR_TYPE_NAME, ANCHOR_ALIAS_MEMBER_NAME, id
+            ));
+        }
+
+        let id = anchors.len() + 1;
+        anchors.insert(key, id);
+
+        self.append(format!(
+            "{}::{}(\"shared_{}\", ",
+            ANCHOR_TYPE_NAME, ANCHOR_DEFINE_MEMBER_NAME, id
+        ))?;
+        value.serialize(&mut *self)?;
+        self.append(")".to_owned())
+    }
 }
 
 impl<W> ser::Serializer for &mut Serializer<'_, W>
@@ -210,6 +337,8 @@ where
                     // it is at the end of a line, so it is best to escape the tab char.
                     // therefor it should be escaped
                     '\t' => "\\t".to_owned(),
+                    '\r' if self.single_line => "\\r".to_owned(),
+                    '\n' if self.single_line => "\\n".to_owned(),
                     _ => c.to_string(),
                 })
                 .collect::<Vec<String>>()
@@ -248,13 +377,17 @@ where
     fn serialize_unit(self) -> Result<()> {
         // The type of `()` in Rust.
         // It represents an anonymous value containing no data.
-        Err(AsonError::Message("Does not support Unit.".to_owned()))
+        Err(AsonError::new(
+            ErrorKind::Unsupported,
+            "Does not support Unit.".to_owned(),
+        ))
     }
 
     fn serialize_unit_struct(self, _name: &'static str) -> Result<()> {
         // For example `struct Unit` or `PhantomData<T>`.
         // It represents a named value containing no data.
-        Err(AsonError::Message(
+        Err(AsonError::new(
+            ErrorKind::Unsupported,
             "Does not support \"Unit\" style Struct.".to_owned(),
         ))
     }
@@ -269,12 +402,17 @@ where
         self.append(format!("{}::{}", name, variant))
     }
 
-    fn serialize_newtype_struct<T>(self, _name: &'static str, _value: &T) -> Result<()>
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<()>
     where
         T: ?Sized + Serialize,
     {
+        if name == SHARED_STRUCT_NAME {
+            return self.serialize_shared(value);
+        }
+
         // For example `struct Millimeters(u8)`.
-        Err(AsonError::Message(
+        Err(AsonError::new(
+            ErrorKind::Unsupported,
             "Does not support \"New-Type\" style Struct.".to_owned(),
         ))
     }
@@ -320,7 +458,8 @@ where
         _len: usize,
     ) -> Result<Self::SerializeTupleStruct> {
         // A named tuple, for example `struct Rgb(u8, u8, u8)`.
-        Err(AsonError::Message(
+        Err(AsonError::new(
+            ErrorKind::Unsupported,
             "Does not support \"Tuple\" style Struct.".to_owned(),
         ))
     }
@@ -388,18 +527,12 @@ where
     where
         T: ?Sized + Serialize,
     {
-        self.is_first_element = false;
-
-        self.append("\n".to_owned())?;
-        self.append_indent()?;
+        self.append_element_separator()?;
         value.serialize(&mut **self)
     }
 
     fn end(self) -> Result<()> {
-        self.decrease_level();
-        self.append("\n".to_owned())?;
-        self.append_indent()?;
-        self.append("]".to_owned())
+        self.append_closing("]")
     }
 }
 
@@ -424,6 +557,7 @@ where
     }
 
     fn end(self) -> Result<()> {
+        self.is_first_element = false;
         self.append(")".to_owned())
     }
 }
@@ -468,6 +602,7 @@ where
     }
 
     fn end(self) -> Result<()> {
+        self.is_first_element = false;
         self.append(")".to_owned())
     }
 }
@@ -483,10 +618,7 @@ where
     where
         T: ?Sized + Serialize,
     {
-        self.is_first_element = false;
-
-        self.append("\n".to_owned())?;
-        self.append_indent()?;
+        self.append_element_separator()?;
         key.serialize(&mut **self)
     }
 
@@ -499,10 +631,7 @@ where
     }
 
     fn end(self) -> Result<()> {
-        self.decrease_level();
-        self.append("\n".to_owned())?;
-        self.append_indent()?;
-        self.append("]".to_owned())
+        self.append_closing("]")
     }
 }
 
@@ -517,19 +646,13 @@ where
     where
         T: ?Sized + Serialize,
     {
-        self.is_first_element = false;
-
-        self.append("\n".to_owned())?;
-        self.append_indent()?;
+        self.append_element_separator()?;
         self.append(format!("{}: ", key))?;
         value.serialize(&mut **self)
     }
 
     fn end(self) -> Result<()> {
-        self.decrease_level();
-        self.append("\n".to_owned())?;
-        self.append_indent()?;
-        self.append("}".to_owned())
+        self.append_closing("}")
     }
 }
 
@@ -544,19 +667,13 @@ where
     where
         T: ?Sized + Serialize,
     {
-        self.is_first_element = false;
-
-        self.append("\n".to_owned())?;
-        self.append_indent()?;
+        self.append_element_separator()?;
         self.append(format!("{}: ", key))?;
         value.serialize(&mut **self)
     }
 
     fn end(self) -> Result<()> {
-        self.decrease_level();
-        self.append("\n".to_owned())?;
-        self.append_indent()?;
-        self.append("}".to_owned())
+        self.append_closing("}")
     }
 }
 
@@ -568,7 +685,7 @@ mod tests {
     use serde::Serialize;
     use serde_bytes::ByteBuf;
 
-    use crate::serde::ser::to_string;
+    use crate::serde::ser::{to_string, to_writer_single_line};
 
     #[test]
     fn test_primitive_types() {
@@ -979,6 +1096,13 @@ mod tests {
     "bar"
 ])"#
         );
+
+        // empty list
+        assert_eq!(
+            to_string(&(11, Vec::<i32>::new(), 13)).unwrap(),
+            r#"(11, [
+], 13)"#
+        );
     }
 
     #[test]
@@ -1132,4 +1256,39 @@ mod tests {
 ]"#
         );
     }
+
+    #[test]
+    fn test_single_line() {
+        #[derive(Serialize)]
+        struct Object {
+            id: i32,
+            name: String,
+            tags: Vec<String>,
+            pairs: HashMap<i32, (i32, Vec<i32>)>,
+            shape: Shape,
+        }
+
+        #[derive(Serialize)]
+        enum Shape {
+            Rect { width: i32, height: i32 },
+        }
+
+        let v0 = Object {
+            id: 123,
+            name: "foo\r\nbar".to_owned(),
+            tags: vec![],
+            pairs: [(11, (13, vec![]))].into_iter().collect(),
+            shape: Shape::Rect {
+                width: 200,
+                height: 100,
+            },
+        };
+
+        let mut buf: Vec<u8> = vec![];
+        to_writer_single_line(&v0, &mut buf).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            r#"{id: 123, name: "foo\r\nbar", tags: [], pairs: [11: (13, [])], shape: Shape::Rect{width: 200, height: 100}}"#
+        );
+    }
 }
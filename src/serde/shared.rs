@@ -0,0 +1,218 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+//! `Shared<P>` wraps an `Rc<T>` or `Arc<T>`, so that the values shared by
+//! multiple owners are written once by `to_string_with_anchors`, e.g.
+//!
+//! ```text
+//! [
+//!     {name: "web", limits: Anchor::Define("shared_1", {cpu: 1})}
+//!     {name: "worker", limits: Anchor::Alias("shared_1")}
+//! ]
+//! ```
+//!
+//! The other serializers (and `to_string`) write the value as `T`.
+//! The deserialized values are not shared, each alias yields a copy.
+
+use std::{ops::Deref, rc::Rc, sync::Arc};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+// the serializer recognizes the shared value by the name of new-type struct,
+// and the address of the value identifies the owners of the same value.
+pub(crate) const SHARED_STRUCT_NAME: &str = "$__ason_private_Shared";
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Shared<P>(pub P);
+
+impl<P> Shared<P> {
+    pub fn new(pointer: P) -> Self {
+        Self(pointer)
+    }
+
+    pub fn into_inner(self) -> P {
+        self.0
+    }
+}
+
+impl<P> Deref for Shared<P> {
+    type Target = P;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<P> From<P> for Shared<P> {
+    fn from(pointer: P) -> Self {
+        Self(pointer)
+    }
+}
+
+impl<T: Serialize> Serialize for Shared<Rc<T>> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_shared(self.0.as_ref(), Rc::strong_count(&self.0), serializer)
+    }
+}
+
+impl<T: Serialize> Serialize for Shared<Arc<T>> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_shared(self.0.as_ref(), Arc::strong_count(&self.0), serializer)
+    }
+}
+
+// the value which has only one owner is written as it is.
+fn serialize_shared<T, S>(value: &T, owner_count: usize, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize,
+    S: Serializer,
+{
+    if owner_count > 1 {
+        serializer.serialize_newtype_struct(SHARED_STRUCT_NAME, value)
+    } else {
+        value.serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Shared<Rc<T>> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(|value| Self(Rc::new(value)))
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Shared<Arc<T>> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(|value| Self(Arc::new(value)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{rc::Rc, sync::Arc};
+
+    use pretty_assertions::assert_eq;
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        options::ParseOptions,
+        serde::{
+            de::from_str,
+            ser::{to_string, to_string_with_anchors},
+        },
+    };
+
+    use super::Shared;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Limits {
+        cpu: u32,
+        memory: u32,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Service {
+        name: String,
+        limits: Shared<Rc<Limits>>,
+    }
+
+    #[test]
+    fn test_serialize_with_anchors() {
+        let small = Rc::new(Limits {
+            cpu: 1,
+            memory: 512,
+        });
+        let large = Shared(Rc::new(Limits {
+            cpu: 4,
+            memory: 4096,
+        }));
+
+        let services = vec![
+            Service {
+                name: "web".to_owned(),
+                limits: Shared(small.clone()),
+            },
+            Service {
+                name: "worker".to_owned(),
+                limits: large,
+            },
+            Service {
+                name: "cron".to_owned(),
+                limits: Shared(small),
+            },
+        ];
+
+        let text = to_string_with_anchors(&services).unwrap();
+        assert_eq!(
+            text,
+            r#"[
+    {
+        name: "web"
+        limits: Anchor::Define("shared_1", {
+            cpu: 1_u32
+            memory: 512_u32
+        })
+    }
+    {
+        name: "worker"
+        limits: {
+            cpu: 4_u32
+            memory: 4096_u32
+        }
+    }
+    {
+        name: "cron"
+        limits: Anchor::Alias("shared_1")
+    }
+]"#
+        );
+
+        assert_eq!(
+            ParseOptions::new()
+                .with_anchors()
+                .from_str::<Vec<Service>>(&text)
+                .unwrap(),
+            services
+        );
+
+        // the shared values are written as they are without the anchors
+        let text = to_string(&services).unwrap();
+        assert!(!text.contains("Anchor::"));
+        assert_eq!(from_str::<Vec<Service>>(&text).unwrap(), services);
+    }
+
+    #[test]
+    fn test_serialize_arc_with_anchors() {
+        let tags = Arc::new(vec!["a".to_owned(), "b".to_owned()]);
+        let value = (Shared(tags.clone()), Shared(tags));
+
+        let text = to_string_with_anchors(&value).unwrap();
+        assert_eq!(
+            text,
+            r#"(Anchor::Define("shared_1", [
+    "a"
+    "b"
+]), Anchor::Alias("shared_1"))"#
+        );
+
+        type Tags = Shared<Arc<Vec<String>>>;
+        let (first, second): (Tags, Tags) =
+            ParseOptions::new().with_anchors().from_str(&text).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(first.as_ref(), &vec!["a".to_owned(), "b".to_owned()]);
+    }
+}
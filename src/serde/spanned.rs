@@ -0,0 +1,317 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+//! `Spanned<T>` records the location of a value (and the location of its
+//! key if the value is the member of an Object or the entry of a Map)
+//! when it is deserialized from ASON text, so that the application can
+//! report semantic errors (e.g. "the port is already used") at the
+//! right place of the document.
+//!
+//! The locations are only available with the text deserializer, i.e.
+//! `from_str` and `from_reader`. Other deserializers (e.g. the binary
+//! format, or a JSON deserializer) read the plain `T`, and the location
+//! is set to the start of document. `Spanned<T>` is serialized as `T`.
+
+use std::{
+    fmt,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+use serde::{
+    de::{self, MapAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{location::Location, AsonError, ErrorKind};
+
+// the text deserializer recognizes `Spanned<T>` by the name of new-type struct,
+// and yields the fields in the following order. The other deserializers
+// see a new-type struct, which is transparent, and yield the plain `T`.
+pub(crate) const SPANNED_STRUCT_NAME: &str = "$__ason_private_Spanned";
+pub(crate) const SPANNED_VALUE_FIELD: &str = "$__ason_private_value";
+pub(crate) const SPANNED_LOCATION_FIELD: &str = "$__ason_private_location";
+pub(crate) const SPANNED_KEY_LOCATION_FIELD: &str = "$__ason_private_key_location";
+
+const SPANNED_FIELDS: [&str; 3] = [
+    SPANNED_VALUE_FIELD,
+    SPANNED_LOCATION_FIELD,
+    SPANNED_KEY_LOCATION_FIELD,
+];
+
+/// A value with its location in the ASON text.
+///
+/// Note that only the values are compared by `PartialEq`.
+#[derive(Debug, Clone)]
+pub struct Spanned<T> {
+    pub value: T,
+
+    // the range of the value, e.g. `{...}` of an Object.
+    pub location: Location,
+
+    // the range of the key name, or the name of Map entry.
+    pub key_location: Option<Location>,
+}
+
+impl<T> Spanned<T> {
+    pub fn new(value: T, location: Location) -> Self {
+        Self {
+            value,
+            location,
+            key_location: None,
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Creates an error located at the value, which can be printed
+    /// along with the source text by `AsonError::with_source`.
+    pub fn new_error(&self, message: &str) -> AsonError {
+        AsonError::new(ErrorKind::Custom, message.to_owned()).with_location(self.location)
+    }
+}
+
+impl<T> Deref for Spanned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for Spanned<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+impl<T: PartialEq> PartialEq for Spanned<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: Serialize> Serialize for Spanned<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.value.serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Spanned<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct(SPANNED_STRUCT_NAME, SpannedVisitor(PhantomData))
+    }
+}
+
+struct SpannedVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for SpannedVisitor<T> {
+    type Value = Spanned<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a value with location")
+    }
+
+    // the deserializers other than ASON text, the location is unknown.
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = T::deserialize(deserializer)?;
+        Ok(Spanned::new(value, Location::new_position(0, 0, 0)))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut value = None;
+        let mut location = None;
+        let mut key_location = None;
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                SPANNED_VALUE_FIELD => value = Some(map.next_value::<T>()?),
+                SPANNED_LOCATION_FIELD => location = Some(to_location(map.next_value()?)),
+                SPANNED_KEY_LOCATION_FIELD => key_location = Some(to_location(map.next_value()?)),
+                _ => return Err(de::Error::unknown_field(&key, &SPANNED_FIELDS)),
+            }
+        }
+
+        match (value, location) {
+            (Some(value), Some(location)) => Ok(Spanned {
+                value,
+                location,
+                key_location,
+            }),
+            (None, _) => Err(de::Error::missing_field(SPANNED_VALUE_FIELD)),
+            (_, None) => Err(de::Error::missing_field(SPANNED_LOCATION_FIELD)),
+        }
+    }
+}
+
+// the location is transferred as a tuple `(index, byte_index, line, column, length)`.
+fn to_location(
+    (index, byte_index, line, column, length): (usize, usize, usize, usize, usize),
+) -> Location {
+    Location::new_range(index, line, column, length).with_byte_index(byte_index)
+}
+
+pub(crate) fn from_location(location: &Location) -> [u64; 5] {
+    [
+        location.index as u64,
+        location.byte_index as u64,
+        location.line as u64,
+        location.column as u64,
+        location.length as u64,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use pretty_assertions::assert_eq;
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        binary,
+        location::Location,
+        serde::{de::from_str, ser::to_string},
+    };
+
+    use super::Spanned;
+
+    #[test]
+    fn test_deserialize() {
+        #[derive(Deserialize, Debug)]
+        struct Server {
+            host: String,
+            port: Spanned<u16>,
+        }
+
+        #[derive(Deserialize, Debug)]
+        struct Config {
+            servers: Vec<Spanned<Server>>,
+            tags: HashMap<String, Spanned<String>>,
+        }
+
+        let text = r#"{
+    servers: [{
+        host: "a"
+        port: 80_u16
+    }]
+    tags: ["env": "dev"]
+}"#;
+
+        let config = from_str::<Config>(text).unwrap();
+        let server = &config.servers[0];
+
+        assert_eq!(server.host, "a");
+        assert_eq!(server.location, Location::new_range(16, 1, 14, 46));
+        assert_eq!(server.key_location, None);
+
+        assert_eq!(*server.port, 80);
+        assert_eq!(server.port.location, Location::new_range(50, 3, 14, 6));
+        assert_eq!(
+            server.port.key_location,
+            Some(Location::new_range(44, 3, 8, 4))
+        );
+
+        let tag = &config.tags["env"];
+        assert_eq!(tag.value, "dev");
+        assert_eq!(tag.location, Location::new_range(82, 5, 18, 5));
+        assert_eq!(tag.key_location, Some(Location::new_range(75, 5, 11, 5)));
+
+        // the root value
+        let root = from_str::<Spanned<Vec<i32>>>("  [1, 2]  ").unwrap();
+        assert_eq!(root.value, vec![1, 2]);
+        assert_eq!(root.location, Location::new_range(2, 0, 2, 6));
+
+        // option
+        let value = from_str::<Option<Spanned<i32>>>("Option::Some(12)").unwrap();
+        assert_eq!(value.unwrap().location, Location::new_range(13, 0, 13, 2));
+    }
+
+    #[test]
+    fn test_error() {
+        #[derive(Deserialize, Debug)]
+        struct Server {
+            port: Spanned<u16>,
+        }
+
+        let text = "{\n    port: 80_u16\n}";
+        let server = from_str::<Server>(text).unwrap();
+        assert_eq!(
+            server
+                .port
+                .new_error("The port is already used.")
+                .with_source(text),
+            r#"error: The port is already used.
+ --> 2:11
+  |
+2 |     port: 80_u16
+  |           ^^^^^^"#
+        );
+    }
+
+    #[test]
+    fn test_serialize() {
+        #[derive(Serialize)]
+        struct Server {
+            host: String,
+            port: Spanned<u16>,
+        }
+
+        let server = Server {
+            host: "a".to_owned(),
+            port: Spanned::new(80, Location::new_range(0, 0, 0, 0)),
+        };
+
+        assert_eq!(
+            to_string(&server).unwrap(),
+            r#"{
+    host: "a"
+    port: 80_u16
+}"#
+        );
+    }
+
+    #[test]
+    fn test_other_deserializers() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Server {
+            host: Spanned<String>,
+            ports: Vec<Spanned<u16>>,
+        }
+
+        let server = Server {
+            host: Spanned::new("a".to_owned(), Location::new_range(0, 0, 0, 0)),
+            ports: vec![Spanned::new(80, Location::new_range(0, 0, 0, 0))],
+        };
+
+        // the plain value is read, and the location is the start of document
+        let data = binary::to_vec(&server).unwrap();
+        let value = binary::from_slice::<Server>(&data).unwrap();
+        assert_eq!(value, server);
+        assert_eq!(value.ports[0].location, Location::new_position(0, 0, 0));
+        assert_eq!(value.ports[0].key_location, None);
+
+        #[cfg(feature = "json")]
+        {
+            let value = serde_json::from_str::<Server>(r#"{"host": "a", "ports": [80]}"#).unwrap();
+            assert_eq!(value, server);
+        }
+    }
+}
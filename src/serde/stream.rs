@@ -0,0 +1,236 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+//! Deserializes and serializes multi-document streams,
+//! see `crate::stream` for the format.
+
+use std::{
+    io::{Read, Write},
+    marker::PhantomData,
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{options::ParseOptions, stream::RecordReader};
+
+use super::{ser::to_writer_single_line, Result};
+
+/// Deserializes the successive values of a multi-document stream.
+///
+/// The errors of a single value do not stop the iterator, while
+/// the I/O errors and the document size errors do.
+///
+/// Note that the locations of `Spanned<T>` are relative to the value
+/// itself rather than the stream.
+pub struct StreamDeserializer<'a, R, T> {
+    records: RecordReader<'a, R>,
+    options: ParseOptions<'a>,
+    _phantom: PhantomData<T>,
+}
+
+impl<'a, R: Read, T: DeserializeOwned> StreamDeserializer<'a, R, T> {
+    pub fn new(reader: R) -> Self {
+        Self::with_options(reader, ParseOptions::default())
+    }
+
+    /// The options apply to each value of the stream.
+    pub fn with_options(reader: R, options: ParseOptions<'a>) -> Self {
+        Self {
+            records: RecordReader::new(reader, options),
+            options,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<R: Read, T: DeserializeOwned> Iterator for StreamDeserializer<'_, R, T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = match self.records.next()? {
+            Ok(record) => record,
+            Err(e) => return Some(Err(e)),
+        };
+
+        Some(
+            self.options
+                .from_str(&record.text)
+                .map_err(|e| record.relocate_error(e)),
+        )
+    }
+}
+
+/// Writes values in the "ASON Lines" format, i.e. one value per line,
+/// which can be read by `StreamDeserializer` and `StreamParser`.
+pub struct StreamWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> StreamWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Appends a value as a single line.
+    pub fn write<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: Serialize,
+    {
+        // writes the line to a buffer first so that a failed
+        // serialization does not leave a partial record.
+        let mut buf: Vec<u8> = vec![];
+        to_writer_single_line(value, &mut buf)?;
+        buf.push(b'\n');
+        self.writer.write_all(&buf)?;
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use pretty_assertions::assert_eq;
+    use serde::{Deserialize, Serialize};
+
+    use crate::{location::Location, options::ParseOptions, AsonError, ErrorKind, Limits};
+
+    use super::{StreamDeserializer, StreamWriter};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Event {
+        id: i32,
+        message: String,
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn test_stream_deserializer() {
+        let text = r#"{id: 1, message: "foo", tags: []}
+{
+    id: 2
+    message: "bar"
+    tags: ["a", "b"]
+}
+
+{id: 3, message: "baz"}
+{id: 4, message: "qux", tags: []}"#;
+
+        let results = StreamDeserializer::<_, Event>::new(text.as_bytes()).collect::<Vec<_>>();
+        assert_eq!(results.len(), 4);
+
+        assert_eq!(
+            results[0],
+            Ok(Event {
+                id: 1,
+                message: "foo".to_owned(),
+                tags: vec![]
+            })
+        );
+
+        assert_eq!(
+            results[1],
+            Ok(Event {
+                id: 2,
+                message: "bar".to_owned(),
+                tags: vec!["a".to_owned(), "b".to_owned()]
+            })
+        );
+
+        // an invalid value does not stop the stream,
+        // and the location is relative to the stream.
+        assert!(matches!(
+            &results[2],
+            Err(AsonError {
+                kind: ErrorKind::Custom,
+                location: Some(Location { line: 7, .. }),
+                ..
+            })
+        ));
+
+        assert_eq!(
+            results[3],
+            Ok(Event {
+                id: 4,
+                message: "qux".to_owned(),
+                tags: vec![]
+            })
+        );
+    }
+
+    #[test]
+    fn test_stream_writer() {
+        let events = vec![
+            Event {
+                id: 1,
+                message: "foo\nbar".to_owned(),
+                tags: vec!["a".to_owned()],
+            },
+            Event {
+                id: 2,
+                message: "".to_owned(),
+                tags: vec![],
+            },
+        ];
+
+        let mut writer = StreamWriter::new(vec![]);
+        for event in &events {
+            writer.write(event).unwrap();
+        }
+        writer.flush().unwrap();
+
+        let buf = writer.into_inner();
+        let text = String::from_utf8(buf).unwrap();
+        assert_eq!(
+            text,
+            r#"{id: 1, message: "foo\nbar", tags: ["a"]}
+{id: 2, message: "", tags: []}
+"#
+        );
+
+        // read back
+        let values = StreamDeserializer::<_, Event>::new(text.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(values, events);
+    }
+
+    #[test]
+    fn test_stream_deserializer_with_options() {
+        let variables = HashMap::from([("MESSAGE".to_owned(), "foo".to_owned())]);
+        let options = ParseOptions::new()
+            .with_variables(&variables)
+            .with_limits(Limits {
+                max_container_length: 3,
+                ..Limits::default()
+            });
+
+        let text = "{id: 1, message: \"${MESSAGE}\", tags: [\"a\"]}\n{id: 2, message: \"\", tags: [\"a\", \"b\", \"c\", \"d\"]}";
+        let results = StreamDeserializer::<_, Event>::with_options(text.as_bytes(), options)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            results[0],
+            Ok(Event {
+                id: 1,
+                message: "foo".to_owned(),
+                tags: vec!["a".to_owned()]
+            })
+        );
+        assert_eq!(
+            results[1].as_ref().unwrap_err().kind,
+            ErrorKind::ContainerLengthLimitExceeded
+        );
+    }
+}
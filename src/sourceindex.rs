@@ -0,0 +1,337 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+use std::{borrow::Cow, ops::Range};
+
+use crate::location::Location;
+
+/// Converts the positions of a source text between byte index, char index,
+/// UTF-16 code unit index and line/column.
+///
+/// The lines and columns are 0-based. The column is counted in chars, and
+/// the UTF-16 column (i.e. the `character` of LSP `Position`) is counted
+/// in UTF-16 code units. The indices beyond the end of text point to the end,
+/// and the columns beyond the end of line point to the end of line.
+pub struct SourceIndex<'a> {
+    source: Cow<'a, str>,
+    line_starts: Vec<LineStart>,
+}
+
+// the indices of the start of line.
+#[derive(Debug, Clone, Copy)]
+struct LineStart {
+    byte_index: usize,
+    char_index: usize,
+    utf16_index: usize,
+}
+
+impl<'a> SourceIndex<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Self::from_cow(Cow::Borrowed(source))
+    }
+
+    /// Builds the index which owns the source text, e.g. the text of
+    /// a document opened in the editor.
+    pub fn from_string(source: String) -> SourceIndex<'static> {
+        SourceIndex::from_cow(Cow::Owned(source))
+    }
+
+    fn from_cow(source: Cow<'a, str>) -> Self {
+        let mut line_starts = vec![LineStart {
+            byte_index: 0,
+            char_index: 0,
+            utf16_index: 0,
+        }];
+
+        let mut utf16_index = 0;
+        for (char_index, (byte_index, c)) in source.char_indices().enumerate() {
+            utf16_index += c.len_utf16();
+            if c == '\n' {
+                line_starts.push(LineStart {
+                    byte_index: byte_index + 1,
+                    char_index: char_index + 1,
+                    utf16_index,
+                });
+            }
+        }
+
+        Self {
+            source,
+            line_starts,
+        }
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    pub fn byte_to_char(&self, byte_index: usize) -> usize {
+        let byte_index = self.floor_char_boundary(byte_index);
+        let start = self.line_starts[self.line_of_byte(byte_index)];
+        start.char_index + self.source[start.byte_index..byte_index].chars().count()
+    }
+
+    pub fn char_to_byte(&self, char_index: usize) -> usize {
+        let line = self
+            .line_starts
+            .partition_point(|start| start.char_index <= char_index)
+            - 1;
+        let start = self.line_starts[line];
+        self.advance(
+            start.byte_index,
+            self.source.len(),
+            char_index - start.char_index,
+            |_| 1,
+        )
+    }
+
+    pub fn byte_to_utf16(&self, byte_index: usize) -> usize {
+        let byte_index = self.floor_char_boundary(byte_index);
+        let start = self.line_starts[self.line_of_byte(byte_index)];
+        start.utf16_index
+            + self.source[start.byte_index..byte_index]
+                .encode_utf16()
+                .count()
+    }
+
+    /// The index in the middle of a surrogate pair points to the start of pair.
+    pub fn utf16_to_byte(&self, utf16_index: usize) -> usize {
+        let line = self
+            .line_starts
+            .partition_point(|start| start.utf16_index <= utf16_index)
+            - 1;
+        let start = self.line_starts[line];
+        self.advance(
+            start.byte_index,
+            self.source.len(),
+            utf16_index - start.utf16_index,
+            char::len_utf16,
+        )
+    }
+
+    /// Returns the line and the column (in chars) of the byte index.
+    pub fn line_column(&self, byte_index: usize) -> (usize, usize) {
+        let byte_index = self.floor_char_boundary(byte_index);
+        let line = self.line_of_byte(byte_index);
+        let line_start = self.line_starts[line].byte_index;
+        (line, self.source[line_start..byte_index].chars().count())
+    }
+
+    /// Returns the line and the column (in UTF-16 code units) of the byte index,
+    /// i.e. the LSP `Position`.
+    pub fn utf16_line_column(&self, byte_index: usize) -> (usize, usize) {
+        let byte_index = self.floor_char_boundary(byte_index);
+        let line = self.line_of_byte(byte_index);
+        let line_start = self.line_starts[line].byte_index;
+        (
+            line,
+            self.source[line_start..byte_index].encode_utf16().count(),
+        )
+    }
+
+    /// Returns the byte index of the line and the column (in chars).
+    pub fn byte_of_line_column(&self, line: usize, column: usize) -> usize {
+        match self.line_starts.get(line) {
+            Some(start) => self.advance(start.byte_index, self.line_end(line), column, |_| 1),
+            None => self.source.len(),
+        }
+    }
+
+    /// Returns the byte index of the line and the column (in UTF-16 code units).
+    pub fn byte_of_utf16_line_column(&self, line: usize, column: usize) -> usize {
+        match self.line_starts.get(line) {
+            Some(start) => self.advance(
+                start.byte_index,
+                self.line_end(line),
+                column,
+                char::len_utf16,
+            ),
+            None => self.source.len(),
+        }
+    }
+
+    /// Builds the `Location` of the byte range, it is a position
+    /// if the range is empty.
+    pub fn location_of(&self, byte_range: Range<usize>) -> Location {
+        let start = self.floor_char_boundary(byte_range.start);
+        let end = self.floor_char_boundary(byte_range.end).max(start);
+        let index = self.byte_to_char(start);
+        let (line, column) = self.line_column(start);
+        Location::new_range(index, line, column, self.byte_to_char(end) - index)
+            .with_byte_index(start)
+    }
+
+    /// Returns the byte range of the location, which can be used to
+    /// slice the source text.
+    pub fn byte_range(&self, location: &Location) -> Range<usize> {
+        let start = self.floor_char_boundary(location.byte_index);
+        let end = self.advance(start, self.source.len(), location.length, |_| 1);
+        start..end
+    }
+
+    /// Returns the text of the location.
+    pub fn slice(&self, location: &Location) -> &str {
+        &self.source[self.byte_range(location)]
+    }
+
+    fn floor_char_boundary(&self, byte_index: usize) -> usize {
+        let mut byte_index = byte_index.min(self.source.len());
+        while !self.source.is_char_boundary(byte_index) {
+            byte_index -= 1;
+        }
+        byte_index
+    }
+
+    fn line_of_byte(&self, byte_index: usize) -> usize {
+        self.line_starts
+            .partition_point(|start| start.byte_index <= byte_index)
+            - 1
+    }
+
+    // the byte index of the end of line, excludes the `\r\n` and `\n`.
+    fn line_end(&self, line: usize) -> usize {
+        match self.line_starts.get(line + 1) {
+            Some(next_start) => {
+                let end = next_start.byte_index - 1;
+                if end > self.line_starts[line].byte_index
+                    && self.source.as_bytes()[end - 1] == b'\r'
+                {
+                    end - 1
+                } else {
+                    end
+                }
+            }
+            None => self.source.len(),
+        }
+    }
+
+    // moves forward from the byte index by the specified amount of units,
+    // stops before the char which can not be passed entirely.
+    fn advance(
+        &self,
+        byte_index: usize,
+        end: usize,
+        amount: usize,
+        unit_length: fn(char) -> usize,
+    ) -> usize {
+        let mut units = 0;
+        for (offset, c) in self.source[byte_index..end].char_indices() {
+            units += unit_length(c);
+            if units > amount {
+                return byte_index + offset;
+            }
+        }
+        end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{location::Location, parse_from_str, tokenizer::Tokenizer};
+
+    use super::SourceIndex;
+
+    // the chars and their (bytes, UTF-16 code units):
+    // 'a' (1, 1), '文' (3, 1), '😀' (4, 2)
+    const SOURCE: &str = "a文😀\r\n😀b\n";
+
+    #[test]
+    fn test_convert_indices() {
+        let index = SourceIndex::new(SOURCE);
+        assert_eq!(index.line_count(), 3);
+
+        // byte: 0 a, 1 文, 4 😀, 8 \r, 9 \n, 10 😀, 14 b, 15 \n, 16 end
+        let bytes = [0, 1, 4, 8, 9, 10, 14, 15, 16];
+        let chars = [0, 1, 2, 3, 4, 5, 6, 7, 8];
+        let utf16s = [0, 1, 2, 4, 5, 6, 8, 9, 10];
+
+        for ((byte, char), utf16) in bytes.iter().zip(chars).zip(utf16s) {
+            assert_eq!(index.byte_to_char(*byte), char);
+            assert_eq!(index.char_to_byte(char), *byte);
+            assert_eq!(index.byte_to_utf16(*byte), utf16);
+            assert_eq!(index.utf16_to_byte(utf16), *byte);
+        }
+
+        // in the middle of a char or a surrogate pair
+        assert_eq!(index.byte_to_char(2), 1);
+        assert_eq!(index.byte_to_utf16(6), 2);
+        assert_eq!(index.utf16_to_byte(3), 4);
+
+        // beyond the end of text
+        assert_eq!(index.byte_to_char(100), 8);
+        assert_eq!(index.char_to_byte(100), 16);
+        assert_eq!(index.byte_to_utf16(100), 10);
+        assert_eq!(index.utf16_to_byte(100), 16);
+    }
+
+    #[test]
+    fn test_convert_line_columns() {
+        let index = SourceIndex::new(SOURCE);
+
+        assert_eq!(index.line_column(4), (0, 2));
+        assert_eq!(index.utf16_line_column(4), (0, 2));
+        assert_eq!(index.line_column(8), (0, 3));
+        assert_eq!(index.utf16_line_column(8), (0, 4));
+        assert_eq!(index.line_column(14), (1, 1));
+        assert_eq!(index.utf16_line_column(14), (1, 2));
+        assert_eq!(index.line_column(16), (2, 0));
+
+        assert_eq!(index.byte_of_line_column(0, 2), 4);
+        assert_eq!(index.byte_of_utf16_line_column(0, 4), 8);
+        assert_eq!(index.byte_of_line_column(1, 1), 14);
+        assert_eq!(index.byte_of_utf16_line_column(1, 2), 14);
+
+        // beyond the end of line, the `\r\n` is excluded
+        assert_eq!(index.byte_of_line_column(0, 100), 8);
+        assert_eq!(index.byte_of_utf16_line_column(1, 100), 15);
+
+        // beyond the last line
+        assert_eq!(index.byte_of_line_column(100, 0), 16);
+    }
+
+    #[test]
+    fn test_locations() {
+        let index = SourceIndex::new(SOURCE);
+
+        let location = index.location_of(10..15);
+        assert_eq!(
+            location,
+            Location {
+                unit: 0,
+                index: 5,
+                byte_index: 10,
+                line: 1,
+                column: 0,
+                length: 2
+            }
+        );
+        assert_eq!(index.byte_range(&location), 10..15);
+        assert_eq!(index.slice(&location), "😀b");
+
+        // the byte indices of the locations of tokens
+        let text = "{\n    name: \"文字\" // 名前\n    emoji: '😀'\n}";
+        let index = SourceIndex::new(text);
+        let tokens: Vec<String> = Tokenizer::new(text)
+            .map(|token| index.slice(&token.range).to_owned())
+            .collect();
+        assert_eq!(tokens.concat(), text);
+        assert!(tokens.contains(&"\"文字\"".to_owned()));
+        assert!(tokens.contains(&"'😀'".to_owned()));
+
+        // the byte index of the location of error
+        let text = "[\"文字\", 😀]";
+        let location = parse_from_str(text).unwrap_err().location.unwrap();
+        assert_eq!(location.index, 7);
+        assert_eq!(location.byte_index, 11);
+        assert_eq!(&text[location.byte_index..], "😀]");
+    }
+}
@@ -0,0 +1,335 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+use std::fmt::{self, Display};
+
+use crate::{
+    errorprinter::{LabelGroup, SourceText},
+    include::SourceUnit,
+    location::Location,
+    AsonError, Diagnostic, RenderOptions,
+};
+
+/// A registry of the source files, the id of file is the `unit`
+/// of `Location`, so that the errors raised by the included or merged
+/// documents can be traced back to the file they come from.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SourceMap {
+    units: Vec<SourceUnit>,
+}
+
+/// The position of a `Location` in the source file, the line and
+/// column are 0-based, and the column is counted in characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourcePosition<'a> {
+    pub file_name: &'a str,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Display for SourcePosition<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}",
+            self.file_name,
+            self.line + 1,
+            self.column + 1
+        )
+    }
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a source file and returns its id. The file which has
+    /// the same name is replaced, and its id is reused.
+    pub fn add(&mut self, name: &str, text: &str) -> usize {
+        self.add_unit(SourceUnit {
+            name: name.to_owned(),
+            text: text.to_owned(),
+        })
+    }
+
+    pub fn add_unit(&mut self, unit: SourceUnit) -> usize {
+        match self.find(&unit.name) {
+            Some(id) => {
+                self.units[id] = unit;
+                id
+            }
+            None => {
+                self.units.push(unit);
+                self.units.len() - 1
+            }
+        }
+    }
+
+    pub fn get(&self, id: usize) -> Option<&SourceUnit> {
+        self.units.get(id)
+    }
+
+    /// Returns the id of the file with the specified name.
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.units.iter().position(|unit| unit.name == name)
+    }
+
+    /// Returns the file where the location is.
+    pub fn source_of(&self, location: &Location) -> Option<&SourceUnit> {
+        self.get(location.unit)
+    }
+
+    pub fn units(&self) -> &[SourceUnit] {
+        &self.units
+    }
+
+    pub fn len(&self) -> usize {
+        self.units.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.units.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.units.clear();
+    }
+
+    /// Resolves the location to the file name, line and column.
+    ///
+    /// The line and column are computed from the `index` of location,
+    /// the index beyond the end of file points to the end.
+    pub fn resolve(&self, location: &Location) -> Option<SourcePosition<'_>> {
+        let unit = self.source_of(location)?;
+        let (line, column) = SourceText::new(&unit.text, 0).line_and_column(location.index);
+        Some(SourcePosition {
+            file_name: &unit.name,
+            line,
+            column,
+        })
+    }
+
+    /// Renders the diagnostic with the snippets of the files where
+    /// the labels are, the file of the primary label is shown first.
+    ///
+    /// The labels in unknown files are ignored, and the `file_name`
+    /// of options is replaced by the names of files.
+    pub fn render(&self, diagnostic: &Diagnostic, options: &RenderOptions) -> String {
+        let mut ids: Vec<usize> = vec![];
+        let primary_id = diagnostic
+            .labels
+            .iter()
+            .find(|label| label.is_primary)
+            .map(|label| label.location.unit);
+        for id in primary_id
+            .into_iter()
+            .chain(diagnostic.labels.iter().map(|label| label.location.unit))
+        {
+            if !ids.contains(&id) && id < self.units.len() {
+                ids.push(id);
+            }
+        }
+
+        let groups: Vec<LabelGroup> = ids
+            .iter()
+            .map(|id| LabelGroup {
+                file_name: Some(&self.units[*id].name),
+                text: SourceText::new(&self.units[*id].text, options.tab_width),
+                labels: diagnostic
+                    .labels
+                    .iter()
+                    .filter(|label| label.location.unit == *id)
+                    .collect(),
+            })
+            .collect();
+
+        diagnostic.render_groups(&groups, options)
+    }
+
+    /// Renders the error with the snippet of the file where it is raised,
+    /// the error without location is rendered as the message only.
+    pub fn render_error(&self, error: &AsonError, options: &RenderOptions) -> String {
+        let id = error.location.map_or(0, |location| location.unit);
+        let source = self.get(id).map_or("", |unit| unit.text.as_str());
+        self.render(&error.to_diagnostic(source), options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use serde::Deserialize;
+
+    use crate::{
+        from_str, location::Location, parse_from_str, AsonError, Diagnostic, ErrorKind,
+        RenderOptions,
+    };
+
+    use super::{SourceMap, SourcePosition};
+
+    #[test]
+    fn test_add_and_resolve() {
+        let mut map = SourceMap::new();
+        assert!(map.is_empty());
+        assert_eq!(map.add("main.ason", "{\n    id: 123\n}"), 0);
+        assert_eq!(map.add("db.ason", "{host: \"localhost\"}"), 1);
+
+        // replaces the file with the same name
+        assert_eq!(map.add("main.ason", "{\n    id: 456\n}"), 0);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.find("db.ason"), Some(1));
+        assert_eq!(map.find("foo.ason"), None);
+        assert_eq!(map.get(0).unwrap().text, "{\n    id: 456\n}");
+
+        let position = map
+            .resolve(&Location::new_range(6, 1, 4, 2).with_unit(0))
+            .unwrap();
+        assert_eq!(
+            position,
+            SourcePosition {
+                file_name: "main.ason",
+                line: 1,
+                column: 4
+            }
+        );
+        assert_eq!(position.to_string(), "main.ason:2:5");
+
+        // the line and column are computed from the index
+        assert_eq!(
+            map.resolve(&Location::new_position(7, 0, 0).with_unit(1))
+                .unwrap()
+                .to_string(),
+            "db.ason:1:8"
+        );
+
+        // beyond the end of file
+        assert_eq!(
+            map.resolve(&Location::new_position(100, 0, 0).with_unit(1))
+                .unwrap()
+                .to_string(),
+            "db.ason:1:20"
+        );
+
+        assert_eq!(
+            map.resolve(&Location::new_position(0, 0, 0).with_unit(2)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let mut map = SourceMap::new();
+        map.add("main.ason", "{\n    db: Include::File(\"db.ason\")\n}");
+        map.add("db.ason", "{\n    port: \"5432\"\n}");
+
+        let diagnostic = Diagnostic::new("Expect a \"u16\" value, found \"String\".")
+            .with_primary_label(Location::new_range(12, 1, 10, 6).with_unit(1), "String")
+            .with_secondary_label(
+                Location::new_range(10, 1, 8, 24).with_unit(0),
+                "included here",
+            );
+
+        assert_eq!(
+            map.render(&diagnostic, &RenderOptions::default()),
+            r#"error: Expect a "u16" value, found "String".
+ --> db.ason:2:11
+  |
+2 |     port: "5432"
+  |           ^^^^^^ String
+  |
+ ::: main.ason:2:9
+  |
+2 |     db: Include::File("db.ason")
+  |         ------------------------ included here"#
+        );
+
+        let err = parse_from_str("{\n    port: }")
+            .map_err(|e| {
+                let location = e.location.unwrap().with_unit(1);
+                e.with_location(location)
+            })
+            .unwrap_err();
+        map.add("db.ason", "{\n    port: }");
+        assert_eq!(
+            map.render_error(&err, &RenderOptions::default()),
+            r#"error: Unexpected token.
+ --> db.ason:2:11
+  |
+2 |     port: }
+  |           ^"#
+        );
+
+        let err = AsonError::new(ErrorKind::Custom, "missing field `id`".to_owned());
+        assert_eq!(
+            map.render_error(&err, &RenderOptions::default()),
+            "error: missing field `id`"
+        );
+    }
+
+    #[test]
+    fn test_render_error_of_units() {
+        let mut map = SourceMap::new();
+        map.add("main.ason", "{\n    name: \"app\"\n}}");
+        map.add("db.ason", "{\n    port: \"5432\"\n}");
+        map.add("tags.ason", "[\n    \"a\"\n    \"b\"");
+
+        // the error of each unit is rendered with the snippet of the unit
+        let parse_unit = |id: usize| {
+            let e = parse_from_str(&map.get(id).unwrap().text).unwrap_err();
+            let location = e.location.unwrap().with_unit(id);
+            e.with_location(location)
+        };
+
+        assert_eq!(
+            map.render_error(&parse_unit(0), &RenderOptions::default()),
+            r#"error: Document has more than one node.
+ --> main.ason:3:2
+  |
+3 | }}
+  |  ^"#
+        );
+
+        // the end of document is the end of the unit
+        assert_eq!(
+            map.render_error(&parse_unit(2), &RenderOptions::default()),
+            r#"error: Unexpected to reach the end of document.
+ --> tags.ason:3:8
+  |
+3 |     "b"
+  |        ^ Expect token: right bracket."#
+        );
+
+        // the deserialization error with path
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct Db {
+            port: u16,
+        }
+
+        let e = from_str::<Db>(&map.get(1).unwrap().text).unwrap_err();
+        let location = e.location.unwrap().with_unit(1);
+        assert_eq!(
+            map.render_error(&e.with_location(location), &RenderOptions::default()),
+            r#"error: Expect a "u16" value, found "String".
+ --> db.ason:2:11
+  |
+2 |     port: "5432"
+  |           ^^^^^^
+  |
+  = note: the path of the value is ".port""#
+        );
+
+        // the unknown unit
+        let e = parse_unit(0);
+        let location = e.location.unwrap().with_unit(5);
+        assert_eq!(
+            map.render_error(&e.with_location(location), &RenderOptions::default()),
+            "error: Document has more than one node."
+        );
+    }
+}
@@ -0,0 +1,1126 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+//! Multi-document streams, i.e. a source that contains successive
+//! top-level values, e.g. logs and message streams.
+//!
+//! The values are separated by new-lines or commas, like the elements
+//! of a List without the brackets. A value may span multiple lines as
+//! long as the line breaks are inside its brackets (or inside a string
+//! or comment), e.g.
+//!
+//! ```text
+//! {id: 1, name: "foo"}
+//! {
+//!     id: 2
+//!     name: "bar"
+//! }
+//! 3, 4, 5
+//! ```
+//!
+//! A value is complete as soon as its outermost bracket is closed, so
+//! `{id: 1}` is available without waiting for the following new-line,
+//! while the other values, e.g. `3`, are complete at the separator
+//! or at the end of data.
+//!
+//! "ASON Lines" is the special case that each line holds exactly one value,
+//! which is produced by `StreamWriter`.
+//!
+//! Each value (record) is parsed independently, so an invalid record
+//! does not stop the stream, and the location of error is relative
+//! to the whole stream.
+//!
+//! `StreamParser` pulls the data from a reader, while `PushParser`
+//! is fed with the byte chunks by the caller, e.g. from a non-blocking socket.
+//!
+//! The `ParseOptions` (e.g. the limits, the variables and the anchors)
+//! apply to each value of the stream, e.g. an anchor is only visible
+//! in the value where it is defined.
+
+use std::{
+    collections::VecDeque,
+    io::{self, BufRead, BufReader, Read},
+};
+
+use serde::de::DeserializeOwned;
+
+use crate::{
+    ast::AsonNode, limits::Limits, location::Location, options::ParseOptions, AsonError, ErrorKind,
+};
+
+/// The text of a single value in the stream.
+pub(crate) struct Record {
+    pub text: String,
+
+    // the position of the first char of the text in the stream.
+    pub location: Location,
+}
+
+impl Record {
+    // converts the location of error which is relative to the record
+    // to the location in the stream.
+    pub fn relocate_error(&self, mut error: AsonError) -> AsonError {
+        if let Some(location) = &mut error.location {
+            if location.line == 0 {
+                location.column += self.location.column;
+            }
+            location.index += self.location.index;
+            location.byte_index += self.location.byte_index;
+            location.line += self.location.line;
+        }
+        error
+    }
+}
+
+enum SplitterState {
+    Normal,
+
+    // '/' is found, it may be the start of comment.
+    Slash,
+    LineComment,
+    BlockComment {
+        depth: usize,
+        previous_char: char,
+    },
+
+    // the number of the leading quotes, to tell the empty string `""`
+    // from the auto-trimmed string `"""`.
+    Quotes(usize),
+    String {
+        escaping: bool,
+    },
+    RawString,
+    RawStringWithHash {
+        quote_found: bool,
+    },
+    AutoTrimmedString {
+        // the number of the consecutive quotes at the beginning of line
+        quotes: usize,
+        is_blank_line: bool,
+    },
+    Char {
+        escaping: bool,
+    },
+}
+
+/// Splits the chars of stream into records, it tracks the brackets,
+/// strings and comments so that the new-lines and commas within
+/// the values are not treated as the separators.
+///
+/// A record is also complete when its outermost bracket is closed,
+/// e.g. `{id: 1}`, so the value is available without waiting for
+/// the following separator.
+///
+/// The chars can be fed in any size of chunks.
+pub(crate) struct RecordSplitter {
+    state: SplitterState,
+
+    // the nesting depth of the brackets
+    depth: usize,
+
+    // the outermost bracket is closed by the last char.
+    closed: bool,
+
+    // the record contains chars other than whitespaces and comments.
+    has_content: bool,
+
+    text: String,
+    start_position: Location,
+
+    // the position of the next char
+    position: Location,
+
+    // the last two chars in the normal state, for detecting the raw strings.
+    recent_chars: [char; 2],
+
+    // the position of the first comma separator, a single document
+    // must not contain the separators.
+    #[cfg(feature = "tokio")]
+    first_comma: Option<Location>,
+}
+
+impl RecordSplitter {
+    pub fn new() -> Self {
+        Self {
+            state: SplitterState::Normal,
+            depth: 0,
+            closed: false,
+            has_content: false,
+            text: String::new(),
+            start_position: Location::new_position(0, 0, 0),
+            position: Location::new_position(0, 0, 0),
+            recent_chars: [' ', ' '],
+            #[cfg(feature = "tokio")]
+            first_comma: None,
+        }
+    }
+
+    /// The position of the next char.
+    pub fn position(&self) -> Location {
+        self.position
+    }
+
+    /// Feeds the chars of a chunk and appends the complete records to
+    /// the queue.
+    ///
+    /// Returns false if the pending record exceeds the document size limit,
+    /// the error is appended to the queue and the rest chars are dropped,
+    /// because the stream can not be resynchronized.
+    pub fn push_str(
+        &mut self,
+        s: &str,
+        limits: &Limits,
+        records: &mut VecDeque<Result<Record, AsonError>>,
+    ) -> bool {
+        for c in s.chars() {
+            let record = self.push(c);
+
+            // the size of the record is counted in bytes, the record
+            // may have been taken by the last char.
+            let text_length = record
+                .as_ref()
+                .map_or(self.text.len(), |record| record.text.len());
+            if text_length > limits.max_document_size {
+                records.push_back(Err(limits.new_document_size_error(self.start_position)));
+                return false;
+            }
+
+            if let Some(record) = record {
+                records.push_back(Ok(record));
+            }
+        }
+
+        true
+    }
+
+    /// Feeds a char, returns the record if the char is a separator or
+    /// the closing bracket of the record.
+    pub fn push(&mut self, c: char) -> Option<Record> {
+        let position = self.position;
+        self.position = if c == '\n' {
+            Location::new_position(position.index + 1, position.line + 1, 0)
+        } else {
+            Location::new_position(position.index + 1, position.line, position.column + 1)
+        }
+        .with_byte_index(position.byte_index + c.len_utf8());
+
+        if self.is_separator(c) {
+            #[cfg(feature = "tokio")]
+            if c == ',' && self.first_comma.is_none() {
+                self.first_comma = Some(position);
+            }
+
+            let record = self.take();
+
+            // the chars after the separator belong to the next record.
+            self.start_position = self.position;
+            return record;
+        }
+
+        if self.text.is_empty() {
+            self.start_position = position;
+        }
+
+        self.text.push(c);
+
+        if std::mem::take(&mut self.closed) {
+            let record = self.take();
+            self.start_position = self.position;
+            return record;
+        }
+
+        None
+    }
+
+    /// Returns the last record when the stream ends.
+    pub fn finish(&mut self) -> Option<Record> {
+        self.state = SplitterState::Normal;
+        self.depth = 0;
+        self.take()
+    }
+
+    // takes the pending text as a record, the text that contains only
+    // whitespaces and comments is dropped.
+    //
+    // the length of text is kept for checking the document size limit,
+    // it is reset when the next record starts.
+    fn take(&mut self) -> Option<Record> {
+        let text = std::mem::take(&mut self.text);
+        self.recent_chars = [' ', ' '];
+
+        if std::mem::take(&mut self.has_content) {
+            Some(Record {
+                text,
+                location: self.start_position,
+            })
+        } else {
+            None
+        }
+    }
+
+    // updates the state, returns true if the char is a separator.
+    fn is_separator(&mut self, c: char) -> bool {
+        match &mut self.state {
+            SplitterState::Normal => {}
+            SplitterState::Slash => match c {
+                '/' => {
+                    self.state = SplitterState::LineComment;
+                    return false;
+                }
+                '*' => {
+                    self.state = SplitterState::BlockComment {
+                        depth: 1,
+                        previous_char: ' ',
+                    };
+                    return false;
+                }
+                _ => {
+                    // not a comment, the slash is an invalid char which
+                    // is reported by the parser.
+                    self.has_content = true;
+                    self.state = SplitterState::Normal;
+                }
+            },
+            SplitterState::LineComment => {
+                if c != '\n' {
+                    return false;
+                }
+                self.state = SplitterState::Normal;
+            }
+            SplitterState::BlockComment {
+                depth,
+                previous_char,
+            } => {
+                match (*previous_char, c) {
+                    ('/', '*') => {
+                        *depth += 1;
+                        *previous_char = ' ';
+                    }
+                    ('*', '/') => {
+                        *depth -= 1;
+                        *previous_char = ' ';
+                    }
+                    _ => *previous_char = c,
+                }
+
+                if *depth == 0 {
+                    self.state = SplitterState::Normal;
+                }
+                return false;
+            }
+            SplitterState::Quotes(count) => match (c, *count) {
+                ('"', 1) => {
+                    *count = 2;
+                    return false;
+                }
+                ('"', _) => {
+                    self.state = SplitterState::AutoTrimmedString {
+                        quotes: 0,
+                        is_blank_line: false,
+                    };
+                    return false;
+                }
+                (_, 1) => {
+                    self.state = SplitterState::String { escaping: false };
+                    return self.is_separator(c);
+                }
+                _ => {
+                    // empty string
+                    self.state = SplitterState::Normal;
+                }
+            },
+            SplitterState::String { escaping } => {
+                if *escaping {
+                    *escaping = false;
+                } else if c == '\\' {
+                    *escaping = true;
+                } else if c == '"' {
+                    self.state = SplitterState::Normal;
+                }
+                return false;
+            }
+            SplitterState::Char { escaping } => {
+                if *escaping {
+                    *escaping = false;
+                } else if c == '\\' {
+                    *escaping = true;
+                } else if c == '\'' {
+                    self.state = SplitterState::Normal;
+                }
+                return false;
+            }
+            SplitterState::RawString => {
+                if c == '"' {
+                    self.state = SplitterState::Normal;
+                }
+                return false;
+            }
+            SplitterState::RawStringWithHash { quote_found } => {
+                if *quote_found && c == '#' {
+                    self.state = SplitterState::Normal;
+                } else {
+                    *quote_found = c == '"';
+                }
+                return false;
+            }
+            SplitterState::AutoTrimmedString {
+                quotes,
+                is_blank_line,
+            } => {
+                match c {
+                    '"' if *is_blank_line => {
+                        *quotes += 1;
+                        if *quotes == 3 {
+                            self.state = SplitterState::Normal;
+                        }
+                    }
+                    '\n' => {
+                        *quotes = 0;
+                        *is_blank_line = true;
+                    }
+                    ' ' | '\t' if *quotes == 0 => {}
+                    _ => {
+                        *quotes = 0;
+                        *is_blank_line = false;
+                    }
+                }
+                return false;
+            }
+        }
+
+        // the normal state
+        let recent_chars = self.recent_chars;
+        self.recent_chars = [recent_chars[1], c];
+
+        match c {
+            ' ' | '\t' | '\r' => false,
+            '\n' | ',' if self.depth == 0 => {
+                // remove the '\r' of "\r\n"
+                if self.text.ends_with('\r') {
+                    self.text.pop();
+                }
+                true
+            }
+            '\n' => false,
+            '/' => {
+                self.state = SplitterState::Slash;
+                false
+            }
+            _ => {
+                self.has_content = true;
+
+                match c {
+                    '(' | '[' | '{' => self.depth += 1,
+                    ')' | ']' | '}' => {
+                        self.closed = self.depth == 1;
+                        self.depth = self.depth.saturating_sub(1);
+                    }
+                    '\'' => self.state = SplitterState::Char { escaping: false },
+                    '"' => {
+                        self.state = match recent_chars {
+                            [_, 'r'] => SplitterState::RawString,
+                            ['r', '#'] => SplitterState::RawStringWithHash { quote_found: false },
+                            _ => SplitterState::Quotes(1),
+                        }
+                    }
+                    _ => {}
+                }
+                false
+            }
+        }
+    }
+}
+
+/// Reads the records from a reader.
+///
+/// The data is fed to a `PushParser` in chunks of the buffer size,
+/// so that a record which exceeds `max_document_size` fails as soon as
+/// the limit is reached, even if there is no separator at all.
+pub(crate) struct RecordReader<'a, R> {
+    reader: BufReader<R>,
+    parser: PushParser<'a>,
+
+    // an I/O error has occurred.
+    is_failed: bool,
+}
+
+impl<'a, R: Read> RecordReader<'a, R> {
+    pub fn new(reader: R, options: ParseOptions<'a>) -> Self {
+        Self {
+            reader: BufReader::new(reader),
+            parser: PushParser::with_options(options),
+            is_failed: false,
+        }
+    }
+}
+
+impl<R: Read> Iterator for RecordReader<'_, R> {
+    type Item = Result<Record, AsonError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_failed {
+            return None;
+        }
+
+        loop {
+            match self.parser.poll_record() {
+                Ok(PushStatus::Value(record)) => return Some(Ok(record)),
+                Ok(PushStatus::End) => return None,
+                Err(e) => return Some(Err(e)),
+                Ok(PushStatus::NeedMoreData) => {}
+            }
+
+            match self.reader.fill_buf() {
+                Ok([]) => self.parser.finish(),
+                Ok(chunk) => {
+                    let length = chunk.len();
+                    self.parser.feed(chunk);
+                    self.reader.consume(length);
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    self.is_failed = true;
+                    return Some(Err(e.into()));
+                }
+            }
+        }
+    }
+}
+
+/// Parses the successive values of a multi-document stream into AST,
+/// see the module documentation for the format.
+///
+/// The errors of a single value do not stop the iterator, while
+/// the I/O errors and the document size errors do.
+pub struct StreamParser<'a, R> {
+    records: RecordReader<'a, R>,
+    options: ParseOptions<'a>,
+}
+
+impl<'a, R: Read> StreamParser<'a, R> {
+    pub fn new(reader: R) -> Self {
+        Self::with_options(reader, ParseOptions::default())
+    }
+
+    /// The options apply to each value of the stream.
+    pub fn with_options(reader: R, options: ParseOptions<'a>) -> Self {
+        Self {
+            records: RecordReader::new(reader, options),
+            options,
+        }
+    }
+}
+
+impl<R: Read> Iterator for StreamParser<'_, R> {
+    type Item = Result<AsonNode, AsonError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let record = match self.records.next()? {
+            Ok(record) => record,
+            Err(e) => return Some(Err(e)),
+        };
+
+        Some(
+            self.options
+                .parse_str(&record.text)
+                .map_err(|e| record.relocate_error(e)),
+        )
+    }
+}
+
+/// Decodes the UTF-8 data that arrives in chunks, a chunk may end
+/// in the middle of a multi-byte character.
+pub(crate) struct Utf8ChunkDecoder {
+    // the incomplete UTF-8 sequence at the end of the last chunk.
+    pending_bytes: Vec<u8>,
+}
+
+impl Utf8ChunkDecoder {
+    pub fn new() -> Self {
+        Self {
+            pending_bytes: vec![],
+        }
+    }
+
+    /// Appends the decoded chars to the output, the chars before
+    /// the invalid data are appended even if an error is returned.
+    pub fn decode(&mut self, chunk: &[u8], output: &mut String) -> Result<(), AsonError> {
+        self.pending_bytes.extend_from_slice(chunk);
+        let bytes = std::mem::take(&mut self.pending_bytes);
+
+        // `error_len` is `None` when the chunk ends in the middle
+        // of a character, the remaining bytes are kept for the next chunk.
+        let (valid_length, is_invalid) = match std::str::from_utf8(&bytes) {
+            Ok(_) => (bytes.len(), false),
+            Err(e) => (e.valid_up_to(), e.error_len().is_some()),
+        };
+
+        let (valid, remains) = bytes.split_at(valid_length);
+        output.push_str(std::str::from_utf8(valid).unwrap());
+
+        if is_invalid {
+            Err(AsonError::new(
+                ErrorKind::InvalidData,
+                "Invalid UTF-8 data.".to_owned(),
+            ))
+        } else {
+            self.pending_bytes = remains.to_vec();
+            Ok(())
+        }
+    }
+
+    /// Checks that the data does not end in the middle of a character.
+    pub fn finish(&self) -> Result<(), AsonError> {
+        if self.pending_bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(AsonError::new(
+                ErrorKind::InvalidData,
+                "Incomplete UTF-8 character at the end of data.".to_owned(),
+            ))
+        }
+    }
+}
+
+/// The result of polling `PushParser`.
+#[derive(Debug, PartialEq)]
+pub enum PushStatus<T> {
+    /// A complete value.
+    Value(T),
+
+    /// No complete value is available until more data is fed.
+    NeedMoreData,
+
+    /// `PushParser::finish` has been called (or the stream has failed)
+    /// and all values have been taken.
+    End,
+}
+
+/// A push-style parser for the multi-document streams, the caller
+/// feeds the byte chunks as they arrive (e.g. from a non-blocking socket)
+/// and polls the complete values, see the module documentation for the format.
+///
+/// A chunk may end anywhere, e.g. in the middle of a multi-byte UTF-8
+/// character, a String or a comment. A value is complete when its outermost
+/// bracket is closed, when its separator (a new-line or a comma) arrives,
+/// or when `finish` is called.
+///
+/// The errors of a single value do not stop the parser, while
+/// the invalid UTF-8 data and the document size errors do.
+pub struct PushParser<'a> {
+    splitter: RecordSplitter,
+    records: VecDeque<Result<Record, AsonError>>,
+    decoder: Utf8ChunkDecoder,
+
+    // no more data is accepted.
+    is_finished: bool,
+    options: ParseOptions<'a>,
+}
+
+impl<'a> PushParser<'a> {
+    pub fn new() -> Self {
+        Self::with_options(ParseOptions::default())
+    }
+
+    /// The options apply to each value of the stream.
+    pub fn with_options(options: ParseOptions<'a>) -> Self {
+        Self {
+            splitter: RecordSplitter::new(),
+            records: VecDeque::new(),
+            decoder: Utf8ChunkDecoder::new(),
+            is_finished: false,
+            options,
+        }
+    }
+
+    pub fn feed(&mut self, chunk: &[u8]) {
+        if self.is_finished {
+            return;
+        }
+
+        let mut s = String::new();
+        let result = self.decoder.decode(chunk, &mut s);
+
+        if !self
+            .splitter
+            .push_str(&s, &self.options.limits, &mut self.records)
+        {
+            self.is_finished = true;
+        } else if let Err(e) = result {
+            self.is_finished = true;
+            self.records
+                .push_back(Err(e.with_location(self.splitter.position())));
+        }
+    }
+
+    /// Signals the end of data, the pending value becomes complete.
+    pub fn finish(&mut self) {
+        if self.is_finished {
+            return;
+        }
+
+        self.is_finished = true;
+
+        if let Err(e) = self.decoder.finish() {
+            self.records
+                .push_back(Err(e.with_location(self.splitter.position())));
+        } else if let Some(record) = self.splitter.finish() {
+            self.records.push_back(Ok(record));
+        }
+    }
+
+    /// Takes the next complete value as AST.
+    pub fn poll_node(&mut self) -> Result<PushStatus<AsonNode>, AsonError> {
+        self.poll_with(|options, text| options.parse_str(text))
+    }
+
+    /// Takes the next complete value and deserializes it.
+    pub fn poll_value<T>(&mut self) -> Result<PushStatus<T>, AsonError>
+    where
+        T: DeserializeOwned,
+    {
+        self.poll_with(|options, text| options.from_str(text))
+    }
+
+    fn poll_with<T>(
+        &mut self,
+        parse: fn(&ParseOptions, &str) -> Result<T, AsonError>,
+    ) -> Result<PushStatus<T>, AsonError> {
+        match self.poll_record()? {
+            PushStatus::Value(record) => parse(&self.options, &record.text)
+                .map(PushStatus::Value)
+                .map_err(|e| record.relocate_error(e)),
+            PushStatus::NeedMoreData => Ok(PushStatus::NeedMoreData),
+            PushStatus::End => Ok(PushStatus::End),
+        }
+    }
+
+    /// Takes the text of the next complete value.
+    pub(crate) fn poll_record(&mut self) -> Result<PushStatus<Record>, AsonError> {
+        match self.records.pop_front() {
+            Some(Ok(record)) => Ok(PushStatus::Value(record)),
+            Some(Err(e)) => Err(e),
+            None if self.is_finished => Ok(PushStatus::End),
+            None => Ok(PushStatus::NeedMoreData),
+        }
+    }
+
+    /// The position of the next char in the stream.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn position(&self) -> Location {
+        self.splitter.position()
+    }
+
+    /// The position of the first comma separator fed so far.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn first_comma(&self) -> Option<Location> {
+        self.splitter.first_comma
+    }
+}
+
+impl Default for PushParser<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        ast::{AsonNode, KeyValuePair, Number},
+        limits::Limits,
+        location::Location,
+        options::ParseOptions,
+        parser::parse_from_str,
+        AsonError, ErrorKind,
+    };
+
+    use super::{PushParser, PushStatus, RecordSplitter, StreamParser};
+
+    fn split(s: &str) -> Vec<(String, usize, usize, usize)> {
+        let mut splitter = RecordSplitter::new();
+        let mut records = vec![];
+        for c in s.chars() {
+            if let Some(record) = splitter.push(c) {
+                records.push(record);
+            }
+        }
+        records.extend(splitter.finish());
+
+        records
+            .into_iter()
+            .map(|r| (r.text, r.location.index, r.location.line, r.location.column))
+            .collect()
+    }
+
+    #[test]
+    fn test_split() {
+        assert_eq!(
+            split("1\n2, 3\n\n  4"),
+            vec![
+                ("1".to_owned(), 0, 0, 0),
+                ("2".to_owned(), 2, 1, 0),
+                (" 3".to_owned(), 4, 1, 2),
+                ("  4".to_owned(), 8, 3, 0),
+            ]
+        );
+
+        // multiple lines value and "\r\n"
+        assert_eq!(
+            split("{\r\n    a: [1,\n2]\r\n}\r\n(3,\n4)"),
+            vec![
+                ("{\r\n    a: [1,\n2]\r\n}".to_owned(), 0, 0, 0),
+                ("(3,\n4)".to_owned(), 21, 4, 0),
+            ]
+        );
+
+        // strings and chars
+        assert_eq!(
+            split(
+                r##""a,\"b
+c", 'x', ',', '\'', "", r"\", r#"",
+"#, h"11 22""##
+            ),
+            vec![
+                ("\"a,\\\"b\nc\"".to_owned(), 0, 0, 0),
+                (" 'x'".to_owned(), 10, 1, 3),
+                (" ','".to_owned(), 15, 1, 8),
+                (" '\\''".to_owned(), 20, 1, 13),
+                (" \"\"".to_owned(), 26, 1, 19),
+                (" r\"\\\"".to_owned(), 30, 1, 23),
+                (" r#\"\",\n\"#".to_owned(), 36, 1, 29),
+                (" h\"11 22\"".to_owned(), 46, 2, 3),
+            ]
+        );
+
+        // auto-trimmed string
+        assert_eq!(
+            split("\"\"\"\n    a, \"\"\"\n    b\n    \"\"\"\n1"),
+            vec![
+                (
+                    "\"\"\"\n    a, \"\"\"\n    b\n    \"\"\"".to_owned(),
+                    0,
+                    0,
+                    0
+                ),
+                ("1".to_owned(), 29, 4, 0),
+            ]
+        );
+
+        // the record ends with its outermost closing bracket
+        assert_eq!(
+            split("{id: 1} [2] // foo\nColor::Rgb(1, 2, 3)3"),
+            vec![
+                ("{id: 1}".to_owned(), 0, 0, 0),
+                (" [2]".to_owned(), 7, 0, 7),
+                ("Color::Rgb(1, 2, 3)".to_owned(), 19, 1, 0),
+                ("3".to_owned(), 38, 1, 19),
+            ]
+        );
+
+        // comments
+        assert_eq!(
+            split("// foo, bar\n/* a\n /* b, */ \n*/ 1 // c\n/* d */\n2"),
+            vec![
+                ("/* a\n /* b, */ \n*/ 1 // c".to_owned(), 12, 1, 0),
+                ("2".to_owned(), 46, 5, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stream_parser() {
+        let text = r#"{id: 1}
+{
+    id: 2
+}
+3, 4
+
+"foo"
+"#;
+
+        let nodes = StreamParser::new(text.as_bytes())
+            .collect::<Result<Vec<AsonNode>, AsonError>>()
+            .unwrap();
+
+        assert_eq!(
+            nodes,
+            vec![
+                AsonNode::Object(vec![KeyValuePair::new(
+                    "id",
+                    AsonNode::Number(Number::I32(1))
+                )]),
+                AsonNode::Object(vec![KeyValuePair::new(
+                    "id",
+                    AsonNode::Number(Number::I32(2))
+                )]),
+                AsonNode::Number(Number::I32(3)),
+                AsonNode::Number(Number::I32(4)),
+                AsonNode::String("foo".to_owned()),
+            ]
+        );
+
+        // an invalid value does not stop the stream,
+        // and the location is relative to the stream.
+        let results = StreamParser::new("1\n{id: 2 3}\n4".as_bytes()).collect::<Vec<_>>();
+        assert_eq!(results.len(), 3);
+        assert!(matches!(
+            &results[1],
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    index: 9,
+                    byte_index: 9,
+                    line: 1,
+                    column: 7,
+                    ..
+                }),
+                ..
+            })
+        ));
+        assert_eq!(results[2], Ok(AsonNode::Number(Number::I32(4))));
+
+        // the size limit applies to each value
+        let limits = Limits {
+            max_document_size: 8,
+            ..Limits::default()
+        };
+        let results = StreamParser::with_options(
+            "[1, 2]\n[1, 2, 3, 4]\n[5]".as_bytes(),
+            ParseOptions::new().with_limits(limits),
+        )
+        .collect::<Vec<_>>();
+        assert_eq!(results.len(), 2);
+        assert!(matches!(
+            &results[1],
+            Err(AsonError {
+                kind: ErrorKind::DocumentSizeLimitExceeded,
+                location: Some(Location { index: 7, .. }),
+                ..
+            })
+        ));
+
+        // a value without separator is not read into memory entirely,
+        // the reader is endless here.
+        let limits = Limits {
+            max_document_size: 100_000,
+            ..Limits::default()
+        };
+        let results = StreamParser::with_options(
+            std::io::repeat(b'1'),
+            ParseOptions::new().with_limits(limits),
+        )
+        .collect::<Vec<_>>();
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results[0].as_ref().unwrap_err().kind,
+            ErrorKind::DocumentSizeLimitExceeded
+        );
+
+        // the variables and anchors apply to each value
+        let variables = HashMap::from([("HOST".to_owned(), "example.com".to_owned())]);
+        let options = ParseOptions::new()
+            .with_variables(&variables)
+            .with_anchors();
+        let results = StreamParser::with_options(
+            "\"${HOST}\"\n[Anchor::Define(\"a\", 1), Anchor::Alias(\"a\")]\nAnchor::Alias(\"a\")"
+                .as_bytes(),
+            options,
+        )
+        .collect::<Vec<_>>();
+        assert_eq!(results[0], Ok(AsonNode::String("example.com".to_owned())));
+        assert_eq!(results[1], parse_from_str("[1, 1]"));
+        assert_eq!(
+            results[2].as_ref().unwrap_err().kind,
+            ErrorKind::InvalidReference
+        );
+    }
+
+    #[test]
+    fn test_push_parser() {
+        let text = "{name: \"文字🍒\" /* 注释, 🍒 */}\n\"\"\"\n    多行\n    \"\"\"\n11, '字'";
+
+        // feed the bytes one by one
+        let mut parser = PushParser::new();
+        let mut nodes = vec![];
+        for b in text.as_bytes() {
+            parser.feed(&[*b]);
+            while let PushStatus::Value(node) = parser.poll_node().unwrap() {
+                nodes.push(node);
+            }
+        }
+
+        assert_eq!(
+            nodes,
+            vec![
+                AsonNode::Object(vec![KeyValuePair::new(
+                    "name",
+                    AsonNode::String("文字🍒".to_owned())
+                )]),
+                AsonNode::String("多行".to_owned()),
+                AsonNode::Number(Number::I32(11)),
+            ]
+        );
+
+        // the last value is complete when the data ends
+        assert_eq!(parser.poll_node(), Ok(PushStatus::NeedMoreData));
+        parser.finish();
+        assert_eq!(
+            parser.poll_node(),
+            Ok(PushStatus::Value(AsonNode::Char('字')))
+        );
+        assert_eq!(parser.poll_node(), Ok(PushStatus::End));
+
+        // deserialize
+        let mut parser = PushParser::new();
+        parser.feed(b"[1, 2]\n[3");
+        assert_eq!(
+            parser.poll_value::<Vec<i32>>(),
+            Ok(PushStatus::Value(vec![1, 2]))
+        );
+        assert_eq!(
+            parser.poll_value::<Vec<i32>>(),
+            Ok(PushStatus::NeedMoreData)
+        );
+        parser.feed(b"]");
+        assert_eq!(
+            parser.poll_value::<Vec<i32>>(),
+            Ok(PushStatus::Value(vec![3]))
+        );
+
+        // the value is complete once its brackets are closed,
+        // without the trailing new-line.
+        let mut parser = PushParser::new();
+        parser.feed(b"{id: 1}");
+        assert_eq!(
+            parser.poll_node(),
+            Ok(PushStatus::Value(AsonNode::Object(vec![
+                KeyValuePair::new("id", AsonNode::Number(Number::I32(1)))
+            ])))
+        );
+        assert_eq!(parser.poll_node(), Ok(PushStatus::NeedMoreData));
+    }
+
+    #[test]
+    fn test_push_parser_errors() {
+        // an invalid value does not stop the parser
+        let mut parser = PushParser::new();
+        parser.feed(b"11\n[12,\n13 14]\n15\n");
+        assert_eq!(
+            parser.poll_node(),
+            Ok(PushStatus::Value(AsonNode::Number(Number::I32(11))))
+        );
+        assert!(matches!(
+            parser.poll_node(),
+            Err(AsonError {
+                kind: ErrorKind::Syntax,
+                location: Some(Location {
+                    index: 11,
+                    byte_index: 11,
+                    line: 2,
+                    column: 3,
+                    ..
+                }),
+                ..
+            })
+        ));
+        assert_eq!(
+            parser.poll_node(),
+            Ok(PushStatus::Value(AsonNode::Number(Number::I32(15))))
+        );
+
+        // invalid UTF-8 data
+        let mut parser = PushParser::new();
+        parser.feed(b"11\n\"a\xff\"\n13\n");
+        assert_eq!(
+            parser.poll_node(),
+            Ok(PushStatus::Value(AsonNode::Number(Number::I32(11))))
+        );
+        assert!(matches!(
+            parser.poll_node(),
+            Err(AsonError {
+                kind: ErrorKind::InvalidData,
+                location: Some(Location { index: 5, .. }),
+                ..
+            })
+        ));
+        assert_eq!(parser.poll_node(), Ok(PushStatus::End));
+
+        // incomplete UTF-8 character
+        let mut parser = PushParser::new();
+        parser.feed(&"'字'".as_bytes()[..3]);
+        parser.finish();
+        assert!(matches!(
+            parser.poll_node(),
+            Err(AsonError {
+                kind: ErrorKind::InvalidData,
+                ..
+            })
+        ));
+
+        // the size limit applies to each value
+        let mut parser = PushParser::with_options(ParseOptions::new().with_limits(Limits {
+            max_document_size: 4,
+            ..Limits::default()
+        }));
+        parser.feed(b"1234\n12345\n");
+        assert_eq!(
+            parser.poll_node(),
+            Ok(PushStatus::Value(AsonNode::Number(Number::I32(1234))))
+        );
+        assert!(matches!(
+            parser.poll_node(),
+            Err(AsonError {
+                kind: ErrorKind::DocumentSizeLimitExceeded,
+                ..
+            })
+        ));
+        assert_eq!(parser.poll_node(), Ok(PushStatus::End));
+
+        // the size is counted in UTF-8 bytes, i.e. 3 bytes for '字'
+        let mut parser = PushParser::with_options(ParseOptions::new().with_limits(Limits {
+            max_document_size: 4,
+            ..Limits::default()
+        }));
+        parser.feed("'字'\n".as_bytes());
+        assert!(matches!(
+            parser.poll_node(),
+            Err(AsonError {
+                kind: ErrorKind::DocumentSizeLimitExceeded,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_push_parser_with_options() {
+        let variables = HashMap::from([("PORT".to_owned(), "8080".to_owned())]);
+        let mut parser = PushParser::with_options(
+            ParseOptions::new()
+                .with_variables(&variables)
+                .with_anchors(),
+        );
+        parser.feed(b"{port: \"${PORT:u16}\"}\n[Anchor::Define(\"a\", 'c'),");
+        parser.feed(b" Anchor::Alias(\"a\")]");
+        parser.finish();
+
+        assert_eq!(
+            parser.poll_node(),
+            Ok(PushStatus::Value(
+                parse_from_str("{port: 8080_u16}").unwrap()
+            ))
+        );
+        assert_eq!(
+            parser.poll_node(),
+            Ok(PushStatus::Value(parse_from_str("['c', 'c']").unwrap()))
+        );
+        assert_eq!(parser.poll_node(), Ok(PushStatus::End));
+    }
+}
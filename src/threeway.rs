@@ -0,0 +1,886 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+//! Three-way merge of ASON documents, e.g. the common ancestor (base)
+//! and the two versions (ours and theirs) of a file in version control.
+//!
+//! A value changed by only one side takes the change. When both sides
+//! change a value, the members of Objects, the entries of Maps, the
+//! elements of Tuples and the payloads of Variants are merged
+//! recursively, and the elements of Lists are aligned with the base
+//! by the longest common subsequence, so that the edits of different
+//! parts of a List are merged. The remaining overlapped changes
+//! are reported as conflicts.
+
+use std::fmt::{self, Display};
+
+use crate::{
+    ast::{AsonNode, KeyValuePair, NameValuePair, Variant, VariantValue},
+    diff::match_list,
+    path::AsonPath,
+    printer::{print_node, print_to_string, DEFAULT_INDENT_CHARS},
+};
+
+// the prefix of the String that takes the place of a conflicted value
+// in the merged document, the private use character avoids
+// clashing with the user strings.
+const PLACEHOLDER_PREFIX: &str = "\u{e000}ason-conflict:";
+
+/// The overlapped changes of both sides.
+///
+/// Each side is a list of values, which is empty if the value
+/// is absent (e.g. removed) and has one value for the members of
+/// Objects and the other single values. For Lists, it is the range
+/// of elements that the sides change differently, and the path
+/// is the index of the first element in the merged List.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Conflict {
+    pub path: AsonPath,
+    pub base: Vec<AsonNode>,
+    pub ours: Vec<AsonNode>,
+    pub theirs: Vec<AsonNode>,
+}
+
+fn format_values(values: &[AsonNode]) -> String {
+    if values.is_empty() {
+        "(absent)".to_owned()
+    } else {
+        values
+            .iter()
+            .map(|value| print_to_string(value).replace('\n', "\n    "))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Renders the conflict in lines, e.g.
+///
+/// ```text
+/// conflict at .server.port
+///   base: 80
+///   ours: 8080
+///   theirs: 9090
+/// ```
+impl Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "conflict at {}", self.path)?;
+        writeln!(f, "  base: {}", format_values(&self.base))?;
+        writeln!(f, "  ours: {}", format_values(&self.ours))?;
+        writeln!(f, "  theirs: {}", format_values(&self.theirs))
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ConflictSide {
+    Ours,
+    Theirs,
+}
+
+/// The result of three-way merge.
+#[derive(Debug, PartialEq, Clone)]
+pub struct MergeResult {
+    // the merged document with placeholders of conflicts
+    node: AsonNode,
+    pub conflicts: Vec<Conflict>,
+}
+
+impl MergeResult {
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+
+    /// Returns the merged document, the conflicts are resolved
+    /// by taking the values of the specified side.
+    pub fn resolve(&self, side: ConflictSide) -> AsonNode {
+        let mut node = self.node.clone();
+        let mut values = resolve_node(&mut node, &self.conflicts, side);
+        values.pop().unwrap_or(node)
+    }
+
+    /// Prints the merged document with conflict markers, e.g.
+    ///
+    /// ```text
+    /// {
+    /// <<<<<<< ours
+    ///     port: 8080
+    /// ||||||| base
+    ///     port: 80
+    /// =======
+    ///     port: 9090
+    /// >>>>>>> theirs
+    /// }
+    /// ```
+    ///
+    /// The labels are printed after the markers.
+    pub fn to_string_with_markers(&self, ours_label: &str, theirs_label: &str) -> String {
+        let text = print_to_string(&self.node);
+        if self.conflicts.is_empty() {
+            return text;
+        }
+
+        let placeholders: Vec<String> = (0..self.conflicts.len())
+            .map(|index| print_to_string(&new_placeholder(index)))
+            .collect();
+
+        let mut lines: Vec<String> = vec![];
+        for line in text.lines() {
+            let found = placeholders
+                .iter()
+                .enumerate()
+                .find_map(|(index, placeholder)| {
+                    line.find(placeholder.as_str()).map(|pos| (index, pos))
+                });
+
+            let Some((index, pos)) = found else {
+                lines.push(line.to_owned());
+                continue;
+            };
+
+            let prefix = &line[..pos];
+            let suffix = &line[pos + placeholders[index].len()..];
+            let indent_level =
+                (prefix.len() - prefix.trim_start().len()) / DEFAULT_INDENT_CHARS.len();
+            let conflict = &self.conflicts[index];
+
+            let print_values = |values: &[AsonNode]| {
+                values
+                    .iter()
+                    .map(|value| {
+                        let mut buf: Vec<u8> = vec![];
+                        print_node(&mut buf, value, DEFAULT_INDENT_CHARS, indent_level).unwrap();
+                        format!("{}{}{}", prefix, String::from_utf8(buf).unwrap(), suffix)
+                    })
+                    .collect::<Vec<_>>()
+            };
+
+            lines.push(format!("<<<<<<< {}", ours_label));
+            lines.extend(print_values(&conflict.ours));
+            lines.push("||||||| base".to_owned());
+            lines.extend(print_values(&conflict.base));
+            lines.push("=======".to_owned());
+            lines.extend(print_values(&conflict.theirs));
+            lines.push(format!(">>>>>>> {}", theirs_label));
+        }
+
+        lines.join("\n")
+    }
+}
+
+/// Merges the changes from `base` to `ours` and from `base` to `theirs`.
+pub fn merge(base: &AsonNode, ours: &AsonNode, theirs: &AsonNode) -> MergeResult {
+    let mut merger = ThreeWayMerger {
+        path: AsonPath::root(),
+        conflicts: vec![],
+    };
+
+    // the root value is always present.
+    let node = merger
+        .merge_value(Some(base), Some(ours), Some(theirs))
+        .unwrap_or_else(|| ours.clone());
+
+    MergeResult {
+        node,
+        conflicts: merger.conflicts,
+    }
+}
+
+struct ThreeWayMerger {
+    // the path of the value being merged
+    path: AsonPath,
+    conflicts: Vec<Conflict>,
+}
+
+impl ThreeWayMerger {
+    // records the conflict and returns the placeholder.
+    fn new_conflict(
+        &mut self,
+        base: Vec<AsonNode>,
+        ours: Vec<AsonNode>,
+        theirs: Vec<AsonNode>,
+    ) -> AsonNode {
+        let placeholder = new_placeholder(self.conflicts.len());
+        self.conflicts.push(Conflict {
+            path: self.path.clone(),
+            base,
+            ours,
+            theirs,
+        });
+        placeholder
+    }
+
+    // `None` means the value is absent.
+    fn merge_value(
+        &mut self,
+        base: Option<&AsonNode>,
+        ours: Option<&AsonNode>,
+        theirs: Option<&AsonNode>,
+    ) -> Option<AsonNode> {
+        if ours == theirs || base == theirs {
+            return ours.cloned();
+        }
+
+        if base == ours {
+            return theirs.cloned();
+        }
+
+        // both sides changed the value differently.
+        let (Some(ours), Some(theirs)) = (ours, theirs) else {
+            return Some(self.new_conflict(
+                base.into_iter().cloned().collect(),
+                ours.into_iter().cloned().collect(),
+                theirs.into_iter().cloned().collect(),
+            ));
+        };
+
+        let merged = match (base, ours, theirs) {
+            (base, AsonNode::Object(ours_kvps), AsonNode::Object(theirs_kvps)) => {
+                let base_kvps = match base {
+                    Some(AsonNode::Object(kvps)) => kvps.as_slice(),
+                    _ => &[],
+                };
+                Some(AsonNode::Object(self.merge_members(
+                    base_kvps,
+                    ours_kvps,
+                    theirs_kvps,
+                )))
+            }
+            (base, AsonNode::Map(ours_nvps), AsonNode::Map(theirs_nvps)) => {
+                let base_nvps = match base {
+                    Some(AsonNode::Map(nvps)) => nvps.as_slice(),
+                    _ => &[],
+                };
+                Some(AsonNode::Map(self.merge_entries(
+                    base_nvps,
+                    ours_nvps,
+                    theirs_nvps,
+                )))
+            }
+            (
+                Some(AsonNode::List(base_items)),
+                AsonNode::List(ours_items),
+                AsonNode::List(theirs_items),
+            ) => Some(AsonNode::List(self.merge_list(
+                base_items,
+                ours_items,
+                theirs_items,
+            ))),
+            (
+                Some(AsonNode::Tuple(base_items)),
+                AsonNode::Tuple(ours_items),
+                AsonNode::Tuple(theirs_items),
+            ) => self
+                .merge_tuple(base_items, ours_items, theirs_items)
+                .map(AsonNode::Tuple),
+            (base, AsonNode::Variant(ours_variant), AsonNode::Variant(theirs_variant)) => self
+                .merge_variant(base, ours_variant, theirs_variant)
+                .map(AsonNode::Variant),
+            _ => None,
+        };
+
+        Some(merged.unwrap_or_else(|| {
+            self.new_conflict(
+                base.into_iter().cloned().collect(),
+                vec![ours.clone()],
+                vec![theirs.clone()],
+            )
+        }))
+    }
+
+    fn merge_members(
+        &mut self,
+        base_kvps: &[KeyValuePair],
+        ours_kvps: &[KeyValuePair],
+        theirs_kvps: &[KeyValuePair],
+    ) -> Vec<KeyValuePair> {
+        let find = |kvps: &'_ [KeyValuePair], key: &str| -> Option<AsonNode> {
+            kvps.iter()
+                .find(|kvp| kvp.key == key)
+                .map(|kvp| kvp.value.as_ref().clone())
+        };
+
+        // the keys in the order of ours, followed by the new keys of theirs.
+        let mut keys: Vec<&str> = ours_kvps.iter().map(|kvp| kvp.key.as_str()).collect();
+        for kvp in base_kvps.iter().chain(theirs_kvps) {
+            if !keys.contains(&kvp.key.as_str()) {
+                keys.push(&kvp.key);
+            }
+        }
+
+        let mut merged = vec![];
+        for key in keys {
+            let (base, ours, theirs) = (
+                find(base_kvps, key),
+                find(ours_kvps, key),
+                find(theirs_kvps, key),
+            );
+
+            self.path.push_key(key);
+            if let Some(value) = self.merge_value(base.as_ref(), ours.as_ref(), theirs.as_ref()) {
+                merged.push(KeyValuePair::new(key, value));
+            }
+            self.path.segments.pop();
+        }
+
+        merged
+    }
+
+    fn merge_entries(
+        &mut self,
+        base_nvps: &[NameValuePair],
+        ours_nvps: &[NameValuePair],
+        theirs_nvps: &[NameValuePair],
+    ) -> Vec<NameValuePair> {
+        let find = |nvps: &'_ [NameValuePair], name: &AsonNode| -> Option<AsonNode> {
+            nvps.iter()
+                .find(|nvp| nvp.name.as_ref() == name)
+                .map(|nvp| nvp.value.as_ref().clone())
+        };
+
+        let mut names: Vec<&AsonNode> = ours_nvps.iter().map(|nvp| nvp.name.as_ref()).collect();
+        for nvp in base_nvps.iter().chain(theirs_nvps) {
+            if !names.contains(&nvp.name.as_ref()) {
+                names.push(&nvp.name);
+            }
+        }
+
+        let mut merged = vec![];
+        for name in names {
+            let (base, ours, theirs) = (
+                find(base_nvps, name),
+                find(ours_nvps, name),
+                find(theirs_nvps, name),
+            );
+
+            self.path.push_name(name);
+            if let Some(value) = self.merge_value(base.as_ref(), ours.as_ref(), theirs.as_ref()) {
+                merged.push(NameValuePair {
+                    name: Box::new(name.clone()),
+                    value: Box::new(value),
+                });
+            }
+            self.path.segments.pop();
+        }
+
+        merged
+    }
+
+    // the elements are merged by position, returns `None`
+    // if the lengths are different.
+    fn merge_tuple(
+        &mut self,
+        base_items: &[AsonNode],
+        ours_items: &[AsonNode],
+        theirs_items: &[AsonNode],
+    ) -> Option<Vec<AsonNode>> {
+        if base_items.len() != ours_items.len() || base_items.len() != theirs_items.len() {
+            return None;
+        }
+
+        let mut merged = vec![];
+        for (index, ((base, ours), theirs)) in base_items
+            .iter()
+            .zip(ours_items)
+            .zip(theirs_items)
+            .enumerate()
+        {
+            self.path.push_index(index);
+            // the elements are always present.
+            merged.extend(self.merge_value(Some(base), Some(ours), Some(theirs)));
+            self.path.segments.pop();
+        }
+
+        Some(merged)
+    }
+
+    // merges the Lists in the manner of diff3: the base elements that
+    // are kept by both sides split the Lists into chunks, and
+    // the chunks between them are merged.
+    fn merge_list(
+        &mut self,
+        base_items: &[AsonNode],
+        ours_items: &[AsonNode],
+        theirs_items: &[AsonNode],
+    ) -> Vec<AsonNode> {
+        let ours_matches = match_list(base_items, ours_items);
+        let theirs_matches = match_list(base_items, theirs_items);
+
+        // the base elements kept by both sides, and their indices in both sides.
+        let stable_items = ours_matches.iter().filter_map(|(base_index, ours_index)| {
+            theirs_matches
+                .iter()
+                .find(|(index, _)| index == base_index)
+                .map(|(_, theirs_index)| (*base_index, *ours_index, *theirs_index))
+        });
+
+        let mut merged = vec![];
+        let (mut b, mut o, mut t) = (0, 0, 0);
+
+        for (base_index, ours_index, theirs_index) in stable_items.chain(std::iter::once((
+            base_items.len(),
+            ours_items.len(),
+            theirs_items.len(),
+        ))) {
+            self.merge_list_chunk(
+                &base_items[b..base_index],
+                &ours_items[o..ours_index],
+                &theirs_items[t..theirs_index],
+                &mut merged,
+            );
+
+            if base_index < base_items.len() {
+                merged.push(base_items[base_index].clone());
+            }
+
+            (b, o, t) = (base_index + 1, ours_index + 1, theirs_index + 1);
+        }
+
+        merged
+    }
+
+    fn merge_list_chunk(
+        &mut self,
+        base_chunk: &[AsonNode],
+        ours_chunk: &[AsonNode],
+        theirs_chunk: &[AsonNode],
+        merged: &mut Vec<AsonNode>,
+    ) {
+        if ours_chunk == theirs_chunk || base_chunk == theirs_chunk {
+            merged.extend_from_slice(ours_chunk);
+        } else if base_chunk == ours_chunk {
+            merged.extend_from_slice(theirs_chunk);
+        } else if base_chunk.len() == ours_chunk.len() && base_chunk.len() == theirs_chunk.len() {
+            // the elements are changed in place, e.g. the different
+            // members of an Object element are changed by both sides.
+            for ((base, ours), theirs) in base_chunk.iter().zip(ours_chunk).zip(theirs_chunk) {
+                self.path.push_index(merged.len());
+                merged.extend(self.merge_value(Some(base), Some(ours), Some(theirs)));
+                self.path.segments.pop();
+            }
+        } else {
+            self.path.push_index(merged.len());
+            let placeholder = self.new_conflict(
+                base_chunk.to_vec(),
+                ours_chunk.to_vec(),
+                theirs_chunk.to_vec(),
+            );
+            self.path.segments.pop();
+            merged.push(placeholder);
+        }
+    }
+
+    // merges the payloads of the Variants of the same member,
+    // returns `None` if they can not be merged.
+    fn merge_variant(
+        &mut self,
+        base: Option<&AsonNode>,
+        ours: &Variant,
+        theirs: &Variant,
+    ) -> Option<Variant> {
+        if ours.type_name != theirs.type_name || ours.member_name != theirs.member_name {
+            return None;
+        }
+
+        let base_value = match base {
+            Some(AsonNode::Variant(base))
+                if base.type_name == ours.type_name && base.member_name == ours.member_name =>
+            {
+                Some(&base.value)
+            }
+            _ => None,
+        };
+
+        let value = match (base_value, &ours.value, &theirs.value) {
+            (base_value, VariantValue::Object(ours_kvps), VariantValue::Object(theirs_kvps)) => {
+                let base_kvps = match base_value {
+                    Some(VariantValue::Object(kvps)) => kvps.as_slice(),
+                    _ => &[],
+                };
+                VariantValue::Object(self.merge_members(base_kvps, ours_kvps, theirs_kvps))
+            }
+            (
+                Some(VariantValue::Value(base_value)),
+                VariantValue::Value(ours_value),
+                VariantValue::Value(theirs_value),
+            ) => {
+                self.path.push_index(0);
+                let value =
+                    self.merge_value(Some(base_value), Some(ours_value), Some(theirs_value));
+                self.path.segments.pop();
+                VariantValue::Value(Box::new(value?))
+            }
+            (
+                Some(VariantValue::Tuple(base_items)),
+                VariantValue::Tuple(ours_items),
+                VariantValue::Tuple(theirs_items),
+            ) => VariantValue::Tuple(self.merge_tuple(base_items, ours_items, theirs_items)?),
+            _ => return None,
+        };
+
+        Some(Variant {
+            type_name: ours.type_name.clone(),
+            member_name: ours.member_name.clone(),
+            value,
+        })
+    }
+}
+
+fn new_placeholder(index: usize) -> AsonNode {
+    AsonNode::String(format!("{}{}", PLACEHOLDER_PREFIX, index))
+}
+
+fn placeholder_index(node: &AsonNode) -> Option<usize> {
+    match node {
+        AsonNode::String(s) => s.strip_prefix(PLACEHOLDER_PREFIX)?.parse().ok(),
+        _ => None,
+    }
+}
+
+// replaces the placeholders inside the node with the values
+// of the side, returns the values if the node itself is a placeholder.
+fn resolve_node(node: &mut AsonNode, conflicts: &[Conflict], side: ConflictSide) -> Vec<AsonNode> {
+    if let Some(index) = placeholder_index(node) {
+        let conflict = &conflicts[index];
+        return match side {
+            ConflictSide::Ours => conflict.ours.clone(),
+            ConflictSide::Theirs => conflict.theirs.clone(),
+        };
+    }
+
+    match node {
+        AsonNode::List(items) | AsonNode::Tuple(items) => resolve_items(items, conflicts, side),
+        AsonNode::Object(kvps) => resolve_members(kvps, conflicts, side),
+        AsonNode::Map(nvps) => {
+            *nvps = std::mem::take(nvps)
+                .into_iter()
+                .filter_map(|mut nvp| {
+                    let mut values = resolve_node(&mut nvp.value, conflicts, side);
+                    if let Some(value) = values.pop() {
+                        *nvp.value = value;
+                    } else if placeholder_index(&nvp.value).is_some() {
+                        return None;
+                    }
+                    Some(nvp)
+                })
+                .collect();
+        }
+        AsonNode::Variant(variant) => match &mut variant.value {
+            VariantValue::Value(value) => {
+                if let Some(resolved) = resolve_node(value, conflicts, side).pop() {
+                    **value = resolved;
+                }
+            }
+            VariantValue::Tuple(items) => resolve_items(items, conflicts, side),
+            VariantValue::Object(kvps) => resolve_members(kvps, conflicts, side),
+            VariantValue::Empty => {}
+        },
+        _ => {}
+    }
+
+    vec![]
+}
+
+fn resolve_items(items: &mut Vec<AsonNode>, conflicts: &[Conflict], side: ConflictSide) {
+    *items = std::mem::take(items)
+        .into_iter()
+        .flat_map(|mut item| {
+            if placeholder_index(&item).is_some() {
+                resolve_node(&mut item, conflicts, side)
+            } else {
+                resolve_node(&mut item, conflicts, side);
+                vec![item]
+            }
+        })
+        .collect();
+}
+
+fn resolve_members(kvps: &mut Vec<KeyValuePair>, conflicts: &[Conflict], side: ConflictSide) {
+    *kvps = std::mem::take(kvps)
+        .into_iter()
+        .filter_map(|mut kvp| {
+            let mut values = resolve_node(&mut kvp.value, conflicts, side);
+            if let Some(value) = values.pop() {
+                *kvp.value = value;
+            } else if placeholder_index(&kvp.value).is_some() {
+                return None;
+            }
+            Some(kvp)
+        })
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{ast::AsonNode, parser::parse_from_str, printer::print_to_string};
+
+    use super::{merge, ConflictSide, MergeResult};
+
+    fn node(s: &str) -> AsonNode {
+        parse_from_str(s).unwrap()
+    }
+
+    fn merge_clean(base: &str, ours: &str, theirs: &str) -> String {
+        let result = merge(&node(base), &node(ours), &node(theirs));
+        assert!(result.is_clean(), "{:?}", result.conflicts);
+        print_to_string(&result.resolve(ConflictSide::Ours))
+    }
+
+    #[test]
+    fn test_merge_clean() {
+        // different keys of the same Object
+        assert_eq!(
+            merge_clean(
+                r#"{name: "app", server: {host: "a", port: 80}, debug: false}"#,
+                r#"{name: "app", server: {host: "b", port: 80}}"#,
+                r#"{name: "app", server: {host: "a", port: 8080}, debug: false, log: 1}"#,
+            ),
+            print_to_string(&node(
+                r#"{name: "app", server: {host: "b", port: 8080}, log: 1}"#
+            ))
+        );
+
+        // Maps, Tuples and Variants
+        assert_eq!(
+            merge_clean(
+                r#"{m: ['a': 1, 'b': 2], t: (1, 2), v: Shape::Rect{w: 1, h: 2}, o: Option::Some(1)}"#,
+                r#"{m: ['a': 10, 'b': 2], t: (10, 2), v: Shape::Rect{w: 10, h: 2}, o: Option::Some(1)}"#,
+                r#"{m: ['a': 1, 'c': 3], t: (1, 20), v: Shape::Rect{w: 1, h: 20}, o: Option::None}"#,
+            ),
+            print_to_string(&node(
+                r#"{m: ['a': 10, 'c': 3], t: (10, 20), v: Shape::Rect{w: 10, h: 20}, o: Option::None}"#
+            ))
+        );
+
+        // Lists
+        assert_eq!(
+            merge_clean(
+                r#"[1, 2, 3, 4, 5]"#,
+                r#"[0, 1, 2, 3, 4, 5]"#,
+                r#"[1, 2, 3, 5, 6]"#,
+            ),
+            print_to_string(&node(r#"[0, 1, 2, 3, 5, 6]"#))
+        );
+
+        // the different members of the same List element
+        assert_eq!(
+            merge_clean(
+                r#"[{id: 1, a: 1, b: 1}, {id: 2, a: 2, b: 2}]"#,
+                r#"[{id: 1, a: 10, b: 1}, {id: 2, a: 2, b: 2}]"#,
+                r#"[{id: 1, a: 1, b: 10}, {id: 2, a: 2, b: 2}, {id: 3, a: 3, b: 3}]"#,
+            ),
+            print_to_string(&node(
+                r#"[{id: 1, a: 10, b: 10}, {id: 2, a: 2, b: 2}, {id: 3, a: 3, b: 3}]"#
+            ))
+        );
+
+        // the same change of both sides
+        assert_eq!(merge_clean("{a: 1}", "{a: 2}", "{a: 2}"), "{\n    a: 2\n}");
+    }
+
+    #[test]
+    fn test_merge_list_conflicts() {
+        let conflicts_text = |result: &MergeResult| {
+            result
+                .conflicts
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>()
+                .join("")
+        };
+
+        // the insertions and removals of different parts are merged
+        assert_eq!(
+            merge_clean("[1, 2, 3, 4, 5]", "[1, 3, 4, 5, 6]", "[0, 1, 2, 3, 5]"),
+            print_to_string(&node("[0, 1, 3, 5, 6]"))
+        );
+        assert_eq!(
+            merge_clean("[1, 2, 3]", "[1, 3]", "[1, 3, 4]"),
+            print_to_string(&node("[1, 3, 4]"))
+        );
+
+        // one side removes an element which the other side changes,
+        // the insertion of the other part is merged.
+        let result = merge(
+            &node("[1, 2, 3, 4]"),
+            &node("[1, 3, 9, 4]"),
+            &node("[1, 20, 3, 4]"),
+        );
+        assert_eq!(
+            conflicts_text(&result),
+            r#"conflict at [1]
+  base: 2
+  ours: (absent)
+  theirs: 20
+"#
+        );
+        assert_eq!(result.resolve(ConflictSide::Ours), node("[1, 3, 9, 4]"));
+        assert_eq!(
+            result.resolve(ConflictSide::Theirs),
+            node("[1, 20, 3, 9, 4]")
+        );
+
+        // one side removes a range which the other side inserts into
+        let result = merge(
+            &node("[1, 2, 3, 4]"),
+            &node("[1, 4]"),
+            &node("[1, 2, 5, 3, 4]"),
+        );
+        assert_eq!(
+            conflicts_text(&result),
+            r#"conflict at [1]
+  base: 2, 3
+  ours: (absent)
+  theirs: 2, 5, 3
+"#
+        );
+        assert_eq!(
+            result.to_string_with_markers("ours", "theirs"),
+            r#"[
+    1
+<<<<<<< ours
+||||||| base
+    2
+    3
+=======
+    2
+    5
+    3
+>>>>>>> theirs
+    4
+]"#
+        );
+
+        // the adjacent removals overlap
+        let result = merge(
+            &node("[1, 2, 3, 4]"),
+            &node("[1, 3, 4]"),
+            &node("[1, 2, 4]"),
+        );
+        assert_eq!(
+            conflicts_text(&result),
+            r#"conflict at [1]
+  base: 2, 3
+  ours: 3
+  theirs: 2
+"#
+        );
+
+        // the different insertions at the same place
+        let result = merge(
+            &node("[1, 2, 3]"),
+            &node("[1, 9, 2, 3]"),
+            &node("[1, 8, 2, 3]"),
+        );
+        assert_eq!(
+            conflicts_text(&result),
+            r#"conflict at [1]
+  base: (absent)
+  ours: 9
+  theirs: 8
+"#
+        );
+        assert_eq!(result.resolve(ConflictSide::Theirs), node("[1, 8, 2, 3]"));
+    }
+
+    #[test]
+    fn test_merge_conflicts() {
+        let result = merge(
+            &node(r#"{port: 80, host: "a", tags: ["x", "y"], debug: false}"#),
+            &node(r#"{port: 8080, host: "a", tags: ["x", "y", "z"]}"#),
+            &node(r#"{port: 9090, host: "b", tags: ["x", "y", "w"], debug: true}"#),
+        );
+
+        assert_eq!(
+            result
+                .conflicts
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>()
+                .join(""),
+            r#"conflict at .port
+  base: 80
+  ours: 8080
+  theirs: 9090
+conflict at .tags[2]
+  base: (absent)
+  ours: "z"
+  theirs: "w"
+conflict at .debug
+  base: false
+  ours: (absent)
+  theirs: true
+"#
+        );
+
+        assert_eq!(
+            result.resolve(ConflictSide::Ours),
+            node(r#"{port: 8080, host: "b", tags: ["x", "y", "z"]}"#)
+        );
+        assert_eq!(
+            result.resolve(ConflictSide::Theirs),
+            node(r#"{port: 9090, host: "b", tags: ["x", "y", "w"], debug: true}"#)
+        );
+
+        assert_eq!(
+            result.to_string_with_markers("ours", "theirs"),
+            r#"{
+<<<<<<< ours
+    port: 8080
+||||||| base
+    port: 80
+=======
+    port: 9090
+>>>>>>> theirs
+    host: "b"
+    tags: [
+        "x"
+        "y"
+<<<<<<< ours
+        "z"
+||||||| base
+=======
+        "w"
+>>>>>>> theirs
+    ]
+<<<<<<< ours
+||||||| base
+    debug: false
+=======
+    debug: true
+>>>>>>> theirs
+}"#
+        );
+
+        // the conflict of nested values
+        let result = merge(
+            &node(r#"{a: {b: 1}}"#),
+            &node(r#"{a: {b: {c: 1}}}"#),
+            &node(r#"{a: {b: 2}}"#),
+        );
+        assert_eq!(
+            result.to_string_with_markers("HEAD", "feature"),
+            r#"{
+    a: {
+<<<<<<< HEAD
+        b: {
+            c: 1
+        }
+||||||| base
+        b: 1
+=======
+        b: 2
+>>>>>>> feature
+    }
+}"#
+        );
+
+        // the root value
+        let result = merge(&node("1"), &node("2"), &node("3"));
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.resolve(ConflictSide::Theirs), node("3"));
+    }
+}
@@ -8,9 +8,9 @@ use std::fmt::Display;
 
 use chrono::{DateTime, FixedOffset};
 
-use crate::location::Location;
+use crate::{location::Location, AsonError};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Token {
     // includes `\n` and `\r\n`
     NewLine,
@@ -62,9 +62,13 @@ pub enum Token {
     HexByteData(Vec<u8>),
 
     Comment(Comment),
+
+    // the invalid text, it is never produced by the lexer, but by the
+    // tokenizer with error recovery (i.e., the `tokenizer::TokenIter`).
+    Error(AsonError),
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum NumberToken {
     // it is possible for literal to overflow for signed numbers,
     // such as `-128`, which consists of a negative/minus sign
@@ -84,7 +88,7 @@ pub enum NumberToken {
     F64(f64),
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Comment {
     // `//...`
     // note that the trailing '\n' or '\r\n' does not belong to line comment
@@ -147,7 +151,7 @@ impl Display for NumberType {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct TokenWithRange {
     pub token: Token,
     pub range: Location,
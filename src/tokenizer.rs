@@ -0,0 +1,485 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+//! Tokenizer for syntax highlighters, editor plugins and other tools.
+//!
+//! Unlike the parser, the tokenizer yields every piece of the source text,
+//! including whitespaces, new lines and comments, so the source text can be
+//! rebuilt by concatenating the text of all tokens.
+//!
+//! The tokenizer stops at the first error by default, the last token is
+//! `TokenKind::Error` in this case. With error recovery enabled, the text
+//! from the start of the invalid token to the end of the line is yielded
+//! as an error token, and the tokenizing continues from the next line.
+//!
+//! Note that a minus sign is a separate token, and it is not part of
+//! the number token following it.
+
+use chrono::{DateTime, FixedOffset};
+
+use crate::{
+    charwithposition::CharsWithPositionIter,
+    lexer::{Lexer, LEXER_PEEK_CHAR_MAX_COUNT},
+    limits::Limits,
+    location::Location,
+    peekableiter::PeekableIter,
+    token::{self, Token as InternalToken, TokenWithRange},
+    AsonError, ErrorKind,
+};
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum TokenKind {
+    // spaces and tabs
+    Whitespace,
+
+    // `\n` or `\r\n`
+    NewLine,
+
+    Comma,
+    Colon,
+    LeftBrace,
+    RightBrace,
+    LeftBracket,
+    RightBracket,
+    LeftParen,
+    RightParen,
+    Plus,
+    Minus,
+
+    // the key name of Object
+    Identifier(String),
+
+    Boolean(bool),
+
+    // the type name and the member name, e.g. `Option::None`.
+    Variant(String, String),
+
+    Number(NumberToken),
+    Char(char),
+    String(String),
+    DateTime(DateTime<FixedOffset>),
+    HexByteData(Vec<u8>),
+    Comment(Comment),
+
+    // the invalid text
+    Error(AsonError),
+}
+
+/// The number literal. Since the minus sign is a separate token, the signed
+/// integers are held by the unsigned types of the same width, e.g. `-128_i8`
+/// is yielded as `Minus` followed by `Number(NumberToken::I8(128))`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum NumberToken {
+    I8(u8),
+    U8(u8),
+    I16(u16),
+    U16(u16),
+    I32(u32),
+    U32(u32),
+    I64(u64),
+    U64(u64),
+    F32(f32),
+    F64(f64),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Comment {
+    // `//...`, the trailing `\n` or `\r\n` is not included.
+    Line(String),
+
+    // `/*...*/`
+    Block(String),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub range: Location,
+}
+
+/// An iterator of the tokens of ASON text, the text is lexed on demand.
+pub struct Tokenizer<'a> {
+    upstream: TokenIter<'a>,
+
+    // the end of the last token, i.e. the start of the whitespaces
+    // before the next token.
+    position: Location,
+
+    // the token after the whitespaces which have been yielded.
+    pending: Option<Token>,
+    is_end: bool,
+}
+
+impl<'a> Tokenizer<'a> {
+    /// Creates a tokenizer which stops at the first error.
+    pub fn new(s: &'a str) -> Self {
+        Self::from_upstream(TokenIter::new(s, false))
+    }
+
+    /// Creates a tokenizer which continues from the next line after errors.
+    pub fn with_error_recovery(s: &'a str) -> Self {
+        Self::from_upstream(TokenIter::new(s, true))
+    }
+
+    fn from_upstream(upstream: TokenIter<'a>) -> Self {
+        Self {
+            position: upstream.position,
+            upstream,
+            pending: None,
+            is_end: false,
+        }
+    }
+
+    // the whitespaces between the end of the last token and the specified index.
+    fn whitespace_before(&self, index: usize) -> Option<Token> {
+        (index > self.position.index).then(|| Token {
+            kind: TokenKind::Whitespace,
+            range: Location::from_position_and_length(&self.position, index - self.position.index),
+        })
+    }
+}
+
+impl Iterator for Tokenizer<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(token) = self.pending.take() {
+            return Some(token);
+        }
+
+        if self.is_end {
+            return None;
+        }
+
+        match self.upstream.next() {
+            Some(TokenWithRange { token, range }) => {
+                // the rest text is dropped if the tokenizing is stopped by an error.
+                if matches!(token, InternalToken::Error(_)) && !self.upstream.error_recovery {
+                    self.is_end = true;
+                }
+
+                let whitespace = self.whitespace_before(range.index);
+                self.position = self.upstream.position;
+
+                let token = Token {
+                    kind: convert_token(token),
+                    range,
+                };
+
+                match whitespace {
+                    Some(whitespace) => {
+                        self.pending = Some(token);
+                        Some(whitespace)
+                    }
+                    None => Some(token),
+                }
+            }
+            None => {
+                // trailing whitespaces
+                self.is_end = true;
+                let rest = &self.upstream.source[self.position.byte_index..];
+                self.whitespace_before(self.position.index + rest.chars().count())
+            }
+        }
+    }
+}
+
+fn convert_token(token: InternalToken) -> TokenKind {
+    match token {
+        InternalToken::NewLine => TokenKind::NewLine,
+        InternalToken::Comma => TokenKind::Comma,
+        InternalToken::Colon => TokenKind::Colon,
+        InternalToken::LeftBrace => TokenKind::LeftBrace,
+        InternalToken::RightBrace => TokenKind::RightBrace,
+        InternalToken::LeftBracket => TokenKind::LeftBracket,
+        InternalToken::RightBracket => TokenKind::RightBracket,
+        InternalToken::LeftParen => TokenKind::LeftParen,
+        InternalToken::RightParen => TokenKind::RightParen,
+        InternalToken::Plus => TokenKind::Plus,
+        InternalToken::Minus => TokenKind::Minus,
+        InternalToken::Identifier(s) => TokenKind::Identifier(s),
+        InternalToken::Boolean(b) => TokenKind::Boolean(b),
+        InternalToken::Variant(type_name, member_name) => {
+            TokenKind::Variant(type_name, member_name)
+        }
+        InternalToken::Number(n) => TokenKind::Number(convert_number(n)),
+        InternalToken::Char(c) => TokenKind::Char(c),
+        InternalToken::String(s) => TokenKind::String(s),
+        InternalToken::Date(d) => TokenKind::DateTime(d),
+        InternalToken::HexByteData(data) => TokenKind::HexByteData(data),
+        InternalToken::Comment(token::Comment::Line(s)) => TokenKind::Comment(Comment::Line(s)),
+        InternalToken::Comment(token::Comment::Block(s)) => TokenKind::Comment(Comment::Block(s)),
+        InternalToken::Error(e) => TokenKind::Error(e),
+    }
+}
+
+fn convert_number(number: token::NumberToken) -> NumberToken {
+    match number {
+        token::NumberToken::I8(v) => NumberToken::I8(v),
+        token::NumberToken::U8(v) => NumberToken::U8(v),
+        token::NumberToken::I16(v) => NumberToken::I16(v),
+        token::NumberToken::U16(v) => NumberToken::U16(v),
+        token::NumberToken::I32(v) => NumberToken::I32(v),
+        token::NumberToken::U32(v) => NumberToken::U32(v),
+        token::NumberToken::I64(v) => NumberToken::I64(v),
+        token::NumberToken::U64(v) => NumberToken::U64(v),
+        token::NumberToken::F32(v) => NumberToken::F32(v),
+        token::NumberToken::F64(v) => NumberToken::F64(v),
+    }
+}
+
+/// Lexes the text into tokens on demand, whitespaces are not included.
+///
+/// The error is yielded as `Token::Error` with the range of the invalid
+/// text, i.e., from the start of the invalid token to the end of the line.
+pub(crate) struct TokenIter<'a> {
+    source: &'a str,
+    error_recovery: bool,
+    limits: Limits,
+
+    // the position of the next char to lex, i.e. the end of the last token.
+    position: Location,
+    is_end: bool,
+}
+
+impl<'a> TokenIter<'a> {
+    pub(crate) fn new(source: &'a str, error_recovery: bool) -> Self {
+        Self::new_with_limits(source, error_recovery, Limits::default())
+    }
+
+    pub(crate) fn new_with_limits(source: &'a str, error_recovery: bool, limits: Limits) -> Self {
+        Self {
+            source,
+            error_recovery,
+            limits,
+            position: Location::new_position(0, 0, 0),
+            is_end: false,
+        }
+    }
+
+    // lexes one token with a lexer which starts from the current position,
+    // so the text does not need to be converted to chars in advance.
+    fn lex_next(&self) -> Option<Result<TokenWithRange, AsonError>> {
+        let mut upstream = self.source[self.position.byte_index..].chars();
+        let mut char_position_iter =
+            CharsWithPositionIter::new_with_start_position(&mut upstream, self.position);
+        let mut peekable_char_position_iter =
+            PeekableIter::new(&mut char_position_iter, LEXER_PEEK_CHAR_MAX_COUNT);
+        let mut lexer = Lexer::new_with_limits(&mut peekable_char_position_iter, self.limits);
+        lexer.next()
+    }
+
+    // the position after the specified number of chars from the position,
+    // the end of text if there are not enough chars.
+    fn advance(&self, position: &Location, count: usize) -> Location {
+        let mut position = Location::from_position_and_length(position, 0);
+        for c in self.source[position.byte_index..].chars().take(count) {
+            position.index += 1;
+            position.byte_index += c.len_utf8();
+            if c == '\n' {
+                position.line += 1;
+                position.column = 0;
+            } else {
+                position.column += 1;
+            }
+        }
+        position
+    }
+
+    // builds the error token which covers the text from the start of the
+    // invalid token to the end of the line.
+    fn error_token(&self, error: AsonError) -> TokenWithRange {
+        // all chars have been consumed if the document ends unexpectedly.
+        let error = if error.location.is_none() {
+            error.locate_end_of_document(self.advance(&self.position, usize::MAX))
+        } else {
+            error
+        };
+
+        // the invalid token starts after the whitespaces.
+        let spaces = self.source[self.position.byte_index..]
+            .chars()
+            .take_while(|c| matches!(c, ' ' | '\t'))
+            .count();
+        let start = self.advance(&self.position, spaces);
+
+        let error_index = error.location.map_or(usize::MAX, |location| location.index);
+
+        // the length till the next line, excludes the `\n` or `\r\n`.
+        let resume_index = error_index.max(start.index + 1);
+        let mut chars = self.source[start.byte_index..].chars().peekable();
+        let mut length = 0;
+        while let Some(c) = chars.next() {
+            if start.index + length >= resume_index
+                && (c == '\n' || (c == '\r' && chars.peek() == Some(&'\n')))
+            {
+                break;
+            }
+            length += 1;
+        }
+
+        TokenWithRange::new(
+            InternalToken::Error(error),
+            Location::from_position_and_length(&start, length),
+        )
+    }
+}
+
+impl Iterator for TokenIter<'_> {
+    type Item = TokenWithRange;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_end {
+            return None;
+        }
+
+        match self.lex_next() {
+            Some(Ok(token_with_range)) => {
+                self.position =
+                    self.advance(&token_with_range.range, token_with_range.range.length);
+                Some(token_with_range)
+            }
+            Some(Err(error)) => {
+                // the text beyond the document size limit is not lexed.
+                let is_fatal = error.kind == ErrorKind::DocumentSizeLimitExceeded;
+                let token_with_range = self.error_token(error);
+                self.position =
+                    self.advance(&token_with_range.range, token_with_range.range.length);
+                self.is_end = !self.error_recovery || is_fatal;
+                Some(token_with_range)
+            }
+            None => {
+                self.is_end = true;
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::location::Location;
+
+    use super::{Comment, NumberToken, TokenKind, Tokenizer};
+
+    fn kinds(tokenizer: Tokenizer<'_>) -> Vec<TokenKind> {
+        tokenizer.map(|t| t.kind).collect()
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let text = "{\n\tid: -123_u8, // id\r\n  name: \"foo\" /* name */\n}  ";
+        let tokens: Vec<_> = Tokenizer::new(text).collect();
+
+        assert_eq!(
+            tokens.iter().map(|t| t.kind.clone()).collect::<Vec<_>>(),
+            vec![
+                TokenKind::LeftBrace,
+                TokenKind::NewLine,
+                TokenKind::Whitespace,
+                TokenKind::Identifier("id".to_owned()),
+                TokenKind::Colon,
+                TokenKind::Whitespace,
+                TokenKind::Minus,
+                TokenKind::Number(NumberToken::U8(123)),
+                TokenKind::Comma,
+                TokenKind::Whitespace,
+                TokenKind::Comment(Comment::Line(" id".to_owned())),
+                TokenKind::NewLine,
+                TokenKind::Whitespace,
+                TokenKind::Identifier("name".to_owned()),
+                TokenKind::Colon,
+                TokenKind::Whitespace,
+                TokenKind::String("foo".to_owned()),
+                TokenKind::Whitespace,
+                TokenKind::Comment(Comment::Block(" name ".to_owned())),
+                TokenKind::NewLine,
+                TokenKind::RightBrace,
+                TokenKind::Whitespace,
+            ]
+        );
+
+        // the ranges cover the whole text
+        let chars: Vec<char> = text.chars().collect();
+        let mut index = 0;
+        for token in &tokens {
+            assert_eq!(token.range.index, index);
+            index += token.range.length;
+        }
+        assert_eq!(index, chars.len());
+
+        assert_eq!(tokens[13].range, Location::new_range(25, 2, 2, 4));
+    }
+
+    #[test]
+    fn test_tokenize_multiple_line_tokens() {
+        let text = "/* 文字\n😀 */ \"a\nb\"  'c'";
+        let tokens: Vec<_> = Tokenizer::new(text).collect();
+
+        assert_eq!(
+            tokens.iter().map(|t| t.kind.clone()).collect::<Vec<_>>(),
+            vec![
+                TokenKind::Comment(Comment::Block(" 文字\n😀 ".to_owned())),
+                TokenKind::Whitespace,
+                TokenKind::String("a\nb".to_owned()),
+                TokenKind::Whitespace,
+                TokenKind::Char('c'),
+            ]
+        );
+
+        assert_eq!(
+            tokens[1].range,
+            Location::new_range(10, 1, 4, 1).with_byte_index(17)
+        );
+        assert_eq!(
+            tokens[3].range,
+            Location::new_range(16, 2, 2, 2).with_byte_index(23)
+        );
+        assert_eq!(
+            tokens[4].range,
+            Location::new_range(18, 2, 4, 3).with_byte_index(25)
+        );
+    }
+
+    #[test]
+    fn test_tokenize_with_error() {
+        let text = "[1, 2_x8, 3]\n['ab', 4]";
+
+        // stop at the first error
+        let tokens: Vec<_> = Tokenizer::new(text).collect();
+        assert_eq!(tokens.len(), 5);
+        assert!(matches!(tokens[4].kind, TokenKind::Error(_)));
+        assert_eq!(tokens[4].range, Location::new_range(4, 0, 4, 8));
+
+        // error recovery
+        assert_eq!(
+            kinds(Tokenizer::with_error_recovery(text))
+                .into_iter()
+                .map(|kind| match kind {
+                    TokenKind::Error(_) => "error".to_owned(),
+                    other => format!("{:?}", other),
+                })
+                .collect::<Vec<String>>(),
+            vec![
+                "LeftBracket",
+                "Number(I32(1))",
+                "Comma",
+                "Whitespace",
+                "error",
+                "NewLine",
+                "LeftBracket",
+                "error",
+            ]
+        );
+
+        // incomplete string
+        let tokens: Vec<_> = Tokenizer::with_error_recovery("[\"abc\n, 1]").collect();
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(tokens[1].range, Location::new_range(1, 0, 1, 9));
+    }
+}
@@ -0,0 +1,651 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+//! The asynchronous variants of the reading and writing functions,
+//! over `tokio::io::AsyncRead` and `tokio::io::AsyncWrite`.
+//!
+//! The functions produce the same values as their synchronous counterparts.
+//! The data is read in chunks without blocking, and each chunk is fed
+//! to a `PushParser`, which decodes it and tracks the brackets, so
+//! the document is parsed as soon as its last bracket arrives, and
+//! `Limits::max_document_size` is checked while reading.
+//!
+//! The output is written through an `AsonWriter` into a bounded buffer,
+//! which is flushed to the writer whenever it is full. A Rust value
+//! is converted to AST first since the serializer of serde can not
+//! wait for the writer.
+
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::{
+    ast::{AsonNode, Variant, VariantValue},
+    location::Location,
+    options::ParseOptions,
+    serde::{node::to_node, stream::StreamWriter},
+    stream::{PushParser, PushStatus},
+    writer::AsonWriter,
+    AsonError,
+};
+
+const READ_BUFFER_SIZE: usize = 8 * 1024;
+const WRITE_BUFFER_SIZE: usize = 8 * 1024;
+
+pub async fn from_async_reader<T, R>(reader: R) -> Result<T, AsonError>
+where
+    T: DeserializeOwned,
+    R: AsyncRead + Unpin,
+{
+    from_async_reader_with_options(reader, ParseOptions::default()).await
+}
+
+pub async fn from_async_reader_with_options<T, R>(
+    reader: R,
+    options: ParseOptions<'_>,
+) -> Result<T, AsonError>
+where
+    T: DeserializeOwned,
+    R: AsyncRead + Unpin,
+{
+    read_document(reader, options, |options, text| options.from_str(text)).await
+}
+
+pub async fn parse_from_async_reader<R>(reader: R) -> Result<AsonNode, AsonError>
+where
+    R: AsyncRead + Unpin,
+{
+    parse_from_async_reader_with_options(reader, ParseOptions::default()).await
+}
+
+pub async fn parse_from_async_reader_with_options<R>(
+    reader: R,
+    options: ParseOptions<'_>,
+) -> Result<AsonNode, AsonError>
+where
+    R: AsyncRead + Unpin,
+{
+    read_document(reader, options, |options, text| options.parse_str(text)).await
+}
+
+// reads the chunks until the end of data, the document is parsed
+// once it is complete, and the data after it must be whitespaces
+// and comments.
+async fn read_document<T, R>(
+    mut reader: R,
+    options: ParseOptions<'_>,
+    parse: fn(&ParseOptions, &str) -> Result<T, AsonError>,
+) -> Result<T, AsonError>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut parser = PushParser::with_options(options);
+    let mut buf = vec![0_u8; READ_BUFFER_SIZE];
+    let mut value = None;
+    let mut value_location = None;
+
+    loop {
+        match parser.poll_record()? {
+            PushStatus::Value(record) => {
+                // the comma separator before the record is reported first,
+                // the same as the text parser does.
+                if let Some(comma) = parser.first_comma() {
+                    if comma.index < record.location.index {
+                        return Err(separator_error(comma, value_location));
+                    }
+                }
+
+                if value.is_some() {
+                    return Err(AsonError::new_syntax(
+                        "Document has more than one node.".to_owned(),
+                        record.location,
+                    ));
+                }
+
+                let result = parse(&options, &record.text).map_err(|e| record.relocate_error(e));
+                value = Some(result?);
+                value_location = Some(record.location);
+            }
+            PushStatus::NeedMoreData => match reader.read(&mut buf).await? {
+                0 => parser.finish(),
+                n => parser.feed(&buf[..n]),
+            },
+            PushStatus::End => break,
+        }
+    }
+
+    if let Some(comma) = parser.first_comma() {
+        return Err(separator_error(comma, value_location));
+    }
+
+    match value {
+        Some(value) => Ok(value),
+        None => {
+            // the document contains only whitespaces and comments.
+            let e = parse(&options, "").err().unwrap();
+            Err(AsonError {
+                location: Some(parser.position()),
+                ..e
+            })
+        }
+    }
+}
+
+// A comma after the value is an extra node, and one before the value
+// is an unexpected token.
+fn separator_error(comma: Location, value_location: Option<Location>) -> AsonError {
+    match value_location {
+        Some(location) if location.index < comma.index => {
+            AsonError::new_syntax("Document has more than one node.".to_owned(), comma)
+        }
+        _ => AsonError::new_syntax("Unexpected token.".to_owned(), comma),
+    }
+}
+
+pub async fn to_async_writer<T, W>(value: &T, writer: W) -> Result<(), AsonError>
+where
+    T: Serialize,
+    W: AsyncWrite + Unpin,
+{
+    let node = to_node(value)?;
+    print_to_async_writer(writer, &node).await
+}
+
+pub async fn print_to_async_writer<W>(mut writer: W, node: &AsonNode) -> Result<(), AsonError>
+where
+    W: AsyncWrite + Unpin,
+{
+    // the nodes and keys to be written, in the reverse order.
+    enum Pending<'a> {
+        Node(&'a AsonNode),
+        Key(&'a str),
+        End,
+    }
+
+    let mut ason_writer = AsonWriter::new(Vec::with_capacity(WRITE_BUFFER_SIZE));
+    let mut pending = vec![Pending::Node(node)];
+
+    while let Some(item) = pending.pop() {
+        match item {
+            Pending::Node(AsonNode::List(items)) => {
+                ason_writer.begin_list()?;
+                pending.push(Pending::End);
+                pending.extend(items.iter().rev().map(Pending::Node));
+            }
+            Pending::Node(AsonNode::Tuple(items)) if !items.is_empty() => {
+                ason_writer.begin_tuple()?;
+                pending.push(Pending::End);
+                pending.extend(items.iter().rev().map(Pending::Node));
+            }
+            Pending::Node(AsonNode::Object(kvps)) => {
+                ason_writer.begin_object()?;
+                pending.push(Pending::End);
+                for kvp in kvps.iter().rev() {
+                    pending.push(Pending::Node(&kvp.value));
+                    pending.push(Pending::Key(&kvp.key));
+                }
+            }
+            Pending::Node(AsonNode::Map(nvps)) => {
+                ason_writer.begin_map()?;
+                pending.push(Pending::End);
+                for nvp in nvps.iter().rev() {
+                    pending.push(Pending::Node(&nvp.value));
+                    pending.push(Pending::Node(&nvp.name));
+                }
+            }
+            Pending::Node(AsonNode::Variant(Variant {
+                type_name,
+                member_name,
+                value: VariantValue::Value(value),
+            })) => {
+                ason_writer.begin_variant(type_name, member_name)?;
+                pending.push(Pending::End);
+                pending.push(Pending::Node(value));
+            }
+            Pending::Node(node) => ason_writer.value(node)?,
+            Pending::Key(key) => ason_writer.key(key)?,
+            Pending::End => ason_writer.end()?,
+        }
+
+        let buf = ason_writer.get_mut();
+        if buf.len() >= WRITE_BUFFER_SIZE {
+            writer.write_all(buf).await?;
+            buf.clear();
+        }
+    }
+
+    let buf = ason_writer.finish()?;
+    writer.write_all(&buf).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Reads the successive values of a multi-document stream asynchronously,
+/// see `StreamParser` for the format.
+///
+/// The errors of a single value do not stop the reader, while
+/// the I/O errors, the invalid UTF-8 data and the document size errors do.
+pub struct AsyncStreamReader<'a, R> {
+    reader: R,
+    parser: PushParser<'a>,
+    buf: Vec<u8>,
+    is_failed: bool,
+}
+
+impl<'a, R: AsyncRead + Unpin> AsyncStreamReader<'a, R> {
+    pub fn new(reader: R) -> Self {
+        Self::with_options(reader, ParseOptions::default())
+    }
+
+    /// The options apply to each value of the stream.
+    pub fn with_options(reader: R, options: ParseOptions<'a>) -> Self {
+        Self {
+            reader,
+            parser: PushParser::with_options(options),
+            buf: vec![0_u8; READ_BUFFER_SIZE],
+            is_failed: false,
+        }
+    }
+
+    /// Reads the next value as AST, returns `None` at the end of stream.
+    pub async fn next_node(&mut self) -> Option<Result<AsonNode, AsonError>> {
+        self.next_with(PushParser::poll_node).await
+    }
+
+    /// Reads and deserializes the next value, returns `None` at the end of stream.
+    pub async fn next_value<T>(&mut self) -> Option<Result<T, AsonError>>
+    where
+        T: DeserializeOwned,
+    {
+        self.next_with(PushParser::poll_value).await
+    }
+
+    async fn next_with<T>(
+        &mut self,
+        poll: fn(&mut PushParser<'a>) -> Result<PushStatus<T>, AsonError>,
+    ) -> Option<Result<T, AsonError>> {
+        if self.is_failed {
+            return None;
+        }
+
+        loop {
+            match poll(&mut self.parser) {
+                Ok(PushStatus::Value(value)) => return Some(Ok(value)),
+                Ok(PushStatus::End) => return None,
+                Ok(PushStatus::NeedMoreData) => {}
+                Err(e) => return Some(Err(e)),
+            }
+
+            match self.reader.read(&mut self.buf).await {
+                Ok(0) => self.parser.finish(),
+                Ok(n) => self.parser.feed(&self.buf[..n]),
+                Err(e) => {
+                    self.is_failed = true;
+                    return Some(Err(e.into()));
+                }
+            }
+        }
+    }
+}
+
+/// Writes values in the "ASON Lines" format asynchronously,
+/// see `StreamWriter`.
+pub struct AsyncStreamWriter<W> {
+    writer: W,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncStreamWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Appends a value as a single line.
+    pub async fn write<T>(&mut self, value: &T) -> Result<(), AsonError>
+    where
+        T: Serialize,
+    {
+        let mut stream_writer = StreamWriter::new(vec![]);
+        stream_writer.write(value)?;
+        self.writer.write_all(&stream_writer.into_inner()).await?;
+        Ok(())
+    }
+
+    pub async fn flush(&mut self) -> Result<(), AsonError> {
+        self.writer.flush().await?;
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    use pretty_assertions::assert_eq;
+    use serde::{Deserialize, Serialize};
+    use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+    use crate::{
+        ast::{AsonNode, Number},
+        limits::Limits,
+        location::Location,
+        options::ParseOptions,
+        parser::parse_from_str,
+        serde::ser::to_string,
+        AsonError, ErrorKind,
+    };
+
+    use super::{
+        from_async_reader, from_async_reader_with_options, parse_from_async_reader,
+        parse_from_async_reader_with_options, print_to_async_writer, to_async_writer,
+        AsyncStreamReader, AsyncStreamWriter, WRITE_BUFFER_SIZE,
+    };
+
+    // a reader that yields the data in small chunks, so that
+    // some chunks end in the middle of the multi-byte characters.
+    struct ChunkedReader<'a> {
+        data: &'a [u8],
+        chunk_size: usize,
+    }
+
+    impl AsyncRead for ChunkedReader<'_> {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            let length = self.chunk_size.min(self.data.len()).min(buf.remaining());
+            let (chunk, remains) = self.data.split_at(length);
+            buf.put_slice(chunk);
+            self.data = remains;
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Event {
+        id: i32,
+        message: String,
+    }
+
+    #[tokio::test]
+    async fn test_read_and_write() {
+        let text = "{\n    id: 1\n    message: \"文字🍒\"\n}";
+
+        let reader = ChunkedReader {
+            data: text.as_bytes(),
+            chunk_size: 3,
+        };
+        let event = from_async_reader::<Event, _>(reader).await.unwrap();
+        assert_eq!(
+            event,
+            Event {
+                id: 1,
+                message: "文字🍒".to_owned()
+            }
+        );
+
+        let mut buf: Vec<u8> = vec![];
+        to_async_writer(&event, &mut buf).await.unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), text);
+
+        let node = parse_from_async_reader(text.as_bytes()).await.unwrap();
+        let mut buf: Vec<u8> = vec![];
+        print_to_async_writer(&mut buf, &node).await.unwrap();
+        assert_eq!(String::from_utf8(buf).unwrap(), text);
+
+        // errors
+        assert!(matches!(
+            from_async_reader::<Event, _>(&b"{id: 1, message: \"\xff\"}"[..]).await,
+            Err(AsonError {
+                kind: ErrorKind::InvalidData,
+                ..
+            })
+        ));
+
+        let limits = Limits {
+            max_document_size: 8,
+            ..Limits::default()
+        };
+        assert!(matches!(
+            from_async_reader_with_options::<Event, _>(
+                text.as_bytes(),
+                ParseOptions::new().with_limits(limits)
+            )
+            .await,
+            Err(AsonError {
+                kind: ErrorKind::DocumentSizeLimitExceeded,
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_read_with_options() {
+        let variables = HashMap::from([("MESSAGE".to_owned(), "foo".to_owned())]);
+        let options = ParseOptions::new()
+            .with_variables(&variables)
+            .with_anchors();
+
+        let text = "{id: 1, message: \"${MESSAGE}\"}";
+        let reader = ChunkedReader {
+            data: text.as_bytes(),
+            chunk_size: 3,
+        };
+        let future = from_async_reader_with_options::<Event, _>(reader, options);
+
+        // the future can be spawned
+        fn assert_send<T: Send>(_: &T) {}
+        assert_send(&future);
+
+        assert_eq!(
+            future.await.unwrap(),
+            Event {
+                id: 1,
+                message: "foo".to_owned()
+            }
+        );
+
+        assert_eq!(
+            parse_from_async_reader_with_options(
+                &b"[Anchor::Define(\"a\", 1), Anchor::Alias(\"a\")]"[..],
+                options
+            )
+            .await
+            .unwrap(),
+            parse_from_str("[1, 1]").unwrap()
+        );
+
+        let mut reader =
+            AsyncStreamReader::with_options(&b"\"${MESSAGE}\"\nAnchor::Alias(\"a\")"[..], options);
+        assert_eq!(
+            reader.next_node().await.unwrap().unwrap(),
+            AsonNode::String("foo".to_owned())
+        );
+        assert_eq!(
+            reader.next_node().await.unwrap().unwrap_err().kind,
+            ErrorKind::InvalidReference
+        );
+        assert!(reader.next_node().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_read_document() {
+        // the locations of errors are relative to the whole data
+        let text = "// comment\n{\n    id: 1\n    message: 2\n}";
+        let reader = ChunkedReader {
+            data: text.as_bytes(),
+            chunk_size: 3,
+        };
+        let e = from_async_reader::<Event, _>(reader).await.unwrap_err();
+        assert_eq!(e.kind, ErrorKind::TypeMismatch);
+        assert_eq!(e.location.unwrap().line, 3);
+
+        assert_eq!(
+            parse_from_async_reader(&b"{id: 1} // trailing comment\n"[..])
+                .await
+                .unwrap(),
+            parse_from_str("{id: 1}").unwrap()
+        );
+
+        assert_eq!(
+            parse_from_async_reader(&b"{id: 1}\n{id: 2}"[..]).await,
+            Err(AsonError::new_syntax(
+                "Document has more than one node.".to_owned(),
+                Location::new_position(8, 1, 0).with_byte_index(8)
+            ))
+        );
+
+        let e = parse_from_async_reader(&b"  // empty\n"[..])
+            .await
+            .unwrap_err();
+        assert_eq!(e.kind, ErrorKind::UnexpectedEndOfDocument);
+        assert_eq!(
+            e.location,
+            Some(Location::new_position(11, 1, 0).with_byte_index(11))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_document_with_separators() {
+        // the same results as the text parser
+        for text in [
+            "[1],",
+            "1,",
+            "1\n,",
+            ",1",
+            "\n ,[1]",
+            ",",
+            "1, 2",
+            ",1,2",
+            "[1] , [2]",
+            "[1]\n",
+            "1 // c\n",
+            "\n1",
+            "1\r\n",
+            "[1,\n2]",
+        ] {
+            let reader = ChunkedReader {
+                data: text.as_bytes(),
+                chunk_size: 1,
+            };
+            assert_eq!(
+                parse_from_async_reader(reader).await,
+                parse_from_str(text),
+                "{:?}",
+                text
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_write_in_chunks() {
+        // a writer that records the size of each write
+        struct RecordingWriter {
+            data: Vec<u8>,
+            sizes: Vec<usize>,
+        }
+
+        impl AsyncWrite for RecordingWriter {
+            fn poll_write(
+                mut self: Pin<&mut Self>,
+                _cx: &mut Context<'_>,
+                buf: &[u8],
+            ) -> Poll<std::io::Result<usize>> {
+                self.data.extend_from_slice(buf);
+                self.sizes.push(buf.len());
+                Poll::Ready(Ok(buf.len()))
+            }
+
+            fn poll_flush(
+                self: Pin<&mut Self>,
+                _cx: &mut Context<'_>,
+            ) -> Poll<std::io::Result<()>> {
+                Poll::Ready(Ok(()))
+            }
+
+            fn poll_shutdown(
+                self: Pin<&mut Self>,
+                _cx: &mut Context<'_>,
+            ) -> Poll<std::io::Result<()>> {
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        let events: Vec<Event> = (0..2000)
+            .map(|id| Event {
+                id,
+                message: format!("message {}", id),
+            })
+            .collect();
+
+        let mut writer = RecordingWriter {
+            data: vec![],
+            sizes: vec![],
+        };
+        to_async_writer(&events, &mut writer).await.unwrap();
+
+        assert_eq!(
+            String::from_utf8(writer.data).unwrap(),
+            to_string(&events).unwrap()
+        );
+        assert!(writer.sizes.len() > 1);
+        assert!(writer
+            .sizes
+            .iter()
+            .all(|size| *size < WRITE_BUFFER_SIZE + 100));
+    }
+
+    #[tokio::test]
+    async fn test_stream() {
+        let events = vec![
+            Event {
+                id: 1,
+                message: "foo\nbar".to_owned(),
+            },
+            Event {
+                id: 2,
+                message: "文字🍒".to_owned(),
+            },
+        ];
+
+        let mut writer = AsyncStreamWriter::new(vec![]);
+        for event in &events {
+            writer.write(event).await.unwrap();
+        }
+        writer.flush().await.unwrap();
+        let buf = writer.into_inner();
+
+        let mut reader = AsyncStreamReader::new(ChunkedReader {
+            data: &buf,
+            chunk_size: 3,
+        });
+        let mut values = vec![];
+        while let Some(result) = reader.next_value::<Event>().await {
+            values.push(result.unwrap());
+        }
+        assert_eq!(values, events);
+
+        // an invalid value does not stop the stream
+        let mut reader = AsyncStreamReader::new(&b"11\n[12 13]\n14"[..]);
+        assert_eq!(
+            reader.next_node().await,
+            Some(Ok(AsonNode::Number(Number::I32(11))))
+        );
+        assert!(matches!(reader.next_node().await, Some(Err(_))));
+        assert_eq!(
+            reader.next_node().await,
+            Some(Ok(AsonNode::Number(Number::I32(14))))
+        );
+        assert_eq!(reader.next_node().await, None);
+    }
+}
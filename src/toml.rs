@@ -0,0 +1,748 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+//! Conversion between ASON and TOML (`toml::Value`).
+//!
+//! From TOML to ASON:
+//!
+//! | TOML                         | ASON                                    |
+//! |------------------------------|-----------------------------------------|
+//! | integer                      | `i32` if the value fits, otherwise `i64`|
+//! | float                        | `f64`                                   |
+//! | boolean                      | Boolean                                 |
+//! | string                       | String                                  |
+//! | offset date-time             | DateTime                                |
+//! | local date-time, local date  | DateTime in UTC (with warning)          |
+//! | local time                   | String (with warning)                   |
+//! | array, array of tables       | List, or Tuple if the elements are of different types |
+//! | table                        | Object, or Map with String names if some keys are not valid identifiers |
+//!
+//! From ASON to TOML, the root value must be an Object (or a Map with
+//! String names):
+//!
+//! | ASON                         | TOML                                    |
+//! |------------------------------|-----------------------------------------|
+//! | integer numbers (i8 ~ u64)   | integer, `u64` greater than `i64::MAX` is converted to float (with warning) |
+//! | f32, f64                     | float                                   |
+//! | Boolean                      | boolean                                 |
+//! | Char                         | string (with warning)                   |
+//! | String                       | string                                  |
+//! | DateTime                     | offset date-time, the seconds of offset are dropped (with warning) |
+//! | HexByteData                  | array of integers (with warning)        |
+//! | List                         | array                                   |
+//! | Tuple                        | array (with warning)                    |
+//! | Object                       | table                                   |
+//! | Map                          | table, the names are converted to strings (with warning if the names are not Strings) |
+//! | `Option::None`               | the key or element is omitted (with warning) |
+//! | `Option::Some(v)`            | `v`                                     |
+//! | other Variants               | the same as the JSON conversion, e.g. `{Member = v}` (with warning) |
+
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, NaiveTime, TimeZone, Timelike};
+use toml::{
+    value::{Date as TomlDate, Datetime as TomlDatetime, Offset as TomlOffset, Time as TomlTime},
+    Table, Value,
+};
+
+use crate::{
+    ast::{AsonNode, KeyValuePair, NameValuePair, Number, VariantValue},
+    conversion::{
+        f32_to_f64, is_homogeneous, join_index_path, join_key_path, ConversionWarning, Converted,
+    },
+    parser::parse_from_str,
+    path::is_valid_identifier,
+    printer::print_to_string,
+    AsonError, ErrorKind,
+};
+
+/// Converts a TOML table (i.e. a TOML document) to ASON Object or Map.
+pub fn toml_to_ason(table: &Table) -> Converted<AsonNode> {
+    let mut warnings = vec![];
+    let value = convert_table(table, "", &mut warnings);
+    Converted { value, warnings }
+}
+
+/// Converts an ASON Object (or a Map with String names) to TOML table.
+pub fn ason_to_toml(node: &AsonNode) -> Result<Converted<Table>, AsonError> {
+    let mut warnings = vec![];
+
+    let value = match node {
+        AsonNode::Object(_) | AsonNode::Map(_) => convert_node(node, "", &mut warnings)?,
+        _ => None,
+    };
+
+    match value {
+        Some(Value::Table(table)) => Ok(Converted {
+            value: table,
+            warnings,
+        }),
+        _ => Err(AsonError::new(
+            ErrorKind::Conversion,
+            "Only Object or Map can be converted to TOML document.".to_owned(),
+        )),
+    }
+}
+
+/// Converts TOML text to ASON text.
+pub fn toml_str_to_ason_string(s: &str) -> Result<Converted<String>, AsonError> {
+    let table = s
+        .parse::<Table>()
+        .map_err(|e| AsonError::new(ErrorKind::Conversion, e.message().to_owned()).caused_by(e))?;
+    let Converted { value, warnings } = toml_to_ason(&table);
+    Ok(Converted {
+        value: print_to_string(&value),
+        warnings,
+    })
+}
+
+/// Converts ASON text to TOML text.
+pub fn ason_str_to_toml_string(s: &str) -> Result<Converted<String>, AsonError> {
+    let node = parse_from_str(s)?;
+    let Converted { value, warnings } = ason_to_toml(&node)?;
+    let text = toml::to_string(&value)
+        .map_err(|e| AsonError::new(ErrorKind::Conversion, e.to_string()).caused_by(e))?;
+    Ok(Converted {
+        value: text,
+        warnings,
+    })
+}
+
+fn convert_table(table: &Table, path: &str, warnings: &mut Vec<ConversionWarning>) -> AsonNode {
+    if table.keys().all(|key| is_valid_identifier(key)) {
+        let kvps = table
+            .iter()
+            .map(|(key, value)| {
+                let value = convert_value(value, &join_key_path(path, key), warnings);
+                KeyValuePair::new(key, value)
+            })
+            .collect();
+        AsonNode::Object(kvps)
+    } else {
+        let nvps = table
+            .iter()
+            .map(|(key, value)| NameValuePair {
+                name: Box::new(AsonNode::String(key.to_owned())),
+                value: Box::new(convert_value(value, &join_key_path(path, key), warnings)),
+            })
+            .collect();
+        AsonNode::Map(nvps)
+    }
+}
+
+fn convert_value(value: &Value, path: &str, warnings: &mut Vec<ConversionWarning>) -> AsonNode {
+    match value {
+        Value::String(s) => AsonNode::String(s.to_owned()),
+        Value::Integer(i) => match i32::try_from(*i) {
+            Ok(v) => AsonNode::Number(Number::I32(v)),
+            Err(_) => AsonNode::Number(Number::I64(*i)),
+        },
+        Value::Float(f) => AsonNode::Number(Number::F64(*f)),
+        Value::Boolean(b) => AsonNode::Boolean(*b),
+        Value::Datetime(d) => convert_datetime(d, path, warnings),
+        Value::Array(items) => {
+            let items: Vec<AsonNode> = items
+                .iter()
+                .enumerate()
+                .map(|(idx, item)| convert_value(item, &join_index_path(path, idx), warnings))
+                .collect();
+            if is_homogeneous(&items) {
+                AsonNode::List(items)
+            } else {
+                AsonNode::Tuple(items)
+            }
+        }
+        Value::Table(table) => convert_table(table, path, warnings),
+    }
+}
+
+fn convert_datetime(
+    d: &TomlDatetime,
+    path: &str,
+    warnings: &mut Vec<ConversionWarning>,
+) -> AsonNode {
+    let offset = match d.offset {
+        Some(TomlOffset::Z) => Some(0),
+        Some(TomlOffset::Custom { minutes }) => Some(minutes as i32 * 60),
+        None => None,
+    };
+
+    let date = d.date.and_then(|date| {
+        NaiveDate::from_ymd_opt(date.year as i32, date.month as u32, date.day as u32)
+    });
+
+    let time = match d.time {
+        Some(time) => NaiveTime::from_hms_nano_opt(
+            time.hour as u32,
+            time.minute as u32,
+            time.second as u32,
+            time.nanosecond,
+        ),
+        None => NaiveTime::from_hms_opt(0, 0, 0),
+    };
+
+    let datetime = match (date, time) {
+        (Some(date), Some(time)) => {
+            let offset_seconds = match offset {
+                Some(seconds) => seconds,
+                None => {
+                    let message = if d.time.is_some() {
+                        "The local date-time is converted to date-time in UTC."
+                    } else {
+                        "The local date is converted to date-time at 00:00:00 in UTC."
+                    };
+                    warnings.push(ConversionWarning::new(path, message));
+                    0
+                }
+            };
+
+            FixedOffset::east_opt(offset_seconds)
+                .and_then(|tz| tz.from_local_datetime(&date.and_time(time)).single())
+        }
+        _ => None,
+    };
+
+    match datetime {
+        Some(datetime) => AsonNode::DateTime(datetime),
+        None => {
+            // local time, or a value that is out of range of chrono.
+            warnings.push(ConversionWarning::new(
+                path,
+                "The local time is converted to String.",
+            ));
+            AsonNode::String(d.to_string())
+        }
+    }
+}
+
+// returns `None` if the value should be omitted, i.e. `Option::None`.
+fn convert_node(
+    node: &AsonNode,
+    path: &str,
+    warnings: &mut Vec<ConversionWarning>,
+) -> Result<Option<Value>, AsonError> {
+    let value = match node {
+        AsonNode::Number(number) => convert_number(number, path, warnings),
+        AsonNode::Boolean(b) => Value::Boolean(*b),
+        AsonNode::Char(c) => {
+            warnings.push(ConversionWarning::new(path, "Char is converted to string."));
+            Value::String(c.to_string())
+        }
+        AsonNode::String(s) => Value::String(s.to_owned()),
+        AsonNode::DateTime(d) => Value::Datetime(convert_to_toml_datetime(d, path, warnings)?),
+        AsonNode::HexByteData(data) => {
+            warnings.push(ConversionWarning::new(
+                path,
+                "Hex byte data is converted to array of integers.",
+            ));
+            Value::Array(data.iter().map(|b| Value::Integer(*b as i64)).collect())
+        }
+        AsonNode::List(items) => Value::Array(convert_items(items, path, warnings)?),
+        AsonNode::Tuple(items) => {
+            warnings.push(ConversionWarning::new(path, "Tuple is converted to array."));
+            Value::Array(convert_items(items, path, warnings)?)
+        }
+        AsonNode::Object(kvps) => Value::Table(convert_key_value_pairs(kvps, path, warnings)?),
+        AsonNode::Map(nvps) => {
+            let mut table = Table::new();
+            for nvp in nvps.iter() {
+                let key = match nvp.name.as_ref() {
+                    AsonNode::String(s) => s.to_owned(),
+                    other => {
+                        let key = convert_map_name(other)?;
+                        warnings.push(ConversionWarning::new(
+                            path,
+                            &format!("The map name {} is converted to string.", key),
+                        ));
+                        key
+                    }
+                };
+
+                // e.g. the names `1` and `"1"`
+                if table.contains_key(&key) {
+                    return Err(AsonError::new(
+                        ErrorKind::Conversion,
+                        format!(
+                            "The name {} of Map is converted to a duplicate TOML key \"{}\".",
+                            print_to_string(&nvp.name),
+                            key
+                        ),
+                    ));
+                }
+
+                let child_path = join_key_path(path, &key);
+                if let Some(value) = convert_node(&nvp.value, &child_path, warnings)? {
+                    table.insert(key, value);
+                }
+            }
+            Value::Table(table)
+        }
+        AsonNode::Variant(variant) => {
+            match (
+                variant.type_name.as_str(),
+                variant.member_name.as_str(),
+                &variant.value,
+            ) {
+                ("Option", "None", VariantValue::Empty) => {
+                    warnings.push(ConversionWarning::new(
+                        path,
+                        "Option::None is omitted since TOML has no null value.",
+                    ));
+                    return Ok(None);
+                }
+                ("Option", "Some", VariantValue::Value(value)) => {
+                    return convert_node(value, path, warnings);
+                }
+                _ => {
+                    warnings.push(ConversionWarning::new(
+                        path,
+                        &format!(
+                            "The type name of variant \"{}::{}\" is dropped.",
+                            variant.type_name, variant.member_name
+                        ),
+                    ));
+
+                    let member_value = match &variant.value {
+                        VariantValue::Empty => {
+                            return Ok(Some(Value::String(variant.member_name.to_owned())));
+                        }
+                        VariantValue::Value(value) => match convert_node(value, path, warnings)? {
+                            Some(v) => v,
+                            None => return Ok(None),
+                        },
+                        VariantValue::Tuple(items) => {
+                            Value::Array(convert_items(items, path, warnings)?)
+                        }
+                        VariantValue::Object(kvps) => {
+                            Value::Table(convert_key_value_pairs(kvps, path, warnings)?)
+                        }
+                    };
+
+                    let mut table = Table::new();
+                    table.insert(variant.member_name.to_owned(), member_value);
+                    Value::Table(table)
+                }
+            }
+        }
+        AsonNode::Error => {
+            return Err(AsonError::new(
+                ErrorKind::Conversion,
+                "Can not convert the error placeholder to TOML.".to_owned(),
+            ));
+        }
+    };
+
+    Ok(Some(value))
+}
+
+fn convert_number(number: &Number, path: &str, warnings: &mut Vec<ConversionWarning>) -> Value {
+    match number {
+        Number::I8(v) => Value::Integer(*v as i64),
+        Number::U8(v) => Value::Integer(*v as i64),
+        Number::I16(v) => Value::Integer(*v as i64),
+        Number::U16(v) => Value::Integer(*v as i64),
+        Number::I32(v) => Value::Integer(*v as i64),
+        Number::U32(v) => Value::Integer(*v as i64),
+        Number::I64(v) => Value::Integer(*v),
+        Number::U64(v) => match i64::try_from(*v) {
+            Ok(i) => Value::Integer(i),
+            Err(_) => {
+                warnings.push(ConversionWarning::new(
+                    path,
+                    &format!(
+                        "The number {} is out of range of TOML integer, it is converted to float.",
+                        v
+                    ),
+                ));
+                Value::Float(*v as f64)
+            }
+        },
+        Number::F32(v) => Value::Float(f32_to_f64(*v)),
+        Number::F64(v) => Value::Float(*v),
+    }
+}
+
+fn convert_items(
+    items: &[AsonNode],
+    path: &str,
+    warnings: &mut Vec<ConversionWarning>,
+) -> Result<Vec<Value>, AsonError> {
+    let mut values = vec![];
+    for (idx, item) in items.iter().enumerate() {
+        if let Some(value) = convert_node(item, &join_index_path(path, idx), warnings)? {
+            values.push(value);
+        }
+    }
+    Ok(values)
+}
+
+fn convert_key_value_pairs(
+    kvps: &[KeyValuePair],
+    path: &str,
+    warnings: &mut Vec<ConversionWarning>,
+) -> Result<Table, AsonError> {
+    let mut table = Table::new();
+    for kvp in kvps {
+        if let Some(value) = convert_node(&kvp.value, &join_key_path(path, &kvp.key), warnings)? {
+            table.insert(kvp.key.to_owned(), value);
+        }
+    }
+    Ok(table)
+}
+
+fn convert_map_name(node: &AsonNode) -> Result<String, AsonError> {
+    let key = match node {
+        AsonNode::Char(c) => c.to_string(),
+        AsonNode::Boolean(b) => b.to_string(),
+        AsonNode::Number(Number::I8(v)) => v.to_string(),
+        AsonNode::Number(Number::U8(v)) => v.to_string(),
+        AsonNode::Number(Number::I16(v)) => v.to_string(),
+        AsonNode::Number(Number::U16(v)) => v.to_string(),
+        AsonNode::Number(Number::I32(v)) => v.to_string(),
+        AsonNode::Number(Number::U32(v)) => v.to_string(),
+        AsonNode::Number(Number::I64(v)) => v.to_string(),
+        AsonNode::Number(Number::U64(v)) => v.to_string(),
+        _ => {
+            return Err(AsonError::new(
+                ErrorKind::Conversion,
+                "Only String, Char, integer number and Boolean can be converted to TOML key."
+                    .to_owned(),
+            ));
+        }
+    };
+
+    Ok(key)
+}
+
+fn convert_to_toml_datetime(
+    d: &DateTime<FixedOffset>,
+    path: &str,
+    warnings: &mut Vec<ConversionWarning>,
+) -> Result<TomlDatetime, AsonError> {
+    let offset_seconds = d.offset().local_minus_utc();
+    let offset_minutes = offset_seconds / 60;
+
+    // TOML offsets are in minutes, the seconds are dropped and the local time is
+    // adjusted, so that the date-time still refers to the same instant.
+    let d = if offset_seconds % 60 == 0 {
+        *d
+    } else {
+        warnings.push(ConversionWarning::new(
+            path,
+            &format!(
+                "The seconds of the offset \"{}\" are dropped, the local time is adjusted.",
+                d.offset()
+            ),
+        ));
+        let tz = FixedOffset::east_opt(offset_minutes * 60).unwrap();
+        d.with_timezone(&tz)
+    };
+
+    let offset = if offset_minutes == 0 {
+        TomlOffset::Z
+    } else {
+        TomlOffset::Custom {
+            minutes: offset_minutes as i16,
+        }
+    };
+
+    let naive = d.naive_local();
+    let year = u16::try_from(naive.year())
+        .ok()
+        .filter(|year| *year <= 9999)
+        .ok_or_else(|| {
+            AsonError::new(
+                ErrorKind::Conversion,
+                format!(
+                    "The year of date-time \"{}\" is out of range of TOML.",
+                    d.to_rfc3339()
+                ),
+            )
+        })?;
+
+    Ok(TomlDatetime {
+        date: Some(TomlDate {
+            year,
+            month: naive.month() as u8,
+            day: naive.day() as u8,
+        }),
+        time: Some(TomlTime {
+            hour: naive.hour() as u8,
+            minute: naive.minute() as u8,
+            second: naive.second() as u8,
+            nanosecond: naive.nanosecond(),
+        }),
+        offset: Some(offset),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, FixedOffset, TimeZone};
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        ast::{AsonNode, KeyValuePair, NameValuePair, Number},
+        conversion::ConversionWarning,
+        parser::parse_from_str,
+        ErrorKind,
+    };
+
+    use super::{ason_str_to_toml_string, ason_to_toml, toml_str_to_ason_string, toml_to_ason};
+
+    #[test]
+    fn test_toml_to_ason() {
+        let text = r#"
+title = "foo"
+port = 8080
+size = 5_000_000_000
+ratio = 0.5
+enabled = true
+released = 2024-03-17T10:01:11+08:00
+local = 2024-03-17T10:01:11
+day = 2024-03-17
+time = 10:01:11
+"first name" = "John"
+
+[database]
+ports = [8000, 8001]
+mixed = [1, "a"]
+
+[[servers]]
+ip = "10.0.0.1"
+
+[[servers]]
+ip = "10.0.0.2"
+"#;
+
+        let table = text.parse::<toml::Table>().unwrap();
+        let converted = toml_to_ason(&table);
+
+        let new_kvp = |key: &str, node: AsonNode| NameValuePair {
+            name: Box::new(AsonNode::String(key.to_owned())),
+            value: Box::new(node),
+        };
+
+        assert_eq!(
+            converted.value,
+            AsonNode::Map(vec![
+                new_kvp("title", AsonNode::String("foo".to_owned())),
+                new_kvp("port", AsonNode::Number(Number::I32(8080))),
+                new_kvp("size", AsonNode::Number(Number::I64(5_000_000_000))),
+                new_kvp("ratio", AsonNode::Number(Number::F64(0.5))),
+                new_kvp("enabled", AsonNode::Boolean(true)),
+                new_kvp(
+                    "released",
+                    AsonNode::DateTime(
+                        DateTime::parse_from_rfc3339("2024-03-17T10:01:11+08:00").unwrap()
+                    )
+                ),
+                new_kvp(
+                    "local",
+                    AsonNode::DateTime(
+                        DateTime::parse_from_rfc3339("2024-03-17T10:01:11Z").unwrap()
+                    )
+                ),
+                new_kvp(
+                    "day",
+                    AsonNode::DateTime(
+                        DateTime::parse_from_rfc3339("2024-03-17T00:00:00Z").unwrap()
+                    )
+                ),
+                new_kvp("time", AsonNode::String("10:01:11".to_owned())),
+                new_kvp("first name", AsonNode::String("John".to_owned())),
+                new_kvp(
+                    "database",
+                    AsonNode::Object(vec![
+                        KeyValuePair::new(
+                            "ports",
+                            AsonNode::List(vec![
+                                AsonNode::Number(Number::I32(8000)),
+                                AsonNode::Number(Number::I32(8001)),
+                            ])
+                        ),
+                        KeyValuePair::new(
+                            "mixed",
+                            AsonNode::Tuple(vec![
+                                AsonNode::Number(Number::I32(1)),
+                                AsonNode::String("a".to_owned()),
+                            ])
+                        ),
+                    ])
+                ),
+                new_kvp(
+                    "servers",
+                    AsonNode::List(vec![
+                        AsonNode::Object(vec![KeyValuePair::new(
+                            "ip",
+                            AsonNode::String("10.0.0.1".to_owned())
+                        )]),
+                        AsonNode::Object(vec![KeyValuePair::new(
+                            "ip",
+                            AsonNode::String("10.0.0.2".to_owned())
+                        )]),
+                    ])
+                ),
+            ])
+        );
+
+        assert_eq!(
+            converted.warnings,
+            vec![
+                ConversionWarning::new(
+                    ".local",
+                    "The local date-time is converted to date-time in UTC."
+                ),
+                ConversionWarning::new(
+                    ".day",
+                    "The local date is converted to date-time at 00:00:00 in UTC."
+                ),
+                ConversionWarning::new(".time", "The local time is converted to String."),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ason_to_toml() {
+        let node = parse_from_str(
+            r#"{
+                name: "foo"
+                id: 123_u8
+                big: 18446744073709551615_u64
+                ratio: 1.5_f32
+                scale: 1.1_f32
+                initial: 'f'
+                released: d"2024-03-17 10:01:11+08:00"
+                data: h"01 02"
+                pair: (1, "a")
+                optional: Option::None
+                present: Option::Some(11)
+                ids: [1: "one", 2: "two"]
+                color: Color::RGB(255_u8, 127_u8, 63_u8)
+                servers: [
+                    {ip: "10.0.0.1"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let converted = ason_to_toml(&node).unwrap();
+        let text = toml::to_string(&converted.value).unwrap();
+
+        assert_eq!(
+            text,
+            r#"name = "foo"
+id = 123
+big = 18446744073709552000.0
+ratio = 1.5
+scale = 1.1
+initial = "f"
+released = 2024-03-17T10:01:11+08:00
+data = [1, 2]
+pair = [1, "a"]
+present = 11
+
+[ids]
+1 = "one"
+2 = "two"
+
+[color]
+RGB = [255, 127, 63]
+
+[[servers]]
+ip = "10.0.0.1"
+"#
+        );
+
+        assert_eq!(
+            converted
+                .warnings
+                .iter()
+                .map(|w| w.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                ".big: The number 18446744073709551615 is out of range of TOML integer, it is converted to float.",
+                ".initial: Char is converted to string.",
+                ".data: Hex byte data is converted to array of integers.",
+                ".pair: Tuple is converted to array.",
+                ".optional: Option::None is omitted since TOML has no null value.",
+                ".ids: The map name 1 is converted to string.",
+                ".ids: The map name 2 is converted to string.",
+                ".color: The type name of variant \"Color::RGB\" is dropped.",
+            ]
+        );
+
+        // the root must be Object or Map
+        assert!(ason_to_toml(&parse_from_str("[1, 2]").unwrap()).is_err());
+
+        // the names which are converted to the same key
+        assert_eq!(
+            ason_to_toml(&parse_from_str(r#"[1: 'a', "1": 'b']"#).unwrap())
+                .unwrap_err()
+                .message,
+            r#"The name "1" of Map is converted to a duplicate TOML key "1"."#
+        );
+
+        // the seconds of offset
+        let released = FixedOffset::east_opt(8 * 3600 + 30)
+            .unwrap()
+            .with_ymd_and_hms(2024, 3, 17, 10, 1, 11)
+            .unwrap();
+        let converted = ason_to_toml(&AsonNode::Object(vec![KeyValuePair::new(
+            "released",
+            AsonNode::DateTime(released),
+        )]))
+        .unwrap();
+        assert_eq!(
+            toml::to_string(&converted.value).unwrap(),
+            "released = 2024-03-17T10:00:41+08:00\n"
+        );
+        assert_eq!(
+            converted.warnings,
+            vec![ConversionWarning::new(
+                ".released",
+                "The seconds of the offset \"+08:00:30\" are dropped, the local time is adjusted."
+            )]
+        );
+
+        // the error placeholder
+        assert_eq!(
+            ason_to_toml(&AsonNode::Object(vec![KeyValuePair::new(
+                "name",
+                AsonNode::Error
+            )]))
+            .unwrap_err()
+            .kind,
+            ErrorKind::Conversion
+        );
+    }
+
+    #[test]
+    fn test_text_conversion() {
+        let toml_text = r#"name = "foo"
+ports = [8000, 8001]
+released = 2024-03-17T10:01:11Z
+"#;
+
+        let converted = toml_str_to_ason_string(toml_text).unwrap();
+        assert_eq!(
+            converted.value,
+            r#"{
+    name: "foo"
+    ports: [
+        8000
+        8001
+    ]
+    released: d"2024-03-17T10:01:11+00:00"
+}"#
+        );
+        assert!(converted.warnings.is_empty());
+
+        let converted = ason_str_to_toml_string(&converted.value).unwrap();
+        assert_eq!(converted.value, toml_text);
+        assert!(converted.warnings.is_empty());
+
+        assert!(toml_str_to_ason_string("name = ").is_err());
+    }
+}
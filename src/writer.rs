@@ -0,0 +1,563 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+use std::io::Write;
+
+use serde::Serialize;
+
+use crate::{
+    ast::AsonNode,
+    path::is_valid_identifier,
+    printer::{print_node, DEFAULT_INDENT_CHARS},
+    serde::ser::to_writer_with_indent_level,
+    AsonError, ErrorKind,
+};
+
+enum Frame {
+    List,
+    Tuple { is_first_element: bool },
+
+    // the name of entry has been written and the value is expected.
+    Map { is_expecting_value: bool },
+
+    // the key has been written and the value is expected.
+    Object { is_expecting_value: bool },
+
+    // a variant with a single value, e.g. `Option::Some(...)`.
+    Variant { has_value: bool },
+}
+
+/// Writes a document incrementally, so that a huge document can be
+/// produced without building the whole AST or the Rust value in memory, e.g.
+///
+/// ```rust
+/// let mut writer = ason::AsonWriter::new(vec![]);
+/// writer.begin_object().unwrap();
+/// writer.key("id").unwrap();
+/// writer.serialize(&123).unwrap();
+/// writer.key("items").unwrap();
+/// writer.serialize_iter((1..4).map(|i| i * 10)).unwrap();
+/// writer.end().unwrap();
+///
+/// let buf = writer.finish().unwrap();
+/// assert_eq!(
+///     String::from_utf8(buf).unwrap(),
+///     "{\n    id: 123\n    items: [\n        10\n        20\n        30\n    ]\n}"
+/// );
+/// ```
+///
+/// The output is formatted in the same way as `print_to_writer`.
+/// The nesting is checked at runtime, the methods return an error
+/// of `ErrorKind::InvalidStructure` if they are called in an invalid order,
+/// e.g. a value without key in an Object.
+pub struct AsonWriter<W: Write> {
+    writer: W,
+    frames: Vec<Frame>,
+
+    // the number of the opening List, Map and Object.
+    indent_level: usize,
+    has_root_value: bool,
+}
+
+impl<W: Write> AsonWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            frames: vec![],
+            indent_level: 0,
+            has_root_value: false,
+        }
+    }
+
+    pub fn begin_list(&mut self) -> Result<(), AsonError> {
+        self.before_value()?;
+        self.append("[\n")?;
+        self.indent_level += 1;
+        self.frames.push(Frame::List);
+        Ok(())
+    }
+
+    pub fn begin_tuple(&mut self) -> Result<(), AsonError> {
+        self.before_value()?;
+        self.append("(")?;
+        self.frames.push(Frame::Tuple {
+            is_first_element: true,
+        });
+        Ok(())
+    }
+
+    /// The entries of Map are written by calling `value` (or `serialize`)
+    /// for the name and the value alternately.
+    pub fn begin_map(&mut self) -> Result<(), AsonError> {
+        self.before_value()?;
+        self.append("[\n")?;
+        self.indent_level += 1;
+        self.frames.push(Frame::Map {
+            is_expecting_value: false,
+        });
+        Ok(())
+    }
+
+    /// The members of Object are written by calling `key` and then
+    /// `value` (or `serialize`, or a `begin_*` method).
+    pub fn begin_object(&mut self) -> Result<(), AsonError> {
+        self.before_value()?;
+        self.append("{\n")?;
+        self.indent_level += 1;
+        self.frames.push(Frame::Object {
+            is_expecting_value: false,
+        });
+        Ok(())
+    }
+
+    /// Begins a variant with a single value, e.g. `Option::Some(...)`,
+    /// the value is written by the next call.
+    ///
+    /// The type name and the member name must be identifiers.
+    pub fn begin_variant(&mut self, type_name: &str, member_name: &str) -> Result<(), AsonError> {
+        for name in [type_name, member_name] {
+            if !is_valid_identifier(name) {
+                return Err(new_invalid_structure_error(&format!(
+                    "Variant name \"{}\" is not a valid identifier.",
+                    name
+                )));
+            }
+        }
+
+        self.before_value()?;
+        self.append(&format!("{}::{}(", type_name, member_name))?;
+        self.frames.push(Frame::Variant { has_value: false });
+        Ok(())
+    }
+
+    /// The key must be an identifier, e.g. `id` or `user_name`.
+    pub fn key(&mut self, key: &str) -> Result<(), AsonError> {
+        if !is_valid_identifier(key) {
+            return Err(new_invalid_structure_error(&format!(
+                "Key \"{}\" is not a valid identifier.",
+                key
+            )));
+        }
+
+        match self.frames.last_mut() {
+            Some(Frame::Object { is_expecting_value }) if !*is_expecting_value => {
+                *is_expecting_value = true;
+            }
+            Some(Frame::Object { .. }) => {
+                return Err(new_invalid_structure_error(
+                    "Expect a value for the previous key.",
+                ))
+            }
+            _ => {
+                return Err(new_invalid_structure_error(
+                    "Key is only allowed in Object.",
+                ))
+            }
+        }
+
+        let leading_space = DEFAULT_INDENT_CHARS.repeat(self.indent_level);
+        self.append(&format!("{}{}: ", leading_space, key))
+    }
+
+    /// Closes the innermost List, Tuple, Map, Object or Variant,
+    /// Tuple and Variant can not be empty.
+    pub fn end(&mut self) -> Result<(), AsonError> {
+        // (closing bracket, is indented)
+        let (closing, is_indented) = match self.frames.last() {
+            None => return Err(new_invalid_structure_error("No container to end.")),
+            Some(Frame::List) => ("]", true),
+            Some(Frame::Tuple {
+                is_first_element: false,
+            }) => (")", false),
+            Some(Frame::Tuple { .. }) => {
+                return Err(new_invalid_structure_error("Tuple can not be empty."))
+            }
+            Some(Frame::Map {
+                is_expecting_value: false,
+            }) => ("]", true),
+            Some(Frame::Object {
+                is_expecting_value: false,
+            }) => ("}", true),
+            Some(Frame::Variant { has_value: true }) => (")", false),
+            Some(Frame::Map { .. }) | Some(Frame::Object { .. }) => {
+                return Err(new_invalid_structure_error(
+                    "Expect a value before the end of Map or Object.",
+                ))
+            }
+            Some(Frame::Variant { .. }) => {
+                return Err(new_invalid_structure_error(
+                    "Expect a value before the end of Variant.",
+                ))
+            }
+        };
+
+        self.frames.pop();
+
+        if is_indented {
+            self.indent_level -= 1;
+            let leading_space = DEFAULT_INDENT_CHARS.repeat(self.indent_level);
+            self.append(&leading_space)?;
+        }
+
+        self.append(closing)?;
+        self.after_value()
+    }
+
+    /// Writes a complete value.
+    pub fn value(&mut self, node: &AsonNode) -> Result<(), AsonError> {
+        if matches!(node, AsonNode::Error) {
+            return Err(new_invalid_structure_error(
+                "Error node can not be written.",
+            ));
+        }
+
+        self.before_value()?;
+        print_node(
+            &mut self.writer,
+            node,
+            DEFAULT_INDENT_CHARS,
+            self.indent_level,
+        )?;
+        self.after_value()
+    }
+
+    /// Serializes a Rust value as a complete value.
+    pub fn serialize<T>(&mut self, value: &T) -> Result<(), AsonError>
+    where
+        T: Serialize,
+    {
+        self.before_value()?;
+        to_writer_with_indent_level(value, &mut self.writer, self.indent_level)?;
+        self.after_value()
+    }
+
+    /// Serializes the items as a List, the items are taken
+    /// from the iterator one by one.
+    pub fn serialize_iter<I>(&mut self, items: I) -> Result<(), AsonError>
+    where
+        I: IntoIterator,
+        I::Item: Serialize,
+    {
+        self.begin_list()?;
+        for item in items {
+            self.serialize(&item)?;
+        }
+        self.end()
+    }
+
+    /// Checks that the document is complete and returns the underlying writer.
+    pub fn finish(mut self) -> Result<W, AsonError> {
+        if !self.frames.is_empty() {
+            return Err(new_invalid_structure_error(
+                "Some containers are not ended.",
+            ));
+        }
+
+        if !self.has_root_value {
+            return Err(new_invalid_structure_error("The document is empty."));
+        }
+
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    /// Gets a mutable reference to the underlying writer, e.g. for
+    /// taking out the written data of a `Vec<u8>`.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    fn append(&mut self, s: &str) -> Result<(), AsonError> {
+        self.writer.write_all(s.as_bytes())?;
+        Ok(())
+    }
+
+    // writes the leading whitespaces or the separator of the value.
+    fn before_value(&mut self) -> Result<(), AsonError> {
+        let leading_space = DEFAULT_INDENT_CHARS.repeat(self.indent_level);
+
+        match self.frames.last() {
+            None if self.has_root_value => Err(new_invalid_structure_error(
+                "A document can only contain one value.",
+            )),
+            None => Ok(()),
+            Some(Frame::List)
+            | Some(Frame::Map {
+                is_expecting_value: false,
+            }) => self.append(&leading_space),
+            Some(Frame::Map {
+                is_expecting_value: true,
+            }) => self.append(": "),
+            Some(Frame::Object {
+                is_expecting_value: true,
+            }) => Ok(()),
+            Some(Frame::Object { .. }) => Err(new_invalid_structure_error(
+                "Expect a key before the value of Object.",
+            )),
+            Some(Frame::Tuple { is_first_element }) => {
+                if *is_first_element {
+                    Ok(())
+                } else {
+                    self.append(", ")
+                }
+            }
+            Some(Frame::Variant { has_value: false }) => Ok(()),
+            Some(Frame::Variant { .. }) => Err(new_invalid_structure_error(
+                "Variant can only contain one value.",
+            )),
+        }
+    }
+
+    // writes the trailing new-line of the value and updates the state.
+    fn after_value(&mut self) -> Result<(), AsonError> {
+        match self.frames.last_mut() {
+            None => {
+                self.has_root_value = true;
+                Ok(())
+            }
+            Some(Frame::List) => self.append("\n"),
+            Some(Frame::Map { is_expecting_value }) => {
+                *is_expecting_value = !*is_expecting_value;
+                if *is_expecting_value {
+                    Ok(())
+                } else {
+                    self.append("\n")
+                }
+            }
+            Some(Frame::Object { is_expecting_value }) => {
+                *is_expecting_value = false;
+                self.append("\n")
+            }
+            Some(Frame::Tuple { is_first_element }) => {
+                *is_first_element = false;
+                Ok(())
+            }
+            Some(Frame::Variant { has_value }) => {
+                *has_value = true;
+                Ok(())
+            }
+        }
+    }
+}
+
+fn new_invalid_structure_error(message: &str) -> AsonError {
+    AsonError::new(ErrorKind::InvalidStructure, message.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde::Serialize;
+
+    use crate::{
+        ast::{AsonNode, Number},
+        parser::parse_from_str,
+        printer::print_to_string,
+        ErrorKind,
+    };
+
+    use super::AsonWriter;
+
+    fn write(f: impl FnOnce(&mut AsonWriter<Vec<u8>>)) -> String {
+        let mut writer = AsonWriter::new(vec![]);
+        f(&mut writer);
+        String::from_utf8(writer.finish().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_write() {
+        let text = write(|w| {
+            w.begin_object().unwrap();
+            w.key("id").unwrap();
+            w.value(&AsonNode::Number(Number::I32(123))).unwrap();
+            w.key("orders").unwrap();
+            w.begin_list().unwrap();
+            w.begin_tuple().unwrap();
+            w.serialize(&1).unwrap();
+            w.serialize(&"foo").unwrap();
+            w.end().unwrap();
+            w.begin_object().unwrap();
+            w.key("name").unwrap();
+            w.serialize(&vec![11, 13]).unwrap();
+            w.end().unwrap();
+            w.end().unwrap();
+            w.key("modules").unwrap();
+            w.begin_map().unwrap();
+            w.serialize(&"foo").unwrap();
+            w.begin_variant("Option", "Some").unwrap();
+            w.value(&parse_from_str("{version: \"1.0\"}").unwrap())
+                .unwrap();
+            w.end().unwrap();
+            w.serialize(&"bar").unwrap();
+            w.serialize(&Option::<i32>::None).unwrap();
+            w.end().unwrap();
+            w.key("empty").unwrap();
+            w.begin_list().unwrap();
+            w.end().unwrap();
+            w.end().unwrap();
+        });
+
+        let expected = r#"{
+    id: 123
+    orders: [
+        (1, "foo")
+        {
+            name: [
+                11
+                13
+            ]
+        }
+    ]
+    modules: [
+        "foo": Option::Some({
+            version: "1.0"
+        })
+        "bar": Option::None
+    ]
+    empty: [
+    ]
+}"#;
+
+        assert_eq!(text, expected);
+
+        // the same as the printer
+        assert_eq!(
+            print_to_string(&parse_from_str(expected).unwrap()),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_serialize_iter() {
+        #[derive(Serialize)]
+        struct Item {
+            id: i32,
+            tags: Vec<String>,
+        }
+
+        let text = write(|w| {
+            w.serialize_iter((1..3).map(|id| Item {
+                id,
+                tags: vec!["foo".to_owned()],
+            }))
+            .unwrap();
+        });
+
+        assert_eq!(
+            text,
+            r#"[
+    {
+        id: 1
+        tags: [
+            "foo"
+        ]
+    }
+    {
+        id: 2
+        tags: [
+            "foo"
+        ]
+    }
+]"#
+        );
+    }
+
+    #[test]
+    fn test_invalid_structure() {
+        let mut writer = AsonWriter::new(vec![]);
+        assert!(writer.end().is_err());
+        writer.begin_object().unwrap();
+        assert!(writer.serialize(&1).is_err());
+        writer.key("id").unwrap();
+        assert!(writer.key("name").is_err());
+        assert!(writer.end().is_err());
+
+        let mut writer = AsonWriter::new(vec![]);
+        assert!(writer.key("id").is_err());
+        writer.begin_variant("Option", "Some").unwrap();
+        assert!(writer.end().is_err());
+        writer.serialize(&1).unwrap();
+        assert!(writer.serialize(&2).is_err());
+        writer.end().unwrap();
+        assert_eq!(
+            writer.serialize(&3).unwrap_err().kind,
+            ErrorKind::InvalidStructure
+        );
+        assert!(writer.finish().is_ok());
+
+        let mut writer = AsonWriter::new(vec![]);
+        writer.begin_map().unwrap();
+        writer.serialize(&1).unwrap();
+        assert!(writer.end().is_err());
+
+        let mut writer = AsonWriter::new(vec![]);
+        writer.begin_list().unwrap();
+        assert!(writer.finish().is_err());
+
+        let writer = AsonWriter::new(vec![]);
+        assert!(writer.finish().is_err());
+
+        // invalid keys
+        let mut writer = AsonWriter::new(vec![]);
+        writer.begin_object().unwrap();
+        for key in ["", "1st", "user name", "a:b", "true", "NaN"] {
+            let e = writer.key(key).unwrap_err();
+            assert_eq!(e.kind, ErrorKind::InvalidStructure);
+            assert_eq!(
+                e.message,
+                format!("Key \"{}\" is not a valid identifier.", key)
+            );
+        }
+        writer.key("user_name").unwrap();
+
+        // invalid variant names
+        let mut writer = AsonWriter::new(vec![]);
+        for (type_name, member_name, name) in [
+            ("", "Some", ""),
+            ("Option", "", ""),
+            ("Option", "Some(1)", "Some(1)"),
+            ("std::Option", "Some", "std::Option"),
+            ("Option", "true", "true"),
+        ] {
+            let e = writer.begin_variant(type_name, member_name).unwrap_err();
+            assert_eq!(e.kind, ErrorKind::InvalidStructure);
+            assert_eq!(
+                e.message,
+                format!("Variant name \"{}\" is not a valid identifier.", name)
+            );
+        }
+        writer.begin_variant("Option", "Some").unwrap();
+        writer.serialize(&1).unwrap();
+        writer.end().unwrap();
+        assert_eq!(
+            String::from_utf8(writer.finish().unwrap()).unwrap(),
+            "Option::Some(1)"
+        );
+
+        // the placeholder of the invalid value
+        let mut writer = AsonWriter::new(vec![]);
+        let e = writer.value(&AsonNode::Error).unwrap_err();
+        assert_eq!(e.kind, ErrorKind::InvalidStructure);
+        assert_eq!(e.message, "Error node can not be written.");
+        writer.value(&AsonNode::Boolean(true)).unwrap();
+        assert_eq!(String::from_utf8(writer.finish().unwrap()).unwrap(), "true");
+
+        // empty Tuple and Variant
+        let mut writer = AsonWriter::new(vec![]);
+        writer.begin_tuple().unwrap();
+        let e = writer.end().unwrap_err();
+        assert_eq!(e.kind, ErrorKind::InvalidStructure);
+        assert_eq!(e.message, "Tuple can not be empty.");
+        writer.serialize(&1).unwrap();
+        writer.end().unwrap();
+
+        let mut writer = AsonWriter::new(vec![]);
+        writer.begin_variant("Option", "Some").unwrap();
+        let e = writer.end().unwrap_err();
+        assert_eq!(e.kind, ErrorKind::InvalidStructure);
+        assert_eq!(e.message, "Expect a value before the end of Variant.");
+    }
+}
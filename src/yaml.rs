@@ -0,0 +1,621 @@
+// Copyright (c) 2024 Hemashushu <hippospark@gmail.com>, All rights reserved.
+//
+// This Source Code Form is subject to the terms of
+// the Mozilla Public License version 2.0 and additional exceptions,
+// more details in file LICENSE, LICENSE.additional and CONTRIBUTING.
+
+//! Conversion between ASON and YAML (`serde_yaml::Value`).
+//!
+//! From YAML to ASON:
+//!
+//! | YAML                         | ASON                                    |
+//! |------------------------------|-----------------------------------------|
+//! | null                         | `Option::None`                          |
+//! | integer                      | `i32` if the value fits, otherwise `i64` or `u64` |
+//! | float                        | `f64`                                   |
+//! | boolean                      | Boolean                                 |
+//! | string                       | String                                  |
+//! | sequence                     | List, or Tuple if the elements are of different types |
+//! | mapping                      | Object, or Map if some keys are not valid identifiers |
+//! | tagged value `!Tag v`        | `v`, the tag is dropped (with warning)  |
+//!
+//! The merge keys (`<<`) are applied before the conversion, and the aliases
+//! are expanded. The sequences which mix nulls with the values of the same
+//! type are converted to Lists of Options, e.g. `[~, 1]` becomes
+//! `[Option::None, Option::Some(1)]`.
+//!
+//! From ASON to YAML:
+//!
+//! | ASON                         | YAML                                    |
+//! |------------------------------|-----------------------------------------|
+//! | numbers                      | integer or float                        |
+//! | Boolean                      | boolean                                 |
+//! | Char                         | string (with warning)                   |
+//! | String                       | string                                  |
+//! | DateTime                     | RFC 3339 string (with warning)          |
+//! | HexByteData                  | sequence of integers (with warning)     |
+//! | List                         | sequence                                |
+//! | Tuple                        | sequence (with warning)                 |
+//! | Object, Map                  | mapping                                 |
+//! | `Option::None`               | null                                    |
+//! | `Option::Some(v)`            | `v`                                     |
+//! | `Type::Member`               | `Member` (with warning)                 |
+//! | other Variants               | tagged value, e.g. `!Member v` (with warning) |
+//!
+//! The other Variants are converted in the same way as `serde_yaml`
+//! serializes Rust enums, the variant type name is dropped.
+
+use serde::Deserialize;
+use serde_yaml::{value::TaggedValue, Mapping, Number as YamlNumber, Value};
+
+use crate::{
+    ast::{AsonNode, KeyValuePair, NameValuePair, Number, Variant, VariantValue},
+    conversion::{
+        f32_to_f64, items_to_list_or_tuple, join_index_path, join_key_path, ConversionWarning,
+        Converted,
+    },
+    parser::parse_from_str,
+    path::is_valid_identifier,
+    printer::print_to_string,
+    AsonError, ErrorKind,
+};
+
+pub fn yaml_to_ason(value: &Value) -> Result<Converted<AsonNode>, AsonError> {
+    let mut value = value.clone();
+    value
+        .apply_merge()
+        .map_err(|e| AsonError::new(ErrorKind::Conversion, e.to_string()).caused_by(e))?;
+
+    let mut warnings = vec![];
+    let node = convert_value(&value, "", &mut warnings);
+    Ok(Converted {
+        value: node,
+        warnings,
+    })
+}
+
+pub fn ason_to_yaml(node: &AsonNode) -> Result<Converted<Value>, AsonError> {
+    let mut warnings = vec![];
+    let value = convert_node(node, "", &mut warnings)?;
+    Ok(Converted { value, warnings })
+}
+
+/// Converts YAML text to ASON text.
+///
+/// If the YAML text contains multiple documents, they are converted to
+/// an ASON List, or a Tuple if the documents are of different types.
+pub fn yaml_str_to_ason_string(s: &str) -> Result<Converted<String>, AsonError> {
+    let mut values = vec![];
+    for document in serde_yaml::Deserializer::from_str(s) {
+        let value = Value::deserialize(document)
+            .map_err(|e| AsonError::new(ErrorKind::Conversion, e.to_string()).caused_by(e))?;
+        values.push(value);
+    }
+
+    let Converted {
+        value: node,
+        warnings,
+    } = match values.len() {
+        0 => yaml_to_ason(&Value::Null)?,
+        1 => yaml_to_ason(&values[0])?,
+        _ => {
+            let mut converted = yaml_to_ason(&Value::Sequence(values))?;
+            let message = match converted.value {
+                AsonNode::Tuple(_) => "Multiple YAML documents are converted to a Tuple.",
+                _ => "Multiple YAML documents are converted to a List.",
+            };
+            converted
+                .warnings
+                .insert(0, ConversionWarning::new("", message));
+            converted
+        }
+    };
+
+    Ok(Converted {
+        value: print_to_string(&node),
+        warnings,
+    })
+}
+
+/// Converts ASON text to YAML text.
+pub fn ason_str_to_yaml_string(s: &str) -> Result<Converted<String>, AsonError> {
+    let node = parse_from_str(s)?;
+    let Converted { value, warnings } = ason_to_yaml(&node)?;
+    let text = serde_yaml::to_string(&value)
+        .map_err(|e| AsonError::new(ErrorKind::Conversion, e.to_string()).caused_by(e))?;
+    Ok(Converted {
+        value: text,
+        warnings,
+    })
+}
+
+fn convert_value(value: &Value, path: &str, warnings: &mut Vec<ConversionWarning>) -> AsonNode {
+    match value {
+        Value::Null => AsonNode::Variant(Variant::new("Option", "None")),
+        Value::Bool(b) => AsonNode::Boolean(*b),
+        Value::Number(n) => AsonNode::Number(convert_yaml_number(n)),
+        Value::String(s) => AsonNode::String(s.to_owned()),
+        Value::Sequence(items) => {
+            let items: Vec<AsonNode> = items
+                .iter()
+                .enumerate()
+                .map(|(idx, item)| convert_value(item, &join_index_path(path, idx), warnings))
+                .collect();
+            items_to_list_or_tuple(items)
+        }
+        Value::Mapping(mapping) => convert_mapping(mapping, path, warnings),
+        Value::Tagged(tagged) => {
+            warnings.push(ConversionWarning::new(
+                path,
+                &format!("The tag \"{}\" is dropped.", tagged.tag),
+            ));
+            convert_value(&tagged.value, path, warnings)
+        }
+    }
+}
+
+fn convert_yaml_number(n: &YamlNumber) -> Number {
+    if let Some(v) = n.as_i64() {
+        match i32::try_from(v) {
+            Ok(i) => Number::I32(i),
+            Err(_) => Number::I64(v),
+        }
+    } else if let Some(v) = n.as_u64() {
+        Number::U64(v)
+    } else {
+        Number::F64(n.as_f64().unwrap())
+    }
+}
+
+fn convert_mapping(
+    mapping: &Mapping,
+    path: &str,
+    warnings: &mut Vec<ConversionWarning>,
+) -> AsonNode {
+    let is_object = mapping
+        .keys()
+        .all(|key| matches!(key, Value::String(s) if is_valid_identifier(s)));
+
+    if is_object {
+        let kvps = mapping
+            .iter()
+            .map(|(key, value)| {
+                let key = key.as_str().unwrap();
+                KeyValuePair::new(
+                    key,
+                    convert_value(value, &join_key_path(path, key), warnings),
+                )
+            })
+            .collect();
+        AsonNode::Object(kvps)
+    } else {
+        let nvps = mapping
+            .iter()
+            .map(|(key, value)| {
+                let child_path = match key {
+                    Value::String(s) => join_key_path(path, s),
+                    _ => format!(
+                        "{}[{}]",
+                        path,
+                        serde_yaml::to_string(key).unwrap_or_default().trim()
+                    ),
+                };
+
+                NameValuePair {
+                    name: Box::new(convert_value(key, &child_path, warnings)),
+                    value: Box::new(convert_value(value, &child_path, warnings)),
+                }
+            })
+            .collect();
+        AsonNode::Map(nvps)
+    }
+}
+
+fn convert_node(
+    node: &AsonNode,
+    path: &str,
+    warnings: &mut Vec<ConversionWarning>,
+) -> Result<Value, AsonError> {
+    let value = match node {
+        AsonNode::Number(number) => Value::Number(match number {
+            Number::I8(v) => YamlNumber::from(*v as i64),
+            Number::U8(v) => YamlNumber::from(*v as u64),
+            Number::I16(v) => YamlNumber::from(*v as i64),
+            Number::U16(v) => YamlNumber::from(*v as u64),
+            Number::I32(v) => YamlNumber::from(*v as i64),
+            Number::U32(v) => YamlNumber::from(*v as u64),
+            Number::I64(v) => YamlNumber::from(*v),
+            Number::U64(v) => YamlNumber::from(*v),
+            Number::F32(v) => YamlNumber::from(f32_to_f64(*v)),
+            Number::F64(v) => YamlNumber::from(*v),
+        }),
+        AsonNode::Boolean(b) => Value::Bool(*b),
+        AsonNode::Char(c) => {
+            warnings.push(ConversionWarning::new(path, "Char is converted to string."));
+            Value::String(c.to_string())
+        }
+        AsonNode::String(s) => Value::String(s.to_owned()),
+        AsonNode::DateTime(d) => {
+            warnings.push(ConversionWarning::new(
+                path,
+                "DateTime is converted to RFC 3339 string.",
+            ));
+            Value::String(d.to_rfc3339())
+        }
+        AsonNode::HexByteData(data) => {
+            warnings.push(ConversionWarning::new(
+                path,
+                "Hex byte data is converted to sequence of integers.",
+            ));
+            Value::Sequence(
+                data.iter()
+                    .map(|b| Value::Number(YamlNumber::from(*b as u64)))
+                    .collect(),
+            )
+        }
+        AsonNode::List(items) => Value::Sequence(convert_items(items, path, warnings)?),
+        AsonNode::Tuple(items) => {
+            warnings.push(ConversionWarning::new(
+                path,
+                "Tuple is converted to sequence.",
+            ));
+            Value::Sequence(convert_items(items, path, warnings)?)
+        }
+        AsonNode::Object(kvps) => Value::Mapping(convert_key_value_pairs(kvps, path, warnings)?),
+        AsonNode::Map(nvps) => {
+            let mut mapping = Mapping::new();
+            for (idx, nvp) in nvps.iter().enumerate() {
+                let child_path = match nvp.name.as_ref() {
+                    AsonNode::String(s) => join_key_path(path, s),
+                    _ => join_index_path(path, idx),
+                };
+                let name = convert_node(&nvp.name, &child_path, warnings)?;
+
+                // e.g. the names `'c'` and `"c"`
+                if mapping.contains_key(&name) {
+                    return Err(AsonError::new(
+                        ErrorKind::Conversion,
+                        format!(
+                            "The name {} of Map is converted to a duplicate YAML key.",
+                            print_to_string(&nvp.name)
+                        ),
+                    ));
+                }
+
+                let value = convert_node(&nvp.value, &child_path, warnings)?;
+                mapping.insert(name, value);
+            }
+            Value::Mapping(mapping)
+        }
+        AsonNode::Variant(variant) => {
+            match (
+                variant.type_name.as_str(),
+                variant.member_name.as_str(),
+                &variant.value,
+            ) {
+                ("Option", "None", VariantValue::Empty) => Value::Null,
+                ("Option", "Some", VariantValue::Value(value)) => {
+                    convert_node(value, path, warnings)?
+                }
+                _ => {
+                    warnings.push(ConversionWarning::new(
+                        path,
+                        &format!(
+                            "The type name of variant \"{}::{}\" is dropped.",
+                            variant.type_name, variant.member_name
+                        ),
+                    ));
+
+                    let member_value = match &variant.value {
+                        VariantValue::Empty => {
+                            return Ok(Value::String(variant.member_name.to_owned()));
+                        }
+                        VariantValue::Value(value) => convert_node(value, path, warnings)?,
+                        VariantValue::Tuple(items) => {
+                            Value::Sequence(convert_items(items, path, warnings)?)
+                        }
+                        VariantValue::Object(kvps) => {
+                            Value::Mapping(convert_key_value_pairs(kvps, path, warnings)?)
+                        }
+                    };
+
+                    Value::Tagged(Box::new(TaggedValue {
+                        tag: serde_yaml::value::Tag::new(&variant.member_name),
+                        value: member_value,
+                    }))
+                }
+            }
+        }
+        AsonNode::Error => {
+            return Err(AsonError::new(
+                ErrorKind::Conversion,
+                "Can not convert the error placeholder to YAML.".to_owned(),
+            ));
+        }
+    };
+
+    Ok(value)
+}
+
+fn convert_items(
+    items: &[AsonNode],
+    path: &str,
+    warnings: &mut Vec<ConversionWarning>,
+) -> Result<Vec<Value>, AsonError> {
+    items
+        .iter()
+        .enumerate()
+        .map(|(idx, item)| convert_node(item, &join_index_path(path, idx), warnings))
+        .collect()
+}
+
+fn convert_key_value_pairs(
+    kvps: &[KeyValuePair],
+    path: &str,
+    warnings: &mut Vec<ConversionWarning>,
+) -> Result<Mapping, AsonError> {
+    let mut mapping = Mapping::new();
+    for kvp in kvps {
+        let value = convert_node(&kvp.value, &join_key_path(path, &kvp.key), warnings)?;
+        mapping.insert(Value::String(kvp.key.to_owned()), value);
+    }
+    Ok(mapping)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde::Deserialize;
+
+    use crate::{
+        ast::{AsonNode, KeyValuePair, NameValuePair, Number, Variant},
+        conversion::ConversionWarning,
+        parser::parse_from_str,
+        ErrorKind,
+    };
+
+    use super::{ason_str_to_yaml_string, ason_to_yaml, yaml_str_to_ason_string, yaml_to_ason};
+
+    #[test]
+    fn test_yaml_to_ason() {
+        let text = r#"
+defaults: &defaults
+  adapter: postgres
+  port: 5432
+
+development:
+  <<: *defaults
+  database: dev
+
+size: 5000000000
+big: 18446744073709551615
+ratio: 0.5
+enabled: true
+nothing: null
+"first name": John
+shape: !Circle 1.5
+mixed: [1, a]
+1: one
+"#;
+
+        let value: serde_yaml::Value = serde_yaml::from_str(text).unwrap();
+        let converted = yaml_to_ason(&value).unwrap();
+
+        let new_nvp = |name: AsonNode, value: AsonNode| NameValuePair {
+            name: Box::new(name),
+            value: Box::new(value),
+        };
+        let new_string = |s: &str| AsonNode::String(s.to_owned());
+
+        let defaults = AsonNode::Object(vec![
+            KeyValuePair::new("adapter", new_string("postgres")),
+            KeyValuePair::new("port", AsonNode::Number(Number::I32(5432))),
+        ]);
+
+        assert_eq!(
+            converted.value,
+            AsonNode::Map(vec![
+                new_nvp(new_string("defaults"), defaults),
+                new_nvp(
+                    new_string("development"),
+                    AsonNode::Object(vec![
+                        KeyValuePair::new("database", new_string("dev")),
+                        KeyValuePair::new("adapter", new_string("postgres")),
+                        KeyValuePair::new("port", AsonNode::Number(Number::I32(5432))),
+                    ])
+                ),
+                new_nvp(
+                    new_string("size"),
+                    AsonNode::Number(Number::I64(5_000_000_000))
+                ),
+                new_nvp(new_string("big"), AsonNode::Number(Number::U64(u64::MAX))),
+                new_nvp(new_string("ratio"), AsonNode::Number(Number::F64(0.5))),
+                new_nvp(new_string("enabled"), AsonNode::Boolean(true)),
+                new_nvp(
+                    new_string("nothing"),
+                    AsonNode::Variant(Variant::new("Option", "None"))
+                ),
+                new_nvp(new_string("first name"), new_string("John")),
+                new_nvp(new_string("shape"), AsonNode::Number(Number::F64(1.5))),
+                new_nvp(
+                    new_string("mixed"),
+                    AsonNode::Tuple(vec![AsonNode::Number(Number::I32(1)), new_string("a")])
+                ),
+                new_nvp(AsonNode::Number(Number::I32(1)), new_string("one")),
+            ])
+        );
+
+        assert_eq!(
+            converted.warnings,
+            vec![ConversionWarning::new(
+                ".shape",
+                "The tag \"!Circle\" is dropped."
+            )]
+        );
+    }
+
+    #[test]
+    fn test_ason_to_yaml() {
+        let node = parse_from_str(
+            r#"{
+                name: "foo"
+                id: 123_u8
+                scale: 1.1_f32
+                initial: 'f'
+                released: d"2024-03-17 10:01:11+08:00"
+                data: h"01 02"
+                pair: (1, "a")
+                optional: Option::None
+                present: Option::Some(11)
+                ids: [1: "one", 2: "two"]
+                kind: Kind::Library
+                color: Color::Rgb(255_u8, 127_u8, 63_u8)
+            }"#,
+        )
+        .unwrap();
+
+        let converted = ason_to_yaml(&node).unwrap();
+        let text = serde_yaml::to_string(&converted.value).unwrap();
+
+        assert_eq!(
+            text,
+            r#"name: foo
+id: 123
+scale: 1.1
+initial: f
+released: 2024-03-17T10:01:11+08:00
+data:
+- 1
+- 2
+pair:
+- 1
+- a
+optional: null
+present: 11
+ids:
+  1: one
+  2: two
+kind: Library
+color: !Rgb
+- 255
+- 127
+- 63
+"#
+        );
+
+        assert_eq!(
+            converted
+                .warnings
+                .iter()
+                .map(|w| w.to_string())
+                .collect::<Vec<_>>(),
+            vec![
+                ".initial: Char is converted to string.",
+                ".released: DateTime is converted to RFC 3339 string.",
+                ".data: Hex byte data is converted to sequence of integers.",
+                ".pair: Tuple is converted to sequence.",
+                ".kind: The type name of variant \"Kind::Library\" is dropped.",
+                ".color: The type name of variant \"Color::Rgb\" is dropped.",
+            ]
+        );
+
+        // the output is compatible with `serde_yaml`
+        #[derive(Debug, PartialEq, Deserialize)]
+        enum Color {
+            #[allow(dead_code)]
+            Grey(u8),
+            Rgb(u8, u8, u8),
+        }
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Item {
+            color: Color,
+        }
+
+        let item: Item = serde_yaml::from_str(&text).unwrap();
+        assert_eq!(item.color, Color::Rgb(255, 127, 63));
+
+        // the error placeholder
+        assert_eq!(
+            ason_to_yaml(&AsonNode::List(vec![AsonNode::Error]))
+                .unwrap_err()
+                .kind,
+            ErrorKind::Conversion
+        );
+
+        // the names which are converted to the same key
+        assert_eq!(
+            ason_to_yaml(&parse_from_str(r#"['c': 1, "c": 2]"#).unwrap())
+                .unwrap_err()
+                .message,
+            r#"The name "c" of Map is converted to a duplicate YAML key."#
+        );
+        assert!(ason_to_yaml(&parse_from_str("[1_u8: 1, 1: 2]").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_text_conversion() {
+        let yaml_text = r#"name: foo
+ports:
+- 8000
+- 8001
+"#;
+
+        let converted = yaml_str_to_ason_string(yaml_text).unwrap();
+        assert_eq!(
+            converted.value,
+            r#"{
+    name: "foo"
+    ports: [
+        8000
+        8001
+    ]
+}"#
+        );
+        assert!(converted.warnings.is_empty());
+
+        let converted = ason_str_to_yaml_string(&converted.value).unwrap();
+        assert_eq!(converted.value, yaml_text);
+        assert!(converted.warnings.is_empty());
+
+        // multiple documents
+        let converted = yaml_str_to_ason_string("a: 1\n---\na: 2\n").unwrap();
+        assert_eq!(
+            converted.value,
+            r#"[
+    {
+        a: 1
+    }
+    {
+        a: 2
+    }
+]"#
+        );
+        assert_eq!(
+            converted.warnings,
+            vec![ConversionWarning::new(
+                "",
+                "Multiple YAML documents are converted to a List."
+            )]
+        );
+
+        let converted = yaml_str_to_ason_string("a: 1\n---\nb: 2\n").unwrap();
+        assert_eq!(
+            converted.value,
+            r#"({
+    a: 1
+}, {
+    b: 2
+})"#
+        );
+        assert_eq!(
+            converted.warnings,
+            vec![ConversionWarning::new(
+                "",
+                "Multiple YAML documents are converted to a Tuple."
+            )]
+        );
+
+        assert!(yaml_str_to_ason_string("a: [1, 2").is_err());
+    }
+}